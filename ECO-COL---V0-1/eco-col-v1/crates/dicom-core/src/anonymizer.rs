@@ -0,0 +1,899 @@
+//! Anonimización (de-identificación) de instancias DICOM
+//!
+//! Implementa el Basic Application Level Confidentiality Profile de PS3.15
+//! (Anexo E) con las opciones más usadas para investigación y docencia:
+//!
+//! - Retain Longitudinal Temporal Information (fechas completas o desplazadas)
+//! - Clean Descriptors
+//! - Retain Patient Characteristics, Device Identity e Institution Identity
+//! - Retain UIDs y Retain Private Tags
+//!
+//! Los UIDs se reemplazan de forma determinística a partir de un salt
+//! persistente, así estudios anonimizados por separado siguen enlazados.
+
+use crate::error::{DicomError, Result};
+use crate::metadata::DicomInstance;
+use crate::parser::{DicomParser, ParseOptions};
+use crate::uid::UidGenerator;
+
+use chrono::{Duration, NaiveDate};
+use dicom::core::dictionary::DataDictionary;
+use dicom::core::value::{DataSetSequence, PrimitiveValue, Value};
+use dicom::core::{DataElement, Length, Tag, VR};
+use dicom::dictionary_std::{tags, StandardDataDictionary};
+use dicom::object::InMemDicomObject;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+mod profile;
+
+/// Raíz de los UIDs definidos por el estándar (SOP Classes, Transfer Syntaxes, ...)
+const DICOM_UID_ROOT: &str = "1.2.840.10008.";
+
+/// Acción sobre un atributo (códigos de PS3.15 Tabla E.1-1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    /// Conservar el valor
+    #[serde(rename = "K")]
+    Keep,
+
+    /// Eliminar el atributo
+    #[serde(rename = "X")]
+    Remove,
+
+    /// Dejar el atributo con valor vacío
+    #[serde(rename = "Z")]
+    Empty,
+
+    /// Reemplazar por un valor ficticio consistente con el VR
+    #[serde(rename = "D")]
+    Dummy,
+
+    /// Limpiar: conservar el texto quitando información identificable
+    #[serde(rename = "C")]
+    Clean,
+
+    /// Reemplazar el UID por uno nuevo (determinístico)
+    #[serde(rename = "U")]
+    ReplaceUid,
+}
+
+/// Tratamiento de fechas y horas (Retain Longitudinal Temporal Information)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LongitudinalDates {
+    /// Perfil básico: fechas eliminadas o vacías
+    #[default]
+    Remove,
+
+    /// Retain Longitudinal Temporal Information with Full Dates
+    RetainFull,
+
+    /// Retain Longitudinal Temporal Information with Modified Dates: todas
+    /// las fechas y horas (DA, DT, TM) se desplazan el mismo intervalo
+    Shift {
+        days: i64,
+        /// Segundos adicionales; al cruzar la medianoche la fecha asociada
+        /// a la hora también cambia de día
+        #[serde(default)]
+        seconds: i64,
+    },
+}
+
+/// Perfil de anonimización (cargable desde JSON)
+///
+/// ```json
+/// {
+///   "name": "docencia",
+///   "longitudinal_dates": { "shift": { "days": -365, "seconds": 3600 } },
+///   "clean_descriptors": true,
+///   "patient_name": "ANONIMO",
+///   "overrides": { "PatientAge": "K", "(0008,1010)": "X" }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnonymizationProfile {
+    /// Nombre del perfil (se registra en De-identification Method)
+    pub name: String,
+
+    pub longitudinal_dates: LongitudinalDates,
+
+    /// Clean Descriptors: conservar descripciones quitando nombres e IDs
+    pub clean_descriptors: bool,
+
+    /// Retain Patient Characteristics (sexo, edad, talla, peso, ...)
+    pub retain_patient_characteristics: bool,
+
+    /// Retain Device Identity (estación, número de serie, ...)
+    pub retain_device_identity: bool,
+
+    /// Retain Institution Identity
+    pub retain_institution_identity: bool,
+
+    /// Retain UIDs
+    pub retain_uids: bool,
+
+    /// Conservar grupos privados (por defecto se eliminan)
+    pub retain_private_tags: bool,
+
+    /// Valor de reemplazo para Patient's Name (None = vacío)
+    pub patient_name: Option<String>,
+
+    /// Valor de reemplazo para Patient ID (None = vacío)
+    pub patient_id: Option<String>,
+
+    /// Acciones por atributo que tienen prioridad sobre el perfil
+    ///
+    /// La clave es un keyword (`PatientAge`) o un tag (`(0010,1010)`, `0010,1010`).
+    pub overrides: BTreeMap<String, Action>,
+}
+
+impl Default for AnonymizationProfile {
+    fn default() -> Self {
+        Self {
+            name: "basic".to_string(),
+            longitudinal_dates: LongitudinalDates::default(),
+            clean_descriptors: false,
+            retain_patient_characteristics: false,
+            retain_device_identity: false,
+            retain_institution_identity: false,
+            retain_uids: false,
+            retain_private_tags: false,
+            patient_name: None,
+            patient_id: None,
+            overrides: BTreeMap::new(),
+        }
+    }
+}
+
+impl AnonymizationProfile {
+    /// Perfil básico de PS3.15 sin opciones
+    pub fn basic() -> Self {
+        Self::default()
+    }
+
+    /// Cargar un perfil desde JSON (los campos omitidos usan el perfil básico)
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| DicomError::parse(format!("Perfil de anonimización inválido: {}", e)))
+    }
+
+    /// Cargar un perfil desde un archivo JSON
+    pub fn from_file(path: &Path) -> Result<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Códigos de De-identification Method Code Sequence (CID 7050)
+    fn method_codes(&self) -> Vec<(&'static str, &'static str)> {
+        let mut codes = vec![("113100", "Basic Application Confidentiality Profile")];
+        match self.longitudinal_dates {
+            LongitudinalDates::Remove => {}
+            LongitudinalDates::RetainFull => {
+                codes.push(("113106", "Retain Longitudinal Temporal Information Full Dates Option"))
+            }
+            LongitudinalDates::Shift { .. } => {
+                codes.push(("113107", "Retain Longitudinal Temporal Information Modified Dates Option"))
+            }
+        }
+        let options = [
+            (self.clean_descriptors, "113105", "Clean Descriptors Option"),
+            (self.retain_patient_characteristics, "113108", "Retain Patient Characteristics Option"),
+            (self.retain_device_identity, "113109", "Retain Device Identity Option"),
+            (self.retain_uids, "113110", "Retain UIDs Option"),
+            (self.retain_private_tags, "113111", "Retain Safe Private Option"),
+            (self.retain_institution_identity, "113112", "Retain Institution Identity Option"),
+        ];
+        codes.extend(options.iter().filter(|(enabled, ..)| *enabled).map(|&(_, code, meaning)| (code, meaning)));
+        codes
+    }
+}
+
+// ============================================
+// Perfil básico (PS3.15 Tabla E.1-1)
+// ============================================
+
+/// Opción del perfil que modifica la acción básica de un atributo
+///
+/// La tabla con la acción de cada atributo está en `profile.rs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Retain {
+    Nothing,
+    Dates,
+    Descriptors,
+    PatientCharacteristics,
+    Device,
+    Institution,
+}
+
+// ============================================
+// Reemplazo de UIDs
+// ============================================
+
+/// Reemplazo determinístico de UIDs a partir de un salt secreto
+///
+/// El mismo UID con el mismo salt siempre produce el mismo resultado, así
+/// que las referencias entre instancias se mantienen. Sin el salt no es
+/// posible volver al UID original.
+#[derive(Debug, Clone)]
+pub struct UidRemapper {
+    salt: Vec<u8>,
+    generator: UidGenerator,
+}
+
+impl UidRemapper {
+    /// Crear con un salt conocido
+    pub fn new(salt: impl Into<Vec<u8>>) -> Self {
+        Self {
+            salt: salt.into(),
+            generator: UidGenerator::uuid(),
+        }
+    }
+
+    /// Generar los UIDs nuevos bajo la raíz de `generator` (por defecto "2.25")
+    pub fn with_generator(mut self, generator: UidGenerator) -> Self {
+        self.generator = generator;
+        self
+    }
+
+    /// Crear con un salt aleatorio de 32 bytes
+    pub fn random() -> Self {
+        let salt: Vec<u8> = [uuid::Uuid::new_v4(), uuid::Uuid::new_v4()]
+            .iter()
+            .flat_map(|u| *u.as_bytes())
+            .collect();
+        Self::new(salt)
+    }
+
+    /// Cargar el salt desde `path` (hexadecimal), creándolo si no existe
+    pub fn load_or_create(path: &Path) -> Result<Self> {
+        if path.exists() {
+            let text = std::fs::read_to_string(path)?;
+            let salt = decode_hex(text.trim())
+                .ok_or_else(|| DicomError::validation(format!("Salt inválido en {:?}", path)))?;
+            return Ok(Self::new(salt));
+        }
+
+        let remapper = Self::random();
+        remapper.save(path)?;
+        Ok(remapper)
+    }
+
+    /// Guardar el salt en `path` (hexadecimal)
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let hex: String = self.salt.iter().map(|b| format!("{:02x}", b)).collect();
+        std::fs::write(path, hex)?;
+        Ok(())
+    }
+
+    /// UID nuevo para `uid`, derivado de SHA-256 sobre el salt y el UID
+    pub fn remap(&self, uid: &str) -> String {
+        let key = [&self.salt[..], uid.trim_end_matches(['\0', ' ']).as_bytes()].concat();
+        self.generator.derive(&key)
+    }
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if text.is_empty() || text.len() % 2 != 0 {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+// ============================================
+// Anonimizador
+// ============================================
+
+/// Motor de anonimización
+#[derive(Debug, Clone)]
+pub struct Anonymizer {
+    profile: AnonymizationProfile,
+    overrides: BTreeMap<Tag, Action>,
+    uids: UidRemapper,
+}
+
+impl Anonymizer {
+    /// Crear anonimizador, validando los tags de `overrides`
+    pub fn new(profile: AnonymizationProfile, uids: UidRemapper) -> Result<Self> {
+        let overrides = profile
+            .overrides
+            .iter()
+            .map(|(key, &action)| {
+                StandardDataDictionary
+                    .parse_tag(key.trim())
+                    .map(|tag| (tag, action))
+                    .ok_or_else(|| DicomError::InvalidTag(key.clone()))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            profile,
+            overrides,
+            uids,
+        })
+    }
+
+    /// Perfil en uso
+    pub fn profile(&self) -> &AnonymizationProfile {
+        &self.profile
+    }
+
+    /// Anonimizar una instancia
+    ///
+    /// Los píxeles no se modifican: texto incrustado en la imagen requiere
+    /// un paso adicional de enmascarado (ver `redaction::Redactor`). La
+    /// metadata se extrae sin validar el IOD, porque el perfil vacía o quita
+    /// atributos requeridos.
+    pub fn anonymize(&self, instance: &DicomInstance) -> Result<DicomInstance> {
+        let dataset = self.anonymize_dataset(&instance.dataset);
+        let parser = DicomParser::with_options(ParseOptions {
+            strict_validation: false,
+            recovery: true,
+            ..ParseOptions::default()
+        });
+        let metadata = parser.extract_metadata(&dataset, &instance.metadata.transfer_syntax_uid)?;
+
+        Ok(DicomInstance {
+            file_path: instance.file_path.clone(),
+            metadata,
+            dataset,
+            regions: instance.regions.clone(),
+            warnings: instance.warnings.clone(),
+            checksums: None,
+            pixel_descriptor: instance.pixel_descriptor.clone(),
+            pixel_data: instance.pixel_data.clone(),
+        })
+    }
+
+    /// Anonimizar un data set, agregando los atributos de de-identificación
+    pub fn anonymize_dataset(&self, dataset: &InMemDicomObject) -> InMemDicomObject {
+        let identifiers = identifiers(dataset);
+        let mut output = self.anonymize_object(dataset, &identifiers);
+
+        let mut put_str = |tag, vr, value: &str| {
+            output.put(DataElement::new(tag, vr, PrimitiveValue::from(value)));
+        };
+        if let Some(name) = &self.profile.patient_name {
+            put_str(tags::PATIENT_NAME, VR::PN, name);
+        }
+        if let Some(id) = &self.profile.patient_id {
+            put_str(tags::PATIENT_ID, VR::LO, id);
+        }
+
+        put_str(tags::PATIENT_IDENTITY_REMOVED, VR::CS, "YES");
+        put_str(
+            tags::DEIDENTIFICATION_METHOD,
+            VR::LO,
+            &format!("PS3.15 Basic Profile ({})", self.profile.name),
+        );
+        put_str(
+            tags::LONGITUDINAL_TEMPORAL_INFORMATION_MODIFIED,
+            VR::CS,
+            match self.profile.longitudinal_dates {
+                LongitudinalDates::Remove => "REMOVED",
+                LongitudinalDates::RetainFull => "UNMODIFIED",
+                LongitudinalDates::Shift { .. } => "MODIFIED",
+            },
+        );
+
+        let codes: Vec<InMemDicomObject> = self
+            .profile
+            .method_codes()
+            .into_iter()
+            .map(|(code, meaning)| {
+                InMemDicomObject::from_element_iter([
+                    DataElement::new(tags::CODE_VALUE, VR::SH, PrimitiveValue::from(code)),
+                    DataElement::new(tags::CODING_SCHEME_DESIGNATOR, VR::SH, PrimitiveValue::from("DCM")),
+                    DataElement::new(tags::CODE_MEANING, VR::LO, PrimitiveValue::from(meaning)),
+                ])
+            })
+            .collect();
+        output.put(DataElement::new(
+            tags::DEIDENTIFICATION_METHOD_CODE_SEQUENCE,
+            VR::SQ,
+            DataSetSequence::new(codes, Length::UNDEFINED),
+        ));
+
+        output
+    }
+
+    fn anonymize_object(&self, obj: &InMemDicomObject, identifiers: &[String]) -> InMemDicomObject {
+        InMemDicomObject::from_element_iter(obj.iter().filter_map(|element| {
+            let header = element.header();
+            let action = self.action(header.tag, header.vr)?;
+            self.apply(element, action, obj, identifiers)
+        }))
+    }
+
+    /// Acción para un atributo; None si el atributo se descarta sin más
+    fn action(&self, tag: Tag, vr: VR) -> Option<Action> {
+        if let Some(&action) = self.overrides.get(&tag) {
+            return Some(action);
+        }
+
+        let group = tag.group();
+        if group % 2 == 1 {
+            // 0001, 0003, 0005, 0007 y FFFF no pueden ser privados (PS3.5 §7.8.1)
+            if group <= 0x0007 || group == 0xFFFF {
+                return None;
+            }
+            // Private creators (gggg,0010-00FF) y sus bloques van juntos:
+            // se conservan o se eliminan todos
+            return self.profile.retain_private_tags.then_some(Action::Keep);
+        }
+        // Curvas (50xx), overlay data / comments (60xx) y GPS
+        if (0x5000..=0x50FF).contains(&group)
+            || ((0x6000..=0x60FF).contains(&group) && matches!(tag.element(), 0x3000 | 0x4000))
+            || profile::is_gps(tag)
+        {
+            return None;
+        }
+
+        if let Some((action, retain)) = profile::lookup(tag) {
+            if action == Action::ReplaceUid && self.profile.retain_uids {
+                return Some(Action::Keep);
+            }
+            let retained = match retain {
+                Retain::Nothing => false,
+                Retain::Dates => self.profile.longitudinal_dates != LongitudinalDates::Remove,
+                Retain::Descriptors => self.profile.clean_descriptors,
+                Retain::PatientCharacteristics => self.profile.retain_patient_characteristics,
+                Retain::Device => self.profile.retain_device_identity,
+                Retain::Institution => self.profile.retain_institution_identity,
+            };
+            return Some(match (retained, retain) {
+                (false, _) => action,
+                (true, Retain::Descriptors) => Action::Clean,
+                (true, _) => Action::Keep,
+            });
+        }
+
+        Some(if vr == VR::UI && !self.profile.retain_uids {
+            Action::ReplaceUid
+        } else {
+            Action::Keep
+        })
+    }
+
+    /// Aplicar la acción a un elemento de `parent`
+    fn apply(
+        &self,
+        element: &DataElement<InMemDicomObject>,
+        action: Action,
+        parent: &InMemDicomObject,
+        identifiers: &[String],
+    ) -> Option<DataElement<InMemDicomObject>> {
+        let header = element.header();
+        let (tag, vr) = (header.tag, header.vr);
+
+        let value = match (action, element.value()) {
+            (Action::Remove, _) => return None,
+            (Action::Empty | Action::Dummy, Value::Sequence(_)) => {
+                Value::from(DataSetSequence::new(Vec::new(), Length::UNDEFINED))
+            }
+            (Action::Empty, _) => Value::from(PrimitiveValue::Empty),
+            (Action::Dummy, _) => Value::from(dummy_value(vr)),
+            // Secuencias: se aplica el perfil a cada item
+            (_, Value::Sequence(sequence)) => Value::from(DataSetSequence::new(
+                sequence
+                    .items()
+                    .iter()
+                    .map(|item| self.anonymize_object(item, identifiers))
+                    .collect::<Vec<_>>(),
+                Length::UNDEFINED,
+            )),
+            (Action::ReplaceUid, Value::Primitive(value)) => {
+                let uids: Vec<String> = value
+                    .to_multi_str()
+                    .iter()
+                    .map(|uid| {
+                        let uid = uid.trim_end_matches(['\0', ' ']);
+                        if uid.starts_with(DICOM_UID_ROOT) || uid.is_empty() {
+                            uid.to_string()
+                        } else {
+                            self.uids.remap(uid)
+                        }
+                    })
+                    .collect();
+                Value::from(PrimitiveValue::Strs(uids.into()))
+            }
+            (Action::Clean, Value::Primitive(value)) => {
+                Value::from(PrimitiveValue::from(clean_text(&value.to_str(), identifiers)))
+            }
+            (_, value) => match (self.profile.longitudinal_dates, vr, value) {
+                (LongitudinalDates::Shift { days, seconds }, VR::DA | VR::DT | VR::TM, Value::Primitive(value)) => {
+                    let shift = TimeShift { days, seconds };
+                    let carry = match vr {
+                        VR::DA => paired_time(parent, tag).map_or(0, |time| shift.carry(&time)),
+                        _ => 0,
+                    };
+                    Value::from(shift.apply(vr, value, carry))
+                }
+                _ => value.clone(),
+            },
+        };
+
+        Some(DataElement::new(tag, vr, value))
+    }
+}
+
+/// Palabras que identifican al paciente (para Clean Descriptors)
+fn identifiers(dataset: &InMemDicomObject) -> Vec<String> {
+    let text = |tag| {
+        dataset
+            .element(tag)
+            .ok()
+            .and_then(|e| e.to_str().ok())
+            .map(|s| s.to_string())
+            .unwrap_or_default()
+    };
+
+    let name = text(tags::PATIENT_NAME);
+    name.split(['^', '=', ' '])
+        .chain(std::iter::once(text(tags::PATIENT_ID).as_str()))
+        .map(|word| word.trim().to_lowercase())
+        .filter(|word| word.chars().count() >= 2)
+        .collect()
+}
+
+/// Quitar del texto las palabras que coinciden con identificadores del paciente
+fn clean_text(text: &str, identifiers: &[String]) -> String {
+    text.split_whitespace()
+        .filter(|word| {
+            let word = word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase();
+            !identifiers.contains(&word)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Valor ficticio válido para el VR
+fn dummy_value(vr: VR) -> PrimitiveValue {
+    match vr {
+        VR::DA => PrimitiveValue::from("19000101"),
+        VR::TM => PrimitiveValue::from("000000"),
+        VR::DT => PrimitiveValue::from("19000101000000"),
+        VR::PN | VR::LO | VR::SH | VR::LT | VR::ST | VR::UT | VR::CS => PrimitiveValue::from("ANONYMOUS"),
+        VR::IS | VR::DS => PrimitiveValue::from("0"),
+        VR::US => PrimitiveValue::from(0u16),
+        VR::UL => PrimitiveValue::from(0u32),
+        _ => PrimitiveValue::Empty,
+    }
+}
+
+/// Hora del par fecha/hora de `date` en el mismo objeto
+fn paired_time(obj: &InMemDicomObject, date: Tag) -> Option<String> {
+    let &(_, time) = profile::DATE_TIME_PAIRS.iter().find(|(d, _)| *d == date)?;
+    let value = obj.element(time).ok()?.to_str().ok()?;
+    value.split('\\').next().map(|time| time.trim_end_matches(['\0', ' ']).to_string())
+}
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Desplazamiento de Retain Longitudinal Temporal Information with Modified Dates
+#[derive(Debug, Clone, Copy)]
+struct TimeShift {
+    days: i64,
+    seconds: i64,
+}
+
+impl TimeShift {
+    /// Desplazar cada valor DA (YYYYMMDD), TM (HHMMSS.FFFFFF) o DT
+    ///
+    /// `carry` son los días que suma la hora asociada a una fecha DA al
+    /// desplazarse. Los valores parciales o inválidos quedan vacíos.
+    fn apply(&self, vr: VR, value: &PrimitiveValue, carry: i64) -> PrimitiveValue {
+        let shifted: Vec<String> = value
+            .to_multi_str()
+            .iter()
+            .map(|text| {
+                let text = text.trim_end_matches(['\0', ' ']);
+                let shifted = match vr {
+                    VR::DA => parse_date(text)
+                        .map(|date| (date + Duration::days(self.days + carry)).format("%Y%m%d").to_string()),
+                    VR::TM => self.time(text),
+                    _ => self.datetime(text),
+                };
+                shifted.unwrap_or_default()
+            })
+            .collect();
+        PrimitiveValue::Strs(shifted.into())
+    }
+
+    /// Días que se suman a la fecha asociada a la hora `time`
+    fn carry(&self, time: &str) -> i64 {
+        parse_time(time).map_or(0, |(seconds, _)| (seconds + self.seconds).div_euclid(SECONDS_PER_DAY))
+    }
+
+    fn time(&self, text: &str) -> Option<String> {
+        let (seconds, fraction) = parse_time(text)?;
+        Some(format_time((seconds + self.seconds).rem_euclid(SECONDS_PER_DAY), fraction))
+    }
+
+    /// DT: YYYYMMDD[HHMMSS[.FFFFFF]][&ZZXX]; sin hora solo se desplaza la fecha
+    fn datetime(&self, text: &str) -> Option<String> {
+        let (value, offset) = match text.find(['+', '-']) {
+            Some(i) if i >= 8 => text.split_at(i),
+            _ => (text, ""),
+        };
+        let date = parse_date(value.get(..8)?)?;
+        let time = &value[8..];
+
+        if time.is_empty() && self.seconds == 0 {
+            return Some(format!("{}{}", (date + Duration::days(self.days)).format("%Y%m%d"), offset));
+        }
+        let (seconds, fraction) = if time.is_empty() { (0, "") } else { parse_time(time)? };
+        let total = seconds + self.seconds;
+        let date = date + Duration::days(self.days + total.div_euclid(SECONDS_PER_DAY));
+        Some(format!(
+            "{}{}{}",
+            date.format("%Y%m%d"),
+            format_time(total.rem_euclid(SECONDS_PER_DAY), fraction),
+            offset
+        ))
+    }
+}
+
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(text.get(..8)?, "%Y%m%d").ok().filter(|_| text.len() == 8)
+}
+
+/// Segundos desde medianoche y fracción (con el punto) de HH[MM[SS[.F]]]
+fn parse_time(text: &str) -> Option<(i64, &str)> {
+    let (hms, fraction) = match text.find('.') {
+        Some(i) => text.split_at(i),
+        None => (text, ""),
+    };
+    if !matches!(hms.len(), 2 | 4 | 6) || !hms.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let field = |i: usize| hms.get(i..i + 2).map_or(Some(0), |f| f.parse::<i64>().ok());
+    let (hours, minutes, seconds) = (field(0)?, field(2)?, field(4)?);
+    // 60 segundos es válido (segundo intercalar)
+    if hours > 23 || minutes > 59 || seconds > 60 {
+        return None;
+    }
+    Some((hours * 3600 + minutes * 60 + seconds.min(59), fraction))
+}
+
+fn format_time(seconds: i64, fraction: &str) -> String {
+    format!("{:02}{:02}{:02}{}", seconds / 3600, seconds / 60 % 60, seconds % 60, fraction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> InMemDicomObject {
+        let mut obj = InMemDicomObject::new_empty();
+        let mut put = |tag, vr, value: &str| {
+            obj.put(DataElement::new(tag, vr, PrimitiveValue::from(value)));
+        };
+        put(tags::PATIENT_NAME, VR::PN, "PEREZ^JUAN");
+        put(tags::PATIENT_ID, VR::LO, "CC123456");
+        put(tags::PATIENT_BIRTH_DATE, VR::DA, "19800101");
+        put(tags::PATIENT_SEX, VR::CS, "M");
+        put(tags::STUDY_DATE, VR::DA, "20260115");
+        put(tags::INSTITUTION_NAME, VR::LO, "HOSPITAL SAN JOSE");
+        put(tags::STUDY_DESCRIPTION, VR::LO, "Ecografia abdominal Juan Perez");
+        put(tags::MODALITY, VR::CS, "US");
+        put(tags::SOP_CLASS_UID, VR::UI, "1.2.840.10008.5.1.4.1.1.6.1");
+        put(tags::STUDY_INSTANCE_UID, VR::UI, "1.2.826.0.1.3680043.8.498.1");
+        put(Tag(0x0009, 0x0010), VR::LO, "VENDOR");
+        put(Tag(0x0009, 0x1001), VR::LO, "PEREZ");
+        obj
+    }
+
+    fn anonymizer(profile: AnonymizationProfile) -> Anonymizer {
+        Anonymizer::new(profile, UidRemapper::new(b"salt".to_vec())).unwrap()
+    }
+
+    fn text(obj: &InMemDicomObject, tag: Tag) -> Option<String> {
+        obj.element(tag).ok().map(|e| e.to_str().unwrap().trim_end().to_string())
+    }
+
+    #[test]
+    fn test_basic_profile() {
+        let output = anonymizer(AnonymizationProfile::basic()).anonymize_dataset(&sample());
+
+        assert_eq!(text(&output, tags::PATIENT_NAME).as_deref(), Some(""));
+        assert_eq!(text(&output, tags::PATIENT_ID).as_deref(), Some(""));
+        assert_eq!(text(&output, tags::STUDY_DATE).as_deref(), Some(""));
+        assert_eq!(text(&output, tags::INSTITUTION_NAME), None);
+        assert_eq!(text(&output, tags::STUDY_DESCRIPTION), None);
+        assert_eq!(text(&output, Tag(0x0009, 0x1001)), None);
+        assert_eq!(text(&output, tags::MODALITY).as_deref(), Some("US"));
+        assert_eq!(text(&output, tags::PATIENT_IDENTITY_REMOVED).as_deref(), Some("YES"));
+
+        // SOP Class se conserva; el Study UID se reemplaza
+        assert_eq!(text(&output, tags::SOP_CLASS_UID).as_deref(), Some("1.2.840.10008.5.1.4.1.1.6.1"));
+        let study_uid = text(&output, tags::STUDY_INSTANCE_UID).unwrap();
+        assert!(study_uid.starts_with("2.25."));
+        assert!(study_uid.len() <= 64);
+    }
+
+    #[test]
+    fn test_uid_remap_is_deterministic() {
+        let a = UidRemapper::new(b"salt".to_vec());
+        let b = UidRemapper::new(b"otro".to_vec());
+        assert_eq!(a.remap("1.2.3"), a.remap("1.2.3\0"));
+        assert_ne!(a.remap("1.2.3"), a.remap("1.2.4"));
+        assert_ne!(a.remap("1.2.3"), b.remap("1.2.3"));
+
+        let rooted = a.clone().with_generator(UidGenerator::new("1.2.826.0.1.3680043.10.99").unwrap());
+        assert!(rooted.remap("1.2.3").starts_with("1.2.826.0.1.3680043.10.99."));
+        assert_eq!(rooted.remap("1.2.3"), rooted.remap("1.2.3"));
+    }
+
+    #[test]
+    fn test_salt_is_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("salt.hex");
+
+        let first = UidRemapper::load_or_create(&path).unwrap();
+        let second = UidRemapper::load_or_create(&path).unwrap();
+        assert_eq!(first.remap("1.2.3"), second.remap("1.2.3"));
+    }
+
+    #[test]
+    fn test_retain_options() {
+        let profile = AnonymizationProfile {
+            longitudinal_dates: LongitudinalDates::Shift { days: -15, seconds: 0 },
+            clean_descriptors: true,
+            retain_patient_characteristics: true,
+            retain_institution_identity: true,
+            ..AnonymizationProfile::basic()
+        };
+        let output = anonymizer(profile).anonymize_dataset(&sample());
+
+        assert_eq!(text(&output, tags::STUDY_DATE).as_deref(), Some("20251231"));
+        assert_eq!(text(&output, tags::STUDY_DESCRIPTION).as_deref(), Some("Ecografia abdominal"));
+        assert_eq!(text(&output, tags::PATIENT_SEX).as_deref(), Some("M"));
+        assert_eq!(text(&output, tags::INSTITUTION_NAME).as_deref(), Some("HOSPITAL SAN JOSE"));
+        assert_eq!(
+            text(&output, tags::LONGITUDINAL_TEMPORAL_INFORMATION_MODIFIED).as_deref(),
+            Some("MODIFIED")
+        );
+
+        let codes = output.element(tags::DEIDENTIFICATION_METHOD_CODE_SEQUENCE).unwrap().items().unwrap();
+        assert_eq!(codes.len(), 5);
+    }
+
+    #[test]
+    fn test_sequences_are_anonymized() {
+        let mut obj = sample();
+        let item = InMemDicomObject::from_element_iter([
+            DataElement::new(tags::REFERENCED_SOP_INSTANCE_UID, VR::UI, PrimitiveValue::from("1.2.3.4")),
+            DataElement::new(tags::OPERATORS_NAME, VR::PN, PrimitiveValue::from("GOMEZ")),
+        ]);
+        obj.put(DataElement::new(
+            tags::REFERENCED_IMAGE_SEQUENCE,
+            VR::SQ,
+            DataSetSequence::from(vec![item]),
+        ));
+
+        let anonymizer = anonymizer(AnonymizationProfile::basic());
+        let output = anonymizer.anonymize_dataset(&obj);
+        let item = &output.element(tags::REFERENCED_IMAGE_SEQUENCE).unwrap().items().unwrap()[0];
+        assert_eq!(text(item, tags::REFERENCED_SOP_INSTANCE_UID), Some(anonymizer.uids.remap("1.2.3.4")));
+        assert_eq!(text(item, tags::OPERATORS_NAME), None);
+    }
+
+    #[test]
+    fn test_profile_from_json() {
+        let profile = AnonymizationProfile::from_json(
+            r#"{
+                "name": "docencia",
+                "longitudinal_dates": "retain_full",
+                "patient_name": "ANONIMO",
+                "overrides": { "PatientAge": "K", "(0008,0060)": "X" }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(profile.longitudinal_dates, LongitudinalDates::RetainFull);
+        assert!(!profile.clean_descriptors);
+
+        let mut obj = sample();
+        obj.put(DataElement::new(tags::PATIENT_AGE, VR::AS, PrimitiveValue::from("045Y")));
+        let output = anonymizer(profile).anonymize_dataset(&obj);
+        assert_eq!(text(&output, tags::PATIENT_NAME).as_deref(), Some("ANONIMO"));
+        assert_eq!(text(&output, tags::PATIENT_AGE).as_deref(), Some("045Y"));
+        assert_eq!(text(&output, tags::STUDY_DATE).as_deref(), Some("20260115"));
+        assert_eq!(text(&output, tags::MODALITY), None);
+    }
+
+    #[test]
+    fn test_no_basic_profile_attribute_survives() {
+        let original = |vr| match vr {
+            VR::DA => "20200102",
+            VR::TM => "101112",
+            VR::DT => "20200102101112",
+            VR::UI => "1.2.826.0.1.3680043.8.498.77",
+            _ => "IDENTIFICA",
+        };
+        let vr_of = |tag| StandardDataDictionary.by_tag(tag).map_or(VR::LO, |entry| entry.vr);
+
+        let mut obj = InMemDicomObject::new_empty();
+        for &(tag, ..) in profile::BASIC_PROFILE {
+            let vr = vr_of(tag);
+            if vr == VR::SQ {
+                let item = InMemDicomObject::from_element_iter([DataElement::new(
+                    tags::PERSON_NAME,
+                    VR::PN,
+                    PrimitiveValue::from("PEREZ^JUAN"),
+                )]);
+                obj.put(DataElement::new(tag, vr, DataSetSequence::from(vec![item])));
+            } else {
+                obj.put(DataElement::new(tag, vr, PrimitiveValue::from(original(vr))));
+            }
+        }
+
+        let output = anonymizer(AnonymizationProfile::basic()).anonymize_dataset(&obj);
+        for &(tag, ..) in profile::BASIC_PROFILE {
+            let Ok(element) = output.element(tag) else { continue };
+            match element.items() {
+                Some(items) => assert!(
+                    items.iter().all(|item| text(item, tags::PERSON_NAME).as_deref() != Some("PEREZ^JUAN")),
+                    "{}",
+                    tag
+                ),
+                None => assert_ne!(text(&output, tag).as_deref(), Some(original(vr_of(tag))), "{}", tag),
+            }
+        }
+    }
+
+    #[test]
+    fn test_private_and_invalid_groups() {
+        let mut obj = sample();
+        obj.put(DataElement::new(Tag(0x0003, 0x0010), VR::LO, PrimitiveValue::from("PEREZ")));
+
+        let profile = AnonymizationProfile {
+            retain_private_tags: true,
+            ..AnonymizationProfile::basic()
+        };
+        let output = anonymizer(profile).anonymize_dataset(&obj);
+        assert_eq!(text(&output, Tag(0x0009, 0x0010)).as_deref(), Some("VENDOR"));
+        assert_eq!(text(&output, Tag(0x0009, 0x1001)).as_deref(), Some("PEREZ"));
+        assert_eq!(text(&output, Tag(0x0003, 0x0010)), None);
+
+        let output = anonymizer(AnonymizationProfile::basic()).anonymize_dataset(&obj);
+        assert_eq!(text(&output, Tag(0x0009, 0x0010)), None);
+    }
+
+    #[test]
+    fn test_shift_moves_times_with_dates() {
+        let mut obj = sample();
+        let mut put = |tag, vr, value: &str| {
+            obj.put(DataElement::new(tag, vr, PrimitiveValue::from(value)));
+        };
+        put(tags::STUDY_TIME, VR::TM, "233000.25");
+        put(tags::SERIES_DATE, VR::DA, "20260115");
+        put(tags::SERIES_TIME, VR::TM, "0100");
+        put(tags::ACQUISITION_DATE_TIME, VR::DT, "20260115233000+0500");
+
+        let profile = AnonymizationProfile {
+            longitudinal_dates: LongitudinalDates::Shift { days: -1, seconds: 3600 },
+            ..AnonymizationProfile::basic()
+        };
+        let output = anonymizer(profile).anonymize_dataset(&obj);
+
+        // 15/01 23:30 + (-1 día + 1 hora) = 15/01 00:30
+        assert_eq!(text(&output, tags::STUDY_DATE).as_deref(), Some("20260115"));
+        assert_eq!(text(&output, tags::STUDY_TIME).as_deref(), Some("003000.25"));
+        assert_eq!(text(&output, tags::SERIES_DATE).as_deref(), Some("20260114"));
+        assert_eq!(text(&output, tags::SERIES_TIME).as_deref(), Some("020000"));
+        assert_eq!(text(&output, tags::ACQUISITION_DATE_TIME).as_deref(), Some("20260115003000+0500"));
+    }
+
+    #[test]
+    fn test_invalid_override() {
+        let mut profile = AnonymizationProfile::basic();
+        profile.overrides.insert("NoExiste".to_string(), Action::Keep);
+        let result = Anonymizer::new(profile, UidRemapper::random());
+        assert!(matches!(result, Err(DicomError::InvalidTag(_))));
+    }
+}
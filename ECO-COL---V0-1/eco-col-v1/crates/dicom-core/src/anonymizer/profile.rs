@@ -0,0 +1,496 @@
+//! Tabla del perfil básico (PS3.15 Tabla E.1-1)
+//!
+//! Una fila por atributo de la tabla, ordenada por tag para búsqueda
+//! binaria. Las acciones compuestas del estándar se resuelven así:
+//! X/Z, X/D y X/Z/D → X; Z/D → Z; X/Z/U* → U (se conservan las secuencias
+//! de referencias y se reemplazan los UIDs de sus items). La columna de
+//! opción es la que cambia la acción del atributo.
+
+use super::Action::{Dummy as D, Empty as Z, Remove as X, ReplaceUid as U};
+use super::{Action, Retain};
+use dicom::core::Tag;
+
+const fn t(group: u16, element: u16) -> Tag {
+    Tag(group, element)
+}
+
+#[rustfmt::skip]
+pub(super) const BASIC_PROFILE: &[(Tag, Action, Retain)] = &[
+    // Identificación de la instancia y del estudio
+    (t(0x0008, 0x0012), X, Retain::Dates),           // Instance Creation Date
+    (t(0x0008, 0x0013), X, Retain::Dates),           // Instance Creation Time
+    (t(0x0008, 0x0014), U, Retain::Nothing),         // Instance Creator UID
+    (t(0x0008, 0x0015), X, Retain::Dates),           // Instance Coercion DateTime
+    (t(0x0008, 0x0017), U, Retain::Nothing),         // Acquisition UID
+    (t(0x0008, 0x0018), U, Retain::Nothing),         // SOP Instance UID
+    (t(0x0008, 0x0019), U, Retain::Nothing),         // Pyramid UID
+    (t(0x0008, 0x0020), Z, Retain::Dates),           // Study Date
+    (t(0x0008, 0x0021), X, Retain::Dates),           // Series Date
+    (t(0x0008, 0x0022), X, Retain::Dates),           // Acquisition Date
+    (t(0x0008, 0x0023), Z, Retain::Dates),           // Content Date
+    (t(0x0008, 0x0024), X, Retain::Dates),           // Overlay Date
+    (t(0x0008, 0x0025), X, Retain::Dates),           // Curve Date
+    (t(0x0008, 0x002A), X, Retain::Dates),           // Acquisition DateTime
+    (t(0x0008, 0x0030), Z, Retain::Dates),           // Study Time
+    (t(0x0008, 0x0031), X, Retain::Dates),           // Series Time
+    (t(0x0008, 0x0032), X, Retain::Dates),           // Acquisition Time
+    (t(0x0008, 0x0033), Z, Retain::Dates),           // Content Time
+    (t(0x0008, 0x0034), X, Retain::Dates),           // Overlay Time
+    (t(0x0008, 0x0035), X, Retain::Dates),           // Curve Time
+    (t(0x0008, 0x0050), Z, Retain::Nothing),         // Accession Number
+    (t(0x0008, 0x0054), X, Retain::Nothing),         // Retrieve AE Title
+    (t(0x0008, 0x0055), X, Retain::Device),          // Station AE Title
+    (t(0x0008, 0x0058), U, Retain::Nothing),         // Failed SOP Instance UID List
+    (t(0x0008, 0x0080), X, Retain::Institution),     // Institution Name
+    (t(0x0008, 0x0081), X, Retain::Institution),     // Institution Address
+    (t(0x0008, 0x0082), X, Retain::Institution),     // Institution Code Sequence
+    (t(0x0008, 0x0090), Z, Retain::Nothing),         // Referring Physician's Name
+    (t(0x0008, 0x0092), X, Retain::Nothing),         // Referring Physician's Address
+    (t(0x0008, 0x0094), X, Retain::Nothing),         // Referring Physician's Telephone Numbers
+    (t(0x0008, 0x0096), X, Retain::Nothing),         // Referring Physician Identification Sequence
+    (t(0x0008, 0x009C), Z, Retain::Nothing),         // Consulting Physician's Name
+    (t(0x0008, 0x009D), X, Retain::Nothing),         // Consulting Physician Identification Sequence
+    (t(0x0008, 0x010D), U, Retain::Nothing),         // Context Group Extension Creator UID
+    (t(0x0008, 0x0201), X, Retain::Dates),           // Timezone Offset From UTC
+    (t(0x0008, 0x1000), X, Retain::Nothing),         // Network ID (retirado)
+    (t(0x0008, 0x1010), X, Retain::Device),          // Station Name
+    (t(0x0008, 0x1030), X, Retain::Descriptors),     // Study Description
+    (t(0x0008, 0x1032), X, Retain::Descriptors),     // Procedure Code Sequence
+    (t(0x0008, 0x103E), X, Retain::Descriptors),     // Series Description
+    (t(0x0008, 0x1040), X, Retain::Institution),     // Institutional Department Name
+    (t(0x0008, 0x1041), X, Retain::Institution),     // Institutional Department Type Code Sequence
+    (t(0x0008, 0x1048), X, Retain::Nothing),         // Physician(s) of Record
+    (t(0x0008, 0x1049), X, Retain::Nothing),         // Physician(s) of Record Identification Sequence
+    (t(0x0008, 0x1050), X, Retain::Nothing),         // Performing Physician's Name
+    (t(0x0008, 0x1052), X, Retain::Nothing),         // Performing Physician Identification Sequence
+    (t(0x0008, 0x1060), X, Retain::Nothing),         // Name of Physician(s) Reading Study
+    (t(0x0008, 0x1062), X, Retain::Nothing),         // Physician(s) Reading Study Identification Sequence
+    (t(0x0008, 0x1070), X, Retain::Nothing),         // Operators' Name
+    (t(0x0008, 0x1072), X, Retain::Nothing),         // Operator Identification Sequence
+    (t(0x0008, 0x1080), X, Retain::Descriptors),     // Admitting Diagnoses Description
+    (t(0x0008, 0x1084), X, Retain::Descriptors),     // Admitting Diagnoses Code Sequence
+    (t(0x0008, 0x1110), X, Retain::Nothing),         // Referenced Study Sequence
+    (t(0x0008, 0x1111), X, Retain::Nothing),         // Referenced Performed Procedure Step Sequence
+    (t(0x0008, 0x1120), X, Retain::Nothing),         // Referenced Patient Sequence
+    (t(0x0008, 0x1140), U, Retain::Nothing),         // Referenced Image Sequence
+    (t(0x0008, 0x1155), U, Retain::Nothing),         // Referenced SOP Instance UID
+    (t(0x0008, 0x1195), U, Retain::Nothing),         // Transaction UID
+    (t(0x0008, 0x2111), X, Retain::Descriptors),     // Derivation Description
+    (t(0x0008, 0x2112), U, Retain::Nothing),         // Source Image Sequence
+    (t(0x0008, 0x3010), U, Retain::Nothing),         // Irradiation Event UID
+    (t(0x0008, 0x4000), X, Retain::Descriptors),     // Identifying Comments
+    // Paciente
+    (t(0x0010, 0x0010), Z, Retain::Nothing),         // Patient's Name
+    (t(0x0010, 0x0020), Z, Retain::Nothing),         // Patient ID
+    (t(0x0010, 0x0021), X, Retain::Nothing),         // Issuer of Patient ID
+    (t(0x0010, 0x0030), Z, Retain::Nothing),         // Patient's Birth Date
+    (t(0x0010, 0x0032), X, Retain::Nothing),         // Patient's Birth Time
+    (t(0x0010, 0x0033), X, Retain::Nothing),         // Patient's Birth Date in Alternative Calendar
+    (t(0x0010, 0x0034), X, Retain::Nothing),         // Patient's Death Date in Alternative Calendar
+    (t(0x0010, 0x0035), X, Retain::Nothing),         // Patient's Alternative Calendar
+    (t(0x0010, 0x0040), Z, Retain::PatientCharacteristics), // Patient's Sex
+    (t(0x0010, 0x0050), X, Retain::Nothing),         // Patient's Insurance Plan Code Sequence
+    (t(0x0010, 0x0101), X, Retain::PatientCharacteristics), // Patient's Primary Language Code Sequence
+    (t(0x0010, 0x0102), X, Retain::PatientCharacteristics), // Patient's Primary Language Modifier Code Sequence
+    (t(0x0010, 0x1000), X, Retain::Nothing),         // Other Patient IDs (retirado)
+    (t(0x0010, 0x1001), X, Retain::Nothing),         // Other Patient Names
+    (t(0x0010, 0x1002), X, Retain::Nothing),         // Other Patient IDs Sequence
+    (t(0x0010, 0x1005), X, Retain::Nothing),         // Patient's Birth Name
+    (t(0x0010, 0x1010), X, Retain::PatientCharacteristics), // Patient's Age
+    (t(0x0010, 0x1020), X, Retain::PatientCharacteristics), // Patient's Size
+    (t(0x0010, 0x1021), X, Retain::PatientCharacteristics), // Patient's Size Code Sequence
+    (t(0x0010, 0x1030), X, Retain::PatientCharacteristics), // Patient's Weight
+    (t(0x0010, 0x1040), X, Retain::Nothing),         // Patient's Address
+    (t(0x0010, 0x1050), X, Retain::Nothing),         // Insurance Plan Identification (retirado)
+    (t(0x0010, 0x1060), X, Retain::Nothing),         // Patient's Mother's Birth Name
+    (t(0x0010, 0x1080), X, Retain::Nothing),         // Military Rank
+    (t(0x0010, 0x1081), X, Retain::Nothing),         // Branch of Service
+    (t(0x0010, 0x1090), X, Retain::Nothing),         // Medical Record Locator (retirado)
+    (t(0x0010, 0x1100), X, Retain::Nothing),         // Referenced Patient Photo Sequence
+    (t(0x0010, 0x2000), X, Retain::Nothing),         // Medical Alerts
+    (t(0x0010, 0x2110), X, Retain::Nothing),         // Allergies
+    (t(0x0010, 0x2150), X, Retain::Nothing),         // Country of Residence
+    (t(0x0010, 0x2152), X, Retain::Nothing),         // Region of Residence
+    (t(0x0010, 0x2154), X, Retain::Nothing),         // Patient's Telephone Numbers
+    (t(0x0010, 0x2155), X, Retain::Nothing),         // Patient's Telecom Information
+    (t(0x0010, 0x2160), X, Retain::PatientCharacteristics), // Ethnic Group
+    (t(0x0010, 0x2180), X, Retain::Descriptors),     // Occupation
+    (t(0x0010, 0x21A0), X, Retain::PatientCharacteristics), // Smoking Status
+    (t(0x0010, 0x21B0), X, Retain::Descriptors),     // Additional Patient History
+    (t(0x0010, 0x21C0), X, Retain::PatientCharacteristics), // Pregnancy Status
+    (t(0x0010, 0x21D0), X, Retain::Dates),           // Last Menstrual Date
+    (t(0x0010, 0x21F0), X, Retain::Nothing),         // Patient's Religious Preference
+    (t(0x0010, 0x2203), X, Retain::PatientCharacteristics), // Patient's Sex Neutered
+    (t(0x0010, 0x2297), X, Retain::Nothing),         // Responsible Person
+    (t(0x0010, 0x2299), X, Retain::Nothing),         // Responsible Organization
+    (t(0x0010, 0x4000), X, Retain::Descriptors),     // Patient Comments
+    // Ensayos clínicos
+    (t(0x0012, 0x0010), D, Retain::Nothing),         // Clinical Trial Sponsor Name
+    (t(0x0012, 0x0020), D, Retain::Nothing),         // Clinical Trial Protocol ID
+    (t(0x0012, 0x0021), Z, Retain::Nothing),         // Clinical Trial Protocol Name
+    (t(0x0012, 0x0030), Z, Retain::Nothing),         // Clinical Trial Site ID
+    (t(0x0012, 0x0031), Z, Retain::Nothing),         // Clinical Trial Site Name
+    (t(0x0012, 0x0040), D, Retain::Nothing),         // Clinical Trial Subject ID
+    (t(0x0012, 0x0042), D, Retain::Nothing),         // Clinical Trial Subject Reading ID
+    (t(0x0012, 0x0050), Z, Retain::Nothing),         // Clinical Trial Time Point ID
+    (t(0x0012, 0x0051), X, Retain::Descriptors),     // Clinical Trial Time Point Description
+    (t(0x0012, 0x0060), Z, Retain::Nothing),         // Clinical Trial Coordinating Center Name
+    (t(0x0012, 0x0071), X, Retain::Nothing),         // Clinical Trial Series ID
+    (t(0x0012, 0x0072), X, Retain::Descriptors),     // Clinical Trial Series Description
+    (t(0x0012, 0x0081), D, Retain::Nothing),         // Clinical Trial Protocol Ethics Committee Name
+    (t(0x0012, 0x0082), X, Retain::Nothing),         // Clinical Trial Protocol Ethics Committee Approval Number
+    // Fotografía (EXIF); los tags GPS se eliminan por rango
+    (t(0x0016, 0x002B), X, Retain::Nothing),         // Maker Note
+    (t(0x0016, 0x004B), X, Retain::Device),          // Device Setting Description
+    (t(0x0016, 0x004D), X, Retain::Nothing),         // Camera Owner Name
+    (t(0x0016, 0x004E), X, Retain::Device),          // Lens Specification
+    (t(0x0016, 0x004F), X, Retain::Device),          // Lens Make
+    (t(0x0016, 0x0050), X, Retain::Device),          // Lens Model
+    (t(0x0016, 0x0051), X, Retain::Device),          // Lens Serial Number
+    // Adquisición y equipo
+    (t(0x0018, 0x0010), Z, Retain::Descriptors),     // Contrast/Bolus Agent
+    (t(0x0018, 0x1000), X, Retain::Device),          // Device Serial Number
+    (t(0x0018, 0x1002), U, Retain::Device),          // Device UID
+    (t(0x0018, 0x1004), X, Retain::Device),          // Plate ID
+    (t(0x0018, 0x1005), X, Retain::Device),          // Generator ID
+    (t(0x0018, 0x1007), X, Retain::Device),          // Cassette ID
+    (t(0x0018, 0x1008), X, Retain::Device),          // Gantry ID
+    (t(0x0018, 0x1009), X, Retain::Device),          // Unique Device Identifier
+    (t(0x0018, 0x100A), X, Retain::Device),          // UDI Sequence
+    (t(0x0018, 0x100B), U, Retain::Device),          // Manufacturer's Device Class UID
+    (t(0x0018, 0x1012), X, Retain::Dates),           // Date of Secondary Capture
+    (t(0x0018, 0x1014), X, Retain::Dates),           // Time of Secondary Capture
+    (t(0x0018, 0x1020), X, Retain::Device),          // Software Versions
+    (t(0x0018, 0x1030), X, Retain::Descriptors),     // Protocol Name
+    (t(0x0018, 0x1200), X, Retain::Dates),           // Date of Last Calibration
+    (t(0x0018, 0x1201), X, Retain::Dates),           // Time of Last Calibration
+    (t(0x0018, 0x1400), X, Retain::Descriptors),     // Acquisition Device Processing Description
+    (t(0x0018, 0x4000), X, Retain::Descriptors),     // Acquisition Comments (retirado)
+    (t(0x0018, 0x700A), X, Retain::Device),          // Detector ID
+    (t(0x0018, 0x700C), X, Retain::Dates),           // Date of Last Detector Calibration
+    (t(0x0018, 0x700E), X, Retain::Dates),           // Time of Last Detector Calibration
+    (t(0x0018, 0x9074), X, Retain::Dates),           // Frame Acquisition DateTime
+    (t(0x0018, 0x9151), X, Retain::Dates),           // Frame Reference DateTime
+    (t(0x0018, 0x9185), X, Retain::Descriptors),     // Respiratory Motion Compensation Technique Description
+    (t(0x0018, 0x9367), X, Retain::Device),          // X-Ray Source ID
+    (t(0x0018, 0x9371), X, Retain::Device),          // X-Ray Detector ID
+    (t(0x0018, 0x9373), X, Retain::Device),          // X-Ray Detector Label
+    (t(0x0018, 0x937B), X, Retain::Descriptors),     // Multi-energy Acquisition Description
+    (t(0x0018, 0x937F), X, Retain::Descriptors),     // Decomposition Description
+    (t(0x0018, 0x9424), X, Retain::Descriptors),     // Acquisition Protocol Description
+    (t(0x0018, 0x9516), X, Retain::Dates),           // Start Acquisition DateTime
+    (t(0x0018, 0x9517), X, Retain::Dates),           // End Acquisition DateTime
+    (t(0x0018, 0x9623), X, Retain::Dates),           // Functional Sync Pulse
+    (t(0x0018, 0x9701), X, Retain::Dates),           // Decay Correction DateTime
+    (t(0x0018, 0x9804), X, Retain::Dates),           // Exclusion Start DateTime
+    (t(0x0018, 0xA002), X, Retain::Dates),           // Contribution DateTime
+    (t(0x0018, 0xA003), X, Retain::Descriptors),     // Contribution Description
+    // Relaciones entre instancias
+    (t(0x0020, 0x000D), U, Retain::Nothing),         // Study Instance UID
+    (t(0x0020, 0x000E), U, Retain::Nothing),         // Series Instance UID
+    (t(0x0020, 0x0010), Z, Retain::Nothing),         // Study ID
+    (t(0x0020, 0x0052), U, Retain::Nothing),         // Frame of Reference UID
+    (t(0x0020, 0x0200), U, Retain::Nothing),         // Synchronization Frame of Reference UID
+    (t(0x0020, 0x3401), X, Retain::Device),          // Modifying Device ID (retirado)
+    (t(0x0020, 0x3404), X, Retain::Device),          // Modifying Device Manufacturer (retirado)
+    (t(0x0020, 0x3406), X, Retain::Descriptors),     // Modified Image Description (retirado)
+    (t(0x0020, 0x4000), X, Retain::Descriptors),     // Image Comments
+    (t(0x0020, 0x9158), X, Retain::Descriptors),     // Frame Comments
+    (t(0x0020, 0x9161), U, Retain::Nothing),         // Concatenation UID
+    (t(0x0020, 0x9164), U, Retain::Nothing),         // Dimension Organization UID
+    // Imagen
+    (t(0x0028, 0x1199), U, Retain::Nothing),         // Palette Color Lookup Table UID
+    (t(0x0028, 0x1214), U, Retain::Nothing),         // Large Palette Color Lookup Table UID (retirado)
+    (t(0x0028, 0x4000), X, Retain::Descriptors),     // Image Presentation Comments (retirado)
+    // Estudio y solicitud
+    (t(0x0032, 0x0012), X, Retain::Nothing),         // Study ID Issuer (retirado)
+    (t(0x0032, 0x0032), X, Retain::Dates),           // Study Verified Date (retirado)
+    (t(0x0032, 0x0033), X, Retain::Dates),           // Study Verified Time (retirado)
+    (t(0x0032, 0x0034), X, Retain::Dates),           // Study Read Date (retirado)
+    (t(0x0032, 0x0035), X, Retain::Dates),           // Study Read Time (retirado)
+    (t(0x0032, 0x1000), X, Retain::Dates),           // Scheduled Study Start Date (retirado)
+    (t(0x0032, 0x1001), X, Retain::Dates),           // Scheduled Study Start Time (retirado)
+    (t(0x0032, 0x1010), X, Retain::Dates),           // Scheduled Study Stop Date (retirado)
+    (t(0x0032, 0x1011), X, Retain::Dates),           // Scheduled Study Stop Time (retirado)
+    (t(0x0032, 0x1020), X, Retain::Nothing),         // Scheduled Study Location (retirado)
+    (t(0x0032, 0x1021), X, Retain::Nothing),         // Scheduled Study Location AE Title (retirado)
+    (t(0x0032, 0x1030), X, Retain::Descriptors),     // Reason for Study (retirado)
+    (t(0x0032, 0x1032), X, Retain::Nothing),         // Requesting Physician
+    (t(0x0032, 0x1033), X, Retain::Nothing),         // Requesting Service
+    (t(0x0032, 0x1034), X, Retain::Nothing),         // Requesting Service Code Sequence
+    (t(0x0032, 0x1040), X, Retain::Dates),           // Study Arrival Date (retirado)
+    (t(0x0032, 0x1041), X, Retain::Dates),           // Study Arrival Time (retirado)
+    (t(0x0032, 0x1050), X, Retain::Dates),           // Study Completion Date (retirado)
+    (t(0x0032, 0x1051), X, Retain::Dates),           // Study Completion Time (retirado)
+    (t(0x0032, 0x1060), X, Retain::Descriptors),     // Requested Procedure Description
+    (t(0x0032, 0x1066), X, Retain::Descriptors),     // Reason for Visit
+    (t(0x0032, 0x1067), X, Retain::Descriptors),     // Reason for Visit Code Sequence
+    (t(0x0032, 0x1070), X, Retain::Descriptors),     // Requested Contrast Agent
+    (t(0x0032, 0x4000), X, Retain::Descriptors),     // Study Comments (retirado)
+    // Visita
+    (t(0x0038, 0x0004), X, Retain::Nothing),         // Referenced Patient Alias Sequence (retirado)
+    (t(0x0038, 0x0010), X, Retain::Nothing),         // Admission ID
+    (t(0x0038, 0x0011), X, Retain::Nothing),         // Issuer of Admission ID (retirado)
+    (t(0x0038, 0x0014), X, Retain::Nothing),         // Issuer of Admission ID Sequence
+    (t(0x0038, 0x001A), X, Retain::Dates),           // Scheduled Admission Date (retirado)
+    (t(0x0038, 0x001B), X, Retain::Dates),           // Scheduled Admission Time (retirado)
+    (t(0x0038, 0x001C), X, Retain::Dates),           // Scheduled Discharge Date (retirado)
+    (t(0x0038, 0x001D), X, Retain::Dates),           // Scheduled Discharge Time (retirado)
+    (t(0x0038, 0x001E), X, Retain::Nothing),         // Scheduled Patient Institution Residence (retirado)
+    (t(0x0038, 0x0020), X, Retain::Dates),           // Admitting Date
+    (t(0x0038, 0x0021), X, Retain::Dates),           // Admitting Time
+    (t(0x0038, 0x0030), X, Retain::Dates),           // Discharge Date (retirado)
+    (t(0x0038, 0x0032), X, Retain::Dates),           // Discharge Time (retirado)
+    (t(0x0038, 0x0040), X, Retain::Descriptors),     // Discharge Diagnosis Description (retirado)
+    (t(0x0038, 0x0050), X, Retain::PatientCharacteristics), // Special Needs
+    (t(0x0038, 0x0060), X, Retain::Nothing),         // Service Episode ID
+    (t(0x0038, 0x0061), X, Retain::Nothing),         // Issuer of Service Episode ID (retirado)
+    (t(0x0038, 0x0062), X, Retain::Descriptors),     // Service Episode Description
+    (t(0x0038, 0x0064), X, Retain::Nothing),         // Issuer of Service Episode ID Sequence
+    (t(0x0038, 0x0300), X, Retain::Nothing),         // Current Patient Location
+    (t(0x0038, 0x0400), X, Retain::Nothing),         // Patient's Institution Residence
+    (t(0x0038, 0x0500), X, Retain::PatientCharacteristics), // Patient State
+    (t(0x0038, 0x4000), X, Retain::Descriptors),     // Visit Comments
+    (t(0x003A, 0x0310), U, Retain::Nothing),         // Multiplex Group UID
+    // Procedimiento, worklist y structured reporting
+    (t(0x0040, 0x0001), X, Retain::Device),          // Scheduled Station AE Title
+    (t(0x0040, 0x0002), X, Retain::Dates),           // Scheduled Procedure Step Start Date
+    (t(0x0040, 0x0003), X, Retain::Dates),           // Scheduled Procedure Step Start Time
+    (t(0x0040, 0x0004), X, Retain::Dates),           // Scheduled Procedure Step End Date
+    (t(0x0040, 0x0005), X, Retain::Dates),           // Scheduled Procedure Step End Time
+    (t(0x0040, 0x0006), X, Retain::Nothing),         // Scheduled Performing Physician's Name
+    (t(0x0040, 0x0007), X, Retain::Descriptors),     // Scheduled Procedure Step Description
+    (t(0x0040, 0x000B), X, Retain::Nothing),         // Scheduled Performing Physician Identification Sequence
+    (t(0x0040, 0x0010), X, Retain::Device),          // Scheduled Station Name
+    (t(0x0040, 0x0011), X, Retain::Device),          // Scheduled Procedure Step Location
+    (t(0x0040, 0x0012), X, Retain::PatientCharacteristics), // Pre-Medication
+    (t(0x0040, 0x0241), X, Retain::Device),          // Performed Station AE Title
+    (t(0x0040, 0x0242), X, Retain::Device),          // Performed Station Name
+    (t(0x0040, 0x0243), X, Retain::Device),          // Performed Location
+    (t(0x0040, 0x0244), X, Retain::Dates),           // Performed Procedure Step Start Date
+    (t(0x0040, 0x0245), X, Retain::Dates),           // Performed Procedure Step Start Time
+    (t(0x0040, 0x0250), X, Retain::Dates),           // Performed Procedure Step End Date
+    (t(0x0040, 0x0251), X, Retain::Dates),           // Performed Procedure Step End Time
+    (t(0x0040, 0x0253), X, Retain::Nothing),         // Performed Procedure Step ID
+    (t(0x0040, 0x0254), X, Retain::Descriptors),     // Performed Procedure Step Description
+    (t(0x0040, 0x0275), X, Retain::Nothing),         // Request Attributes Sequence
+    (t(0x0040, 0x0280), X, Retain::Descriptors),     // Comments on the Performed Procedure Step
+    (t(0x0040, 0x0555), X, Retain::Nothing),         // Acquisition Context Sequence
+    (t(0x0040, 0x1001), X, Retain::Nothing),         // Requested Procedure ID
+    (t(0x0040, 0x1004), X, Retain::Nothing),         // Patient Transport Arrangements
+    (t(0x0040, 0x1005), X, Retain::Nothing),         // Requested Procedure Location
+    (t(0x0040, 0x1010), X, Retain::Nothing),         // Names of Intended Recipients of Results
+    (t(0x0040, 0x1011), X, Retain::Nothing),         // Intended Recipients of Results Identification Sequence
+    (t(0x0040, 0x1101), D, Retain::Nothing),         // Person Identification Code Sequence
+    (t(0x0040, 0x1102), X, Retain::Nothing),         // Person's Address
+    (t(0x0040, 0x1103), X, Retain::Nothing),         // Person's Telephone Numbers
+    (t(0x0040, 0x1104), X, Retain::Nothing),         // Person's Telecom Information
+    (t(0x0040, 0x1400), X, Retain::Descriptors),     // Requested Procedure Comments
+    (t(0x0040, 0x2001), X, Retain::Descriptors),     // Reason for the Imaging Service Request (retirado)
+    (t(0x0040, 0x2004), X, Retain::Dates),           // Issue Date of Imaging Service Request
+    (t(0x0040, 0x2005), X, Retain::Dates),           // Issue Time of Imaging Service Request
+    (t(0x0040, 0x2008), X, Retain::Nothing),         // Order Entered By
+    (t(0x0040, 0x2009), X, Retain::Nothing),         // Order Enterer's Location
+    (t(0x0040, 0x2010), X, Retain::Nothing),         // Order Callback Phone Number
+    (t(0x0040, 0x2011), X, Retain::Nothing),         // Order Callback Telecom Information
+    (t(0x0040, 0x2016), Z, Retain::Nothing),         // Placer Order Number / Imaging Service Request
+    (t(0x0040, 0x2017), Z, Retain::Nothing),         // Filler Order Number / Imaging Service Request
+    (t(0x0040, 0x2400), X, Retain::Descriptors),     // Imaging Service Request Comments
+    (t(0x0040, 0x3001), X, Retain::Nothing),         // Confidentiality Constraint on Patient Data Description
+    (t(0x0040, 0x4005), X, Retain::Dates),           // Scheduled Procedure Step Start DateTime
+    (t(0x0040, 0x4008), X, Retain::Dates),           // Scheduled Procedure Step Expiration DateTime
+    (t(0x0040, 0x4010), X, Retain::Dates),           // Scheduled Procedure Step Modification DateTime
+    (t(0x0040, 0x4011), X, Retain::Dates),           // Expected Completion DateTime
+    (t(0x0040, 0x4023), U, Retain::Nothing),         // Referenced General Purpose Scheduled Procedure Step Transaction UID
+    (t(0x0040, 0x4025), X, Retain::Device),          // Scheduled Station Name Code Sequence
+    (t(0x0040, 0x4027), X, Retain::Device),          // Scheduled Station Geographic Location Code Sequence
+    (t(0x0040, 0x4028), X, Retain::Device),          // Performed Station Name Code Sequence
+    (t(0x0040, 0x4030), X, Retain::Device),          // Performed Station Geographic Location Code Sequence
+    (t(0x0040, 0x4034), X, Retain::Nothing),         // Scheduled Human Performers Sequence
+    (t(0x0040, 0x4035), X, Retain::Nothing),         // Actual Human Performers Sequence
+    (t(0x0040, 0x4036), X, Retain::Nothing),         // Human Performer's Organization
+    (t(0x0040, 0x4037), X, Retain::Nothing),         // Human Performer's Name
+    (t(0x0040, 0x4050), X, Retain::Dates),           // Performed Procedure Step Start DateTime
+    (t(0x0040, 0x4051), X, Retain::Dates),           // Performed Procedure Step End DateTime
+    (t(0x0040, 0x4052), X, Retain::Dates),           // Procedure Step Cancellation DateTime
+    (t(0x0040, 0xA027), X, Retain::Institution),     // Verifying Organization
+    (t(0x0040, 0xA030), D, Retain::Dates),           // Verification DateTime
+    (t(0x0040, 0xA032), X, Retain::Dates),           // Observation DateTime
+    (t(0x0040, 0xA033), X, Retain::Dates),           // Observation Start DateTime
+    (t(0x0040, 0xA073), D, Retain::Nothing),         // Verifying Observer Sequence
+    (t(0x0040, 0xA075), D, Retain::Nothing),         // Verifying Observer Name
+    (t(0x0040, 0xA078), X, Retain::Nothing),         // Author Observer Sequence
+    (t(0x0040, 0xA07A), X, Retain::Nothing),         // Participant Sequence
+    (t(0x0040, 0xA07C), X, Retain::Nothing),         // Custodial Organization Sequence
+    (t(0x0040, 0xA082), X, Retain::Dates),           // Participation DateTime
+    (t(0x0040, 0xA088), Z, Retain::Nothing),         // Verifying Observer Identification Code Sequence
+    (t(0x0040, 0xA120), D, Retain::Dates),           // DateTime
+    (t(0x0040, 0xA121), D, Retain::Dates),           // Date
+    (t(0x0040, 0xA122), D, Retain::Dates),           // Time
+    (t(0x0040, 0xA123), D, Retain::Nothing),         // Person Name
+    (t(0x0040, 0xA124), U, Retain::Nothing),         // UID
+    (t(0x0040, 0xA13A), D, Retain::Dates),           // Referenced DateTime
+    (t(0x0040, 0xA171), U, Retain::Nothing),         // Observation UID
+    (t(0x0040, 0xA192), X, Retain::Dates),           // Observation Date (Trial) (retirado)
+    (t(0x0040, 0xA193), X, Retain::Dates),           // Observation Time (Trial) (retirado)
+    (t(0x0040, 0xA307), X, Retain::Nothing),         // Current Observer (Trial) (retirado)
+    (t(0x0040, 0xA352), X, Retain::Nothing),         // Verbal Source (Trial) (retirado)
+    (t(0x0040, 0xA353), X, Retain::Nothing),         // Address (Trial) (retirado)
+    (t(0x0040, 0xA354), X, Retain::Nothing),         // Telephone Number (Trial) (retirado)
+    (t(0x0040, 0xA358), X, Retain::Nothing),         // Verbal Source Identifier Code Sequence (Trial) (retirado)
+    (t(0x0040, 0xA402), U, Retain::Nothing),         // Observation Subject UID (Trial) (retirado)
+    (t(0x0040, 0xA730), X, Retain::Nothing),         // Content Sequence
+    (t(0x0040, 0xDB0C), U, Retain::Nothing),         // Template Extension Organization UID (retirado)
+    (t(0x0040, 0xDB0D), U, Retain::Nothing),         // Template Extension Creator UID (retirado)
+    // Segmentación, registro y presentación
+    (t(0x0062, 0x0021), U, Retain::Nothing),         // Tracking UID
+    (t(0x0064, 0x0003), U, Retain::Nothing),         // Source Frame of Reference UID
+    (t(0x0070, 0x0001), X, Retain::Nothing),         // Graphic Annotation Sequence
+    (t(0x0070, 0x0084), Z, Retain::Nothing),         // Content Creator's Name
+    (t(0x0070, 0x0086), X, Retain::Nothing),         // Content Creator's Identification Code Sequence
+    (t(0x0070, 0x031A), U, Retain::Nothing),         // Fiducial UID
+    (t(0x0070, 0x1101), U, Retain::Nothing),         // Presentation Display Collection UID
+    (t(0x0070, 0x1102), U, Retain::Nothing),         // Presentation Sequence Collection UID
+    // Medios y topics (retirados)
+    (t(0x0088, 0x0140), U, Retain::Nothing),         // Storage Media File-set UID
+    (t(0x0088, 0x0200), X, Retain::Nothing),         // Icon Image Sequence
+    (t(0x0088, 0x0904), X, Retain::Descriptors),     // Topic Title (retirado)
+    (t(0x0088, 0x0906), X, Retain::Descriptors),     // Topic Subject (retirado)
+    (t(0x0088, 0x0910), X, Retain::Nothing),         // Topic Author (retirado)
+    (t(0x0088, 0x0912), X, Retain::Descriptors),     // Topic Keywords (retirado)
+    // Autorización, firmas y modificaciones
+    (t(0x0100, 0x0420), X, Retain::Dates),           // SOP Authorization DateTime
+    (t(0x0100, 0x0424), X, Retain::Nothing),         // SOP Authorization Comment
+    (t(0x0100, 0x0426), X, Retain::Nothing),         // Authorization Equipment Certification Number
+    (t(0x0400, 0x0100), X, Retain::Nothing),         // Digital Signature UID
+    (t(0x0400, 0x0105), X, Retain::Dates),           // Digital Signature DateTime
+    (t(0x0400, 0x0115), X, Retain::Nothing),         // Certificate of Signer
+    (t(0x0400, 0x0310), X, Retain::Nothing),         // Certified Timestamp
+    (t(0x0400, 0x0402), X, Retain::Nothing),         // Referenced Digital Signature Sequence
+    (t(0x0400, 0x0403), X, Retain::Nothing),         // Referenced SOP Instance MAC Sequence
+    (t(0x0400, 0x0404), X, Retain::Nothing),         // MAC
+    (t(0x0400, 0x0500), X, Retain::Nothing),         // Encrypted Attributes Sequence
+    (t(0x0400, 0x0550), X, Retain::Nothing),         // Modified Attributes Sequence
+    (t(0x0400, 0x0561), X, Retain::Nothing),         // Original Attributes Sequence
+    (t(0x0400, 0x0562), X, Retain::Dates),           // Attribute Modification DateTime
+    (t(0x0400, 0x0563), X, Retain::Nothing),         // Modifying System
+    (t(0x0400, 0x0564), X, Retain::Nothing),         // Source of Previous Values
+    (t(0x0400, 0x0565), X, Retain::Nothing),         // Reason for the Attribute Modification
+    // Radioterapia
+    (t(0x3006, 0x0002), D, Retain::Nothing),         // Structure Set Label
+    (t(0x3006, 0x0004), X, Retain::Descriptors),     // Structure Set Name
+    (t(0x3006, 0x0006), X, Retain::Descriptors),     // Structure Set Description
+    (t(0x3006, 0x0008), Z, Retain::Dates),           // Structure Set Date
+    (t(0x3006, 0x0009), Z, Retain::Dates),           // Structure Set Time
+    (t(0x3006, 0x0024), U, Retain::Nothing),         // Referenced Frame of Reference UID
+    (t(0x3006, 0x0026), Z, Retain::Descriptors),     // ROI Name
+    (t(0x3006, 0x0028), X, Retain::Descriptors),     // ROI Description
+    (t(0x3006, 0x0038), X, Retain::Descriptors),     // ROI Generation Description
+    (t(0x3006, 0x0085), X, Retain::Descriptors),     // ROI Observation Label
+    (t(0x3006, 0x00A6), Z, Retain::Nothing),         // ROI Interpreter
+    (t(0x3006, 0x00C2), U, Retain::Nothing),         // Related Frame of Reference UID
+    (t(0x300A, 0x0002), D, Retain::Nothing),         // RT Plan Label
+    (t(0x300A, 0x0003), X, Retain::Descriptors),     // RT Plan Name
+    (t(0x300A, 0x0004), X, Retain::Descriptors),     // RT Plan Description
+    (t(0x300A, 0x0006), X, Retain::Dates),           // RT Plan Date
+    (t(0x300A, 0x0007), X, Retain::Dates),           // RT Plan Time
+    (t(0x300A, 0x000E), X, Retain::Descriptors),     // Prescription Description
+    (t(0x300A, 0x0013), U, Retain::Nothing),         // Dose Reference UID
+    (t(0x300A, 0x0016), X, Retain::Descriptors),     // Dose Reference Description
+    (t(0x300A, 0x0072), X, Retain::Descriptors),     // Fraction Group Description
+    (t(0x300A, 0x00B2), X, Retain::Device),          // Treatment Machine Name
+    (t(0x300A, 0x0196), X, Retain::Descriptors),     // Fixation Device Description
+    (t(0x300A, 0x01A6), X, Retain::Descriptors),     // Shielding Device Description
+    (t(0x300A, 0x0650), U, Retain::Nothing),         // Patient Setup UID
+    (t(0x300A, 0x0700), U, Retain::Nothing),         // Treatment Session UID
+    (t(0x300C, 0x0113), X, Retain::Descriptors),     // Reason for Omission Description
+    (t(0x300E, 0x0008), X, Retain::Nothing),         // Reviewer Name
+    // Texto libre y resultados (retirados)
+    (t(0x4000, 0x0010), X, Retain::Nothing),         // Arbitrary
+    (t(0x4000, 0x4000), X, Retain::Descriptors),     // Text Comments
+    (t(0x4008, 0x0042), X, Retain::Nothing),         // Results ID Issuer
+    (t(0x4008, 0x0100), X, Retain::Dates),           // Interpretation Recorded Date
+    (t(0x4008, 0x0101), X, Retain::Dates),           // Interpretation Recorded Time
+    (t(0x4008, 0x0102), X, Retain::Nothing),         // Interpretation Recorder
+    (t(0x4008, 0x0108), X, Retain::Dates),           // Interpretation Transcription Date
+    (t(0x4008, 0x0109), X, Retain::Dates),           // Interpretation Transcription Time
+    (t(0x4008, 0x010A), X, Retain::Nothing),         // Interpretation Transcriber
+    (t(0x4008, 0x010B), X, Retain::Nothing),         // Interpretation Text
+    (t(0x4008, 0x010C), X, Retain::Nothing),         // Interpretation Author
+    (t(0x4008, 0x0111), X, Retain::Nothing),         // Interpretation Approver Sequence
+    (t(0x4008, 0x0112), X, Retain::Dates),           // Interpretation Approval Date
+    (t(0x4008, 0x0113), X, Retain::Dates),           // Interpretation Approval Time
+    (t(0x4008, 0x0114), X, Retain::Nothing),         // Physician Approving Interpretation
+    (t(0x4008, 0x0115), X, Retain::Descriptors),     // Interpretation Diagnosis Description
+    (t(0x4008, 0x0118), X, Retain::Nothing),         // Results Distribution List Sequence
+    (t(0x4008, 0x0119), X, Retain::Nothing),         // Distribution Name
+    (t(0x4008, 0x011A), X, Retain::Nothing),         // Distribution Address
+    (t(0x4008, 0x0202), X, Retain::Nothing),         // Interpretation ID Issuer
+    (t(0x4008, 0x0300), X, Retain::Descriptors),     // Impressions
+    (t(0x4008, 0x4000), X, Retain::Descriptors),     // Results Comments
+    (t(0x4FFE, 0x0001), X, Retain::Nothing),         // MAC Parameters Sequence
+    (t(0xFFFA, 0xFFFA), X, Retain::Nothing),         // Digital Signatures Sequence
+    (t(0xFFFC, 0xFFFC), X, Retain::Nothing),         // Data Set Trailing Padding
+];
+
+/// Pares fecha/hora que forman un mismo instante; al desplazar la hora
+/// puede cambiar el día de la fecha asociada
+#[rustfmt::skip]
+pub(super) const DATE_TIME_PAIRS: &[(Tag, Tag)] = &[
+    (t(0x0008, 0x0012), t(0x0008, 0x0013)), // Instance Creation
+    (t(0x0008, 0x0020), t(0x0008, 0x0030)), // Study
+    (t(0x0008, 0x0021), t(0x0008, 0x0031)), // Series
+    (t(0x0008, 0x0022), t(0x0008, 0x0032)), // Acquisition
+    (t(0x0008, 0x0023), t(0x0008, 0x0033)), // Content
+    (t(0x0008, 0x0024), t(0x0008, 0x0034)), // Overlay
+    (t(0x0008, 0x0025), t(0x0008, 0x0035)), // Curve
+    (t(0x0010, 0x0030), t(0x0010, 0x0032)), // Patient's Birth
+    (t(0x0018, 0x1012), t(0x0018, 0x1014)), // Secondary Capture
+    (t(0x0018, 0x1200), t(0x0018, 0x1201)), // Last Calibration
+    (t(0x0018, 0x700C), t(0x0018, 0x700E)), // Last Detector Calibration
+    (t(0x0032, 0x0032), t(0x0032, 0x0033)), // Study Verified
+    (t(0x0032, 0x0034), t(0x0032, 0x0035)), // Study Read
+    (t(0x0032, 0x1000), t(0x0032, 0x1001)), // Scheduled Study Start
+    (t(0x0032, 0x1010), t(0x0032, 0x1011)), // Scheduled Study Stop
+    (t(0x0032, 0x1040), t(0x0032, 0x1041)), // Study Arrival
+    (t(0x0032, 0x1050), t(0x0032, 0x1051)), // Study Completion
+    (t(0x0038, 0x001A), t(0x0038, 0x001B)), // Scheduled Admission
+    (t(0x0038, 0x001C), t(0x0038, 0x001D)), // Scheduled Discharge
+    (t(0x0038, 0x0020), t(0x0038, 0x0021)), // Admitting
+    (t(0x0038, 0x0030), t(0x0038, 0x0032)), // Discharge
+    (t(0x0040, 0x0002), t(0x0040, 0x0003)), // Scheduled Procedure Step Start
+    (t(0x0040, 0x0004), t(0x0040, 0x0005)), // Scheduled Procedure Step End
+    (t(0x0040, 0x0244), t(0x0040, 0x0245)), // Performed Procedure Step Start
+    (t(0x0040, 0x0250), t(0x0040, 0x0251)), // Performed Procedure Step End
+    (t(0x0040, 0x2004), t(0x0040, 0x2005)), // Issue of Imaging Service Request
+    (t(0x0040, 0xA121), t(0x0040, 0xA122)), // Date / Time (SR)
+    (t(0x0040, 0xA192), t(0x0040, 0xA193)), // Observation (Trial)
+    (t(0x3006, 0x0008), t(0x3006, 0x0009)), // Structure Set
+    (t(0x300A, 0x0006), t(0x300A, 0x0007)), // RT Plan
+    (t(0x4008, 0x0100), t(0x4008, 0x0101)), // Interpretation Recorded
+    (t(0x4008, 0x0108), t(0x4008, 0x0109)), // Interpretation Transcription
+    (t(0x4008, 0x0112), t(0x4008, 0x0113)), // Interpretation Approval
+];
+
+/// Buscar la fila de un tag
+pub(super) fn lookup(tag: Tag) -> Option<(Action, Retain)> {
+    BASIC_PROFILE
+        .binary_search_by_key(&tag, |&(t, ..)| t)
+        .ok()
+        .map(|i| (BASIC_PROFILE[i].1, BASIC_PROFILE[i].2))
+}
+
+/// Tags GPS de la fotografía (EXIF), todos con acción X
+pub(super) fn is_gps(tag: Tag) -> bool {
+    tag.group() == 0x0016 && (0x0070..=0x008E).contains(&tag.element())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_is_sorted_without_duplicates() {
+        assert!(BASIC_PROFILE.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+}
@@ -0,0 +1,172 @@
+//! Acceso genérico a atributos del data set
+//!
+//! Permite leer cualquier atributo por [`Tag`] o por keyword del diccionario
+//! estándar sin tocar el parser:
+//!
+//! ```rust,no_run
+//! # use dicom_core::DicomParser;
+//! # let instance = DicomParser::new().parse_file(std::path::Path::new("cine.dcm"))?;
+//! let frame_time: Option<f64> = instance.get("FrameTime");
+//! let rows = instance.get::<u16>(dicom::dictionary_std::tags::ROWS);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::error::{DicomError, Result};
+use crate::person_name::PersonName;
+
+use dicom::core::dictionary::DataDictionary;
+use dicom::core::Tag;
+use dicom::dictionary_std::StandardDataDictionary;
+use dicom::object::mem::InMemElement;
+use dicom::object::InMemDicomObject;
+
+/// Elemento del data set en memoria
+pub type Element = InMemElement;
+
+// ============================================
+// Claves
+// ============================================
+
+/// Forma de identificar un atributo: `Tag`, keyword ("FrameTime") o "(0018,1063)"
+pub trait AttributeKey {
+    /// Resolver a un tag (InvalidTag si la keyword no existe)
+    fn to_tag(&self) -> Result<Tag>;
+}
+
+impl AttributeKey for Tag {
+    fn to_tag(&self) -> Result<Tag> {
+        Ok(*self)
+    }
+}
+
+impl AttributeKey for &str {
+    fn to_tag(&self) -> Result<Tag> {
+        StandardDataDictionary
+            .parse_tag(self.trim())
+            .ok_or_else(|| DicomError::InvalidTag(self.to_string()))
+    }
+}
+
+impl AttributeKey for String {
+    fn to_tag(&self) -> Result<Tag> {
+        self.as_str().to_tag()
+    }
+}
+
+// ============================================
+// Conversión de valores
+// ============================================
+
+/// Tipos que se pueden leer desde un elemento
+pub trait FromElement: Sized {
+    /// Convertir el valor (None si el VR o el contenido no son compatibles)
+    fn from_element(element: &Element) -> Option<Self>;
+}
+
+impl FromElement for String {
+    fn from_element(element: &Element) -> Option<Self> {
+        element.to_str().ok().map(|s| s.trim().to_string())
+    }
+}
+
+impl FromElement for Vec<String> {
+    fn from_element(element: &Element) -> Option<Self> {
+        let values = element.to_multi_str().ok()?;
+        Some(values.iter().map(|s| s.trim().to_string()).collect())
+    }
+}
+
+impl FromElement for PersonName {
+    fn from_element(element: &Element) -> Option<Self> {
+        element.to_str().ok().map(|s| PersonName::parse(&s))
+    }
+}
+
+impl FromElement for f64 {
+    fn from_element(element: &Element) -> Option<Self> {
+        element.to_float64().ok()
+    }
+}
+
+impl FromElement for f32 {
+    fn from_element(element: &Element) -> Option<Self> {
+        element.to_float32().ok()
+    }
+}
+
+impl FromElement for Vec<f64> {
+    fn from_element(element: &Element) -> Option<Self> {
+        element.to_multi_float64().ok()
+    }
+}
+
+impl FromElement for Vec<u8> {
+    fn from_element(element: &Element) -> Option<Self> {
+        element.to_bytes().ok().map(|bytes| bytes.into_owned())
+    }
+}
+
+/// Items de una secuencia (SQ)
+impl FromElement for Vec<InMemDicomObject> {
+    fn from_element(element: &Element) -> Option<Self> {
+        element.items().map(|items| items.to_vec())
+    }
+}
+
+macro_rules! impl_from_element_int {
+    ($($t:ty),*) => {
+        $(
+            impl FromElement for $t {
+                fn from_element(element: &Element) -> Option<Self> {
+                    element.to_int::<$t>().ok()
+                }
+            }
+
+            impl FromElement for Vec<$t> {
+                fn from_element(element: &Element) -> Option<Self> {
+                    element.to_multi_int::<$t>().ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_from_element_int!(i16, i32, i64, u16, u32, u64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dicom::core::value::DataSetSequence;
+    use dicom::core::{dicom_value, DataElement, PrimitiveValue, VR};
+    use dicom::dictionary_std::tags;
+
+    #[test]
+    fn test_keys() {
+        assert_eq!("FrameTime".to_tag().unwrap(), tags::FRAME_TIME);
+        assert_eq!("(0028,0010)".to_tag().unwrap(), tags::ROWS);
+        assert!(matches!("NoExiste".to_tag(), Err(DicomError::InvalidTag(_))));
+    }
+
+    #[test]
+    fn test_conversions() {
+        let element = DataElement::new(tags::FRAME_TIME, VR::DS, PrimitiveValue::from("33.3 "));
+        assert_eq!(f64::from_element(&element), Some(33.3));
+        assert_eq!(String::from_element(&element).as_deref(), Some("33.3"));
+        assert_eq!(u16::from_element(&element), None);
+
+        let element = DataElement::new(
+            tags::WINDOW_CENTER,
+            VR::DS,
+            dicom_value!(Strs, ["40", "400"]),
+        );
+        assert_eq!(Vec::<f64>::from_element(&element), Some(vec![40.0, 400.0]));
+        assert_eq!(Vec::<i32>::from_element(&element), Some(vec![40, 400]));
+
+        let element = DataElement::new(
+            tags::SEQUENCE_OF_ULTRASOUND_REGIONS,
+            VR::SQ,
+            DataSetSequence::from(vec![InMemDicomObject::new_empty()]),
+        );
+        assert_eq!(Vec::<InMemDicomObject>::from_element(&element).map(|items| items.len()), Some(1));
+    }
+}
@@ -0,0 +1,382 @@
+//! Ingesta de directorios en lote
+//!
+//! Los equipos de adquisición y los exports a USB dejan cientos de archivos
+//! por examen en una carpeta, muchas veces sin DICOMDIR y con nombres sin
+//! extensión. [`DicomParser::parse_directory`] recorre el árbol, parsea los
+//! archivos en paralelo y arma la jerarquía Patient > Study > Series >
+//! Instance. Los errores se acumulan por archivo en vez de cortar el lote.
+
+use crate::error::{DicomError, Result};
+use crate::metadata::DicomInstance;
+use crate::parser::DicomParser;
+use crate::person_name::PersonName;
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// ============================================
+// Resultado
+// ============================================
+
+/// Resultado de procesar un directorio
+#[derive(Debug, Default)]
+pub struct DirectoryScan {
+    /// Instancias agrupadas por paciente
+    pub patients: Vec<PatientNode>,
+
+    /// Archivos ignorados por no ser instancias DICOM
+    pub skipped: Vec<SkippedFile>,
+
+    /// Archivos DICOM que no se pudieron parsear
+    pub errors: Vec<FileError>,
+}
+
+impl DirectoryScan {
+    /// Cantidad de instancias parseadas
+    pub fn instance_count(&self) -> usize {
+        self.instances().count()
+    }
+
+    /// Todas las instancias, en el orden del árbol
+    pub fn instances(&self) -> impl Iterator<Item = &DicomInstance> {
+        self.patients
+            .iter()
+            .flat_map(|p| &p.studies)
+            .flat_map(|s| &s.series)
+            .flat_map(|s| &s.instances)
+    }
+}
+
+/// Paciente con sus estudios
+#[derive(Debug, Clone)]
+pub struct PatientNode {
+    pub patient_id: String,
+    pub patient_name: PersonName,
+    pub patient_birth_date: Option<String>,
+    pub patient_sex: Option<String>,
+    pub studies: Vec<StudyNode>,
+}
+
+/// Estudio con sus series
+#[derive(Debug, Clone)]
+pub struct StudyNode {
+    pub study_instance_uid: String,
+    pub study_date: Option<String>,
+    pub study_description: Option<String>,
+    pub accession_number: Option<String>,
+    pub series: Vec<SeriesNode>,
+}
+
+/// Serie con sus instancias (ordenadas por Instance Number)
+#[derive(Debug, Clone)]
+pub struct SeriesNode {
+    pub series_instance_uid: String,
+    pub series_number: Option<i32>,
+    pub modality: String,
+    pub series_description: Option<String>,
+    pub instances: Vec<DicomInstance>,
+}
+
+/// Motivo para ignorar un archivo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// Sin "DICM" en el offset 128 (o ilegible como DICOM en modo recuperación)
+    NotDicom,
+    /// Índice DICOMDIR, no una instancia
+    Dicomdir,
+    /// Archivo oculto (.DS_Store, "._*" de macOS, Thumbs.db, ...)
+    Hidden,
+}
+
+/// Archivo ignorado
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkippedFile {
+    pub path: PathBuf,
+    pub reason: SkipReason,
+}
+
+/// Error al parsear un archivo
+#[derive(Debug)]
+pub struct FileError {
+    pub path: PathBuf,
+    pub error: DicomError,
+}
+
+// ============================================
+// Recorrido y parsing
+// ============================================
+
+/// Resultado de un archivo
+enum Outcome {
+    Parsed(Box<DicomInstance>),
+    Skipped(SkipReason),
+    Failed(DicomError),
+}
+
+/// Recorrer `dir` y parsear sus archivos en paralelo
+pub(crate) fn parse_directory(parser: &DicomParser, dir: &Path, recovery: bool) -> Result<DirectoryScan> {
+    if !dir.is_dir() {
+        return Err(DicomError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("Directorio no encontrado: {:?}", dir),
+        )));
+    }
+
+    let mut scan = DirectoryScan::default();
+    let mut files = Vec::new();
+    walk(dir, &mut files, &mut scan.errors);
+
+    let outcomes = parse_parallel(&files, |path| parse_one(parser, path, recovery));
+    collect(&mut scan, files, outcomes);
+    Ok(scan)
+}
+
+/// Parsear en paralelo archivos ya identificados como DICOM (p.ej. los
+/// referenciados por un DICOMDIR)
+pub(crate) fn parse_files(parser: &DicomParser, files: Vec<PathBuf>) -> DirectoryScan {
+    let outcomes = parse_parallel(&files, |path| match parser.parse_file(path) {
+        Ok(instance) => Outcome::Parsed(Box::new(instance)),
+        Err(e) => Outcome::Failed(e),
+    });
+
+    let mut scan = DirectoryScan::default();
+    collect(&mut scan, files, outcomes);
+    scan
+}
+
+/// Repartir los resultados y armar la jerarquía
+fn collect(scan: &mut DirectoryScan, files: Vec<PathBuf>, outcomes: Vec<Outcome>) {
+    let mut instances = Vec::new();
+    for (path, outcome) in files.into_iter().zip(outcomes) {
+        match outcome {
+            Outcome::Parsed(instance) => instances.push(*instance),
+            Outcome::Skipped(reason) => scan.skipped.push(SkippedFile { path, reason }),
+            Outcome::Failed(error) => scan.errors.push(FileError { path, error }),
+        }
+    }
+
+    scan.patients = build_tree(instances);
+}
+
+/// Listar archivos recursivamente, en orden alfabético
+///
+/// Los links simbólicos a directorios no se siguen (evita ciclos).
+fn walk(dir: &Path, files: &mut Vec<PathBuf>, errors: &mut Vec<FileError>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            errors.push(FileError {
+                path: dir.to_path_buf(),
+                error: e.into(),
+            });
+            return;
+        }
+    };
+
+    let mut paths: Vec<PathBuf> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+    paths.sort();
+
+    for path in paths {
+        let is_symlink = path.symlink_metadata().map(|m| m.file_type().is_symlink()).unwrap_or(false);
+        if path.is_dir() {
+            if !is_symlink {
+                walk(&path, files, errors);
+            }
+        } else if path.is_file() {
+            files.push(path);
+        }
+    }
+}
+
+/// Ejecutar `parse` sobre cada archivo con un hilo por CPU
+///
+/// Un panic al procesar un archivo queda como error de ese archivo y el
+/// hilo sigue con los demás.
+fn parse_parallel<F>(files: &[PathBuf], parse: F) -> Vec<Outcome>
+where
+    F: Fn(&Path) -> Outcome + Sync,
+{
+    let threads = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(files.len())
+        .max(1);
+    let next = AtomicUsize::new(0);
+
+    let results: Vec<(usize, Outcome)> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(path) = files.get(i) else { break };
+                        let outcome = panic::catch_unwind(AssertUnwindSafe(|| parse(path)))
+                            .unwrap_or_else(|payload| Outcome::Failed(panicked(payload.as_ref())));
+                        done.push((i, outcome));
+                    }
+                    done
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap_or_default())
+            .collect()
+    });
+
+    // Si un hilo murió igual, sus archivos quedan como error
+    let mut outcomes: Vec<Option<Outcome>> = files.iter().map(|_| None).collect();
+    for (i, outcome) in results {
+        outcomes[i] = Some(outcome);
+    }
+    outcomes
+        .into_iter()
+        .map(|outcome| outcome.unwrap_or_else(|| Outcome::Failed(DicomError::internal("hilo de parsing abortado"))))
+        .collect()
+}
+
+/// Error de un archivo cuyo parsing terminó en panic
+fn panicked(payload: &(dyn std::any::Any + Send)) -> DicomError {
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("sin mensaje");
+    DicomError::internal(format!("panic al parsear: {}", message))
+}
+
+fn parse_one(parser: &DicomParser, path: &Path, recovery: bool) -> Outcome {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    if name.starts_with('.') || name.eq_ignore_ascii_case("Thumbs.db") {
+        return Outcome::Skipped(SkipReason::Hidden);
+    }
+    if name.eq_ignore_ascii_case("DICOMDIR") {
+        return Outcome::Skipped(SkipReason::Dicomdir);
+    }
+
+    // Sin "DICM" se descarta sin parsear, salvo en modo recuperación
+    match has_dicm_prefix(path) {
+        Ok(false) if !recovery => return Outcome::Skipped(SkipReason::NotDicom),
+        Ok(_) => {}
+        Err(e) => return Outcome::Failed(e.into()),
+    }
+
+    match parser.parse_file(path) {
+        Ok(instance) => Outcome::Parsed(Box::new(instance)),
+        Err(DicomError::InvalidMagicBytes) => Outcome::Skipped(SkipReason::NotDicom),
+        Err(e) => Outcome::Failed(e),
+    }
+}
+
+/// Verificar "DICM" en el offset 128
+fn has_dicm_prefix(path: &Path) -> std::io::Result<bool> {
+    let mut header = [0u8; 132];
+    let mut file = File::open(path)?;
+    let mut read = 0;
+    while read < header.len() {
+        match file.read(&mut header[read..])? {
+            0 => return Ok(false),
+            n => read += n,
+        }
+    }
+    Ok(&header[128..] == b"DICM")
+}
+
+// ============================================
+// Jerarquía
+// ============================================
+
+/// Agrupar por Patient ID, Study Instance UID y Series Instance UID
+///
+/// Los datos de cada nivel se toman de la primera instancia encontrada.
+fn build_tree(instances: Vec<DicomInstance>) -> Vec<PatientNode> {
+    type Series = BTreeMap<String, Vec<DicomInstance>>;
+    type Studies = BTreeMap<String, Series>;
+    let mut patients: BTreeMap<String, Studies> = BTreeMap::new();
+
+    for instance in instances {
+        let metadata = &instance.metadata;
+        patients
+            .entry(metadata.patient_id.clone())
+            .or_default()
+            .entry(metadata.study_instance_uid.clone())
+            .or_default()
+            .entry(metadata.series_instance_uid.clone())
+            .or_default()
+            .push(instance);
+    }
+
+    patients
+        .into_values()
+        .map(|studies| {
+            let studies: Vec<StudyNode> = studies.into_values().map(study_node).collect();
+            let first = &studies[0].series[0].instances[0].metadata;
+            PatientNode {
+                patient_id: first.patient_id.clone(),
+                patient_name: first.patient_person_name.clone(),
+                patient_birth_date: first.patient_birth_date.clone(),
+                patient_sex: first.patient_sex.clone(),
+                studies,
+            }
+        })
+        .collect()
+}
+
+fn study_node(series: BTreeMap<String, Vec<DicomInstance>>) -> StudyNode {
+    let mut series: Vec<SeriesNode> = series.into_values().map(series_node).collect();
+    series.sort_by_key(|s| s.series_number.unwrap_or(i32::MAX));
+
+    let first = &series[0].instances[0].metadata;
+    StudyNode {
+        study_instance_uid: first.study_instance_uid.clone(),
+        study_date: first.study_date.clone(),
+        study_description: first.study_description.clone(),
+        accession_number: first.accession_number.clone(),
+        series,
+    }
+}
+
+fn series_node(mut instances: Vec<DicomInstance>) -> SeriesNode {
+    instances.sort_by(|a, b| {
+        let number = |i: &DicomInstance| i.metadata.instance_number.unwrap_or(i32::MAX);
+        number(a).cmp(&number(b)).then_with(|| a.file_path.cmp(&b.file_path))
+    });
+
+    let first = &instances[0].metadata;
+    SeriesNode {
+        series_instance_uid: first.series_instance_uid.clone(),
+        series_number: first.series_number,
+        modality: first.modality.clone(),
+        series_description: first.series_description.clone(),
+        instances,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_panic_becomes_file_error() {
+        let files: Vec<PathBuf> = ["a.dcm", "b.dcm", "c.dcm"].iter().map(PathBuf::from).collect();
+        let outcomes = parse_parallel(&files, |path| {
+            if path == Path::new("b.dcm") {
+                panic!("valor inesperado");
+            }
+            Outcome::Skipped(SkipReason::Hidden)
+        });
+
+        assert_eq!(outcomes.len(), 3);
+        assert!(matches!(outcomes[0], Outcome::Skipped(SkipReason::Hidden)));
+        assert!(matches!(&outcomes[1], Outcome::Failed(DicomError::Internal(m)) if m == "panic al parsear: valor inesperado"));
+        assert!(matches!(outcomes[2], Outcome::Skipped(SkipReason::Hidden)));
+    }
+}
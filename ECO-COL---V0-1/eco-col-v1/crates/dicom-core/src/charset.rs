@@ -0,0 +1,386 @@
+//! Decodificación de texto según Specific Character Set (0008,0005)
+//!
+//! Soporta los repertorios de un byte (ISO_IR 100, 101, 144, ...), UTF-8,
+//! GB18030/GBK y las extensiones de código ISO 2022 (PS3.5 §6.1.2.5) usadas
+//! para japonés, coreano y chino.
+//!
+//! Solo SH, LO, UC, ST, LT, UT y PN usan el charset declarado; el resto de
+//! los VR de texto se limitan al repertorio por defecto.
+
+use dicom::core::{PrimitiveValue, VR};
+use dicom::dictionary_std::tags;
+use dicom::object::InMemDicomObject;
+use encoding_rs::Encoding;
+
+/// Escape (ESC) que inicia una secuencia de cambio de juego de caracteres
+const ESC: u8 = 0x1B;
+
+/// Repertorio usado para decodificar un valor completo o un byte de G1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Codec {
+    /// ISO-IR 100: cada byte es el code point (no necesita tabla)
+    Latin1,
+    /// JIS X 0201: romaji en 0x00-0x7F y katakana de medio ancho en 0xA1-0xDF
+    JisX0201,
+    /// Cualquier otro repertorio de encoding_rs (ISO 8859-x, UTF-8, GB18030, ...)
+    Table(&'static Encoding),
+}
+
+impl Codec {
+    fn decode_into(&self, bytes: &[u8], out: &mut String) {
+        match self {
+            Codec::Latin1 => out.extend(bytes.iter().map(|&b| b as char)),
+            Codec::JisX0201 => out.extend(bytes.iter().map(|&b| jis_x0201(b))),
+            Codec::Table(encoding) => out.push_str(&encoding.decode_without_bom_handling(bytes).0),
+        }
+    }
+}
+
+/// Juego activo en G0 (bytes 0x21-0x7E)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum G0 {
+    /// ISO-IR 6
+    Ascii,
+    /// ISO-IR 14: JIS X 0201 romaji
+    Romaji,
+    /// ISO-IR 87: JIS X 0208 (kanji, dos bytes)
+    JisX0208,
+    /// ISO-IR 159: JIS X 0212 (kanji suplementario, dos bytes)
+    JisX0212,
+}
+
+/// Juego activo en G1 (bytes 0xA1-0xFE)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum G1 {
+    /// Sin juego designado: los bytes altos se decodifican como Latin-1
+    None,
+    /// Repertorio de un byte (ISO-IR 100, 13, 144, ...)
+    SingleByte(Codec),
+    /// ISO-IR 149: KS X 1001 (coreano, dos bytes)
+    KsX1001,
+    /// ISO-IR 58: GB 2312 (chino simplificado, dos bytes)
+    Gb2312,
+}
+
+/// Cambio indicado por una secuencia de escape
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Designation {
+    G0(G0),
+    G1(G1),
+}
+
+/// Forma de decodificar los valores
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// Un solo repertorio, sin secuencias de escape
+    Single(Codec),
+    /// Extensiones de código ISO 2022 con el estado inicial de G0 y G1
+    Iso2022 { g0: G0, g1: G1 },
+}
+
+/// Specific Character Set declarado en un data set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CharacterSet {
+    terms: Vec<String>,
+    mode: Mode,
+}
+
+impl Default for CharacterSet {
+    /// Repertorio por defecto (ISO-IR 6), leído como Latin-1 por tolerancia
+    fn default() -> Self {
+        Self {
+            terms: Vec::new(),
+            mode: Mode::Single(Codec::Latin1),
+        }
+    }
+}
+
+impl CharacterSet {
+    /// Construir desde los valores de (0008,0005)
+    ///
+    /// Los términos desconocidos se tratan como Latin-1.
+    pub fn from_terms<S: AsRef<str>>(terms: &[S]) -> Self {
+        let terms: Vec<String> = terms.iter().map(|t| t.as_ref().trim().to_string()).collect();
+        let iso2022 = terms.len() > 1 || terms.iter().any(|t| t.starts_with("ISO 2022"));
+
+        let first = terms.first().map(String::as_str).unwrap_or("");
+        let mode = if iso2022 {
+            let (g0, g1) = iso2022_initial_state(first);
+            Mode::Iso2022 { g0, g1 }
+        } else {
+            Mode::Single(single_codec(first))
+        };
+
+        Self { terms, mode }
+    }
+
+    /// Leer (0008,0005) del data set (por defecto si no está)
+    pub fn from_dataset(dataset: &InMemDicomObject) -> Self {
+        dataset
+            .element(tags::SPECIFIC_CHARACTER_SET)
+            .ok()
+            .and_then(|e| e.to_multi_str().ok())
+            .map(|terms| Self::from_terms(&terms))
+            .unwrap_or_default()
+    }
+
+    /// Términos declarados, en el orden original
+    pub fn terms(&self) -> &[String] {
+        &self.terms
+    }
+
+    /// Verificar si el data set usa extensiones de código ISO 2022
+    pub fn is_iso2022(&self) -> bool {
+        matches!(self.mode, Mode::Iso2022 { .. })
+    }
+
+    /// Decodificar un valor de texto completo (incluyendo separadores `\`)
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        let mut out = String::with_capacity(bytes.len());
+        match self.mode {
+            Mode::Single(codec) => codec.decode_into(bytes, &mut out),
+            Mode::Iso2022 { g0, g1 } => decode_iso2022(bytes, g0, g1, &mut out),
+        }
+        out
+    }
+
+    /// Decodificar el valor de un elemento con VR de texto
+    ///
+    /// Se decodifica antes de separar por `\`: en GBK o JIS X 0208 el byte
+    /// 0x5C puede ser parte de un carácter multibyte.
+    pub(crate) fn decode_value(&self, vr: VR, bytes: &[u8]) -> PrimitiveValue {
+        let text = self.decode(bytes);
+        match vr {
+            VR::ST | VR::LT | VR::UT => PrimitiveValue::Str(text),
+            _ => PrimitiveValue::Strs(text.split('\\').map(str::to_string).collect()),
+        }
+    }
+}
+
+/// VR cuyo contenido depende de Specific Character Set
+pub fn uses_character_set(vr: VR) -> bool {
+    matches!(vr, VR::SH | VR::LO | VR::UC | VR::ST | VR::LT | VR::UT | VR::PN)
+}
+
+/// Repertorio para un término sin extensiones de código
+fn single_codec(term: &str) -> Codec {
+    use encoding_rs::*;
+
+    match term {
+        "ISO_IR 101" => Codec::Table(ISO_8859_2),
+        "ISO_IR 109" => Codec::Table(ISO_8859_3),
+        "ISO_IR 110" => Codec::Table(ISO_8859_4),
+        "ISO_IR 144" => Codec::Table(ISO_8859_5),
+        "ISO_IR 127" => Codec::Table(ISO_8859_6),
+        "ISO_IR 126" => Codec::Table(ISO_8859_7),
+        "ISO_IR 138" => Codec::Table(ISO_8859_8),
+        // ISO 8859-9 y TIS 620 coinciden con sus variantes windows en 0xA0-0xFF
+        "ISO_IR 148" => Codec::Table(WINDOWS_1254),
+        "ISO_IR 166" => Codec::Table(WINDOWS_874),
+        "ISO_IR 203" => Codec::Table(ISO_8859_15),
+        "ISO_IR 13" => Codec::JisX0201,
+        "ISO_IR 192" => Codec::Table(UTF_8),
+        "GB18030" => Codec::Table(GB18030),
+        "GBK" => Codec::Table(GBK),
+        // ISO_IR 6, ISO_IR 100 y términos desconocidos
+        _ => Codec::Latin1,
+    }
+}
+
+/// Estado inicial de G0 y G1 según el primer término ISO 2022
+fn iso2022_initial_state(term: &str) -> (G0, G1) {
+    match term {
+        "ISO 2022 IR 13" => (G0::Romaji, G1::SingleByte(Codec::JisX0201)),
+        "ISO 2022 IR 87" => (G0::JisX0208, G1::None),
+        "ISO 2022 IR 159" => (G0::JisX0212, G1::None),
+        "ISO 2022 IR 149" => (G0::Ascii, G1::KsX1001),
+        "ISO 2022 IR 58" => (G0::Ascii, G1::Gb2312),
+        "" | "ISO 2022 IR 6" => (G0::Ascii, G1::None),
+        other => (G0::Ascii, G1::SingleByte(single_codec(&other.replace("ISO 2022 IR", "ISO_IR")))),
+    }
+}
+
+/// Interpretar una secuencia de escape (devuelve su longitud y el cambio)
+fn escape_sequence(bytes: &[u8]) -> Option<(usize, Designation)> {
+    use encoding_rs::*;
+
+    let single = |codec| Some((3, Designation::G1(G1::SingleByte(codec))));
+    match bytes {
+        [ESC, b'(', b'B', ..] => Some((3, Designation::G0(G0::Ascii))),
+        [ESC, b'(', b'J', ..] => Some((3, Designation::G0(G0::Romaji))),
+        [ESC, b'$', b'B', ..] => Some((3, Designation::G0(G0::JisX0208))),
+        [ESC, b'$', b'(', b'D', ..] => Some((4, Designation::G0(G0::JisX0212))),
+        [ESC, b'$', b')', b'C', ..] => Some((4, Designation::G1(G1::KsX1001))),
+        [ESC, b'$', b')', b'A', ..] => Some((4, Designation::G1(G1::Gb2312))),
+        [ESC, b')', b'I', ..] => Some((3, Designation::G1(G1::SingleByte(Codec::JisX0201)))),
+        [ESC, b'-', b'A', ..] => single(Codec::Latin1),
+        [ESC, b'-', b'B', ..] => single(Codec::Table(ISO_8859_2)),
+        [ESC, b'-', b'C', ..] => single(Codec::Table(ISO_8859_3)),
+        [ESC, b'-', b'D', ..] => single(Codec::Table(ISO_8859_4)),
+        [ESC, b'-', b'L', ..] => single(Codec::Table(ISO_8859_5)),
+        [ESC, b'-', b'G', ..] => single(Codec::Table(ISO_8859_6)),
+        [ESC, b'-', b'F', ..] => single(Codec::Table(ISO_8859_7)),
+        [ESC, b'-', b'H', ..] => single(Codec::Table(ISO_8859_8)),
+        [ESC, b'-', b'M', ..] => single(Codec::Table(WINDOWS_1254)),
+        [ESC, b'-', b'T', ..] => single(Codec::Table(WINDOWS_874)),
+        [ESC, b'-', b'b', ..] => single(Codec::Table(ISO_8859_15)),
+        _ => None,
+    }
+}
+
+/// Decodificar bytes con extensiones de código ISO 2022
+fn decode_iso2022(bytes: &[u8], initial_g0: G0, initial_g1: G1, out: &mut String) {
+    let (mut g0, mut g1) = (initial_g0, initial_g1);
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let b = bytes[i];
+
+        if b == ESC {
+            if let Some((len, designation)) = escape_sequence(&bytes[i..]) {
+                match designation {
+                    Designation::G0(set) => g0 = set,
+                    Designation::G1(set) => g1 = set,
+                }
+                i += len;
+                continue;
+            }
+        }
+
+        // Cada valor de un atributo multivaluado empieza en el estado inicial
+        if b == b'\\' && matches!(g0, G0::Ascii | G0::Romaji) {
+            out.push('\\');
+            (g0, g1) = (initial_g0, initial_g1);
+            i += 1;
+            continue;
+        }
+
+        let consumed = if b >= 0x80 {
+            decode_g1(&bytes[i..], g1, out)
+        } else {
+            decode_g0(&bytes[i..], g0, out)
+        };
+        i += consumed;
+    }
+}
+
+/// Decodificar un carácter de G0 (devuelve los bytes consumidos)
+fn decode_g0(bytes: &[u8], g0: G0, out: &mut String) -> usize {
+    let b = bytes[0];
+    match g0 {
+        G0::Ascii => {
+            out.push(b as char);
+            1
+        }
+        G0::Romaji => {
+            out.push(jis_x0201(b));
+            1
+        }
+        // Los controles (CR, LF, espacio) siguen siendo de un byte
+        G0::JisX0208 | G0::JisX0212 if b <= 0x20 || bytes.len() < 2 => {
+            out.push(b as char);
+            1
+        }
+        // JIS X 0208/0212 en G0 equivalen a EUC-JP con el bit alto encendido
+        G0::JisX0208 => {
+            Codec::Table(encoding_rs::EUC_JP).decode_into(&[b | 0x80, bytes[1] | 0x80], out);
+            2
+        }
+        G0::JisX0212 => {
+            Codec::Table(encoding_rs::EUC_JP).decode_into(&[0x8F, b | 0x80, bytes[1] | 0x80], out);
+            2
+        }
+    }
+}
+
+/// Decodificar un carácter de G1 (devuelve los bytes consumidos)
+fn decode_g1(bytes: &[u8], g1: G1, out: &mut String) -> usize {
+    let codec = match g1 {
+        G1::None => Codec::Latin1,
+        G1::SingleByte(codec) => codec,
+        G1::KsX1001 | G1::Gb2312 if bytes.len() >= 2 => {
+            let encoding = if g1 == G1::KsX1001 { encoding_rs::EUC_KR } else { encoding_rs::GBK };
+            Codec::Table(encoding).decode_into(&bytes[..2], out);
+            return 2;
+        }
+        G1::KsX1001 | G1::Gb2312 => {
+            out.push(char::REPLACEMENT_CHARACTER);
+            return 1;
+        }
+    };
+
+    codec.decode_into(&bytes[..1], out);
+    1
+}
+
+/// JIS X 0201: romaji (0x5C = ¥, 0x7E = ‾) y katakana de medio ancho
+fn jis_x0201(b: u8) -> char {
+    match b {
+        0x5C => '¥',
+        0x7E => '‾',
+        0xA1..=0xDF => char::from_u32(0xFF61 + (b - 0xA1) as u32).unwrap_or(char::REPLACEMENT_CHARACTER),
+        0x80.. => char::REPLACEMENT_CHARACTER,
+        _ => b as char,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_byte_character_sets() {
+        let latin1 = CharacterSet::from_terms(&["ISO_IR 100"]);
+        assert_eq!(latin1.decode(b"P\xC9REZ^JOS\xC9 MU\xD1OZ"), "PÉREZ^JOSÉ MUÑOZ");
+
+        let utf8 = CharacterSet::from_terms(&["ISO_IR 192"]);
+        assert_eq!(utf8.decode("NÚÑEZ^MARÍA".as_bytes()), "NÚÑEZ^MARÍA");
+
+        let cyrillic = CharacterSet::from_terms(&["ISO_IR 144"]);
+        assert_eq!(cyrillic.decode(b"\xBB\xEE\xDA\xE1\xE2"), "Люкст");
+        assert!(!cyrillic.is_iso2022());
+    }
+
+    #[test]
+    fn test_iso2022_japanese() {
+        // PS3.5 H.3.1
+        let charset = CharacterSet::from_terms(&["", "ISO 2022 IR 87"]);
+        let bytes = [
+            &b"Yamada^Tarou="[..],
+            b"\x1B$B\x3B\x33\x45\x44\x1B(B^\x1B$B\x42\x40\x4F\x3A\x1B(B=",
+            b"\x1B$B\x24\x64\x24\x5E\x24\x40\x1B(B^\x1B$B\x24\x3F\x24\x6D\x24\x26\x1B(B",
+        ]
+        .concat();
+
+        assert!(charset.is_iso2022());
+        assert_eq!(charset.decode(&bytes), "Yamada^Tarou=山田^太郎=やまだ^たろう");
+    }
+
+    #[test]
+    fn test_iso2022_korean() {
+        // PS3.5 I.2
+        let charset = CharacterSet::from_terms(&["", "ISO 2022 IR 149"]);
+        let bytes = [
+            &b"Hong^Gildong="[..],
+            b"\x1B$)C\xFB\xF3^\x1B$)C\xD1\xCE\xD4\xD7=",
+            b"\x1B$)C\xC8\xAB^\x1B$)C\xB1\xE6\xB5\xBF",
+        ]
+        .concat();
+
+        assert_eq!(charset.decode(&bytes), "Hong^Gildong=洪^吉洞=홍^길동");
+    }
+
+    #[test]
+    fn test_multibyte_backslash_is_not_a_delimiter() {
+        // 0x5C es el segundo byte de "乗" en GBK
+        let charset = CharacterSet::from_terms(&["GBK"]);
+        let value = charset.decode_value(VR::LO, b"\x81\x5C\\A");
+        assert_eq!(value.to_multi_str().as_ref(), ["乗", "A"]);
+    }
+
+    #[test]
+    fn test_jis_x0201_katakana() {
+        let charset = CharacterSet::from_terms(&["ISO 2022 IR 13", "ISO 2022 IR 87"]);
+        assert_eq!(charset.decode(b"\xD4\xCF\xC0\xDE^\xC0\xDB\xB3"), "ﾔﾏﾀﾞ^ﾀﾛｳ");
+    }
+}
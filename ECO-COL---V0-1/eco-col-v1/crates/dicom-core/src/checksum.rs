@@ -0,0 +1,114 @@
+//! Checksums de integridad (SHA-256)
+//!
+//! Con `ParseOptions::validate_checksums` el parser calcula el hash del
+//! archivo completo (columna `instances.file_sha256`) y uno por frame de
+//! pixel data. Los hashes de frame se calculan sobre los bytes tal como
+//! están almacenados (fragmentos sin decodificar en transfer syntaxes
+//! encapsuladas), así no dependen de los codecs disponibles.
+
+use crate::error::{DicomError, Result};
+use crate::metadata::DicomInstance;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{Read, Seek, SeekFrom};
+
+/// Hashes SHA-256 en hexadecimal (minúsculas)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checksums {
+    /// Archivo completo
+    pub file_sha256: String,
+
+    /// Pixel data de cada frame, en orden
+    pub frame_sha256: Vec<String>,
+}
+
+impl Checksums {
+    /// Comparar con hashes almacenados
+    ///
+    /// Los campos vacíos de `expected` no se verifican. Cualquier diferencia
+    /// es `CorruptedPixelData`.
+    pub fn verify(&self, expected: &Checksums) -> Result<()> {
+        let file_ok = expected.file_sha256.is_empty() || self.file_sha256.eq_ignore_ascii_case(&expected.file_sha256);
+        let frames_ok = expected.frame_sha256.is_empty()
+            || (expected.frame_sha256.len() == self.frame_sha256.len()
+                && self
+                    .frame_sha256
+                    .iter()
+                    .zip(&expected.frame_sha256)
+                    .all(|(actual, expected)| actual.eq_ignore_ascii_case(expected)));
+
+        if file_ok && frames_ok {
+            Ok(())
+        } else {
+            Err(DicomError::CorruptedPixelData)
+        }
+    }
+}
+
+/// SHA-256 en hexadecimal
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    hex(&Sha256::digest(bytes))
+}
+
+fn hex(digest: &[u8]) -> String {
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Calcular los hashes del objeto leído desde `reader`
+///
+/// Los frames se leen con la ubicación del descriptor; si no hay ubicación
+/// (p.ej. Deflated) se usan los píxeles ya cargados en memoria.
+pub(crate) fn compute<R: Read + Seek>(reader: &mut R, instance: &DicomInstance) -> Result<Checksums> {
+    reader.seek(SeekFrom::Start(0))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(reader, &mut hasher)?;
+    let file_sha256 = hex(&hasher.finalize());
+
+    let frame_sha256 = match (&instance.pixel_descriptor, &instance.pixel_data) {
+        (Some(descriptor), _) if descriptor.location.is_some() => (0..descriptor.number_of_frames.max(1))
+            .map(|n| descriptor.read_stored_frame(reader, n).map(|frame| sha256_hex(&frame)))
+            .collect::<Result<_>>()?,
+        (_, Some(pixel_data)) => pixel_data.frames().map(sha256_hex).collect(),
+        _ => Vec::new(),
+    };
+
+    Ok(Checksums {
+        file_sha256,
+        frame_sha256,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_verify() {
+        let actual = Checksums {
+            file_sha256: sha256_hex(b"archivo"),
+            frame_sha256: vec![sha256_hex(b"0"), sha256_hex(b"1")],
+        };
+
+        assert!(actual.verify(&Checksums::default()).is_ok());
+        let upper = Checksums {
+            file_sha256: actual.file_sha256.to_uppercase(),
+            frame_sha256: Vec::new(),
+        };
+        assert!(actual.verify(&upper).is_ok());
+
+        let mut corrupted = actual.clone();
+        corrupted.frame_sha256[1] = sha256_hex(b"2");
+        assert!(matches!(actual.verify(&corrupted), Err(DicomError::CorruptedPixelData)));
+        corrupted.frame_sha256.pop();
+        assert!(matches!(actual.verify(&corrupted), Err(DicomError::CorruptedPixelData)));
+    }
+}
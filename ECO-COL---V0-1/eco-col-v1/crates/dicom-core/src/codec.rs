@@ -0,0 +1,225 @@
+//! Decodificación de pixel data encapsulado
+//!
+//! Cada codec implementa [`PixelDecoder`] y se registra en un
+//! [`CodecRegistry`] por transfer syntax. Los codecs incluidos se activan
+//! con su propia cargo feature:
+//!
+//! | Feature    | Transfer syntaxes                                   |
+//! |------------|-----------------------------------------------------|
+//! | `rle`      | RLE Lossless (1.2.840.10008.1.2.5)                  |
+//! | `jpeg`     | JPEG Baseline/Extended/Lossless (1.2.840.10008.1.2.4.50, .51, .57, .70) |
+//! | `jpeg-ls`  | JPEG-LS Lossless/Near-Lossless (1.2.840.10008.1.2.4.80, .81) |
+//! | `jpeg2000` | JPEG 2000 (1.2.840.10008.1.2.4.90, .91)             |
+
+use crate::error::{DicomError, Result};
+use crate::pixel::{Fragment, PixelDataDescriptor};
+
+use std::sync::OnceLock;
+
+#[cfg(feature = "jpeg")]
+mod jpeg;
+#[cfg(feature = "jpeg-ls")]
+mod jpegls;
+#[cfg(feature = "jpeg2000")]
+mod jpeg2000;
+#[cfg(feature = "rle")]
+mod rle;
+
+#[cfg(feature = "jpeg")]
+pub use jpeg::JpegDecoder;
+#[cfg(feature = "jpeg-ls")]
+pub use jpegls::JpegLsDecoder;
+#[cfg(feature = "jpeg2000")]
+pub use jpeg2000::Jpeg2000Decoder;
+#[cfg(feature = "rle")]
+pub use rle::RleDecoder;
+
+/// Decodificador de frames encapsulados a pixel data nativo
+pub trait PixelDecoder: Send + Sync {
+    /// Transfer syntaxes que este codec sabe decodificar
+    fn transfer_syntaxes(&self) -> &[&'static str];
+
+    /// Decodificar un frame completo (fragmentos ya concatenados)
+    ///
+    /// La salida debe ser pixel data nativo en little endian, con las
+    /// muestras intercaladas por píxel (Planar Configuration 0) y el
+    /// tamaño de un frame en `output_photometric_interpretation`.
+    fn decode_frame(&self, data: &[u8], descriptor: &PixelDataDescriptor) -> Result<Vec<u8>>;
+
+    /// Interpretación fotométrica de la salida decodificada
+    ///
+    /// Por ejemplo, JPEG entrega RGB aunque el archivo declare YBR_FULL_422.
+    fn output_photometric_interpretation(&self, descriptor: &PixelDataDescriptor) -> String {
+        descriptor.photometric_interpretation.clone()
+    }
+}
+
+/// Registro de codecs por transfer syntax
+#[derive(Default)]
+pub struct CodecRegistry {
+    decoders: Vec<Box<dyn PixelDecoder>>,
+}
+
+impl CodecRegistry {
+    /// Crear registro vacío (sin codecs)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Crear registro con todos los codecs habilitados por features
+    pub fn with_builtin_codecs() -> Self {
+        #[allow(unused_mut)]
+        let mut registry = Self::new();
+
+        #[cfg(feature = "rle")]
+        registry.register(Box::new(RleDecoder));
+        #[cfg(feature = "jpeg")]
+        registry.register(Box::new(JpegDecoder));
+        #[cfg(feature = "jpeg-ls")]
+        registry.register(Box::new(JpegLsDecoder));
+        #[cfg(feature = "jpeg2000")]
+        registry.register(Box::new(Jpeg2000Decoder));
+
+        registry
+    }
+
+    /// Registro compartido con los codecs incluidos
+    pub fn builtin() -> &'static CodecRegistry {
+        static BUILTIN: OnceLock<CodecRegistry> = OnceLock::new();
+        BUILTIN.get_or_init(Self::with_builtin_codecs)
+    }
+
+    /// Registrar un codec; tiene prioridad sobre los ya registrados
+    pub fn register(&mut self, decoder: Box<dyn PixelDecoder>) {
+        self.decoders.insert(0, decoder);
+    }
+
+    /// Buscar el codec para una transfer syntax
+    pub fn decoder(&self, transfer_syntax_uid: &str) -> Option<&dyn PixelDecoder> {
+        self.decoders
+            .iter()
+            .find(|d| d.transfer_syntaxes().contains(&transfer_syntax_uid))
+            .map(|d| d.as_ref())
+    }
+
+    /// Verificar si hay codec para una transfer syntax
+    pub fn supports(&self, transfer_syntax_uid: &str) -> bool {
+        self.decoder(transfer_syntax_uid).is_some()
+    }
+
+    /// Decodificar un frame, validando el tamaño de la salida
+    pub fn decode_frame(
+        &self,
+        transfer_syntax_uid: &str,
+        data: &[u8],
+        descriptor: &PixelDataDescriptor,
+    ) -> Result<Vec<u8>> {
+        let decoder = self
+            .decoder(transfer_syntax_uid)
+            .ok_or_else(|| DicomError::UnsupportedTransferSyntax(transfer_syntax_uid.to_string()))?;
+
+        let frame = decoder.decode_frame(data, descriptor)?;
+        let photometric_interpretation = decoder.output_photometric_interpretation(descriptor);
+        if Some(frame.len()) != descriptor.frame_size_with(&photometric_interpretation) {
+            return Err(DicomError::CorruptedPixelData);
+        }
+
+        Ok(frame)
+    }
+}
+
+impl std::fmt::Debug for CodecRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let syntaxes: Vec<_> = self.decoders.iter().flat_map(|d| d.transfer_syntaxes()).collect();
+        f.debug_struct("CodecRegistry").field("transfer_syntaxes", &syntaxes).finish()
+    }
+}
+
+/// Determinar qué fragmentos forman el frame `n`
+///
+/// Usa la Basic Offset Table si existe; si no, asume un fragmento por frame
+/// o, para objetos de un solo frame, todos los fragmentos.
+pub(crate) fn frame_fragments(
+    offset_table: &[u32],
+    fragments: &[Fragment],
+    number_of_frames: u32,
+    n: u32,
+) -> Result<Vec<Fragment>> {
+    let frames = number_of_frames.max(1) as usize;
+    let n = n as usize;
+
+    if offset_table.len() == frames {
+        // Offset de cada fragmento relativo al primer item (8 bytes de header)
+        let mut relative = Vec::with_capacity(fragments.len());
+        let mut position = 0u64;
+        for fragment in fragments {
+            relative.push(position);
+            position += 8 + u64::from(fragment.length);
+        }
+
+        let start = u64::from(offset_table[n]);
+        let end = offset_table.get(n + 1).map_or(u64::MAX, |&end| u64::from(end));
+        let selected: Vec<Fragment> = fragments
+            .iter()
+            .zip(&relative)
+            .filter(|(_, &offset)| offset >= start && offset < end)
+            .map(|(fragment, _)| *fragment)
+            .collect();
+
+        if selected.is_empty() {
+            return Err(DicomError::CorruptedPixelData);
+        }
+        return Ok(selected);
+    }
+
+    if fragments.len() == frames {
+        return Ok(vec![fragments[n]]);
+    }
+
+    if frames == 1 && !fragments.is_empty() {
+        return Ok(fragments.to_vec());
+    }
+
+    Err(DicomError::parse(format!(
+        "No se puede asignar {} fragmentos a {} frames sin Basic Offset Table",
+        fragments.len(),
+        frames
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fragment(offset: u64, length: u32) -> Fragment {
+        Fragment { offset, length }
+    }
+
+    #[test]
+    fn test_unsupported_transfer_syntax() {
+        let registry = CodecRegistry::new();
+        let descriptor = crate::pixel::tests::descriptor(1, 1, 8, 1);
+        let result = registry.decode_frame("1.2.840.10008.1.2.4.100", &[], &descriptor);
+        assert!(matches!(result, Err(DicomError::UnsupportedTransferSyntax(_))));
+    }
+
+    #[test]
+    fn test_frame_fragments_with_offset_table() {
+        // Frame 0 = fragmentos 0 y 1, frame 1 = fragmento 2
+        let fragments = [fragment(100, 10), fragment(118, 6), fragment(132, 4)];
+        let offset_table = [0, 32];
+
+        let frame = frame_fragments(&offset_table, &fragments, 2, 0).unwrap();
+        assert_eq!(frame, vec![fragments[0], fragments[1]]);
+        let frame = frame_fragments(&offset_table, &fragments, 2, 1).unwrap();
+        assert_eq!(frame, vec![fragments[2]]);
+    }
+
+    #[test]
+    fn test_frame_fragments_without_offset_table() {
+        let fragments = [fragment(100, 10), fragment(118, 6)];
+        assert_eq!(frame_fragments(&[], &fragments, 2, 1).unwrap(), vec![fragments[1]]);
+        assert_eq!(frame_fragments(&[], &fragments, 1, 0).unwrap().len(), 2);
+        assert!(frame_fragments(&[], &fragments, 3, 0).is_err());
+    }
+}
@@ -0,0 +1,122 @@
+//! JPEG Baseline, Extended y Lossless vía `jpeg-decoder`
+
+use super::PixelDecoder;
+use crate::error::{DicomError, Result};
+use crate::pixel::PixelDataDescriptor;
+
+use jpeg_decoder::PixelFormat;
+
+/// Decodificador JPEG (procesos 1, 2/4 y 14)
+pub struct JpegDecoder;
+
+impl PixelDecoder for JpegDecoder {
+    fn transfer_syntaxes(&self) -> &[&'static str] {
+        &[
+            "1.2.840.10008.1.2.4.50", // JPEG Baseline (Process 1)
+            "1.2.840.10008.1.2.4.51", // JPEG Extended (Process 2 & 4)
+            "1.2.840.10008.1.2.4.57", // JPEG Lossless, Non-Hierarchical (Process 14)
+            "1.2.840.10008.1.2.4.70", // JPEG Lossless, First-Order Prediction
+        ]
+    }
+
+    fn decode_frame(&self, data: &[u8], descriptor: &PixelDataDescriptor) -> Result<Vec<u8>> {
+        descriptor.checked_frame_size_bytes()?;
+        // jpeg-decoder mide el límite en muestras, no en bytes
+        let samples = (descriptor.rows as usize)
+            .checked_mul(descriptor.columns as usize)
+            .and_then(|pixels| pixels.checked_mul(descriptor.samples_per_pixel as usize))
+            .ok_or(DicomError::CorruptedPixelData)?;
+
+        // Comparar la cabecera del stream con el descriptor antes de reservar
+        let mut decoder = jpeg_decoder::Decoder::new(data);
+        decoder.set_max_decoding_buffer_size(samples);
+        decoder
+            .read_info()
+            .map_err(|e| DicomError::parse(format!("Error decoding JPEG: {}", e)))?;
+        let info = decoder.info().ok_or(DicomError::CorruptedPixelData)?;
+
+        if u32::from(info.width) != descriptor.columns || u32::from(info.height) != descriptor.rows {
+            return Err(DicomError::CorruptedPixelData);
+        }
+
+        let pixels = decoder
+            .decode()
+            .map_err(|e| DicomError::parse(format!("Error decoding JPEG: {}", e)))?;
+
+        match info.pixel_format {
+            PixelFormat::L8 | PixelFormat::RGB24 => Ok(pixels),
+            // jpeg-decoder entrega 16 bits en el endianness de la plataforma
+            PixelFormat::L16 => Ok(pixels
+                .chunks_exact(2)
+                .flat_map(|b| u16::from_ne_bytes([b[0], b[1]]).to_le_bytes())
+                .collect()),
+            PixelFormat::CMYK32 => Err(DicomError::parse("JPEG CMYK no es válido en DICOM")),
+        }
+    }
+
+    fn output_photometric_interpretation(&self, descriptor: &PixelDataDescriptor) -> String {
+        // La conversión YCbCr -> RGB ocurre dentro del decodificador
+        if descriptor.samples_per_pixel == 3 {
+            "RGB".to_string()
+        } else {
+            descriptor.photometric_interpretation.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel::tests::descriptor;
+    use jpeg_encoder::{ColorType, Encoder};
+
+    fn encode(pixels: &[u8], width: u16, height: u16, color: ColorType) -> Vec<u8> {
+        let mut output = Vec::new();
+        Encoder::new(&mut output, 100)
+            .encode(pixels, width, height, color)
+            .unwrap();
+        output
+    }
+
+    #[test]
+    fn test_decode_grayscale() {
+        let pixels: Vec<u8> = (0..64).map(|i| (i * 4) as u8).collect();
+        let jpeg = encode(&pixels, 8, 8, ColorType::Luma);
+
+        let output = JpegDecoder.decode_frame(&jpeg, &descriptor(8, 8, 8, 1)).unwrap();
+        assert_eq!(output.len(), 64);
+        // Con calidad 100 el error debe ser mínimo
+        assert!(output.iter().zip(&pixels).all(|(a, b)| a.abs_diff(*b) <= 4));
+    }
+
+    #[test]
+    fn test_decode_color_outputs_rgb() {
+        let pixels: Vec<u8> = [200u8, 40, 40].repeat(64);
+        let jpeg = encode(&pixels, 8, 8, ColorType::Rgb);
+
+        let mut descriptor = descriptor(8, 8, 8, 3);
+        descriptor.photometric_interpretation = "YBR_FULL_422".to_string();
+
+        let output = JpegDecoder.decode_frame(&jpeg, &descriptor).unwrap();
+        assert!(output.iter().zip(&pixels).all(|(a, b)| a.abs_diff(*b) <= 4));
+        assert_eq!(JpegDecoder.output_photometric_interpretation(&descriptor), "RGB");
+    }
+
+    #[test]
+    fn test_dimension_mismatch() {
+        let jpeg = encode(&[0; 64], 8, 8, ColorType::Luma);
+        let result = JpegDecoder.decode_frame(&jpeg, &descriptor(4, 4, 8, 1));
+        assert!(matches!(result, Err(DicomError::CorruptedPixelData)));
+    }
+
+    #[test]
+    fn test_stream_larger_than_descriptor_is_rejected_before_decoding() {
+        // Cabecera SOF que declara 65535x65535 en un stream de pocos bytes
+        let mut jpeg = encode(&[0; 64], 8, 8, ColorType::Luma);
+        let sof = jpeg.windows(2).position(|w| w == [0xFF, 0xC0]).unwrap();
+        jpeg[sof + 5..sof + 9].copy_from_slice(&[0xFF; 4]);
+
+        let result = JpegDecoder.decode_frame(&jpeg, &descriptor(8, 8, 8, 1));
+        assert!(matches!(result, Err(DicomError::CorruptedPixelData)));
+    }
+}
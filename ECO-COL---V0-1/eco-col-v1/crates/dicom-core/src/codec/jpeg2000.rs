@@ -0,0 +1,152 @@
+//! JPEG 2000 Lossless y Lossy vía `jpeg2k` (OpenJPEG en Rust puro)
+
+use super::PixelDecoder;
+use crate::error::{DicomError, Result};
+use crate::pixel::PixelDataDescriptor;
+
+/// Decodificador JPEG 2000 (Part 1)
+pub struct Jpeg2000Decoder;
+
+impl PixelDecoder for Jpeg2000Decoder {
+    fn transfer_syntaxes(&self) -> &[&'static str] {
+        &[
+            "1.2.840.10008.1.2.4.90", // JPEG 2000 Lossless Only
+            "1.2.840.10008.1.2.4.91", // JPEG 2000
+        ]
+    }
+
+    fn decode_frame(&self, data: &[u8], descriptor: &PixelDataDescriptor) -> Result<Vec<u8>> {
+        let image = jpeg2k::Image::from_bytes(data)
+            .map_err(|e| DicomError::parse(format!("Error decoding JPEG 2000: {}", e)))?;
+
+        let components = image.components();
+        if components.len() != descriptor.samples_per_pixel as usize
+            || components
+                .iter()
+                .any(|c| c.width() != descriptor.columns || c.height() != descriptor.rows)
+        {
+            return Err(DicomError::CorruptedPixelData);
+        }
+
+        let pixels = (descriptor.rows as usize)
+            .checked_mul(descriptor.columns as usize)
+            .filter(|&pixels| components.iter().all(|c| c.data().len() >= pixels))
+            .ok_or(DicomError::CorruptedPixelData)?;
+        let bytes_per_sample = match descriptor.bits_allocated {
+            8 => 1,
+            16 => 2,
+            bits => {
+                return Err(DicomError::parse(format!(
+                    "JPEG 2000 con Bits Allocated {} no soportado",
+                    bits
+                )))
+            }
+        };
+
+        // OpenJPEG entrega cada componente en su propio plano
+        let mut output = Vec::with_capacity(pixels * components.len() * bytes_per_sample);
+        for pixel in 0..pixels {
+            for component in components {
+                let value = component.data()[pixel];
+                if bytes_per_sample == 1 {
+                    output.push(value as u8);
+                } else {
+                    output.extend_from_slice(&(value as u16).to_le_bytes());
+                }
+            }
+        }
+
+        Ok(output)
+    }
+
+    fn output_photometric_interpretation(&self, descriptor: &PixelDataDescriptor) -> String {
+        // La transformación de color (YBR_RCT / YBR_ICT) se invierte al decodificar
+        if descriptor.samples_per_pixel == 3 {
+            "RGB".to_string()
+        } else {
+            descriptor.photometric_interpretation.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel::tests::descriptor;
+
+    /// Header común de los codestreams de prueba (comentario COM de OpenJPEG)
+    const COMMENT: &[u8] = b"Created by OpenJPEG version 2.5.2";
+
+    fn codestream(head: &[u8], tail: &[u8]) -> Vec<u8> {
+        [head, &[255, 100, 0, 37, 0, 1], COMMENT, tail].concat()
+    }
+
+    #[test]
+    fn test_decode_grayscale_lossless() {
+        #[rustfmt::skip]
+        let stream = codestream(
+            &[
+                255, 79, 255, 81, 0, 41, 0, 0, 0, 0, 0, 4, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4,
+                0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 7, 1, 1, 255, 82, 0, 12, 0, 0, 0, 1, 0, 1, 4, 4,
+                0, 1, 255, 92, 0, 7, 64, 64, 72, 72, 80,
+            ],
+            &[
+                255, 144, 0, 10, 0, 0, 0, 0, 0, 30, 0, 1, 255, 147, 223, 128, 48, 7, 138, 193, 141, 149,
+                223, 195, 234, 3, 0, 13, 2, 17, 255, 217,
+            ],
+        );
+
+        let output = Jpeg2000Decoder.decode_frame(&stream, &descriptor(3, 4, 8, 1)).unwrap();
+        assert_eq!(output, vec![0, 37, 74, 111, 11, 48, 85, 122, 22, 59, 96, 133]);
+    }
+
+    #[test]
+    fn test_decode_rgb_interleaves_components() {
+        #[rustfmt::skip]
+        let stream = codestream(
+            &[
+                255, 79, 255, 81, 0, 47, 0, 0, 0, 0, 0, 3, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 3,
+                0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 3, 7, 1, 1, 7, 1, 1, 7, 1, 1, 255, 82, 0, 12, 0, 0,
+                0, 1, 1, 1, 4, 4, 0, 1, 255, 92, 0, 7, 64, 64, 72, 72, 80,
+            ],
+            &[
+                255, 144, 0, 10, 0, 0, 0, 0, 0, 40, 0, 1, 255, 147, 199, 212, 6, 6, 49, 231, 199, 212, 6,
+                1, 159, 63, 199, 212, 6, 8, 87, 95, 160, 124, 128, 128, 2, 95, 128, 128, 255, 217,
+            ],
+        );
+
+        let mut descriptor = descriptor(2, 3, 8, 3);
+        descriptor.photometric_interpretation = "YBR_RCT".to_string();
+
+        let output = Jpeg2000Decoder.decode_frame(&stream, &descriptor).unwrap();
+        assert_eq!(
+            output,
+            vec![0, 60, 120, 37, 97, 157, 74, 134, 194, 11, 71, 131, 48, 108, 168, 85, 145, 205]
+        );
+        assert_eq!(Jpeg2000Decoder.output_photometric_interpretation(&descriptor), "RGB");
+    }
+
+    #[test]
+    fn test_decode_signed_12bit() {
+        #[rustfmt::skip]
+        let stream = codestream(
+            &[
+                255, 79, 255, 81, 0, 41, 0, 0, 0, 0, 0, 3, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 3,
+                0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 139, 1, 1, 255, 82, 0, 12, 0, 0, 0, 1, 0, 1, 4,
+                4, 0, 1, 255, 92, 0, 7, 64, 96, 104, 104, 112,
+            ],
+            &[
+                255, 144, 0, 10, 0, 0, 0, 0, 0, 27, 0, 1, 255, 147, 207, 228, 16, 8, 92, 130, 95, 160, 7,
+                200, 8, 2, 95, 255, 217,
+            ],
+        );
+
+        let mut descriptor = descriptor(2, 3, 16, 1);
+        descriptor.bits_stored = 12;
+        descriptor.pixel_representation = 1;
+
+        let output = Jpeg2000Decoder.decode_frame(&stream, &descriptor).unwrap();
+        let values: Vec<i16> = output.chunks_exact(2).map(|b| i16::from_le_bytes([b[0], b[1]])).collect();
+        assert_eq!(values, vec![-2048, -2011, -1974, -2037, -2000, -1963]);
+    }
+}
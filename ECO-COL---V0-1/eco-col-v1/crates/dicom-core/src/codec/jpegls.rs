@@ -0,0 +1,925 @@
+//! JPEG-LS Lossless y Near-Lossless (ISO/IEC 14495-1)
+//!
+//! Implementación propia, sin dependencias. Soporta los tres modos de
+//! intercalado y los parámetros LSE de tipo 1; no soporta tablas de mapeo,
+//! restart markers ni submuestreo.
+
+use super::PixelDecoder;
+use crate::error::{DicomError, Result};
+use crate::pixel::PixelDataDescriptor;
+
+const SOI: u8 = 0xD8;
+const EOI: u8 = 0xD9;
+const SOS: u8 = 0xDA;
+const COM: u8 = 0xFE;
+const SOF55: u8 = 0xF7;
+const LSE: u8 = 0xF8;
+
+/// Umbrales básicos para calcular T1, T2 y T3 por defecto (C.2.4.1.1.1)
+const BASIC_T1: i32 = 3;
+const BASIC_T2: i32 = 7;
+const BASIC_T3: i32 = 21;
+
+/// Longitud de las corridas según el índice de run mode (tabla J, A.2.1)
+const J: [u32; 32] = [
+    0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 9, 10, 11, 12, 13, 14, 15,
+];
+
+/// Decodificador JPEG-LS
+pub struct JpegLsDecoder;
+
+impl PixelDecoder for JpegLsDecoder {
+    fn transfer_syntaxes(&self) -> &[&'static str] {
+        &[
+            "1.2.840.10008.1.2.4.80", // JPEG-LS Lossless
+            "1.2.840.10008.1.2.4.81", // JPEG-LS Near-Lossless
+        ]
+    }
+
+    fn decode_frame(&self, data: &[u8], descriptor: &PixelDataDescriptor) -> Result<Vec<u8>> {
+        let image = decode(data)?;
+
+        if image.width != descriptor.columns
+            || image.height != descriptor.rows
+            || image.components != descriptor.samples_per_pixel as usize
+        {
+            return Err(DicomError::CorruptedPixelData);
+        }
+
+        match descriptor.bits_allocated {
+            8 if image.bits <= 8 => Ok(image.samples.iter().map(|&s| s as u8).collect()),
+            16 => Ok(image.samples.iter().flat_map(|s| s.to_le_bytes()).collect()),
+            bits => Err(DicomError::parse(format!(
+                "JPEG-LS de {} bits no cabe en Bits Allocated {}",
+                image.bits, bits
+            ))),
+        }
+    }
+}
+
+/// Imagen decodificada, con las muestras intercaladas por píxel
+struct Image {
+    width: u32,
+    height: u32,
+    components: usize,
+    bits: u8,
+    samples: Vec<u16>,
+}
+
+/// Header del frame (SOF55)
+struct Frame {
+    width: usize,
+    height: usize,
+    bits: u8,
+    component_ids: Vec<u8>,
+}
+
+/// Parámetros LSE de tipo 1 (0 = valor por defecto)
+#[derive(Default)]
+struct Preset {
+    maxval: i32,
+    t1: i32,
+    t2: i32,
+    t3: i32,
+    reset: i32,
+}
+
+/// Decodificar un stream JPEG-LS completo (SOI ... EOI)
+fn decode(data: &[u8]) -> Result<Image> {
+    let mut markers = MarkerReader { data, pos: 0 };
+    if markers.next_marker()? != SOI {
+        return Err(DicomError::parse("Stream JPEG-LS sin marcador SOI"));
+    }
+
+    let mut frame: Option<Frame> = None;
+    let mut preset = Preset::default();
+    let mut samples = Vec::new();
+    let mut decoded = Vec::new();
+
+    loop {
+        match markers.next_marker()? {
+            SOF55 => {
+                let segment = markers.segment()?;
+                let parsed = parse_frame(segment)?;
+                // Cada línea de cada componente ocupa al menos un bit del stream,
+                // así que un header que declara más líneas que bits es corrupto
+                let lines = parsed.height * parsed.component_ids.len();
+                if lines > data.len().saturating_mul(8) {
+                    return Err(DicomError::CorruptedPixelData);
+                }
+                let len = lines.checked_mul(parsed.width).ok_or(DicomError::CorruptedPixelData)?;
+                samples = vec![0u16; len];
+                decoded = vec![false; parsed.component_ids.len()];
+                frame = Some(parsed);
+            }
+            LSE => preset = parse_preset(markers.segment()?)?,
+            SOS => {
+                let frame = frame
+                    .as_ref()
+                    .ok_or_else(|| DicomError::parse("Scan JPEG-LS antes del header de frame"))?;
+                let header = parse_scan_header(markers.segment()?, frame)?;
+
+                let start = markers.pos;
+                let end = scan_end(data, start);
+                let params = Parameters::new(frame.bits, &preset, header.near)?;
+                decode_scan(&data[start..end], frame, &header, params, &mut samples)?;
+                header.components.iter().for_each(|&c| decoded[c] = true);
+                markers.pos = end;
+            }
+            EOI => break,
+            0xE0..=0xEF | COM => {
+                markers.segment()?;
+            }
+            marker => {
+                return Err(DicomError::parse(format!(
+                    "Marcador JPEG-LS no soportado: FF{:02X}",
+                    marker
+                )))
+            }
+        }
+    }
+
+    let frame = frame.ok_or_else(|| DicomError::parse("Stream JPEG-LS sin header de frame"))?;
+    if decoded.iter().any(|&done| !done) {
+        return Err(DicomError::CorruptedPixelData);
+    }
+
+    Ok(Image {
+        width: frame.width as u32,
+        height: frame.height as u32,
+        components: frame.component_ids.len(),
+        bits: frame.bits,
+        samples,
+    })
+}
+
+/// Lector de marcadores y segmentos
+struct MarkerReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> MarkerReader<'a> {
+    fn byte(&mut self) -> Result<u8> {
+        let byte = *self.data.get(self.pos).ok_or(DicomError::CorruptedPixelData)?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn next_marker(&mut self) -> Result<u8> {
+        if self.byte()? != 0xFF {
+            return Err(DicomError::parse("Se esperaba un marcador JPEG-LS"));
+        }
+        // Puede haber bytes 0xFF de relleno antes del código
+        loop {
+            match self.byte()? {
+                0xFF => continue,
+                marker => return Ok(marker),
+            }
+        }
+    }
+
+    fn segment(&mut self) -> Result<&'a [u8]> {
+        let length = u16::from_be_bytes([self.byte()?, self.byte()?]) as usize;
+        let end = self.pos + length.checked_sub(2).ok_or(DicomError::CorruptedPixelData)?;
+        let segment = self.data.get(self.pos..end).ok_or(DicomError::CorruptedPixelData)?;
+        self.pos = end;
+        Ok(segment)
+    }
+}
+
+fn read_u16(bytes: &[u8], at: usize) -> Result<u16> {
+    bytes
+        .get(at..at + 2)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]))
+        .ok_or(DicomError::CorruptedPixelData)
+}
+
+fn parse_frame(segment: &[u8]) -> Result<Frame> {
+    let bits = *segment.first().ok_or(DicomError::CorruptedPixelData)?;
+    let height = read_u16(segment, 1)? as usize;
+    let width = read_u16(segment, 3)? as usize;
+    let count = *segment.get(5).ok_or(DicomError::CorruptedPixelData)? as usize;
+
+    if !(2..=16).contains(&bits) || width == 0 || height == 0 || count == 0 {
+        return Err(DicomError::parse("Header de frame JPEG-LS inválido"));
+    }
+
+    let components = segment.get(6..6 + count * 3).ok_or(DicomError::CorruptedPixelData)?;
+    if components.chunks_exact(3).any(|c| c[1] != 0x11) {
+        return Err(DicomError::parse("JPEG-LS con submuestreo no soportado"));
+    }
+
+    Ok(Frame {
+        width,
+        height,
+        bits,
+        component_ids: components.chunks_exact(3).map(|c| c[0]).collect(),
+    })
+}
+
+fn parse_preset(segment: &[u8]) -> Result<Preset> {
+    match segment.first() {
+        Some(1) => Ok(Preset {
+            maxval: read_u16(segment, 1)? as i32,
+            t1: read_u16(segment, 3)? as i32,
+            t2: read_u16(segment, 5)? as i32,
+            t3: read_u16(segment, 7)? as i32,
+            reset: read_u16(segment, 9)? as i32,
+        }),
+        _ => Err(DicomError::parse("Segmento LSE de JPEG-LS no soportado")),
+    }
+}
+
+/// Header de un scan (SOS)
+struct ScanHeader {
+    /// Índices (dentro del frame) de los componentes del scan
+    components: Vec<usize>,
+    near: i32,
+    interleave: u8,
+}
+
+fn parse_scan_header(segment: &[u8], frame: &Frame) -> Result<ScanHeader> {
+    let count = *segment.first().ok_or(DicomError::CorruptedPixelData)? as usize;
+    let selectors = segment.get(1..1 + count * 2).ok_or(DicomError::CorruptedPixelData)?;
+    let tail = segment.get(1 + count * 2..4 + count * 2).ok_or(DicomError::CorruptedPixelData)?;
+
+    let mut components = Vec::with_capacity(count);
+    for selector in selectors.chunks_exact(2) {
+        if selector[1] != 0 {
+            return Err(DicomError::parse("JPEG-LS con tablas de mapeo no soportado"));
+        }
+        let index = frame
+            .component_ids
+            .iter()
+            .position(|&id| id == selector[0])
+            .ok_or(DicomError::CorruptedPixelData)?;
+        components.push(index);
+    }
+
+    let (near, interleave, point_transform) = (tail[0] as i32, tail[1], tail[2] & 0x0F);
+    if point_transform != 0 {
+        return Err(DicomError::parse("JPEG-LS con point transform no soportado"));
+    }
+
+    let valid = match interleave {
+        0 => count == 1,
+        1 | 2 => count == frame.component_ids.len(),
+        _ => false,
+    };
+    if !valid {
+        return Err(DicomError::parse(format!(
+            "Modo de intercalado JPEG-LS inválido: {} con {} componentes",
+            interleave, count
+        )));
+    }
+
+    Ok(ScanHeader {
+        components,
+        near,
+        interleave,
+    })
+}
+
+/// Fin de los datos del scan: primer 0xFF seguido de un byte >= 0x80
+///
+/// Dentro del scan, después de cada 0xFF se inserta un bit 0, así que el
+/// byte siguiente nunca tiene el bit más significativo en 1.
+fn scan_end(data: &[u8], start: usize) -> usize {
+    data[start..]
+        .windows(2)
+        .position(|w| w[0] == 0xFF && w[1] >= 0x80)
+        .map_or(data.len(), |i| start + i)
+}
+
+/// Parámetros derivados de MAXVAL y NEAR (A.2.1 y C.2.4.1.1)
+#[derive(Clone, Copy)]
+struct Parameters {
+    maxval: i32,
+    near: i32,
+    range: i32,
+    qbpp: u32,
+    limit: u32,
+    t1: i32,
+    t2: i32,
+    t3: i32,
+    reset: i32,
+}
+
+impl Parameters {
+    fn new(bits: u8, preset: &Preset, near: i32) -> Result<Self> {
+        let maxval = if preset.maxval != 0 { preset.maxval } else { (1 << bits) - 1 };
+        if near > (maxval / 2).min(255) {
+            return Err(DicomError::parse(format!("NEAR de JPEG-LS inválido: {}", near)));
+        }
+
+        let range = (maxval + 2 * near) / (2 * near + 1) + 1;
+        let bpp = ceil_log2(maxval + 1).max(2);
+
+        let clamp = |i: i32, j: i32| if i > maxval || i < j { j } else { i };
+        let (t1, t2, t3) = if maxval >= 128 {
+            let factor = (maxval.min(4095) + 128) / 256;
+            let t1 = clamp(factor * (BASIC_T1 - 2) + 2 + 3 * near, near + 1);
+            let t2 = clamp(factor * (BASIC_T2 - 3) + 3 + 5 * near, t1);
+            (t1, t2, clamp(factor * (BASIC_T3 - 4) + 4 + 7 * near, t2))
+        } else {
+            let factor = 256 / (maxval + 1);
+            let t1 = clamp((BASIC_T1 / factor + 3 * near).max(2), near + 1);
+            let t2 = clamp((BASIC_T2 / factor + 5 * near).max(3), t1);
+            (t1, t2, clamp((BASIC_T3 / factor + 7 * near).max(4), t2))
+        };
+
+        let or_default = |value: i32, default: i32| if value != 0 { value } else { default };
+
+        Ok(Self {
+            maxval,
+            near,
+            range,
+            qbpp: ceil_log2(range),
+            limit: 2 * (bpp + bpp.max(8)),
+            t1: or_default(preset.t1, t1),
+            t2: or_default(preset.t2, t2),
+            t3: or_default(preset.t3, t3),
+            reset: or_default(preset.reset, 64),
+        })
+    }
+
+    /// Cuantizar un gradiente local a -4..=4
+    fn quantize(&self, d: i32) -> i32 {
+        match d {
+            d if d <= -self.t3 => -4,
+            d if d <= -self.t2 => -3,
+            d if d <= -self.t1 => -2,
+            d if d < -self.near => -1,
+            d if d <= self.near => 0,
+            d if d < self.t1 => 1,
+            d if d < self.t2 => 2,
+            d if d < self.t3 => 3,
+            _ => 4,
+        }
+    }
+
+    fn clamp(&self, value: i32) -> i32 {
+        value.clamp(0, self.maxval)
+    }
+
+    /// Reconstruir una muestra a partir de la predicción y el error
+    fn reconstruct(&self, predicted: i32, error: i32) -> i32 {
+        let step = 2 * self.near + 1;
+        let mut value = predicted + error * step;
+        if value < -self.near {
+            value += self.range * step;
+        } else if value > self.maxval + self.near {
+            value -= self.range * step;
+        }
+        self.clamp(value)
+    }
+}
+
+fn ceil_log2(n: i32) -> u32 {
+    let mut x = 0;
+    while (1 << x) < n {
+        x += 1;
+    }
+    x
+}
+
+/// Contexto del modo regular (A, B, C, N)
+#[derive(Clone, Copy)]
+struct RegularContext {
+    a: i32,
+    b: i32,
+    c: i32,
+    n: i32,
+}
+
+impl RegularContext {
+    fn new(range: i32) -> Self {
+        Self {
+            a: ((range + 32) / 64).max(2),
+            b: 0,
+            c: 0,
+            n: 1,
+        }
+    }
+
+    fn golomb_k(&self) -> Result<u32> {
+        let mut k = 0;
+        while (self.n << k) < self.a {
+            k += 1;
+            if k == 16 {
+                return Err(DicomError::CorruptedPixelData);
+            }
+        }
+        Ok(k)
+    }
+
+    /// Actualizar variables y corrección de bias (A.6)
+    fn update(&mut self, error: i32, near: i32, reset: i32) -> Result<()> {
+        self.a += error.abs();
+        self.b += error * (2 * near + 1);
+        if self.a >= 1 << 24 || self.b.abs() >= 1 << 24 {
+            return Err(DicomError::CorruptedPixelData);
+        }
+
+        if self.n == reset {
+            self.a >>= 1;
+            self.b >>= 1;
+            self.n >>= 1;
+        }
+        self.n += 1;
+
+        if self.b + self.n <= 0 {
+            self.b += self.n;
+            if self.b <= -self.n {
+                self.b = -self.n + 1;
+            }
+            if self.c > -128 {
+                self.c -= 1;
+            }
+        } else if self.b > 0 {
+            self.b -= self.n;
+            if self.b > 0 {
+                self.b = 0;
+            }
+            if self.c < 127 {
+                self.c += 1;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Contexto de interrupción de corrida (A.7.2)
+#[derive(Clone, Copy)]
+struct RunContext {
+    kind: i32,
+    a: i32,
+    n: i32,
+    nn: i32,
+}
+
+impl RunContext {
+    fn new(kind: i32, range: i32) -> Self {
+        Self {
+            kind,
+            a: ((range + 32) / 64).max(2),
+            n: 1,
+            nn: 0,
+        }
+    }
+
+    fn golomb_k(&self) -> u32 {
+        let target = self.a + (self.n >> 1) * self.kind;
+        let mut k = 0;
+        while (self.n << k) < target {
+            k += 1;
+        }
+        k
+    }
+
+    fn error_value(&self, mapped: i32, k: u32) -> i32 {
+        let map = mapped & 1 == 1;
+        let magnitude = (mapped + map as i32) / 2;
+        if (k != 0 || 2 * self.nn >= self.n) == map {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    fn update(&mut self, error: i32, mapped: i32, reset: i32) {
+        if error < 0 {
+            self.nn += 1;
+        }
+        self.a += (mapped + 1 - self.kind) >> 1;
+
+        if self.n == reset {
+            self.a >>= 1;
+            self.n >>= 1;
+            self.nn >>= 1;
+        }
+        self.n += 1;
+    }
+}
+
+/// Lector de bits del scan, descartando el bit de relleno tras cada 0xFF
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    cache: u64,
+    bits: u32,
+    after_ff: bool,
+    padding: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            pos: 0,
+            cache: 0,
+            bits: 0,
+            after_ff: false,
+            padding: 0,
+        }
+    }
+
+    fn ensure(&mut self, n: u32) -> Result<()> {
+        while self.bits < n {
+            let (value, width) = match self.data.get(self.pos) {
+                Some(&byte) => {
+                    let width = if self.after_ff { 7 } else { 8 };
+                    self.after_ff = byte == 0xFF;
+                    self.pos += 1;
+                    (u64::from(byte) & ((1 << width) - 1), width)
+                }
+                None => {
+                    // El encoder completa el último byte con ceros
+                    self.padding += 1;
+                    if self.padding > 4 {
+                        return Err(DicomError::CorruptedPixelData);
+                    }
+                    (0, 8)
+                }
+            };
+            self.cache |= value << (64 - self.bits - width);
+            self.bits += width;
+        }
+        Ok(())
+    }
+
+    fn consume(&mut self, n: u32) {
+        self.cache <<= n;
+        self.bits -= n;
+    }
+
+    fn read_bits(&mut self, n: u32) -> Result<i32> {
+        if n == 0 {
+            return Ok(0);
+        }
+        self.ensure(n)?;
+        let value = (self.cache >> (64 - n)) as i32;
+        self.consume(n);
+        Ok(value)
+    }
+
+    fn read_bit(&mut self) -> Result<bool> {
+        Ok(self.read_bits(1)? == 1)
+    }
+
+    /// Contar ceros hasta el siguiente 1 (parte unaria del código Golomb)
+    fn read_high_bits(&mut self) -> Result<u32> {
+        let mut count = 0;
+        loop {
+            self.ensure(1)?;
+            let zeros = self.cache.leading_zeros().min(self.bits);
+            if zeros < self.bits {
+                self.consume(zeros + 1);
+                return Ok(count + zeros);
+            }
+            count += self.bits;
+            self.consume(self.bits);
+            if count > 64 {
+                return Err(DicomError::CorruptedPixelData);
+            }
+        }
+    }
+
+    /// Leer un valor Golomb con longitud máxima `limit` (A.5.3)
+    fn decode_value(&mut self, k: u32, limit: u32, qbpp: u32) -> Result<i32> {
+        let high = self.read_high_bits()?;
+        if high >= limit - (qbpp + 1) {
+            return Ok(self.read_bits(qbpp)? + 1);
+        }
+        Ok(((high as i32) << k) + self.read_bits(k)?)
+    }
+}
+
+/// Estado de decodificación de un scan
+struct ScanDecoder<'a> {
+    reader: BitReader<'a>,
+    params: Parameters,
+    contexts: Vec<RegularContext>,
+    run_contexts: [RunContext; 2],
+    run_index: usize,
+}
+
+impl ScanDecoder<'_> {
+    fn context_id(&self, ra: i32, rb: i32, rc: i32, rd: i32) -> i32 {
+        let p = &self.params;
+        (p.quantize(rd - rb) * 9 + p.quantize(rb - rc)) * 9 + p.quantize(rc - ra)
+    }
+
+    /// Decodificar una muestra en modo regular
+    fn decode_regular(&mut self, qs: i32, ra: i32, rb: i32, rc: i32) -> Result<i32> {
+        let sign = if qs < 0 { -1 } else { 1 };
+        let context = &mut self.contexts[qs.unsigned_abs() as usize];
+        let k = context.golomb_k()?;
+        let predicted = self.params.clamp(predict(ra, rb, rc) + sign * context.c);
+
+        let mapped = self.reader.decode_value(k, self.params.limit, self.params.qbpp)?;
+        let mut error = unmap(mapped);
+        if k == 0 && self.params.near == 0 && 2 * context.b + context.n - 1 < 0 {
+            error = !error;
+        }
+
+        context.update(error, self.params.near, self.params.reset)?;
+        Ok(self.params.reconstruct(predicted, sign * error))
+    }
+
+    /// Decodificar la longitud de una corrida de hasta `remaining` muestras
+    fn decode_run_length(&mut self, remaining: usize) -> Result<usize> {
+        let mut count = 0;
+        while self.reader.read_bit()? {
+            let full = 1usize << J[self.run_index];
+            let step = full.min(remaining - count);
+            count += step;
+            if step == full {
+                self.run_index = (self.run_index + 1).min(31);
+            }
+            if count == remaining {
+                break;
+            }
+        }
+
+        if count != remaining {
+            count += self.reader.read_bits(J[self.run_index])? as usize;
+        }
+        if count > remaining {
+            return Err(DicomError::CorruptedPixelData);
+        }
+        Ok(count)
+    }
+
+    /// Decodificar la muestra que interrumpe una corrida
+    fn decode_run_interruption(&mut self, kind: usize, ra: i32, rb: i32) -> Result<i32> {
+        let context = &mut self.run_contexts[kind];
+        let k = context.golomb_k();
+        let limit = self.params.limit - J[self.run_index] - 1;
+        let mapped = self.reader.decode_value(k, limit, self.params.qbpp)?;
+        let error = context.error_value(mapped + context.kind, k);
+        context.update(error, mapped, self.params.reset);
+
+        if kind == 1 {
+            Ok(self.params.reconstruct(ra, error))
+        } else {
+            let sign = if rb - ra < 0 { -1 } else { 1 };
+            Ok(self.params.reconstruct(rb, error * sign))
+        }
+    }
+
+    /// Decodificar una línea de un solo componente
+    ///
+    /// `prev` y `cur` tienen un píxel extra a cada lado: la muestra `x`
+    /// está en el índice `x + 1`.
+    fn decode_line(&mut self, prev: &[i32], cur: &mut [i32]) -> Result<()> {
+        let width = cur.len() - 2;
+        let mut x = 0;
+
+        while x < width {
+            let (ra, rc, rb, rd) = (cur[x], prev[x], prev[x + 1], prev[x + 2]);
+            let qs = self.context_id(ra, rb, rc, rd);
+
+            if qs != 0 {
+                cur[x + 1] = self.decode_regular(qs, ra, rb, rc)?;
+                x += 1;
+                continue;
+            }
+
+            let count = self.decode_run_length(width - x)?;
+            cur[x + 1..x + 1 + count].fill(ra);
+            x += count;
+
+            if x < width {
+                let rb = prev[x + 1];
+                let kind = if (ra - rb).abs() <= self.params.near { 1 } else { 0 };
+                cur[x + 1] = self.decode_run_interruption(kind, ra, rb)?;
+                self.run_index = self.run_index.saturating_sub(1);
+                x += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Decodificar una línea con las muestras intercaladas (ILV = 2)
+    fn decode_sample_line(&mut self, prev: &[Vec<i32>], cur: &mut [Vec<i32>]) -> Result<()> {
+        let width = cur[0].len() - 2;
+        let mut qs = vec![0; cur.len()];
+        let mut x = 0;
+
+        while x < width {
+            for (c, q) in qs.iter_mut().enumerate() {
+                *q = self.context_id(cur[c][x], prev[c][x + 1], prev[c][x], prev[c][x + 2]);
+            }
+
+            if qs.iter().any(|&q| q != 0) {
+                for (c, &q) in qs.iter().enumerate() {
+                    cur[c][x + 1] = self.decode_regular(q, cur[c][x], prev[c][x + 1], prev[c][x])?;
+                }
+                x += 1;
+                continue;
+            }
+
+            let count = self.decode_run_length(width - x)?;
+            for line in cur.iter_mut() {
+                let ra = line[x];
+                line[x + 1..x + 1 + count].fill(ra);
+            }
+
+            let start = x;
+            x += count;
+            if x < width {
+                for c in 0..cur.len() {
+                    cur[c][x + 1] = self.decode_run_interruption(0, cur[c][start], prev[c][x + 1])?;
+                }
+                self.run_index = self.run_index.saturating_sub(1);
+                x += 1;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Predictor MED (mediana de Ra, Rb y Ra + Rb - Rc)
+fn predict(ra: i32, rb: i32, rc: i32) -> i32 {
+    if rc >= ra.max(rb) {
+        ra.min(rb)
+    } else if rc <= ra.min(rb) {
+        ra.max(rb)
+    } else {
+        ra + rb - rc
+    }
+}
+
+/// Invertir el mapeo de errores a enteros no negativos
+fn unmap(mapped: i32) -> i32 {
+    if mapped & 1 == 0 {
+        mapped >> 1
+    } else {
+        !(mapped >> 1)
+    }
+}
+
+/// Decodificar un scan y escribir sus muestras en `samples`
+fn decode_scan(
+    data: &[u8],
+    frame: &Frame,
+    header: &ScanHeader,
+    params: Parameters,
+    samples: &mut [u16],
+) -> Result<()> {
+    let mut scan = ScanDecoder {
+        reader: BitReader::new(data),
+        params,
+        contexts: vec![RegularContext::new(params.range); 365],
+        run_contexts: [RunContext::new(0, params.range), RunContext::new(1, params.range)],
+        run_index: 0,
+    };
+
+    let lines = header.components.len();
+    let stride = frame.component_ids.len();
+    let mut prev = vec![vec![0i32; frame.width + 2]; lines];
+    let mut cur = prev.clone();
+    let mut run_indexes = vec![0; lines];
+
+    for y in 0..frame.height {
+        if y > 0 {
+            std::mem::swap(&mut prev, &mut cur);
+        }
+
+        // Bordes: Rd al final de la línea y Ra/Rc al inicio
+        for (prev, cur) in prev.iter_mut().zip(cur.iter_mut()) {
+            prev[frame.width + 1] = prev[frame.width];
+            cur[0] = prev[1];
+        }
+
+        if header.interleave == 2 {
+            scan.decode_sample_line(&prev, &mut cur)?;
+        } else {
+            // ILV 1 comparte contextos, pero cada componente tiene su índice de corrida
+            for line in 0..lines {
+                scan.run_index = run_indexes[line];
+                scan.decode_line(&prev[line], &mut cur[line])?;
+                run_indexes[line] = scan.run_index;
+            }
+        }
+
+        for (line, &component) in header.components.iter().enumerate() {
+            for x in 0..frame.width {
+                samples[(y * frame.width + x) * stride + component] = cur[line][x + 1] as u16;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel::tests::descriptor;
+
+    // Streams generados con CharLS a partir de `input`
+
+    #[test]
+    fn test_decode_lossless_8bit() {
+        #[rustfmt::skip]
+        let stream = [
+            255, 216, 255, 247, 0, 11, 8, 0, 4, 0, 6, 1, 1, 17, 0, 255, 218, 0, 8, 1, 1, 0, 0, 0, 0, 133,
+            0, 40, 207, 0, 0, 91, 0, 0, 0, 217, 0, 0, 0, 181, 128, 0, 0, 215, 58, 1, 88, 0, 0, 6, 248, 0,
+            0, 6, 217, 128, 0, 0, 89, 1, 171, 70, 51, 47, 0, 1, 0, 184, 255, 217,
+        ];
+        let input: Vec<u8> = vec![
+            0, 7, 28, 21, 28, 98, 3, 173, 119, 24, 85, 85, 6, 13, 105, 27, 34, 198, 9, 16, 23, 0, 37, 44,
+        ];
+
+        let output = JpegLsDecoder.decode_frame(&stream, &descriptor(4, 6, 8, 1)).unwrap();
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn test_decode_near_lossless() {
+        #[rustfmt::skip]
+        let stream = [
+            255, 216, 255, 247, 0, 11, 8, 0, 4, 0, 8, 1, 1, 17, 0, 255, 218, 0, 8, 1, 1, 0, 2, 0, 0, 176,
+            201, 128, 3, 16, 7, 0, 0, 0, 128, 0, 1, 136, 161, 4, 0, 0, 192, 0, 0, 64, 1, 128, 0, 82, 0, 0,
+            193, 242, 0, 3, 37, 140, 2, 136, 255, 217,
+        ];
+        let input: Vec<u8> = vec![
+            0, 7, 28, 21, 28, 98, 85, 173, 119, 10, 17, 24, 31, 85, 105, 85, 6, 198, 20, 27, 34, 0, 48,
+            55, 9, 16, 230, 30, 37, 44, 51, 63,
+        ];
+        // Salida de CharLS para el mismo stream
+        let expected: Vec<u8> = vec![
+            0, 5, 30, 20, 29, 99, 84, 173, 120, 10, 15, 25, 29, 84, 104, 83, 5, 200, 20, 25, 34, 0, 50,
+            55, 11, 15, 229, 30, 39, 45, 50, 65,
+        ];
+
+        let output = JpegLsDecoder.decode_frame(&stream, &descriptor(4, 8, 8, 1)).unwrap();
+        assert_eq!(output, expected);
+        // NEAR = 2: cada muestra a lo sumo a 2 del original
+        assert!(output.iter().zip(&input).all(|(a, b)| a.abs_diff(*b) <= 2));
+    }
+
+    #[test]
+    fn test_decode_12bit_to_little_endian() {
+        #[rustfmt::skip]
+        let stream = [
+            255, 216, 255, 247, 0, 11, 12, 0, 3, 0, 5, 1, 1, 17, 0, 255, 218, 0, 8, 1, 1, 0, 0, 0, 0, 166,
+            128, 0, 0, 0, 13, 20, 56, 128, 0, 0, 0, 14, 57, 128, 0, 7, 176, 0, 0, 80, 0, 0, 0, 0, 27, 33,
+            107, 0, 140, 0, 0, 8, 240, 128, 0, 0, 0, 0, 214, 100, 176, 51, 160, 255, 217,
+        ];
+        let input: Vec<u16> = vec![0, 7, 1308, 1365, 3868, 3426, 10, 2733, 2679, 31, 6, 13, 20, 27, 873];
+
+        let mut descriptor = descriptor(3, 5, 16, 1);
+        descriptor.bits_stored = 12;
+        let output = JpegLsDecoder.decode_frame(&stream, &descriptor).unwrap();
+        let expected: Vec<u8> = input.iter().flat_map(|v| v.to_le_bytes()).collect();
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_decode_sample_interleaved_rgb() {
+        #[rustfmt::skip]
+        let stream = [
+            255, 216, 255, 247, 0, 17, 8, 0, 3, 0, 4, 3, 1, 17, 0, 2, 17, 0, 3, 17, 0, 255, 218, 0, 12, 3,
+            1, 0, 2, 0, 3, 0, 0, 2, 0, 64, 0, 0, 44, 110, 6, 0, 16, 3, 162, 139, 218, 192, 0, 22, 112, 66,
+            101, 0, 0, 1, 153, 96, 0, 49, 225, 128, 0, 0, 117, 76, 64, 0, 0, 53, 2, 128, 0, 10, 200, 59,
+            38, 26, 20, 227, 0, 14, 128, 0, 0, 11, 104, 255, 217,
+        ];
+        let input: Vec<u8> = vec![
+            0, 50, 28, 7, 28, 98, 14, 173, 119, 85, 85, 85, 3, 53, 105, 10, 60, 198, 17, 67, 117, 0, 74,
+            124, 6, 56, 230, 13, 63, 113, 20, 63, 120, 27, 77, 179,
+        ];
+
+        let output = JpegLsDecoder.decode_frame(&stream, &descriptor(3, 4, 8, 3)).unwrap();
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn test_rejects_regular_jpeg() {
+        // SOI seguido de SOF0 (JPEG Baseline)
+        let stream = [255, 216, 255, 192, 0, 11, 8, 0, 1, 0, 1, 1, 1, 17, 0];
+        assert!(JpegLsDecoder.decode_frame(&stream, &descriptor(1, 1, 8, 1)).is_err());
+    }
+
+    #[test]
+    fn test_rejects_oversized_frame_header() {
+        // SOF55 de 65535x65535 con 255 componentes y ningún dato de scan
+        let mut stream = vec![255, 216, 255, 247, 3, 5, 8, 255, 255, 255, 255, 255];
+        for id in 1..=255u8 {
+            stream.extend_from_slice(&[id, 17, 0]);
+        }
+        stream.extend_from_slice(&[255, 217]);
+
+        let descriptor = descriptor(65535, 65535, 8, 255);
+        assert!(matches!(
+            JpegLsDecoder.decode_frame(&stream, &descriptor),
+            Err(DicomError::CorruptedPixelData)
+        ));
+    }
+}
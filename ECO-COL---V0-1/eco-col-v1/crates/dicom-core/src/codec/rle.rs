@@ -0,0 +1,173 @@
+//! RLE Lossless (PS3.5 Anexo G)
+
+use super::PixelDecoder;
+use crate::error::{DicomError, Result};
+use crate::pixel::PixelDataDescriptor;
+
+/// Decodificador RLE Lossless
+pub struct RleDecoder;
+
+impl PixelDecoder for RleDecoder {
+    fn transfer_syntaxes(&self) -> &[&'static str] {
+        &["1.2.840.10008.1.2.5"]
+    }
+
+    fn decode_frame(&self, data: &[u8], descriptor: &PixelDataDescriptor) -> Result<Vec<u8>> {
+        if data.len() < 64 {
+            return Err(DicomError::CorruptedPixelData);
+        }
+
+        // Header: número de segmentos + 15 offsets, todo u32 little endian
+        let header: Vec<usize> = data[..64]
+            .chunks_exact(4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+            .collect();
+
+        let bytes_per_sample = (descriptor.bits_allocated as usize).div_ceil(8);
+        let samples = descriptor.samples_per_pixel as usize;
+        let segments = header[0];
+        if segments != bytes_per_sample * samples || segments > 15 {
+            return Err(DicomError::CorruptedPixelData);
+        }
+
+        // Dimensiones que desbordan: el mismo error de validación que el resto
+        descriptor.checked_frame_size_bytes()?;
+        let pixels = (descriptor.rows as usize)
+            .checked_mul(descriptor.columns as usize)
+            .ok_or(DicomError::CorruptedPixelData)?;
+        let output_size = pixels.checked_mul(segments).ok_or(DicomError::CorruptedPixelData)?;
+
+        // Validar los segmentos antes de reservar: PackBits expande como
+        // máximo 128 veces (2 bytes -> 128 repeticiones)
+        let bounds = (0..segments)
+            .map(|segment| {
+                let start = header[segment + 1];
+                let end = if segment + 1 < segments { header[segment + 2] } else { data.len() };
+                if start < 64 || start > end || end > data.len() {
+                    return Err(DicomError::CorruptedPixelData);
+                }
+                if !(end - start).checked_mul(128).is_some_and(|max| max >= pixels) {
+                    return Err(DicomError::CorruptedPixelData);
+                }
+                Ok((start, end))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut output = vec![0u8; output_size];
+
+        // Un segmento por byte de cada muestra, del más al menos significativo
+        for (segment, (start, end)) in bounds.into_iter().enumerate() {
+            let plane = decode_packbits(&data[start..end], pixels)?;
+
+            let sample = segment / bytes_per_sample;
+            // Salida en little endian: el byte más significativo va al final
+            let byte = bytes_per_sample - 1 - segment % bytes_per_sample;
+            for (pixel, value) in plane.into_iter().enumerate() {
+                output[(pixel * samples + sample) * bytes_per_sample + byte] = value;
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+/// Decodificar un segmento PackBits hasta obtener `expected` bytes
+fn decode_packbits(segment: &[u8], expected: usize) -> Result<Vec<u8>> {
+    let mut output = Vec::with_capacity(expected);
+    let mut i = 0;
+
+    while output.len() < expected && i < segment.len() {
+        let n = segment[i] as i8;
+        i += 1;
+
+        match n {
+            // Copiar los siguientes n + 1 bytes literalmente
+            0..=127 => {
+                let count = n as usize + 1;
+                let literal = segment.get(i..i + count).ok_or(DicomError::CorruptedPixelData)?;
+                output.extend_from_slice(literal);
+                i += count;
+            }
+            // -128 no hace nada
+            -128 => {}
+            // Repetir el siguiente byte -n + 1 veces
+            _ => {
+                let count = (-(n as i16)) as usize + 1;
+                let value = *segment.get(i).ok_or(DicomError::CorruptedPixelData)?;
+                output.extend(std::iter::repeat(value).take(count));
+                i += 1;
+            }
+        }
+    }
+
+    if output.len() < expected {
+        return Err(DicomError::CorruptedPixelData);
+    }
+    output.truncate(expected);
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel::tests::descriptor;
+
+    /// Construir un frame RLE a partir de sus segmentos
+    fn rle_frame(segments: &[&[u8]]) -> Vec<u8> {
+        let mut header = [0u32; 16];
+        header[0] = segments.len() as u32;
+        let mut offset = 64;
+        for (i, segment) in segments.iter().enumerate() {
+            header[i + 1] = offset;
+            offset += segment.len() as u32;
+        }
+
+        let mut frame: Vec<u8> = header.iter().flat_map(|v| v.to_le_bytes()).collect();
+        segments.iter().for_each(|segment| frame.extend_from_slice(segment));
+        frame
+    }
+
+    #[test]
+    fn test_decode_8bit() {
+        // 2 literales (10, 20) y luego 3 repeticiones de 30
+        let frame = rle_frame(&[&[1, 10, 20, (-2i8) as u8, 30]]);
+        let output = RleDecoder.decode_frame(&frame, &descriptor(1, 5, 8, 1)).unwrap();
+        assert_eq!(output, vec![10, 20, 30, 30, 30]);
+    }
+
+    #[test]
+    fn test_decode_16bit_to_little_endian() {
+        // Segmento 0 = bytes altos, segmento 1 = bytes bajos
+        let frame = rle_frame(&[&[1, 0x01, 0x02], &[1, 0x34, 0x78]]);
+        let output = RleDecoder.decode_frame(&frame, &descriptor(1, 2, 16, 1)).unwrap();
+        assert_eq!(output, vec![0x34, 0x01, 0x78, 0x02]);
+    }
+
+    #[test]
+    fn test_decode_rgb_interleaves_samples() {
+        let frame = rle_frame(&[&[(-1i8) as u8, 255], &[(-1i8) as u8, 128], &[(-1i8) as u8, 0]]);
+        let output = RleDecoder.decode_frame(&frame, &descriptor(1, 2, 8, 3)).unwrap();
+        assert_eq!(output, vec![255, 128, 0, 255, 128, 0]);
+    }
+
+    #[test]
+    fn test_huge_dimensions_with_tiny_fragment() {
+        let segments: Vec<&[u8]> = vec![&[(-127i8) as u8, 0]; 6];
+        let frame = rle_frame(&segments);
+        let result = RleDecoder.decode_frame(&frame, &descriptor(65535, 65535, 16, 3));
+        assert!(matches!(result, Err(DicomError::CorruptedPixelData)));
+
+        // Offset dentro del header
+        let mut frame = rle_frame(&[&[0, 7]]);
+        frame[4..8].copy_from_slice(&16u32.to_le_bytes());
+        let result = RleDecoder.decode_frame(&frame, &descriptor(1, 1, 8, 1));
+        assert!(matches!(result, Err(DicomError::CorruptedPixelData)));
+    }
+
+    #[test]
+    fn test_truncated_segment() {
+        let frame = rle_frame(&[&[4, 1, 2]]);
+        let result = RleDecoder.decode_frame(&frame, &descriptor(1, 5, 8, 1));
+        assert!(matches!(result, Err(DicomError::CorruptedPixelData)));
+    }
+}
@@ -0,0 +1,1031 @@
+//! Validación de conformidad de IODs (PS3.3)
+//!
+//! Revisa el data set contra las tablas de módulos de Ultrasound Image
+//! (A.6) y Ultrasound Multi-frame Image (A.7): presencia según el tipo del
+//! atributo (1, 1C, 2, 2C, 3), VR, VM, valores enumerados y formato de
+//! fechas, horas y UIDs. El resultado es un [`ConformanceReport`] con severidades.
+//!
+//! Criterio de severidad:
+//! - `Error`: falta o está vacío un Type 1, o un valor de un atributo
+//!   Type 1/2 no cumple VR, VM, enumerados o formato.
+//! - `Warning`: falta un Type 2 (muchos equipos lo omiten sin que afecte la
+//!   lectura) o un atributo Type 3 tiene un valor inválido.
+//! - `Info`: observaciones, p.ej. SOP Class sin tabla de conformidad.
+
+use crate::error::{DicomError, Result};
+use crate::uid;
+
+use chrono::NaiveDate;
+use dicom::core::dictionary::{DataDictionary, DataDictionaryEntry};
+use dicom::core::value::{PrimitiveValue, Value};
+use dicom::core::{Tag, VR};
+use dicom::dictionary_std::{tags, uids, StandardDataDictionary};
+use dicom::object::mem::InMemElement;
+use dicom::object::InMemDicomObject;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+
+// ============================================
+// Reporte
+// ============================================
+
+/// Severidad de un hallazgo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        })
+    }
+}
+
+/// Problema de conformidad en un atributo
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Finding {
+    pub severity: Severity,
+
+    /// Módulo de PS3.3 ("Image Pixel", "Cine", ...)
+    pub module: String,
+
+    /// Tag, o ruta dentro de una secuencia: "(0018,6011)[0](0018,6024)"
+    pub tag: String,
+
+    /// Keyword del diccionario ("PatientSex")
+    pub keyword: String,
+
+    pub message: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}] {} {} {}: {}",
+            self.severity, self.module, self.tag, self.keyword, self.message
+        )
+    }
+}
+
+/// IODs con tabla de conformidad
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Iod {
+    UltrasoundImage,
+    UltrasoundMultiFrameImage,
+}
+
+impl Iod {
+    /// IOD correspondiente a un SOP Class UID
+    pub fn from_sop_class(uid: &str) -> Option<Self> {
+        match uid.trim_end_matches(['\0', ' ']) {
+            uids::ULTRASOUND_IMAGE_STORAGE => Some(Self::UltrasoundImage),
+            uids::ULTRASOUND_MULTI_FRAME_IMAGE_STORAGE => Some(Self::UltrasoundMultiFrameImage),
+            _ => None,
+        }
+    }
+
+    /// Nombre del IOD en PS3.3
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::UltrasoundImage => "Ultrasound Image",
+            Self::UltrasoundMultiFrameImage => "Ultrasound Multi-frame Image",
+        }
+    }
+
+    /// Módulos del IOD en el orden de PS3.3
+    fn modules(&self) -> Vec<&'static Module> {
+        let mut modules = vec![
+            &PATIENT,
+            &GENERAL_STUDY,
+            &GENERAL_SERIES,
+            &GENERAL_EQUIPMENT,
+            &GENERAL_IMAGE,
+            &IMAGE_PIXEL,
+        ];
+        if *self == Self::UltrasoundMultiFrameImage {
+            modules.extend([&CINE, &MULTI_FRAME]);
+        }
+        modules.extend([&US_REGION_CALIBRATION, &US_IMAGE, &SOP_COMMON]);
+        modules
+    }
+}
+
+/// Resultado de validar un data set
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConformanceReport {
+    /// IOD validado (None si el SOP Class no tiene tabla: solo se revisan
+    /// los módulos comunes)
+    pub iod: Option<Iod>,
+
+    pub findings: Vec<Finding>,
+}
+
+impl ConformanceReport {
+    /// Verificar que no hay errores (las advertencias no cuentan)
+    pub fn is_conformant(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// Severidad más alta encontrada
+    pub fn max_severity(&self) -> Option<Severity> {
+        self.findings.iter().map(|f| f.severity).max()
+    }
+
+    /// Hallazgos con severidad `Error`
+    pub fn errors(&self) -> impl Iterator<Item = &Finding> {
+        self.with_severity(Severity::Error)
+    }
+
+    /// Hallazgos con severidad `Warning`
+    pub fn warnings(&self) -> impl Iterator<Item = &Finding> {
+        self.with_severity(Severity::Warning)
+    }
+
+    fn with_severity(&self, severity: Severity) -> impl Iterator<Item = &Finding> {
+        self.findings.iter().filter(move |f| f.severity == severity)
+    }
+
+    /// Convertir los errores en un `ValidationError`
+    pub fn ensure_conformant(&self) -> Result<()> {
+        if self.is_conformant() {
+            return Ok(());
+        }
+        let errors: Vec<String> = self.errors().map(Finding::to_string).collect();
+        Err(DicomError::validation(format!(
+            "Data set no conforme ({} errores): {}",
+            errors.len(),
+            errors.join("; ")
+        )))
+    }
+}
+
+// ============================================
+// Tablas de módulos
+// ============================================
+
+/// Tipo de atributo (PS3.3 §7.4)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AttributeType {
+    Type1,
+    Type1C,
+    Type2,
+    Type2C,
+    Type3,
+}
+
+/// Uso del módulo en el IOD
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Usage {
+    /// M: siempre presente
+    Mandatory,
+    /// U: se valida solo si alguno de sus atributos está presente
+    UserOptional,
+}
+
+/// Multiplicidad de valores permitida
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Vm {
+    min: u32,
+    max: u32,
+}
+
+const VM_1: Vm = Vm { min: 1, max: 1 };
+const VM_2: Vm = Vm { min: 2, max: 2 };
+const VM_1_N: Vm = Vm { min: 1, max: u32::MAX };
+
+impl fmt::Display for Vm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.min, self.max) {
+            (min, max) if min == max => write!(f, "{}", min),
+            (min, u32::MAX) => write!(f, "{}-n", min),
+            (min, max) => write!(f, "{}-{}", min, max),
+        }
+    }
+}
+
+/// Condición de un atributo 1C/2C
+type Condition = fn(&InMemDicomObject) -> bool;
+
+/// Atributo de un módulo
+struct Attribute {
+    tag: Tag,
+    vr: VR,
+    vm: Vm,
+    kind: AttributeType,
+    condition: Option<Condition>,
+    enumerated: &'static [&'static str],
+    /// Atributos de cada item (solo SQ)
+    items: &'static [Attribute],
+}
+
+const fn attr(tag: Tag, vr: VR, vm: Vm, kind: AttributeType) -> Attribute {
+    Attribute {
+        tag,
+        vr,
+        vm,
+        kind,
+        condition: None,
+        enumerated: &[],
+        items: &[],
+    }
+}
+
+impl Attribute {
+    const fn values(self, enumerated: &'static [&'static str]) -> Self {
+        Self { enumerated, ..self }
+    }
+
+    const fn when(self, condition: Condition) -> Self {
+        Self {
+            condition: Some(condition),
+            ..self
+        }
+    }
+
+    const fn items(self, items: &'static [Attribute]) -> Self {
+        Self { items, ..self }
+    }
+
+    /// El atributo es exigible (Type 1/2, o 1C/2C con la condición cumplida)
+    fn required(&self, dataset: &InMemDicomObject) -> bool {
+        match self.kind {
+            AttributeType::Type1 | AttributeType::Type2 => true,
+            AttributeType::Type1C | AttributeType::Type2C => self.condition.is_some_and(|c| c(dataset)),
+            AttributeType::Type3 => false,
+        }
+    }
+
+    /// Severidad de un valor inválido
+    fn value_severity(&self) -> Severity {
+        if self.kind == AttributeType::Type3 {
+            Severity::Warning
+        } else {
+            Severity::Error
+        }
+    }
+}
+
+/// Módulo de PS3.3
+struct Module {
+    name: &'static str,
+    usage: Usage,
+    attributes: &'static [Attribute],
+    /// Valores enumerados que el módulo impone a atributos de otro módulo
+    /// (p.ej. US Image sobre Image Pixel): solo se revisa el valor, la
+    /// presencia, VR y VM los reporta el módulo que define el atributo
+    restricts: &'static [(Tag, &'static [&'static str])],
+}
+
+use AttributeType::*;
+
+/// C.7.1.1
+static PATIENT: Module = Module {
+    name: "Patient",
+    usage: Usage::Mandatory,
+    attributes: &[
+        attr(tags::PATIENT_NAME, VR::PN, VM_1, Type2),
+        attr(tags::PATIENT_ID, VR::LO, VM_1, Type2),
+        attr(tags::PATIENT_BIRTH_DATE, VR::DA, VM_1, Type2),
+        attr(tags::PATIENT_SEX, VR::CS, VM_1, Type2).values(&["M", "F", "O"]),
+    ],    restricts: &[],
+};
+
+/// C.7.2.1
+static GENERAL_STUDY: Module = Module {
+    name: "General Study",
+    usage: Usage::Mandatory,
+    attributes: &[
+        attr(tags::STUDY_INSTANCE_UID, VR::UI, VM_1, Type1),
+        attr(tags::STUDY_DATE, VR::DA, VM_1, Type2),
+        attr(tags::STUDY_TIME, VR::TM, VM_1, Type2),
+        attr(tags::REFERRING_PHYSICIAN_NAME, VR::PN, VM_1, Type2),
+        attr(tags::STUDY_ID, VR::SH, VM_1, Type2),
+        attr(tags::ACCESSION_NUMBER, VR::SH, VM_1, Type2),
+        attr(tags::STUDY_DESCRIPTION, VR::LO, VM_1, Type3),
+    ],    restricts: &[],
+};
+
+/// C.7.3.1
+static GENERAL_SERIES: Module = Module {
+    name: "General Series",
+    usage: Usage::Mandatory,
+    attributes: &[
+        attr(tags::MODALITY, VR::CS, VM_1, Type1),
+        attr(tags::SERIES_INSTANCE_UID, VR::UI, VM_1, Type1),
+        attr(tags::SERIES_NUMBER, VR::IS, VM_1, Type2),
+        attr(tags::LATERALITY, VR::CS, VM_1, Type3).values(&["R", "L"]),
+        attr(tags::SERIES_DATE, VR::DA, VM_1, Type3),
+        attr(tags::SERIES_TIME, VR::TM, VM_1, Type3),
+        attr(tags::SERIES_DESCRIPTION, VR::LO, VM_1, Type3),
+    ],    restricts: &[],
+};
+
+/// C.7.5.1
+static GENERAL_EQUIPMENT: Module = Module {
+    name: "General Equipment",
+    usage: Usage::Mandatory,
+    attributes: &[
+        attr(tags::MANUFACTURER, VR::LO, VM_1, Type2),
+        attr(tags::INSTITUTION_NAME, VR::LO, VM_1, Type3),
+        attr(tags::MANUFACTURER_MODEL_NAME, VR::LO, VM_1, Type3),
+    ],    restricts: &[],
+};
+
+/// C.7.6.1
+static GENERAL_IMAGE: Module = Module {
+    name: "General Image",
+    usage: Usage::Mandatory,
+    attributes: &[
+        attr(tags::INSTANCE_NUMBER, VR::IS, VM_1, Type2),
+        attr(tags::PATIENT_ORIENTATION, VR::CS, VM_2, Type2C).when(without_image_orientation),
+        attr(tags::CONTENT_DATE, VR::DA, VM_1, Type3),
+        attr(tags::CONTENT_TIME, VR::TM, VM_1, Type3),
+        attr(tags::ACQUISITION_DATE_TIME, VR::DT, VM_1, Type3),
+        attr(tags::BURNED_IN_ANNOTATION, VR::CS, VM_1, Type3).values(&["YES", "NO"]),
+        attr(tags::LOSSY_IMAGE_COMPRESSION, VR::CS, VM_1, Type3).values(&["00", "01"]),
+    ],    restricts: &[],
+};
+
+/// C.7.6.3 (Pixel Data no se valida: el lector se detiene antes)
+static IMAGE_PIXEL: Module = Module {
+    name: "Image Pixel",
+    usage: Usage::Mandatory,
+    attributes: &[
+        attr(tags::SAMPLES_PER_PIXEL, VR::US, VM_1, Type1),
+        attr(tags::PHOTOMETRIC_INTERPRETATION, VR::CS, VM_1, Type1),
+        attr(tags::ROWS, VR::US, VM_1, Type1),
+        attr(tags::COLUMNS, VR::US, VM_1, Type1),
+        attr(tags::BITS_ALLOCATED, VR::US, VM_1, Type1),
+        attr(tags::BITS_STORED, VR::US, VM_1, Type1),
+        attr(tags::HIGH_BIT, VR::US, VM_1, Type1),
+        attr(tags::PIXEL_REPRESENTATION, VR::US, VM_1, Type1).values(&["0", "1"]),
+        attr(tags::PLANAR_CONFIGURATION, VR::US, VM_1, Type1C)
+            .when(multiple_samples)
+            .values(&["0", "1"]),
+    ],    restricts: &[],
+};
+
+/// C.7.6.5
+static CINE: Module = Module {
+    name: "Cine",
+    usage: Usage::Mandatory,
+    attributes: &[
+        attr(tags::FRAME_TIME, VR::DS, VM_1, Type1C).when(frame_time_increment),
+        attr(tags::FRAME_TIME_VECTOR, VR::DS, VM_1_N, Type1C).when(frame_time_vector_increment),
+        attr(tags::CINE_RATE, VR::IS, VM_1, Type3),
+        attr(tags::RECOMMENDED_DISPLAY_FRAME_RATE, VR::IS, VM_1, Type3),
+    ],    restricts: &[],
+};
+
+/// C.7.6.6
+static MULTI_FRAME: Module = Module {
+    name: "Multi-frame",
+    usage: Usage::Mandatory,
+    attributes: &[
+        attr(tags::NUMBER_OF_FRAMES, VR::IS, VM_1, Type1),
+        attr(tags::FRAME_INCREMENT_POINTER, VR::AT, VM_1_N, Type1),
+    ],    restricts: &[],
+};
+
+/// C.8.5.5
+static US_REGION_CALIBRATION: Module = Module {
+    name: "US Region Calibration",
+    usage: Usage::UserOptional,
+    attributes: &[attr(tags::SEQUENCE_OF_ULTRASOUND_REGIONS, VR::SQ, VM_1, Type1).items(&[
+        attr(tags::REGION_SPATIAL_FORMAT, VR::US, VM_1, Type1),
+        attr(tags::REGION_DATA_TYPE, VR::US, VM_1, Type1),
+        attr(tags::REGION_FLAGS, VR::UL, VM_1, Type1),
+        attr(tags::REGION_LOCATION_MIN_X0, VR::UL, VM_1, Type1),
+        attr(tags::REGION_LOCATION_MIN_Y0, VR::UL, VM_1, Type1),
+        attr(tags::REGION_LOCATION_MAX_X1, VR::UL, VM_1, Type1),
+        attr(tags::REGION_LOCATION_MAX_Y1, VR::UL, VM_1, Type1),
+        attr(tags::PHYSICAL_UNITS_X_DIRECTION, VR::US, VM_1, Type1),
+        attr(tags::PHYSICAL_UNITS_Y_DIRECTION, VR::US, VM_1, Type1),
+        attr(tags::PHYSICAL_DELTA_X, VR::FD, VM_1, Type1),
+        attr(tags::PHYSICAL_DELTA_Y, VR::FD, VM_1, Type1),
+        attr(tags::REFERENCE_PIXEL_X0, VR::SL, VM_1, Type3),
+        attr(tags::REFERENCE_PIXEL_Y0, VR::SL, VM_1, Type3),
+    ])],    restricts: &[],
+};
+
+/// C.8.5.6
+static US_IMAGE: Module = Module {
+    name: "US Image",
+    usage: Usage::Mandatory,
+    attributes: &[
+        attr(tags::FRAME_INCREMENT_POINTER, VR::AT, VM_1, Type1C).when(multi_frame),
+        attr(tags::IMAGE_TYPE, VR::CS, Vm { min: 2, max: 4 }, Type2),
+    ],
+    restricts: &[
+        (tags::SAMPLES_PER_PIXEL, &["1", "3"]),
+        (
+            tags::PHOTOMETRIC_INTERPRETATION,
+            &[
+                "MONOCHROME2",
+                "PALETTE COLOR",
+                "RGB",
+                "YBR_FULL",
+                "YBR_FULL_422",
+                "YBR_PARTIAL_422",
+                "YBR_PARTIAL_420",
+                "YBR_ICT",
+                "YBR_RCT",
+            ],
+        ),
+        (tags::BITS_ALLOCATED, &["8", "16"]),
+        (tags::PIXEL_REPRESENTATION, &["0"]),
+    ],
+};
+
+/// C.12.1
+static SOP_COMMON: Module = Module {
+    name: "SOP Common",
+    usage: Usage::Mandatory,
+    attributes: &[
+        attr(tags::SOP_CLASS_UID, VR::UI, VM_1, Type1),
+        attr(tags::SOP_INSTANCE_UID, VR::UI, VM_1, Type1),
+        attr(tags::SPECIFIC_CHARACTER_SET, VR::CS, VM_1_N, Type3),
+        attr(tags::INSTANCE_CREATION_DATE, VR::DA, VM_1, Type3),
+        attr(tags::INSTANCE_CREATION_TIME, VR::TM, VM_1, Type3),
+    ],    restricts: &[],
+};
+
+// ============================================
+// Condiciones 1C/2C
+// ============================================
+
+fn int_value(dataset: &InMemDicomObject, tag: Tag) -> Option<i64> {
+    dataset.element(tag).ok()?.to_int::<i64>().ok()
+}
+
+/// Patient Orientation es exigible si no hay Image Orientation (Patient)
+fn without_image_orientation(dataset: &InMemDicomObject) -> bool {
+    dataset.element(tags::IMAGE_ORIENTATION_PATIENT).is_err()
+}
+
+/// Planar Configuration es exigible con más de una muestra por píxel
+fn multiple_samples(dataset: &InMemDicomObject) -> bool {
+    int_value(dataset, tags::SAMPLES_PER_PIXEL).is_some_and(|samples| samples > 1)
+}
+
+fn multi_frame(dataset: &InMemDicomObject) -> bool {
+    int_value(dataset, tags::NUMBER_OF_FRAMES).is_some_and(|frames| frames > 1)
+}
+
+/// El Frame Increment Pointer apunta a `tag`
+fn increments_by(dataset: &InMemDicomObject, tag: Tag) -> bool {
+    match dataset.element(tags::FRAME_INCREMENT_POINTER).map(InMemElement::value) {
+        Ok(Value::Primitive(PrimitiveValue::Tags(pointers))) => pointers.contains(&tag),
+        _ => false,
+    }
+}
+
+fn frame_time_increment(dataset: &InMemDicomObject) -> bool {
+    increments_by(dataset, tags::FRAME_TIME)
+}
+
+fn frame_time_vector_increment(dataset: &InMemDicomObject) -> bool {
+    increments_by(dataset, tags::FRAME_TIME_VECTOR)
+}
+
+// ============================================
+// Validación
+// ============================================
+
+/// Validar el data set contra el IOD de su SOP Class
+///
+/// Con un SOP Class sin tabla se validan solo los módulos comunes a todos
+/// los IODs (paciente, estudio, serie, equipo, SOP), más General Image e
+/// Image Pixel si el data set tiene píxeles: SR, KO, PR o PDF encapsulado
+/// no los llevan.
+pub fn validate_iod(dataset: &InMemDicomObject) -> ConformanceReport {
+    let sop_class = dataset
+        .element(tags::SOP_CLASS_UID)
+        .ok()
+        .and_then(|e| e.to_str().ok())
+        .map(|uid| uid.to_string())
+        .unwrap_or_default();
+    let iod = Iod::from_sop_class(&sop_class);
+
+    let mut validator = Validator {
+        findings: Vec::new(),
+        checked: HashSet::new(),
+    };
+
+    let modules = match iod {
+        Some(iod) => iod.modules(),
+        None => {
+            validator.findings.push(Finding {
+                severity: Severity::Info,
+                module: "SOP Common".to_string(),
+                tag: tags::SOP_CLASS_UID.to_string(),
+                keyword: keyword(tags::SOP_CLASS_UID),
+                message: format!("SOP Class sin tabla de conformidad: {}", sop_class),
+            });
+            let mut modules = vec![&PATIENT, &GENERAL_STUDY, &GENERAL_SERIES, &GENERAL_EQUIPMENT];
+            if has_pixels(dataset) {
+                modules.extend([&GENERAL_IMAGE, &IMAGE_PIXEL]);
+            }
+            modules.push(&SOP_COMMON);
+            modules
+        }
+    };
+
+    for module in modules {
+        validator.module(dataset, module);
+    }
+    validator.remaining_dates(dataset);
+
+    ConformanceReport {
+        iod,
+        findings: validator.findings,
+    }
+}
+
+/// El data set tiene Pixel Data, o al menos el módulo Image Pixel (el lector
+/// se detiene antes de (7FE0,0010), así que se mira también Rows)
+fn has_pixels(dataset: &InMemDicomObject) -> bool {
+    dataset.element(tags::PIXEL_DATA).is_ok() || dataset.element(tags::ROWS).is_ok()
+}
+
+/// Keyword del diccionario estándar ("" si el tag es privado o desconocido)
+fn keyword(tag: Tag) -> String {
+    StandardDataDictionary
+        .by_tag(tag)
+        .map(|entry| entry.alias().to_string())
+        .unwrap_or_default()
+}
+
+struct Validator {
+    findings: Vec<Finding>,
+    /// Tags del nivel raíz ya revisados por algún módulo
+    checked: HashSet<Tag>,
+}
+
+impl Validator {
+    fn push(&mut self, severity: Severity, module: &str, path: &str, tag: Tag, message: String) {
+        self.findings.push(Finding {
+            severity,
+            module: module.to_string(),
+            tag: format!("{}{}", path, tag),
+            keyword: keyword(tag),
+            message,
+        });
+    }
+
+    fn module(&mut self, dataset: &InMemDicomObject, module: &Module) {
+        if module.usage == Usage::UserOptional
+            && !module.attributes.iter().any(|a| dataset.element(a.tag).is_ok())
+        {
+            return;
+        }
+
+        self.checked.extend(module.attributes.iter().map(|a| a.tag));
+        self.attributes(dataset, module.name, "", module.attributes);
+        self.restrictions(dataset, module);
+    }
+
+    /// Revisar los valores enumerados que el módulo impone a atributos ajenos
+    fn restrictions(&mut self, dataset: &InMemDicomObject, module: &Module) {
+        for &(tag, enumerated) in module.restricts {
+            let Ok(element) = dataset.element(tag) else {
+                continue;
+            };
+            let values: Vec<String> = element
+                .to_multi_str()
+                .map(|values| values.iter().map(|v| v.trim().to_string()).collect())
+                .unwrap_or_default();
+            for value in values.iter().filter(|v| !v.is_empty() && !enumerated.contains(&v.as_str())) {
+                self.push(
+                    Severity::Error,
+                    module.name,
+                    "",
+                    tag,
+                    format!("valor '{}' no enumerado ({})", value, enumerated.join(", ")),
+                );
+            }
+        }
+    }
+
+    fn attributes(&mut self, dataset: &InMemDicomObject, module: &str, path: &str, attributes: &[Attribute]) {
+        for attribute in attributes {
+            let element = match dataset.element(attribute.tag) {
+                Ok(element) => element,
+                Err(_) => {
+                    if attribute.required(dataset) {
+                        let (severity, kind) = match attribute.kind {
+                            Type1 | Type1C => (Severity::Error, "Type 1"),
+                            _ => (Severity::Warning, "Type 2"),
+                        };
+                        self.push(severity, module, path, attribute.tag, format!("falta atributo {}", kind));
+                    }
+                    continue;
+                }
+            };
+
+            if is_empty(element) {
+                if matches!(attribute.kind, Type1 | Type1C) && attribute.required(dataset) {
+                    self.push(Severity::Error, module, path, attribute.tag, "atributo Type 1 vacío".to_string());
+                }
+                continue;
+            }
+
+            self.value(element, attribute, module, path);
+        }
+    }
+
+    fn value(&mut self, element: &InMemElement, attribute: &Attribute, module: &str, path: &str) {
+        let severity = attribute.value_severity();
+        let tag = attribute.tag;
+
+        if element.vr() != attribute.vr {
+            self.push(
+                severity,
+                module,
+                path,
+                tag,
+                format!("VR {} en vez de {}", element.vr(), attribute.vr),
+            );
+            return;
+        }
+
+        if let Some(items) = element.items() {
+            for (i, item) in items.iter().enumerate() {
+                let item_path = format!("{}{}[{}]", path, tag, i);
+                self.attributes(item, module, &item_path, attribute.items);
+            }
+            return;
+        }
+
+        let vm = element.value().multiplicity();
+        if vm < attribute.vm.min || vm > attribute.vm.max {
+            self.push(
+                severity,
+                module,
+                path,
+                tag,
+                format!("VM {} fuera de {}", vm, attribute.vm),
+            );
+        }
+
+        let values: Vec<String> = element
+            .to_multi_str()
+            .map(|values| values.iter().map(|v| v.trim().to_string()).collect())
+            .unwrap_or_default();
+
+        if !attribute.enumerated.is_empty() {
+            for value in values.iter().filter(|v| !attribute.enumerated.contains(&v.as_str())) {
+                self.push(
+                    severity,
+                    module,
+                    path,
+                    tag,
+                    format!("valor '{}' no enumerado ({})", value, attribute.enumerated.join(", ")),
+                );
+            }
+        }
+
+        if let Some(message) = format_error(element.vr(), &values) {
+            self.push(severity, module, path, tag, message);
+        }
+    }
+
+    /// Revisar el formato de fechas, horas y UIDs que no cubre ningún módulo
+    fn remaining_dates(&mut self, dataset: &InMemDicomObject) {
+        for element in dataset.iter() {
+            let tag = element.header().tag;
+            if self.checked.contains(&tag) || !matches!(element.vr(), VR::DA | VR::TM | VR::DT | VR::UI) {
+                continue;
+            }
+            let values: Vec<String> = element
+                .to_multi_str()
+                .map(|values| values.iter().map(|v| v.trim().to_string()).collect())
+                .unwrap_or_default();
+            if let Some(message) = format_error(element.vr(), &values) {
+                self.push(Severity::Warning, "Data Set", "", tag, message);
+            }
+        }
+    }
+}
+
+/// Verificar si el elemento no tiene valor
+fn is_empty(element: &InMemElement) -> bool {
+    match element.value() {
+        Value::Primitive(PrimitiveValue::Empty) => true,
+        Value::Primitive(value) => value.to_str().trim_matches(['\0', ' ', '\\']).is_empty(),
+        Value::Sequence(sequence) => sequence.items().is_empty(),
+        Value::PixelSequence(_) => false,
+    }
+}
+
+// ============================================
+// Formato de fechas, horas y UIDs (PS3.5 §6.2 y §9)
+// ============================================
+
+fn format_error(vr: VR, values: &[String]) -> Option<String> {
+    let (valid, format): (fn(&str) -> bool, &str) = match vr {
+        VR::DA => (is_valid_date, "YYYYMMDD"),
+        VR::TM => (is_valid_time, "HH[MM[SS[.FFFFFF]]]"),
+        VR::DT => (is_valid_datetime, "YYYY[MM[DD[HH[MM[SS[.FFFFFF]]]]]][&ZZXX]"),
+        VR::UI => (uid::is_valid_uid, "de UID (PS3.5 §9)"),
+        _ => return None,
+    };
+
+    values
+        .iter()
+        .find(|value| !value.is_empty() && !valid(value))
+        .map(|value| format!("valor '{}' no cumple el formato {}", value, format))
+}
+
+fn digits(value: &str) -> Option<u32> {
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    value.parse().ok()
+}
+
+fn is_valid_date(value: &str) -> bool {
+    value.len() == 8 && valid_date_prefix(value)
+}
+
+/// Validar "YYYY", "YYYYMM" o "YYYYMMDD"
+fn valid_date_prefix(value: &str) -> bool {
+    let (Some(year), month, day) = (
+        value.get(..4).and_then(digits),
+        value.get(4..6).map(digits),
+        value.get(6..8).map(digits),
+    ) else {
+        return false;
+    };
+
+    match (month, day) {
+        (None, _) => true,
+        (Some(Some(month)), None) => (1..=12).contains(&month),
+        (Some(Some(month)), Some(Some(day))) => NaiveDate::from_ymd_opt(year as i32, month, day).is_some(),
+        _ => false,
+    }
+}
+
+fn is_valid_time(value: &str) -> bool {
+    let (main, fraction) = match value.split_once('.') {
+        Some((main, fraction)) => (main, Some(fraction)),
+        None => (value, None),
+    };
+
+    if let Some(fraction) = fraction {
+        // La fracción solo puede seguir a los segundos
+        if main.len() != 6 || fraction.len() > 6 || digits(fraction).is_none() {
+            return false;
+        }
+    }
+
+    let limits = [23, 59, 60];
+    matches!(main.len(), 2 | 4 | 6)
+        && main
+            .as_bytes()
+            .chunks(2)
+            .zip(limits)
+            .all(|(pair, max)| std::str::from_utf8(pair).ok().and_then(digits).is_some_and(|v| v <= max))
+}
+
+fn is_valid_datetime(value: &str) -> bool {
+    // Sufijo opcional de zona horaria "&ZZXX"
+    let (main, offset) = match value.rfind(['+', '-']) {
+        Some(i) => (&value[..i], Some(&value[i + 1..])),
+        None => (value, None),
+    };
+    if let Some(offset) = offset {
+        let valid_offset = offset.len() == 4
+            && digits(&offset[..2]).is_some_and(|h| h <= 14)
+            && digits(&offset[2..]).is_some_and(|m| m <= 59);
+        if !valid_offset {
+            return false;
+        }
+    }
+
+    let date_len = main.find('.').unwrap_or(main.len()).min(8);
+    let (date, time) = main.split_at(date_len);
+    matches!(date.len(), 4 | 6 | 8) && valid_date_prefix(date) && (time.is_empty() || (date.len() == 8 && is_valid_time(time)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ultrasound::tests::{region_item, with_regions};
+    use dicom::core::{dicom_value, DataElement};
+
+    fn put(dataset: &mut InMemDicomObject, tag: Tag, vr: VR, value: &str) {
+        dataset.put(DataElement::new(tag, vr, PrimitiveValue::from(value)));
+    }
+
+    /// Ultrasound Image conforme
+    fn us_image() -> InMemDicomObject {
+        let mut dataset = InMemDicomObject::new_empty();
+        for (tag, vr, value) in [
+            (tags::SOP_CLASS_UID, VR::UI, uids::ULTRASOUND_IMAGE_STORAGE),
+            (tags::SOP_INSTANCE_UID, VR::UI, "1.2.3.4"),
+            (tags::PATIENT_NAME, VR::PN, "PEREZ^JUAN"),
+            (tags::PATIENT_ID, VR::LO, "CC123"),
+            (tags::PATIENT_BIRTH_DATE, VR::DA, "19800229"),
+            (tags::PATIENT_SEX, VR::CS, "F"),
+            (tags::STUDY_INSTANCE_UID, VR::UI, "1.2.3"),
+            (tags::STUDY_DATE, VR::DA, "20260115"),
+            (tags::STUDY_TIME, VR::TM, "093000.25"),
+            (tags::REFERRING_PHYSICIAN_NAME, VR::PN, ""),
+            (tags::STUDY_ID, VR::SH, "1"),
+            (tags::ACCESSION_NUMBER, VR::SH, ""),
+            (tags::MODALITY, VR::CS, "US"),
+            (tags::SERIES_INSTANCE_UID, VR::UI, "1.2.3.1"),
+            (tags::SERIES_NUMBER, VR::IS, "1"),
+            (tags::MANUFACTURER, VR::LO, "ECO"),
+            (tags::INSTANCE_NUMBER, VR::IS, "1"),
+            (tags::PATIENT_ORIENTATION, VR::CS, ""),
+            (tags::PHOTOMETRIC_INTERPRETATION, VR::CS, "MONOCHROME2"),
+        ] {
+            put(&mut dataset, tag, vr, value);
+        }
+        dataset.put(DataElement::new(tags::IMAGE_TYPE, VR::CS, dicom_value!(Strs, ["ORIGINAL", "PRIMARY"])));
+        for (tag, value) in [
+            (tags::SAMPLES_PER_PIXEL, 1u16),
+            (tags::ROWS, 480),
+            (tags::COLUMNS, 640),
+            (tags::BITS_ALLOCATED, 8),
+            (tags::BITS_STORED, 8),
+            (tags::HIGH_BIT, 7),
+            (tags::PIXEL_REPRESENTATION, 0),
+        ] {
+            dataset.put(DataElement::new(tag, VR::US, PrimitiveValue::from(value)));
+        }
+        dataset
+    }
+
+    fn finding(report: &ConformanceReport, tag: Tag) -> Option<&Finding> {
+        report.findings.iter().find(|f| f.tag == tag.to_string())
+    }
+
+    #[test]
+    fn test_conformant_us_image() {
+        let report = validate_iod(&us_image());
+        assert_eq!(report.iod, Some(Iod::UltrasoundImage));
+        assert!(report.findings.is_empty(), "{:?}", report.findings);
+        assert!(report.ensure_conformant().is_ok());
+    }
+
+    #[test]
+    fn test_missing_and_empty_attributes() {
+        let mut dataset = us_image();
+        dataset.remove_element(tags::ROWS);
+        dataset.remove_element(tags::STUDY_TIME);
+        put(&mut dataset, tags::SERIES_INSTANCE_UID, VR::UI, "");
+
+        let report = validate_iod(&dataset);
+        assert_eq!(finding(&report, tags::ROWS).unwrap().severity, Severity::Error);
+        assert_eq!(finding(&report, tags::SERIES_INSTANCE_UID).unwrap().message, "atributo Type 1 vacío");
+        let study_time = finding(&report, tags::STUDY_TIME).unwrap();
+        assert_eq!((study_time.severity, study_time.keyword.as_str()), (Severity::Warning, "StudyTime"));
+        assert_eq!(report.errors().count(), 2);
+        assert!(!report.is_conformant());
+        assert!(matches!(report.ensure_conformant(), Err(DicomError::ValidationError(_))));
+    }
+
+    #[test]
+    fn test_values_vr_and_vm() {
+        let mut dataset = us_image();
+        put(&mut dataset, tags::PATIENT_SEX, VR::CS, "X");
+        put(&mut dataset, tags::BURNED_IN_ANNOTATION, VR::CS, "SI");
+        put(&mut dataset, tags::MANUFACTURER, VR::SH, "ECO");
+        dataset.put(DataElement::new(tags::IMAGE_TYPE, VR::CS, PrimitiveValue::from("ORIGINAL")));
+
+        let report = validate_iod(&dataset);
+        let sex = finding(&report, tags::PATIENT_SEX).unwrap();
+        assert_eq!(sex.severity, Severity::Error);
+        assert_eq!(sex.message, "valor 'X' no enumerado (M, F, O)");
+        assert_eq!(finding(&report, tags::BURNED_IN_ANNOTATION).unwrap().severity, Severity::Warning);
+        assert_eq!(finding(&report, tags::MANUFACTURER).unwrap().message, "VR SH en vez de LO");
+        assert_eq!(finding(&report, tags::IMAGE_TYPE).unwrap().message, "VM 1 fuera de 2-4");
+
+        put(&mut dataset, tags::SOP_INSTANCE_UID, VR::UI, "1.02.3");
+        let report = validate_iod(&dataset);
+        assert_eq!(finding(&report, tags::SOP_INSTANCE_UID).unwrap().severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_date_time_formats() {
+        assert!(is_valid_date("20240229"));
+        assert!(!is_valid_date("20230229"));
+        assert!(!is_valid_date("2024.01.15"));
+        assert!(is_valid_time("23"));
+        assert!(is_valid_time("2359"));
+        assert!(is_valid_time("235960.123456"));
+        assert!(!is_valid_time("2400"));
+        assert!(!is_valid_time("1230.5"));
+        assert!(is_valid_datetime("2026"));
+        assert!(is_valid_datetime("20260115093000.5-0500"));
+        assert!(!is_valid_datetime("202613"));
+        assert!(!is_valid_datetime("20260115+5"));
+
+        let mut dataset = us_image();
+        put(&mut dataset, tags::STUDY_DATE, VR::DA, "15/01/2026");
+        put(&mut dataset, tags::ACQUISITION_DATE, VR::DA, "20261301");
+
+        let report = validate_iod(&dataset);
+        assert_eq!(finding(&report, tags::STUDY_DATE).unwrap().severity, Severity::Error);
+        let acquisition = finding(&report, tags::ACQUISITION_DATE).unwrap();
+        assert_eq!((acquisition.severity, acquisition.module.as_str()), (Severity::Warning, "Data Set"));
+    }
+
+    #[test]
+    fn test_multi_frame_conditions() {
+        let mut dataset = us_image();
+        put(&mut dataset, tags::SOP_CLASS_UID, VR::UI, uids::ULTRASOUND_MULTI_FRAME_IMAGE_STORAGE);
+        put(&mut dataset, tags::NUMBER_OF_FRAMES, VR::IS, "30");
+        put(&mut dataset, tags::PHOTOMETRIC_INTERPRETATION, VR::CS, "RGB");
+        dataset.put(DataElement::new(tags::SAMPLES_PER_PIXEL, VR::US, PrimitiveValue::from(3u16)));
+
+        let report = validate_iod(&dataset);
+        assert_eq!(report.iod, Some(Iod::UltrasoundMultiFrameImage));
+        for tag in [tags::FRAME_INCREMENT_POINTER, tags::PLANAR_CONFIGURATION] {
+            assert_eq!(finding(&report, tag).unwrap().severity, Severity::Error);
+        }
+        assert!(finding(&report, tags::FRAME_TIME).is_none());
+
+        dataset.put(DataElement::new(tags::FRAME_INCREMENT_POINTER, VR::AT, PrimitiveValue::from(tags::FRAME_TIME)));
+        dataset.put(DataElement::new(tags::PLANAR_CONFIGURATION, VR::US, PrimitiveValue::from(0u16)));
+        let report = validate_iod(&dataset);
+        assert_eq!(report.errors().map(|f| f.keyword.as_str()).collect::<Vec<_>>(), vec!["FrameTime"]);
+    }
+
+    #[test]
+    fn test_region_items_and_unknown_sop_class() {
+        let mut dataset = us_image();
+        let regions = with_regions(vec![region_item(0, 0, 99, 99)]);
+        dataset.put(regions.element(tags::SEQUENCE_OF_ULTRASOUND_REGIONS).unwrap().clone());
+
+        let report = validate_iod(&dataset);
+        let tags_found: Vec<&str> = report.findings.iter().map(|f| f.tag.as_str()).collect();
+        assert_eq!(tags_found.len(), 4);
+        assert_eq!(tags_found[0], "(0018,6011)[0](0018,6024)");
+
+        put(&mut dataset, tags::SOP_CLASS_UID, VR::UI, uids::CT_IMAGE_STORAGE);
+        let report = validate_iod(&dataset);
+        assert_eq!(report.iod, None);
+        assert_eq!(report.max_severity(), Some(Severity::Info));
+    }
+
+    #[test]
+    fn test_non_image_sop_class_skips_image_modules() {
+        let mut dataset = us_image();
+        for tag in [
+            tags::SAMPLES_PER_PIXEL,
+            tags::PHOTOMETRIC_INTERPRETATION,
+            tags::ROWS,
+            tags::COLUMNS,
+            tags::BITS_ALLOCATED,
+            tags::BITS_STORED,
+            tags::HIGH_BIT,
+            tags::PIXEL_REPRESENTATION,
+            tags::PATIENT_ORIENTATION,
+            tags::IMAGE_TYPE,
+        ] {
+            dataset.remove_element(tag);
+        }
+        put(&mut dataset, tags::MODALITY, VR::CS, "SR");
+        put(&mut dataset, tags::SOP_CLASS_UID, VR::UI, uids::COMPREHENSIVE_SR_STORAGE);
+
+        let report = validate_iod(&dataset);
+        assert!(report.is_conformant(), "{:?}", report.findings);
+        assert!(report.findings.iter().all(|f| f.module != "Image Pixel" && f.module != "General Image"));
+
+        // Sin tabla pero con píxeles: se exige Image Pixel
+        put(&mut dataset, tags::SOP_CLASS_UID, VR::UI, uids::CT_IMAGE_STORAGE);
+        dataset.put(DataElement::new(tags::ROWS, VR::US, PrimitiveValue::from(512u16)));
+        let report = validate_iod(&dataset);
+        assert_eq!(finding(&report, tags::COLUMNS).unwrap().module, "Image Pixel");
+    }
+
+    #[test]
+    fn test_image_pixel_attributes_reported_once() {
+        let mut dataset = us_image();
+        dataset.remove_element(tags::SAMPLES_PER_PIXEL);
+        dataset.put(DataElement::new(tags::BITS_ALLOCATED, VR::US, PrimitiveValue::from(32u16)));
+
+        let report = validate_iod(&dataset);
+        let findings = |tag: Tag| report.findings.iter().filter(|f| f.tag == tag.to_string()).collect::<Vec<_>>();
+        let samples = findings(tags::SAMPLES_PER_PIXEL);
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].module, "Image Pixel");
+        let bits = findings(tags::BITS_ALLOCATED);
+        assert_eq!(bits.len(), 1);
+        assert_eq!((bits[0].module.as_str(), bits[0].message.as_str()), ("US Image", "valor '32' no enumerado (8, 16)"));
+    }
+}
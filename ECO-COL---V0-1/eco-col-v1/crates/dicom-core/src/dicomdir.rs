@@ -0,0 +1,909 @@
+//! DICOMDIR (Media Storage Directory, PS3.10 §8 y PS3.3 Annex F)
+//!
+//! Índice de los CDs y pendrives que traen los pacientes, y de los medios
+//! que se graban para derivaciones. Los registros (PATIENT, STUDY, SERIES,
+//! IMAGE, SR DOCUMENT, ...) están todos en Directory Record Sequence y se
+//! enlazan entre sí por el offset en bytes, desde el inicio del archivo,
+//! del item de cada registro.
+
+use crate::attribute::{AttributeKey, Element, FromElement};
+use crate::error::{DicomError, Result};
+use crate::metadata::DicomInstance;
+use crate::reader;
+use crate::uid::UidGenerator;
+use crate::writer::{self, IMPLEMENTATION_CLASS_UID, IMPLEMENTATION_VERSION_NAME};
+
+use dicom::core::value::{DataSetSequence, PrimitiveValue};
+use dicom::core::{DataElement, Length, Tag, VR};
+use dicom::dictionary_std::{tags, uids};
+use dicom::encoding::text::SpecificCharacterSet;
+use dicom::encoding::TransferSyntaxIndex;
+use dicom::object::{FileMetaTable, FileMetaTableBuilder, InMemDicomObject};
+use dicom::transfer_syntax::TransferSyntaxRegistry;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Nombre del archivo en la raíz del medio
+pub const DICOMDIR_FILE_NAME: &str = "DICOMDIR";
+
+/// Componentes máximos de un Referenced File ID (PS3.10 §8.5)
+const MAX_FILE_ID_COMPONENTS: usize = 8;
+
+/// Caracteres máximos de cada componente
+const MAX_COMPONENT_LENGTH: usize = 8;
+
+/// Caracteres máximos del File-set ID (VR CS)
+const MAX_FILE_SET_ID_LENGTH: usize = 16;
+
+// ============================================
+// Registros
+// ============================================
+
+/// Directory Record Type (0004,1430)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordType {
+    Patient,
+    Study,
+    Series,
+    Image,
+    /// Cualquier otro tipo ("SR DOCUMENT", "PRIVATE", ...)
+    Other(String),
+}
+
+impl RecordType {
+    fn from_code(code: &str) -> Self {
+        match code.trim() {
+            "PATIENT" => Self::Patient,
+            "STUDY" => Self::Study,
+            "SERIES" => Self::Series,
+            "IMAGE" => Self::Image,
+            other => Self::Other(other.to_string()),
+        }
+    }
+
+    /// Código tal como se escribe en el DICOMDIR
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Patient => "PATIENT",
+            Self::Study => "STUDY",
+            Self::Series => "SERIES",
+            Self::Image => "IMAGE",
+            Self::Other(code) => code,
+        }
+    }
+
+    /// Nivel en la jerarquía (para DICOMDIR sin offsets)
+    fn level(&self) -> usize {
+        match self {
+            Self::Patient => 0,
+            Self::Study => 1,
+            Self::Series => 2,
+            _ => 3,
+        }
+    }
+}
+
+/// Registro del DICOMDIR con sus registros de nivel inferior
+#[derive(Debug, Clone)]
+pub struct DirectoryRecord {
+    pub record_type: RecordType,
+
+    /// Record In-use Flag (0004,1410); los registros borrados quedan en 0
+    pub in_use: bool,
+
+    /// Referenced File ID (0004,1500), relativo al directorio del DICOMDIR
+    pub file_id: Vec<String>,
+
+    /// Referenced SOP Class UID in File (0004,1510)
+    pub referenced_sop_class_uid: Option<String>,
+
+    /// Referenced SOP Instance UID in File (0004,1511)
+    pub referenced_sop_instance_uid: Option<String>,
+
+    /// Referenced Transfer Syntax UID in File (0004,1512)
+    pub referenced_transfer_syntax_uid: Option<String>,
+
+    /// Item completo, con las claves del registro (Patient ID, Study Date, ...)
+    pub item: InMemDicomObject,
+
+    /// Registros de nivel inferior (STUDY dentro de PATIENT, ...)
+    pub children: Vec<DirectoryRecord>,
+}
+
+impl DirectoryRecord {
+    fn from_item(item: InMemDicomObject) -> Self {
+        let uid = |tag| get::<String>(&item, tag).map(|uid| uid.trim_end_matches('\0').to_string());
+
+        Self {
+            record_type: RecordType::from_code(&get::<String>(&item, tags::DIRECTORY_RECORD_TYPE).unwrap_or_default()),
+            in_use: get::<u16>(&item, tags::RECORD_IN_USE_FLAG) != Some(0),
+            file_id: get::<Vec<String>>(&item, tags::REFERENCED_FILE_ID)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|component| !component.is_empty())
+                .collect(),
+            referenced_sop_class_uid: uid(tags::REFERENCED_SOP_CLASS_UID_IN_FILE),
+            referenced_sop_instance_uid: uid(tags::REFERENCED_SOP_INSTANCE_UID_IN_FILE),
+            referenced_transfer_syntax_uid: uid(tags::REFERENCED_TRANSFER_SYNTAX_UID_IN_FILE),
+            item,
+            children: Vec::new(),
+        }
+    }
+
+    /// Valor tipado de una clave del registro, p.ej. `record.get::<String>("PatientID")`
+    pub fn get<T: FromElement>(&self, key: impl AttributeKey) -> Option<T> {
+        get(&self.item, key.to_tag().ok()?)
+    }
+
+    /// Este registro y todos los de nivel inferior, en orden
+    pub fn iter(&self) -> Box<dyn Iterator<Item = &DirectoryRecord> + '_> {
+        Box::new(std::iter::once(self).chain(self.children.iter().flat_map(|child| child.iter())))
+    }
+}
+
+fn get<T: FromElement>(item: &InMemDicomObject, tag: Tag) -> Option<T> {
+    item.element(tag).ok().and_then(T::from_element)
+}
+
+// ============================================
+// Lectura
+// ============================================
+
+/// DICOMDIR leído de un medio
+#[derive(Debug, Clone)]
+pub struct DicomDir {
+    /// Path del archivo DICOMDIR
+    pub path: PathBuf,
+
+    /// File-set ID (0004,1130)
+    pub file_set_id: Option<String>,
+
+    /// Registros de la raíz (normalmente PATIENT)
+    pub records: Vec<DirectoryRecord>,
+}
+
+impl DicomDir {
+    /// Leer un DICOMDIR
+    ///
+    /// Si los offsets no enlazan ningún registro (generadores defectuosos),
+    /// la jerarquía se arma según el orden y el tipo de los registros.
+    pub fn open(path: &Path) -> Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+
+        let mut preamble = [0u8; 132];
+        reader.read_exact(&mut preamble).map_err(|_| DicomError::InvalidMagicBytes)?;
+        if &preamble[128..] != b"DICM" {
+            return Err(DicomError::InvalidMagicBytes);
+        }
+
+        // `from_reader` lee el prefijo "DICM"
+        reader.seek(SeekFrom::Start(128))?;
+        let meta = FileMetaTable::from_reader(&mut reader)
+            .map_err(|e| DicomError::parse(format!("Error parsing File Meta: {}", e)))?;
+        let sop_class = meta.media_storage_sop_class_uid().trim_end_matches(['\0', ' ']);
+        if sop_class != uids::MEDIA_STORAGE_DIRECTORY_STORAGE {
+            return Err(DicomError::validation(format!("No es un DICOMDIR (SOP Class {})", sop_class)));
+        }
+
+        let ts_uid = meta.transfer_syntax().trim_end_matches(['\0', ' ']);
+        let ts = TransferSyntaxRegistry
+            .get(ts_uid)
+            .ok_or_else(|| DicomError::UnsupportedTransferSyntax(ts_uid.to_string()))?;
+        let position = reader.stream_position()?;
+        let mut head = reader::read_until_pixel_data(&mut reader, ts, position, false, false)?;
+
+        let items = head
+            .dataset
+            .take_element(tags::DIRECTORY_RECORD_SEQUENCE)
+            .ok()
+            .and_then(|e| e.items().map(|items| items.to_vec()))
+            .unwrap_or_default();
+        let first = get::<u32>(&head.dataset, tags::OFFSET_OF_THE_FIRST_DIRECTORY_RECORD_OF_THE_ROOT_DIRECTORY_ENTITY)
+            .unwrap_or(0);
+
+        let records = if first == 0 && !items.is_empty() {
+            link_by_order(items)
+        } else {
+            link_by_offset(items, &head.record_offsets, first)?
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            file_set_id: get::<String>(&head.dataset, tags::FILE_SET_ID).filter(|id| !id.is_empty()),
+            records,
+        })
+    }
+
+    /// Todos los registros, en orden jerárquico
+    pub fn iter(&self) -> impl Iterator<Item = &DirectoryRecord> {
+        self.records.iter().flat_map(|record| record.iter())
+    }
+
+    /// Archivos referenciados por registros en uso
+    ///
+    /// Los File IDs están en mayúsculas, pero algunos sistemas montan los CDs
+    /// ISO 9660 en minúsculas: cada componente se busca también sin
+    /// distinguir mayúsculas. Los File IDs que no cumplen PS3.10 §8.5 (p.ej.
+    /// con "..") se omiten; ver [`invalid_file_ids`](Self::invalid_file_ids).
+    pub fn referenced_files(&self) -> Vec<PathBuf> {
+        let base = self.path.parent().unwrap_or(Path::new(""));
+        self.referencing_records()
+            .filter(|record| file_id_problem(&record.file_id).is_none())
+            .map(|record| resolve(base, &record.file_id))
+            .collect()
+    }
+
+    /// Problemas de los File IDs omitidos por [`referenced_files`](Self::referenced_files)
+    pub fn invalid_file_ids(&self) -> Vec<String> {
+        self.referencing_records()
+            .filter_map(|record| file_id_problem(&record.file_id))
+            .collect()
+    }
+
+    fn referencing_records(&self) -> impl Iterator<Item = &DirectoryRecord> {
+        self.iter().filter(|record| record.in_use && !record.file_id.is_empty())
+    }
+}
+
+/// Enlazar registros siguiendo Offset of the Next Directory Record y
+/// Offset of Referenced Lower-Level Directory Entity
+fn link_by_offset(items: Vec<InMemDicomObject>, offsets: &[u64], first: u32) -> Result<Vec<DirectoryRecord>> {
+    let index: HashMap<u64, usize> = offsets.iter().enumerate().map(|(i, offset)| (*offset, i)).collect();
+    let mut items: Vec<Option<InMemDicomObject>> = items.into_iter().map(Some).collect();
+    chain(first, &index, &mut items)
+}
+
+fn chain(
+    mut offset: u32,
+    index: &HashMap<u64, usize>,
+    items: &mut [Option<InMemDicomObject>],
+) -> Result<Vec<DirectoryRecord>> {
+    let mut records = Vec::new();
+
+    while offset != 0 {
+        let i = *index
+            .get(&u64::from(offset))
+            .ok_or_else(|| DicomError::parse(format!("DICOMDIR: offset {} no apunta a ningún registro", offset)))?;
+        // Un registro ya tomado indica un ciclo
+        let item = items[i]
+            .take()
+            .ok_or_else(|| DicomError::parse(format!("DICOMDIR: registro en el offset {} referenciado dos veces", offset)))?;
+
+        offset = get::<u32>(&item, tags::OFFSET_OF_THE_NEXT_DIRECTORY_RECORD).unwrap_or(0);
+        let lower = get::<u32>(&item, tags::OFFSET_OF_REFERENCED_LOWER_LEVEL_DIRECTORY_ENTITY).unwrap_or(0);
+
+        let mut record = DirectoryRecord::from_item(item);
+        record.children = chain(lower, index, items)?;
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+/// Enlazar registros por su orden en la secuencia y el nivel de su tipo
+fn link_by_order(items: Vec<InMemDicomObject>) -> Vec<DirectoryRecord> {
+    let mut roots: Vec<DirectoryRecord> = Vec::new();
+    // Registros abiertos, del nivel superior al inferior
+    let mut open: Vec<DirectoryRecord> = Vec::new();
+
+    for item in items {
+        let record = DirectoryRecord::from_item(item);
+        while open.last().is_some_and(|parent| parent.record_type.level() >= record.record_type.level()) {
+            close(&mut open, &mut roots);
+        }
+        open.push(record);
+    }
+    while !open.is_empty() {
+        close(&mut open, &mut roots);
+    }
+
+    roots
+}
+
+fn close(open: &mut Vec<DirectoryRecord>, roots: &mut Vec<DirectoryRecord>) {
+    if let Some(record) = open.pop() {
+        match open.last_mut() {
+            Some(parent) => parent.children.push(record),
+            None => roots.push(record),
+        }
+    }
+}
+
+/// Path del archivo referenciado, tolerando diferencias de mayúsculas
+fn resolve(base: &Path, file_id: &[String]) -> PathBuf {
+    let mut path = base.to_path_buf();
+    for component in file_id {
+        let exact = path.join(component);
+        path = if exact.exists() {
+            exact
+        } else {
+            std::fs::read_dir(&path)
+                .ok()
+                .and_then(|entries| {
+                    entries
+                        .filter_map(|e| e.ok())
+                        .find(|e| e.file_name().to_string_lossy().eq_ignore_ascii_case(component))
+                })
+                .map(|e| e.path())
+                .unwrap_or(exact)
+        };
+    }
+    path
+}
+
+// ============================================
+// Escritura
+// ============================================
+
+/// Opciones para generar el DICOMDIR
+#[derive(Debug, Clone, Default)]
+pub struct DicomDirOptions {
+    /// File-set ID (0004,1130), hasta 16 caracteres A-Z, 0-9, "_" o espacio
+    pub file_set_id: Option<String>,
+
+    /// Exigir el perfil General Purpose CD-R (STD-GEN-CD, PS3.11 Annex D)
+    pub general_purpose_cd: bool,
+
+    /// Generador del Media Storage SOP Instance UID del DICOMDIR
+    pub uid_generator: UidGenerator,
+}
+
+/// Generador de DICOMDIR
+pub struct DicomDirWriter {
+    options: DicomDirOptions,
+}
+
+impl DicomDirWriter {
+    /// Crear writer con opciones por defecto (sin File-set ID ni perfil)
+    pub fn new() -> Self {
+        Self {
+            options: DicomDirOptions::default(),
+        }
+    }
+
+    /// Crear writer con opciones custom
+    pub fn with_options(options: DicomDirOptions) -> Self {
+        Self { options }
+    }
+
+    /// Escribir `root/DICOMDIR` con las instancias, que deben estar bajo `root`
+    pub fn write_file(&self, root: &Path, instances: &[DicomInstance]) -> Result<PathBuf> {
+        let path = root.join(DICOMDIR_FILE_NAME);
+        let mut writer = BufWriter::new(File::create(&path)?);
+        self.write(root, instances, &mut writer)?;
+        writer.flush()?;
+        Ok(path)
+    }
+
+    /// Escribir el DICOMDIR en `to`, con los File IDs relativos a `root`
+    pub fn write<W: Write>(&self, root: &Path, instances: &[DicomInstance], mut to: W) -> Result<()> {
+        let file_set_id = self.options.file_set_id.as_deref().unwrap_or("");
+        if let Some(problem) = file_set_id_problem(file_set_id) {
+            return Err(DicomError::validation(problem));
+        }
+        if self.options.general_purpose_cd {
+            let problems = check_general_purpose_cd(root, instances);
+            if !problems.is_empty() {
+                return Err(DicomError::validation(format!(
+                    "Perfil STD-GEN-CD: {}",
+                    problems.join("; ")
+                )));
+            }
+        }
+
+        let mut records = Vec::new();
+        flatten(build_tree(root, instances)?, &mut records);
+
+        let meta = FileMetaTableBuilder::new()
+            .media_storage_sop_class_uid(uids::MEDIA_STORAGE_DIRECTORY_STORAGE)
+            .media_storage_sop_instance_uid(self.options.uid_generator.generate())
+            .transfer_syntax(uids::EXPLICIT_VR_LITTLE_ENDIAN)
+            .implementation_class_uid(IMPLEMENTATION_CLASS_UID)
+            .implementation_version_name(IMPLEMENTATION_VERSION_NAME)
+            .build()
+            .map_err(|e| DicomError::internal(format!("Error building File Meta: {}", e)))?;
+        let mut meta_bytes = Vec::new();
+        meta.write(&mut meta_bytes)
+            .map_err(|e| DicomError::internal(format!("Error writing File Meta: {}", e)))?;
+
+        // Los offsets son UL de tamaño fijo: el largo de cada item no depende
+        // de sus valores, así que se calculan antes de codificar
+        let mut dataset = InMemDicomObject::new_empty();
+        dataset.put(DataElement::new(tags::FILE_SET_ID, VR::CS, PrimitiveValue::from(file_set_id)));
+        let root_offset_tags = [
+            tags::OFFSET_OF_THE_FIRST_DIRECTORY_RECORD_OF_THE_ROOT_DIRECTORY_ENTITY,
+            tags::OFFSET_OF_THE_LAST_DIRECTORY_RECORD_OF_THE_ROOT_DIRECTORY_ENTITY,
+        ];
+        for tag in root_offset_tags {
+            dataset.put(DataElement::new(tag, VR::UL, PrimitiveValue::from(0u32)));
+        }
+        dataset.put(DataElement::new(tags::FILE_SET_CONSISTENCY_FLAG, VR::US, PrimitiveValue::from(0u16)));
+
+        // Preamble + "DICM" + File Meta + elementos previos + header de la secuencia
+        let mut offset = 132 + meta_bytes.len() as u64 + encoded_len(&dataset)? + 12;
+        let mut offsets = Vec::with_capacity(records.len());
+        for record in &records {
+            offsets.push(u32::try_from(offset).map_err(|_| DicomError::validation("DICOMDIR de más de 4 GB"))?);
+            // Item de longitud indefinida: header + contenido + delimitador
+            offset += 8 + encoded_len(&record.item)? + 8;
+        }
+
+        let offset_of = |index: Option<usize>| index.map_or(0, |i| offsets[i]);
+        let first_root = (!records.is_empty()).then_some(0);
+        let last_root = std::iter::successors(first_root, |&i| records[i].next).last();
+        dataset.put(DataElement::new(root_offset_tags[0], VR::UL, PrimitiveValue::from(offset_of(first_root))));
+        dataset.put(DataElement::new(root_offset_tags[1], VR::UL, PrimitiveValue::from(offset_of(last_root))));
+
+        let items: Vec<InMemDicomObject> = records
+            .into_iter()
+            .map(|mut record| {
+                record.item.put(DataElement::new(
+                    tags::OFFSET_OF_THE_NEXT_DIRECTORY_RECORD,
+                    VR::UL,
+                    PrimitiveValue::from(offset_of(record.next)),
+                ));
+                record.item.put(DataElement::new(
+                    tags::OFFSET_OF_REFERENCED_LOWER_LEVEL_DIRECTORY_ENTITY,
+                    VR::UL,
+                    PrimitiveValue::from(offset_of(record.lower)),
+                ));
+                record.item
+            })
+            .collect();
+        dataset.put(DataElement::new(
+            tags::DIRECTORY_RECORD_SEQUENCE,
+            VR::SQ,
+            DataSetSequence::new(items, Length::UNDEFINED),
+        ));
+
+        to.write_all(&[0u8; 128])?;
+        to.write_all(b"DICM")?;
+        to.write_all(&meta_bytes)?;
+        writer::write_dataset(&dataset, &mut to, uids::EXPLICIT_VR_LITTLE_ENDIAN, utf8())?;
+        Ok(())
+    }
+}
+
+impl Default for DicomDirWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Verificar el perfil General Purpose CD-R (STD-GEN-CD)
+///
+/// Además de los File IDs válidos que exige cualquier medio, el perfil pide
+/// Explicit VR Little Endian en todos los archivos. Devuelve los problemas
+/// encontrados (vacío si el conjunto cumple el perfil).
+pub fn check_general_purpose_cd(root: &Path, instances: &[DicomInstance]) -> Vec<String> {
+    let mut problems = Vec::new();
+    for instance in instances {
+        if let Err(e) = file_id(root, &instance.file_path) {
+            problems.push(e.to_string());
+        }
+        let ts = instance.metadata.transfer_syntax_uid.trim_end_matches(['\0', ' ']);
+        if ts != uids::EXPLICIT_VR_LITTLE_ENDIAN {
+            problems.push(format!(
+                "{}: transfer syntax {} (el perfil exige Explicit VR Little Endian)",
+                instance.file_path.display(),
+                ts
+            ));
+        }
+    }
+    problems
+}
+
+/// Registro a escribir, con los índices del siguiente y del primer hijo
+struct FlatRecord {
+    item: InMemDicomObject,
+    next: Option<usize>,
+    lower: Option<usize>,
+}
+
+struct RecordNode {
+    item: InMemDicomObject,
+    children: Vec<RecordNode>,
+}
+
+/// Aplanar en preorden (orden en el que quedan en la secuencia)
+fn flatten(nodes: Vec<RecordNode>, records: &mut Vec<FlatRecord>) -> Option<usize> {
+    let mut first = None;
+    let mut previous: Option<usize> = None;
+
+    for node in nodes {
+        let index = records.len();
+        records.push(FlatRecord {
+            item: node.item,
+            next: None,
+            lower: None,
+        });
+        match previous {
+            Some(previous) => records[previous].next = Some(index),
+            None => first = Some(index),
+        }
+        records[index].lower = flatten(node.children, records);
+        previous = Some(index);
+    }
+
+    first
+}
+
+/// Agrupar las instancias en registros PATIENT > STUDY > SERIES > IMAGE
+/// (o SR DOCUMENT, KEY OBJECT DOC, PRESENTATION, ENCAP DOC)
+fn build_tree(root: &Path, instances: &[DicomInstance]) -> Result<Vec<RecordNode>> {
+    type Series<'a> = BTreeMap<&'a str, Vec<&'a DicomInstance>>;
+    type Studies<'a> = BTreeMap<&'a str, Series<'a>>;
+    let mut patients: BTreeMap<&str, Studies> = BTreeMap::new();
+
+    for instance in instances {
+        let metadata = &instance.metadata;
+        patients
+            .entry(&metadata.patient_id)
+            .or_default()
+            .entry(&metadata.study_instance_uid)
+            .or_default()
+            .entry(&metadata.series_instance_uid)
+            .or_default()
+            .push(instance);
+    }
+
+    let mut tree = Vec::new();
+    for studies in patients.into_values() {
+        let mut study_nodes = Vec::new();
+        let mut first_patient = None;
+
+        for series in studies.into_values() {
+            let mut series_nodes = Vec::new();
+            let mut first_study = None;
+
+            let mut series: Vec<Vec<&DicomInstance>> = series.into_values().collect();
+            series.sort_by_key(|instances| instances[0].metadata.series_number.unwrap_or(i32::MAX));
+            for mut instances in series {
+                instances.sort_by(|a, b| {
+                    let number = |i: &DicomInstance| i.metadata.instance_number.unwrap_or(i32::MAX);
+                    number(a).cmp(&number(b)).then_with(|| a.file_path.cmp(&b.file_path))
+                });
+
+                let images = instances
+                    .iter()
+                    .map(|instance| leaf_record(root, instance))
+                    .collect::<Result<Vec<_>>>()?;
+                first_study = first_study.or(Some(instances[0]));
+                series_nodes.push(RecordNode {
+                    item: series_record(instances[0]),
+                    children: images,
+                });
+            }
+
+            let first = first_study.expect("serie con instancias");
+            first_patient = first_patient.or(Some(first));
+            study_nodes.push(RecordNode {
+                item: study_record(first),
+                children: series_nodes,
+            });
+        }
+
+        tree.push(RecordNode {
+            item: patient_record(first_patient.expect("estudio con instancias")),
+            children: study_nodes,
+        });
+    }
+
+    Ok(tree)
+}
+
+/// Item de registro con el tipo y las claves copiadas de la instancia
+///
+/// Las claves ausentes se escriben vacías. Como los textos ya están
+/// decodificados, si la instancia declara un charset el registro se
+/// escribe en UTF-8.
+fn record(record_type: RecordType, instance: &DicomInstance, keys: &[(Tag, VR)]) -> InMemDicomObject {
+    let mut item = InMemDicomObject::new_empty();
+    // Los offsets se completan al escribir, pero ya cuentan para el largo del item
+    for tag in [
+        tags::OFFSET_OF_THE_NEXT_DIRECTORY_RECORD,
+        tags::OFFSET_OF_REFERENCED_LOWER_LEVEL_DIRECTORY_ENTITY,
+    ] {
+        item.put(DataElement::new(tag, VR::UL, PrimitiveValue::from(0u32)));
+    }
+    item.put(DataElement::new(tags::RECORD_IN_USE_FLAG, VR::US, PrimitiveValue::from(0xFFFFu16)));
+    item.put(DataElement::new(
+        tags::DIRECTORY_RECORD_TYPE,
+        VR::CS,
+        PrimitiveValue::from(record_type.as_str()),
+    ));
+    if instance.element(tags::SPECIFIC_CHARACTER_SET).is_some() {
+        item.put(DataElement::new(
+            tags::SPECIFIC_CHARACTER_SET,
+            VR::CS,
+            PrimitiveValue::from("ISO_IR 192"),
+        ));
+    }
+
+    for &(tag, vr) in keys {
+        let element: Element = instance
+            .element(tag)
+            .cloned()
+            .unwrap_or_else(|| DataElement::new(tag, vr, PrimitiveValue::Empty));
+        item.put(element);
+    }
+    item
+}
+
+fn patient_record(instance: &DicomInstance) -> InMemDicomObject {
+    let mut item = record(
+        RecordType::Patient,
+        instance,
+        &[(tags::PATIENT_NAME, VR::PN), (tags::PATIENT_ID, VR::LO)],
+    );
+    for tag in [tags::PATIENT_BIRTH_DATE, tags::PATIENT_SEX] {
+        if let Some(element) = instance.element(tag) {
+            item.put(element.clone());
+        }
+    }
+    item
+}
+
+fn study_record(instance: &DicomInstance) -> InMemDicomObject {
+    record(
+        RecordType::Study,
+        instance,
+        &[
+            (tags::STUDY_DATE, VR::DA),
+            (tags::STUDY_TIME, VR::TM),
+            (tags::ACCESSION_NUMBER, VR::SH),
+            (tags::STUDY_DESCRIPTION, VR::LO),
+            (tags::STUDY_INSTANCE_UID, VR::UI),
+            (tags::STUDY_ID, VR::SH),
+        ],
+    )
+}
+
+fn series_record(instance: &DicomInstance) -> InMemDicomObject {
+    record(
+        RecordType::Series,
+        instance,
+        &[
+            (tags::MODALITY, VR::CS),
+            (tags::SERIES_INSTANCE_UID, VR::UI),
+            (tags::SERIES_NUMBER, VR::IS),
+        ],
+    )
+}
+
+/// Tipo del registro de una instancia según su SOP Class (PS3.3 F.5)
+fn leaf_record_type(sop_class: &str) -> RecordType {
+    let code = match sop_class.trim_end_matches(['\0', ' ']) {
+        uids::BASIC_TEXT_SR_STORAGE
+        | uids::ENHANCED_SR_STORAGE
+        | uids::COMPREHENSIVE_SR_STORAGE
+        | uids::COMPREHENSIVE3_DSR_STORAGE
+        | uids::EXTENSIBLE_SR_STORAGE
+        | uids::MAMMOGRAPHY_CADSR_STORAGE
+        | uids::CHEST_CADSR_STORAGE
+        | uids::COLON_CADSR_STORAGE
+        | uids::X_RAY_RADIATION_DOSE_SR_STORAGE
+        | uids::ENHANCED_X_RAY_RADIATION_DOSE_SR_STORAGE
+        | uids::RADIOPHARMACEUTICAL_RADIATION_DOSE_SR_STORAGE
+        | uids::PATIENT_RADIATION_DOSE_SR_STORAGE
+        | uids::IMPLANTATION_PLAN_SR_STORAGE
+        | uids::ACQUISITION_CONTEXT_SR_STORAGE
+        | uids::SIMPLIFIED_ADULT_ECHO_SR_STORAGE
+        | uids::PLANNED_IMAGING_AGENT_ADMINISTRATION_SR_STORAGE
+        | uids::PERFORMED_IMAGING_AGENT_ADMINISTRATION_SR_STORAGE => "SR DOCUMENT",
+        uids::KEY_OBJECT_SELECTION_DOCUMENT_STORAGE => "KEY OBJECT DOC",
+        uids::GRAYSCALE_SOFTCOPY_PRESENTATION_STATE_STORAGE
+        | uids::COLOR_SOFTCOPY_PRESENTATION_STATE_STORAGE
+        | uids::PSEUDO_COLOR_SOFTCOPY_PRESENTATION_STATE_STORAGE
+        | uids::BLENDING_SOFTCOPY_PRESENTATION_STATE_STORAGE
+        | uids::XAXRF_GRAYSCALE_SOFTCOPY_PRESENTATION_STATE_STORAGE
+        | uids::ADVANCED_BLENDING_PRESENTATION_STATE_STORAGE
+        | uids::VARIABLE_MODALITY_LUT_PRESENTATION_STATE_STORAGE
+        | uids::GRAYSCALE_PLANAR_MPR_VOLUMETRIC_PRESENTATION_STATE_STORAGE
+        | uids::COMPOSITING_PLANAR_MPR_VOLUMETRIC_PRESENTATION_STATE_STORAGE
+        | uids::VOLUME_RENDERING_VOLUMETRIC_PRESENTATION_STATE_STORAGE
+        | uids::SEGMENTED_VOLUME_RENDERING_VOLUMETRIC_PRESENTATION_STATE_STORAGE
+        | uids::MULTIPLE_VOLUME_RENDERING_VOLUMETRIC_PRESENTATION_STATE_STORAGE => "PRESENTATION",
+        uids::ENCAPSULATED_PDF_STORAGE
+        | uids::ENCAPSULATED_CDA_STORAGE
+        | uids::ENCAPSULATED_STL_STORAGE
+        | uids::ENCAPSULATED_OBJ_STORAGE
+        | uids::ENCAPSULATED_MTL_STORAGE => "ENCAP DOC",
+        _ => return RecordType::Image,
+    };
+    RecordType::Other(code.to_string())
+}
+
+/// Claves del registro de una instancia según su tipo (PS3.3 F.5)
+///
+/// Las secuencias se copian solo si la instancia las tiene.
+fn leaf_keys(record_type: &RecordType) -> (&'static [(Tag, VR)], &'static [Tag]) {
+    match record_type.as_str() {
+        "SR DOCUMENT" => (
+            &[
+                (tags::INSTANCE_NUMBER, VR::IS),
+                (tags::COMPLETION_FLAG, VR::CS),
+                (tags::VERIFICATION_FLAG, VR::CS),
+                (tags::CONTENT_DATE, VR::DA),
+                (tags::CONTENT_TIME, VR::TM),
+            ],
+            &[tags::CONCEPT_NAME_CODE_SEQUENCE],
+        ),
+        "KEY OBJECT DOC" => (
+            &[
+                (tags::INSTANCE_NUMBER, VR::IS),
+                (tags::CONTENT_DATE, VR::DA),
+                (tags::CONTENT_TIME, VR::TM),
+            ],
+            &[tags::CONCEPT_NAME_CODE_SEQUENCE],
+        ),
+        "PRESENTATION" => (
+            &[
+                (tags::INSTANCE_NUMBER, VR::IS),
+                (tags::CONTENT_LABEL, VR::CS),
+                (tags::CONTENT_DESCRIPTION, VR::LO),
+                (tags::PRESENTATION_CREATION_DATE, VR::DA),
+                (tags::PRESENTATION_CREATION_TIME, VR::TM),
+                (tags::CONTENT_CREATOR_NAME, VR::PN),
+            ],
+            &[tags::REFERENCED_SERIES_SEQUENCE],
+        ),
+        "ENCAP DOC" => (
+            &[
+                (tags::INSTANCE_NUMBER, VR::IS),
+                (tags::CONTENT_DATE, VR::DA),
+                (tags::CONTENT_TIME, VR::TM),
+                (tags::DOCUMENT_TITLE, VR::ST),
+                (tags::MIME_TYPE_OF_ENCAPSULATED_DOCUMENT, VR::LO),
+            ],
+            &[tags::CONCEPT_NAME_CODE_SEQUENCE],
+        ),
+        _ => (&[(tags::INSTANCE_NUMBER, VR::IS)], &[]),
+    }
+}
+
+fn leaf_record(root: &Path, instance: &DicomInstance) -> Result<RecordNode> {
+    let sop_class = instance
+        .get::<String>(tags::SOP_CLASS_UID)
+        .map(|uid| uid.trim_end_matches('\0').to_string())
+        .ok_or_else(|| DicomError::MissingRequiredTag(tags::SOP_CLASS_UID.to_string()))?;
+
+    let record_type = leaf_record_type(&sop_class);
+    let (keys, sequences) = leaf_keys(&record_type);
+    let mut item = record(record_type, instance, keys);
+    for &tag in sequences {
+        if let Some(element) = instance.element(tag) {
+            item.put(element.clone());
+        }
+    }
+    let references = [
+        (tags::REFERENCED_SOP_CLASS_UID_IN_FILE, sop_class.as_str()),
+        (tags::REFERENCED_SOP_INSTANCE_UID_IN_FILE, instance.metadata.sop_instance_uid.as_str()),
+        (tags::REFERENCED_TRANSFER_SYNTAX_UID_IN_FILE, instance.metadata.transfer_syntax_uid.as_str()),
+    ];
+    for (tag, uid) in references {
+        item.put(DataElement::new(tag, VR::UI, PrimitiveValue::from(uid.trim_end_matches(['\0', ' ']))));
+    }
+
+    let components = file_id(root, &instance.file_path)?;
+    item.put(DataElement::new(
+        tags::REFERENCED_FILE_ID,
+        VR::CS,
+        PrimitiveValue::Strs(components.into()),
+    ));
+
+    Ok(RecordNode {
+        item,
+        children: Vec::new(),
+    })
+}
+
+/// Referenced File ID de `path` relativo a `root` (PS3.10 §8.5)
+///
+/// Hasta 8 componentes de 1 a 8 caracteres A-Z, 0-9 o "_".
+fn file_id(root: &Path, path: &Path) -> Result<Vec<String>> {
+    let relative = path.strip_prefix(root).map_err(|_| {
+        DicomError::validation(format!("{} no está dentro de {}", path.display(), root.display()))
+    })?;
+    let components: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+
+    match file_id_problem(&components) {
+        Some(problem) => Err(DicomError::validation(problem)),
+        None => Ok(components),
+    }
+}
+
+/// Mismo criterio al escribir y al leer: un File ID del medio nunca sale
+/// del directorio del DICOMDIR
+fn file_id_problem(components: &[String]) -> Option<String> {
+    let valid_component = |c: &String| {
+        (1..=MAX_COMPONENT_LENGTH).contains(&c.len())
+            && c.bytes().all(|b| b.is_ascii_uppercase() || b.is_ascii_digit() || b == b'_')
+    };
+    let valid = !components.is_empty()
+        && components.len() <= MAX_FILE_ID_COMPONENTS
+        && components.iter().all(valid_component);
+    (!valid).then(|| {
+        format!(
+            "File ID inválido para medios DICOM: {} (hasta {} componentes de 1-{} caracteres A-Z, 0-9, _)",
+            components.join("\\"),
+            MAX_FILE_ID_COMPONENTS,
+            MAX_COMPONENT_LENGTH
+        )
+    })
+}
+
+fn file_set_id_problem(id: &str) -> Option<String> {
+    let valid = id.len() <= MAX_FILE_SET_ID_LENGTH
+        && id.bytes().all(|b| b.is_ascii_uppercase() || b.is_ascii_digit() || b == b'_' || b == b' ');
+    (!valid).then(|| format!("File-set ID inválido: {:?}", id))
+}
+
+/// Largo del data set codificado en Explicit VR Little Endian
+fn encoded_len(dataset: &InMemDicomObject) -> Result<u64> {
+    let mut encoded = Vec::new();
+    writer::write_dataset(dataset, &mut encoded, uids::EXPLICIT_VR_LITTLE_ENDIAN, utf8())?;
+    Ok(encoded.len() as u64)
+}
+
+fn utf8() -> SpecificCharacterSet {
+    SpecificCharacterSet::from_code("ISO_IR 192").unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(record_type: &str) -> InMemDicomObject {
+        let mut item = InMemDicomObject::new_empty();
+        item.put(DataElement::new(tags::DIRECTORY_RECORD_TYPE, VR::CS, PrimitiveValue::from(record_type)));
+        item
+    }
+
+    #[test]
+    fn test_link_by_order() {
+        let types = ["PATIENT", "STUDY", "SERIES", "IMAGE", "IMAGE", "SERIES", "IMAGE", "PATIENT"];
+        let records = link_by_order(types.iter().map(|t| item(t)).collect());
+
+        assert_eq!(records.len(), 2);
+        let series = &records[0].children[0].children;
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[0].children.len(), 2);
+        assert_eq!(series[1].children[0].record_type, RecordType::Image);
+        assert_eq!(records[0].iter().count(), 7);
+    }
+
+    #[test]
+    fn test_leaf_record_type() {
+        for (sop_class, code) in [
+            (uids::ULTRASOUND_IMAGE_STORAGE, "IMAGE"),
+            (uids::ENHANCED_SR_STORAGE, "SR DOCUMENT"),
+            (uids::KEY_OBJECT_SELECTION_DOCUMENT_STORAGE, "KEY OBJECT DOC"),
+            (uids::GRAYSCALE_SOFTCOPY_PRESENTATION_STATE_STORAGE, "PRESENTATION"),
+            (uids::ENCAPSULATED_PDF_STORAGE, "ENCAP DOC"),
+        ] {
+            assert_eq!(leaf_record_type(&format!("{}\0", sop_class)).as_str(), code);
+        }
+    }
+
+    #[test]
+    fn test_file_id() {
+        let root = Path::new("/media/cd");
+        assert_eq!(
+            file_id(root, Path::new("/media/cd/DICOM/IM_0001")).unwrap(),
+            ["DICOM", "IM_0001"]
+        );
+        assert!(file_id(root, Path::new("/media/cd/dicom/IM1")).is_err());
+        assert!(file_id(root, Path::new("/media/cd/IMAGE.DCM")).is_err());
+        assert!(file_id(root, Path::new("/tmp/IM1")).is_err());
+        assert!(file_id_problem(&["..".to_string(), "ETC".to_string()]).is_some());
+        assert!(file_id_problem(&vec!["A".to_string(); MAX_FILE_ID_COMPONENTS + 1]).is_some());
+        assert!(file_set_id_problem("ECOCOL_2026").is_none());
+        assert!(file_set_id_problem("eco-col").is_some());
+    }
+}
@@ -1,5 +1,6 @@
 //! Error types para DICOM parsing
 
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// Resultado genérico para operaciones DICOM
@@ -58,3 +59,38 @@ impl DicomError {
         DicomError::Internal(msg.into())
     }
 }
+
+/// Problema tolerado al parsear en modo recuperación (`ParseOptions::recovery`)
+///
+/// En modo estricto estos casos abortan el parsing con un `DicomError`.
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ParseWarning {
+    #[error("Falta el preamble de 128 bytes y/o el prefijo 'DICM'")]
+    MissingPreamble,
+
+    #[error("Falta el File Meta group: se generó a partir del data set")]
+    MissingFileMeta,
+
+    #[error("Transfer Syntax deducida del primer elemento: {uid}")]
+    GuessedTransferSyntax { uid: String },
+
+    #[error("Elemento {tag} con longitud impar ({length})")]
+    OddLength { tag: String, length: u32 },
+
+    #[error("Data set truncado en el offset {offset}: se descartó el resto")]
+    TruncatedDataSet { offset: u64 },
+
+    /// Solo se conservan los frames (o fragmentos) completos
+    #[error("Pixel data truncado: {available} de {expected} bytes")]
+    TruncatedPixelData { expected: u64, available: u64 },
+
+    #[error("Pixel data ilegible: {message}")]
+    UnreadablePixelData { message: String },
+
+    #[error("Atributo requerido ausente: {tag}")]
+    MissingAttribute { tag: String },
+
+    #[error("Validación: {message}")]
+    Validation { message: String },
+}
@@ -0,0 +1,408 @@
+//! Exportación de frames a PNG y JPEG (y de cine loops, ver [`CineFormat`])
+//!
+//! Previews para la worklist y los informes: el frame pasa por el pipeline
+//! de visualización ([`PixelData::render_frame`]), se escala en CPU y se
+//! codifica con encoders en Rust puro, así que funciona sin GPU ni display.
+//! Requiere la cargo feature `export` (incluida por defecto).
+//!
+//! ```rust,no_run
+//! use dicom_core::export::{ExportOptions, ImageFormat};
+//! # let instance = dicom_core::DicomParser::new().parse_file(std::path::Path::new("cine.dcm"))?;
+//! let thumbnail = instance.export_frame(0, &ExportOptions {
+//!     format: ImageFormat::Jpeg { quality: 85 },
+//!     size: Some((256, 256)),
+//!     ..ExportOptions::default()
+//! })?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::codec::CodecRegistry;
+use crate::error::{DicomError, Result};
+use crate::metadata::DicomInstance;
+use crate::pixel::{PixelData, PixelDataLocation, RenderOptions, RenderedFrame};
+
+use std::path::Path;
+
+mod cine;
+
+pub use cine::{CineExportOptions, CineFormat, DEFAULT_FRAME_RATE};
+
+// ============================================
+// Opciones
+// ============================================
+
+/// Formato de salida
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// PNG sin pérdida (gris o RGB de 8 bits)
+    #[default]
+    Png,
+    /// JPEG baseline con calidad 1-100
+    Jpeg { quality: u8 },
+}
+
+/// Opciones de exportación de un frame
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExportOptions {
+    pub format: ImageFormat,
+
+    /// Ventana / VOI LUT (por defecto la del archivo)
+    pub render: RenderOptions,
+
+    /// Caja (ancho, alto) en la que debe entrar la imagen, conservando la
+    /// proporción; None = tamaño original
+    pub size: Option<(u32, u32)>,
+}
+
+/// Opciones de la hoja de contactos de un cine loop
+#[derive(Debug, Clone, Copy)]
+pub struct ContactSheetOptions {
+    pub format: ImageFormat,
+
+    /// Ventana / VOI LUT, igual para todos los frames
+    pub render: RenderOptions,
+
+    /// Cantidad de frames, tomados a intervalos regulares del loop
+    pub frames: u32,
+
+    /// Columnas de la grilla (None = la raíz cuadrada redondeada hacia arriba)
+    pub columns: Option<u32>,
+
+    /// Caja (ancho, alto) de cada frame
+    pub tile_size: (u32, u32),
+
+    /// Separación en píxeles entre frames y en el borde
+    pub spacing: u32,
+
+    /// Nivel de gris del fondo
+    pub background: u8,
+}
+
+impl Default for ContactSheetOptions {
+    fn default() -> Self {
+        Self {
+            format: ImageFormat::default(),
+            render: RenderOptions::default(),
+            frames: 9,
+            columns: None,
+            tile_size: (160, 120),
+            spacing: 4,
+            background: 0,
+        }
+    }
+}
+
+// ============================================
+// API
+// ============================================
+
+impl DicomInstance {
+    /// Renderizar el frame `n` y codificarlo en el formato pedido
+    ///
+    /// Con lazy loading solo se lee ese frame del archivo.
+    pub fn export_frame(&self, n: u32, options: &ExportOptions) -> Result<Vec<u8>> {
+        let mut frame = frame_pixels(self, n)?.render_frame(0, &options.render)?;
+        if let Some((width, height)) = options.size {
+            frame = frame.fit_within(width, height);
+        }
+        frame.encode(options.format)
+    }
+
+    /// Igual que [`export_frame`](Self::export_frame), escribiendo en `path`
+    pub fn export_frame_to_file(&self, n: u32, options: &ExportOptions, path: &Path) -> Result<()> {
+        std::fs::write(path, self.export_frame(n, options)?)?;
+        Ok(())
+    }
+
+    /// Hoja de contactos con `frames` frames del cine loop en una grilla
+    ///
+    /// Si la instancia tiene menos frames se usan todos. La imagen es RGB si
+    /// algún frame es a color.
+    pub fn contact_sheet(&self, options: &ContactSheetOptions) -> Result<Vec<u8>> {
+        let total = self
+            .pixel_descriptor
+            .as_ref()
+            .ok_or_else(|| DicomError::MissingRequiredTag("PixelData".to_string()))?
+            .number_of_frames
+            .max(1);
+
+        let (tile_width, tile_height) = (options.tile_size.0.max(1), options.tile_size.1.max(1));
+        let frames = sample_frames(total, options.frames);
+
+        let count = frames.len() as u32;
+        let columns = options
+            .columns
+            .unwrap_or_else(|| (count as f64).sqrt().ceil() as u32)
+            .clamp(1, count);
+        let rows = count.div_ceil(columns);
+        let spacing = options.spacing;
+        let (width, height) = (
+            sheet_extent(columns, tile_width, spacing)?,
+            sheet_extent(rows, tile_height, spacing)?,
+        );
+
+        let tiles = frames
+            .into_iter()
+            .map(|n| Ok(frame_pixels(self, n)?.render_frame(0, &options.render)?.fit_within(tile_width, tile_height)))
+            .collect::<Result<Vec<_>>>()?;
+        let samples_per_pixel = tiles.iter().map(|t| t.samples_per_pixel).max().unwrap_or(1);
+
+        let len = (width as usize)
+            .checked_mul(height as usize)
+            .and_then(|n| n.checked_mul(samples_per_pixel as usize))
+            .ok_or_else(|| sheet_too_large(width, height))?;
+        let mut sheet = RenderedFrame {
+            width,
+            height,
+            samples_per_pixel,
+            data: vec![options.background; len],
+        };
+
+        for (i, tile) in tiles.into_iter().enumerate() {
+            let (column, row) = (i as u32 % columns, i as u32 / columns);
+            // Centrado dentro de la celda
+            let x = spacing + column * (tile_width + spacing) + (tile_width - tile.width) / 2;
+            let y = spacing + row * (tile_height + spacing) + (tile_height - tile.height) / 2;
+            let tile = if tile.samples_per_pixel == samples_per_pixel {
+                tile
+            } else {
+                RenderedFrame {
+                    width: tile.width,
+                    height: tile.height,
+                    samples_per_pixel: 3,
+                    data: tile.into_rgb8(),
+                }
+            };
+            sheet.blit(&tile, x, y);
+        }
+
+        sheet.encode(options.format)
+    }
+}
+
+/// Largo de la grilla en un eje: `cells` celdas de `tile` con `spacing`
+/// entre ellas y en los bordes
+fn sheet_extent(cells: u32, tile: u32, spacing: u32) -> Result<u32> {
+    tile.checked_add(spacing)
+        .and_then(|cell| cell.checked_mul(cells))
+        .and_then(|n| n.checked_add(spacing))
+        .ok_or_else(|| {
+            DicomError::validation(format!(
+                "Contact sheet demasiado grande: {} celdas de {} px con separación {}",
+                cells, tile, spacing
+            ))
+        })
+}
+
+fn sheet_too_large(width: u32, height: u32) -> DicomError {
+    DicomError::validation(format!("Contact sheet demasiado grande: {}x{}", width, height))
+}
+
+impl RenderedFrame {
+    /// Escalar a `width` x `height` (promedio de área al reducir, bilineal al ampliar)
+    pub fn resize(&self, width: u32, height: u32) -> RenderedFrame {
+        let (width, height) = (width.max(1), height.max(1));
+        let channels = self.samples_per_pixel as usize;
+        let columns = axis_weights(self.width, width);
+        let rows = axis_weights(self.height, height);
+
+        let mut data = Vec::with_capacity(width as usize * height as usize * channels);
+        let mut sums = vec![0f32; channels];
+        for row in &rows {
+            for column in &columns {
+                sums.iter_mut().for_each(|s| *s = 0.0);
+                for &(y, wy) in row {
+                    for &(x, wx) in column {
+                        let offset = (y * self.width as usize + x) * channels;
+                        for (c, sum) in sums.iter_mut().enumerate() {
+                            *sum += self.data[offset + c] as f32 * wx * wy;
+                        }
+                    }
+                }
+                data.extend(sums.iter().map(|s| s.round().clamp(0.0, 255.0) as u8));
+            }
+        }
+
+        RenderedFrame {
+            width,
+            height,
+            samples_per_pixel: self.samples_per_pixel,
+            data,
+        }
+    }
+
+    /// Escalar para entrar en la caja `width` x `height` conservando la proporción
+    pub fn fit_within(&self, width: u32, height: u32) -> RenderedFrame {
+        let scale = (width as f64 / self.width as f64).min(height as f64 / self.height as f64);
+        let target_width = ((self.width as f64 * scale).round() as u32).clamp(1, width.max(1));
+        let target_height = ((self.height as f64 * scale).round() as u32).clamp(1, height.max(1));
+
+        if (target_width, target_height) == (self.width, self.height) {
+            return self.clone();
+        }
+        self.resize(target_width, target_height)
+    }
+
+    /// Codificar como PNG o JPEG
+    pub fn encode(&self, format: ImageFormat) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+        match format {
+            ImageFormat::Png => {
+                let color = if self.samples_per_pixel == 3 {
+                    png::ColorType::Rgb
+                } else {
+                    png::ColorType::Grayscale
+                };
+                let mut encoder = png::Encoder::new(&mut out, self.width, self.height);
+                encoder.set_color(color);
+                encoder.set_depth(png::BitDepth::Eight);
+                encoder
+                    .write_header()
+                    .and_then(|mut writer| writer.write_image_data(&self.data))
+                    .map_err(|e| DicomError::internal(format!("Error encoding PNG: {}", e)))?;
+            }
+            ImageFormat::Jpeg { quality } => {
+                let color = if self.samples_per_pixel == 3 {
+                    jpeg_encoder::ColorType::Rgb
+                } else {
+                    jpeg_encoder::ColorType::Luma
+                };
+                let (width, height) = match (u16::try_from(self.width), u16::try_from(self.height)) {
+                    (Ok(width), Ok(height)) => (width, height),
+                    _ => {
+                        return Err(DicomError::validation(format!(
+                            "Imagen de {}x{} demasiado grande para JPEG",
+                            self.width, self.height
+                        )))
+                    }
+                };
+                jpeg_encoder::Encoder::new(&mut out, quality.clamp(1, 100))
+                    .encode(&self.data, width, height, color)
+                    .map_err(|e| DicomError::internal(format!("Error encoding JPEG: {}", e)))?;
+            }
+        }
+        Ok(out)
+    }
+
+    /// Copiar `tile` (mismas muestras por píxel) en la posición (x, y)
+    fn blit(&mut self, tile: &RenderedFrame, x: u32, y: u32) {
+        let channels = self.samples_per_pixel as usize;
+        let row_len = tile.width as usize * channels;
+        for row in 0..tile.height as usize {
+            let source = row * row_len;
+            let target = ((y as usize + row) * self.width as usize + x as usize) * channels;
+            self.data[target..target + row_len].copy_from_slice(&tile.data[source..source + row_len]);
+        }
+    }
+}
+
+// ============================================
+// Auxiliares
+// ============================================
+
+/// Frame `n` como `PixelData` de un solo frame
+///
+/// Si los píxeles no están en memoria se lee solo ese frame; los frames
+/// decodificados por un codec pueden cambiar de espacio de color.
+fn frame_pixels(instance: &DicomInstance, n: u32) -> Result<PixelData> {
+    let mut descriptor = match (&instance.pixel_data, &instance.pixel_descriptor) {
+        (Some(pixel_data), _) => pixel_data.descriptor.clone(),
+        (None, Some(descriptor)) => descriptor.clone(),
+        (None, None) => return Err(DicomError::MissingRequiredTag("PixelData".to_string())),
+    };
+    let data = instance.load_frame(n)?;
+
+    if instance.pixel_data.is_none() && matches!(descriptor.location, Some(PixelDataLocation::Encapsulated { .. })) {
+        if let Some(decoder) = CodecRegistry::builtin().decoder(&instance.metadata.transfer_syntax_uid) {
+            descriptor.photometric_interpretation = decoder.output_photometric_interpretation(&descriptor);
+            descriptor.planar_configuration = 0;
+        }
+    }
+    descriptor.number_of_frames = 1;
+
+    Ok(PixelData { descriptor, data })
+}
+
+/// `count` índices repartidos uniformemente entre 0 y `total - 1`
+fn sample_frames(total: u32, count: u32) -> Vec<u32> {
+    let count = count.clamp(1, total);
+    if count == 1 {
+        return vec![0];
+    }
+    (0..count)
+        .map(|i| (u64::from(i) * u64::from(total - 1) / u64::from(count - 1)) as u32)
+        .collect()
+}
+
+/// Pesos de las muestras de origen para cada posición de destino en un eje
+///
+/// Al reducir cada destino promedia el área que cubre; al ampliar se
+/// interpola linealmente entre las dos muestras más cercanas.
+fn axis_weights(source: u32, target: u32) -> Vec<Vec<(usize, f32)>> {
+    let scale = source as f64 / target as f64;
+    let last = source.saturating_sub(1) as usize;
+
+    (0..target)
+        .map(|i| {
+            if scale > 1.0 {
+                let (start, end) = (i as f64 * scale, (i + 1) as f64 * scale);
+                let mut weights = Vec::new();
+                let mut x = start.floor();
+                while x < end {
+                    let coverage = (end.min(x + 1.0) - start.max(x)) / scale;
+                    if coverage > 0.0 {
+                        weights.push(((x as usize).min(last), coverage as f32));
+                    }
+                    x += 1.0;
+                }
+                weights
+            } else {
+                let center = ((i as f64 + 0.5) * scale - 0.5).max(0.0);
+                let x0 = (center.floor() as usize).min(last);
+                let x1 = (x0 + 1).min(last);
+                let t = (center - x0 as f64).clamp(0.0, 1.0) as f32;
+                vec![(x0, 1.0 - t), (x1, t)]
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gray(width: u32, height: u32, data: Vec<u8>) -> RenderedFrame {
+        RenderedFrame {
+            width,
+            height,
+            samples_per_pixel: 1,
+            data,
+        }
+    }
+
+    #[test]
+    fn test_sample_frames() {
+        assert_eq!(sample_frames(30, 4), [0, 9, 19, 29]);
+        assert_eq!(sample_frames(3, 9), [0, 1, 2]);
+        assert_eq!(sample_frames(1, 9), [0]);
+    }
+
+    #[test]
+    fn test_sheet_extent() {
+        assert_eq!(sheet_extent(2, 4, 1).unwrap(), 11);
+        assert!(sheet_extent(2, u32::MAX / 2, 1).is_err());
+        assert!(sheet_extent(1, u32::MAX - 1, 1).is_err());
+    }
+
+    #[test]
+    fn test_resize_averages_and_interpolates() {
+        let frame = gray(4, 2, vec![0, 100, 200, 250, 0, 100, 200, 250]);
+        assert_eq!(frame.resize(2, 1).data, [50, 225]);
+
+        let up = gray(2, 1, vec![0, 200]).resize(4, 1);
+        assert_eq!(up.data, [0, 50, 150, 200]);
+
+        let fitted = gray(4, 2, vec![0; 8]).fit_within(100, 100);
+        assert_eq!((fitted.width, fitted.height), (100, 50));
+    }
+}
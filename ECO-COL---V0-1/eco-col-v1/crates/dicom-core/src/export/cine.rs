@@ -0,0 +1,413 @@
+//! Exportación de cine loops animados (GIF, APNG, MJPEG en AVI)
+//!
+//! Para que los médicos derivantes vean el loop sin visor DICOM. La
+//! velocidad de reproducción sale del data set: Frame Time Vector conserva
+//! el ritmo real de adquisición (p.ej. gatillado por ECG); sin vector se usa
+//! Recommended Display Frame Rate, y después Frame Time o Cine Rate.
+
+use super::{frame_pixels, sample_frames};
+use crate::error::{DicomError, Result};
+use crate::metadata::DicomInstance;
+use crate::pixel::{RenderOptions, RenderedFrame};
+
+use dicom::dictionary_std::tags;
+use std::borrow::Cow;
+use std::ops::Range;
+use std::path::Path;
+
+/// Frecuencia usada si el data set no trae ningún dato de tiempo
+pub const DEFAULT_FRAME_RATE: f64 = 25.0;
+
+/// Delay mínimo de GIF en centésimas: los navegadores muestran los menores
+/// como 10 cs
+const MIN_GIF_DELAY_CS: u16 = 2;
+
+/// Formato del loop animado
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CineFormat {
+    /// GIF animado (256 colores por frame; exacto en escala de grises)
+    #[default]
+    Gif,
+    /// PNG animado, sin pérdida
+    Apng,
+    /// Motion JPEG en contenedor AVI (frecuencia constante: la media de los delays)
+    MjpegAvi { quality: u8 },
+}
+
+/// Opciones de exportación del cine loop
+#[derive(Debug, Clone, Default)]
+pub struct CineExportOptions {
+    pub format: CineFormat,
+
+    /// Ventana / VOI LUT, igual para todos los frames
+    pub render: RenderOptions,
+
+    /// Frames a exportar (desde 0, fin exclusivo); None = el loop completo
+    pub frames: Option<Range<u32>>,
+
+    /// Caja (ancho, alto) máxima; los frames más grandes se reducen
+    pub max_size: Option<(u32, u32)>,
+
+    /// Máximo de frames; se descartan frames a intervalos regulares sin
+    /// cambiar la duración total
+    pub max_frames: Option<u32>,
+}
+
+impl DicomInstance {
+    /// Tiempo en ms que se muestra cada frame al reproducir el loop
+    ///
+    /// Frame Time Vector da el tiempo desde el frame anterior, así que cada
+    /// frame dura lo que indica el valor del siguiente.
+    pub fn playback_delays_ms(&self) -> Result<Vec<f64>> {
+        let descriptor = self
+            .pixel_descriptor
+            .as_ref()
+            .or(self.pixel_data.as_ref().map(|p| &p.descriptor))
+            .ok_or_else(|| DicomError::MissingRequiredTag("PixelData".to_string()))?;
+        let total = descriptor.number_of_frames.max(1);
+        let recommended = self
+            .get::<f64>(tags::RECOMMENDED_DISPLAY_FRAME_RATE)
+            .filter(|&rate| rate > 0.0)
+            .map(|rate| 1000.0 / rate);
+
+        Ok((0..total)
+            .map(|n| {
+                let next = if n + 1 < total { n + 1 } else { n };
+                let from_vector = descriptor
+                    .frame_time_vector
+                    .as_ref()
+                    .and_then(|vector| vector.get(next as usize).copied());
+
+                from_vector
+                    .filter(|&ms| ms > 0.0)
+                    .or(recommended)
+                    .or_else(|| descriptor.frame_duration_ms(next).filter(|&ms| ms > 0.0))
+                    .unwrap_or(1000.0 / DEFAULT_FRAME_RATE)
+            })
+            .collect())
+    }
+
+    /// Exportar el cine loop como animación
+    pub fn export_cine(&self, options: &CineExportOptions) -> Result<Vec<u8>> {
+        let delays = self.playback_delays_ms()?;
+        let total = delays.len() as u32;
+
+        let range = options.frames.clone().unwrap_or(0..total);
+        let (start, end) = (range.start.min(total), range.end.min(total));
+        if start >= end {
+            return Err(DicomError::validation(format!(
+                "Rango de frames vacío: {:?} (total: {})",
+                range, total
+            )));
+        }
+
+        let mut selected: Vec<(u32, f64)> = (start..end).map(|n| (n, delays[n as usize])).collect();
+        if let Some(max_frames) = options.max_frames {
+            selected = decimate(&selected, max_frames);
+        }
+
+        let count = selected.len() as u32;
+        let mut frames = selected.into_iter().map(|(n, delay)| {
+            let frame = frame_pixels(self, n)?.render_frame(0, &options.render)?;
+            let frame = match options.max_size {
+                Some((width, height)) if frame.width > width || frame.height > height => {
+                    frame.fit_within(width, height)
+                }
+                _ => frame,
+            };
+            Ok((frame, delay))
+        });
+
+        let first = frames.next().expect("rango no vacío")?;
+        let frames = std::iter::once(Ok(first.clone())).chain(frames);
+        let (width, height) = (first.0.width, first.0.height);
+
+        match options.format {
+            CineFormat::Gif => encode_gif(frames, width, height),
+            CineFormat::Apng => encode_apng(frames, width, height, first.0.samples_per_pixel, count),
+            CineFormat::MjpegAvi { quality } => encode_avi(frames, width, height, quality),
+        }
+    }
+
+    /// Igual que [`export_cine`](Self::export_cine), escribiendo en `path`
+    pub fn export_cine_to_file(&self, options: &CineExportOptions, path: &Path) -> Result<()> {
+        std::fs::write(path, self.export_cine(options)?)?;
+        Ok(())
+    }
+}
+
+/// Quedarse con `max_frames` frames; cada uno dura hasta el siguiente conservado
+fn decimate(selected: &[(u32, f64)], max_frames: u32) -> Vec<(u32, f64)> {
+    let keep = sample_frames(selected.len() as u32, max_frames.max(1));
+    keep.iter()
+        .enumerate()
+        .map(|(i, &position)| {
+            let until = keep.get(i + 1).map_or(selected.len(), |&next| next as usize);
+            let delay = selected[position as usize..until].iter().map(|(_, delay)| delay).sum();
+            (selected[position as usize].0, delay)
+        })
+        .collect()
+}
+
+fn dimensions_u16(width: u32, height: u32, format: &str) -> Result<(u16, u16)> {
+    match (u16::try_from(width), u16::try_from(height)) {
+        (Ok(width), Ok(height)) => Ok((width, height)),
+        _ => Err(DicomError::validation(format!(
+            "Imagen de {}x{} demasiado grande para {}",
+            width, height, format
+        ))),
+    }
+}
+
+// ============================================
+// GIF
+// ============================================
+
+fn encode_gif<I>(frames: I, width: u32, height: u32) -> Result<Vec<u8>>
+where
+    I: Iterator<Item = Result<(RenderedFrame, f64)>>,
+{
+    let gif_error = |e: gif::EncodingError| DicomError::internal(format!("Error encoding GIF: {}", e));
+    let (width, height) = dimensions_u16(width, height, "GIF")?;
+
+    // Paleta global de grises: el valor renderizado es el índice
+    let grays: Vec<u8> = (0..=255u8).flat_map(|v| [v, v, v]).collect();
+    let mut encoder = gif::Encoder::new(Vec::new(), width, height, &grays).map_err(gif_error)?;
+    encoder.set_repeat(gif::Repeat::Infinite).map_err(gif_error)?;
+
+    for frame in frames {
+        let (frame, delay) = frame?;
+        let mut gif_frame = if frame.samples_per_pixel == 3 {
+            gif::Frame::from_rgb_speed(width, height, &frame.data, 10)
+        } else {
+            gif::Frame {
+                width,
+                height,
+                buffer: Cow::Owned(frame.data),
+                ..gif::Frame::default()
+            }
+        };
+        gif_frame.delay = ((delay / 10.0).round() as u16).max(MIN_GIF_DELAY_CS);
+        encoder.write_frame(&gif_frame).map_err(gif_error)?;
+    }
+
+    Ok(encoder.into_inner()?)
+}
+
+// ============================================
+// APNG
+// ============================================
+
+fn encode_apng<I>(frames: I, width: u32, height: u32, samples_per_pixel: u16, count: u32) -> Result<Vec<u8>>
+where
+    I: Iterator<Item = Result<(RenderedFrame, f64)>>,
+{
+    let png_error = |e: png::EncodingError| DicomError::internal(format!("Error encoding APNG: {}", e));
+
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, width, height);
+    encoder.set_color(if samples_per_pixel == 3 {
+        png::ColorType::Rgb
+    } else {
+        png::ColorType::Grayscale
+    });
+    encoder.set_depth(png::BitDepth::Eight);
+    // 0 repeticiones = loop infinito
+    encoder.set_animated(count, 0).map_err(png_error)?;
+
+    let mut writer = encoder.write_header().map_err(png_error)?;
+    for frame in frames {
+        let (frame, delay) = frame?;
+        writer
+            .set_frame_delay(delay.round().clamp(1.0, u16::MAX as f64) as u16, 1000)
+            .map_err(png_error)?;
+        writer.write_image_data(&frame.data).map_err(png_error)?;
+    }
+    writer.finish().map_err(png_error)?;
+
+    Ok(out)
+}
+
+// ============================================
+// MJPEG AVI
+// ============================================
+
+/// AVIF_HASINDEX
+const AVI_HAS_INDEX: u32 = 0x10;
+
+/// AVIIF_KEYFRAME
+const AVI_KEYFRAME: u32 = 0x10;
+
+fn encode_avi<I>(frames: I, width: u32, height: u32, quality: u8) -> Result<Vec<u8>>
+where
+    I: Iterator<Item = Result<(RenderedFrame, f64)>>,
+{
+    let (width16, height16) = dimensions_u16(width, height, "JPEG")?;
+    // biSizeImage: 65535 x 65535 x 3 no entra en u32
+    let image_size = (width as usize)
+        .checked_mul(height as usize)
+        .and_then(|n| n.checked_mul(3))
+        .and_then(|n| u32::try_from(n).ok())
+        .ok_or_else(|| DicomError::validation(format!("Imagen de {}x{} demasiado grande para AVI", width, height)))?;
+
+    let mut jpegs = Vec::new();
+    let mut total_ms = 0.0;
+    for frame in frames {
+        let (frame, delay) = frame?;
+        let mut jpeg = Vec::new();
+        jpeg_encoder::Encoder::new(&mut jpeg, quality.clamp(1, 100))
+            .encode(&frame.into_rgb8(), width16, height16, jpeg_encoder::ColorType::Rgb)
+            .map_err(|e| DicomError::internal(format!("Error encoding JPEG: {}", e)))?;
+        jpegs.push(jpeg);
+        total_ms += delay;
+    }
+
+    let count = jpegs.len() as u32;
+    let us_per_frame = ((total_ms * 1000.0 / count as f64).round() as u32).max(1);
+    let max_chunk = jpegs.iter().map(|j| j.len()).max().unwrap_or(0) as u32;
+
+    let mut out = Vec::new();
+    let riff = begin_chunk(&mut out, b"RIFF");
+    out.extend_from_slice(b"AVI ");
+
+    let hdrl = begin_list(&mut out, b"hdrl");
+    let avih = begin_chunk(&mut out, b"avih");
+    for value in [
+        us_per_frame,
+        max_chunk.saturating_mul(1_000_000 / us_per_frame),
+        0,
+        AVI_HAS_INDEX,
+        count,
+        0,
+        1,
+        max_chunk,
+        width,
+        height,
+        0,
+        0,
+        0,
+        0,
+    ] {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+    end_chunk(&mut out, avih);
+
+    let strl = begin_list(&mut out, b"strl");
+    let strh = begin_chunk(&mut out, b"strh");
+    out.extend_from_slice(b"vidsMJPG");
+    // dwFlags, wPriority + wLanguage, dwInitialFrames
+    out.extend_from_slice(&[0; 12]);
+    // dwScale / dwRate = segundos por frame
+    for value in [us_per_frame, 1_000_000, 0, count, max_chunk, u32::MAX, 0] {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+    for value in [0, 0, width16, height16] {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+    end_chunk(&mut out, strh);
+
+    // BITMAPINFOHEADER
+    let strf = begin_chunk(&mut out, b"strf");
+    out.extend_from_slice(&40u32.to_le_bytes());
+    out.extend_from_slice(&width.to_le_bytes());
+    out.extend_from_slice(&height.to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes());
+    out.extend_from_slice(&24u16.to_le_bytes());
+    out.extend_from_slice(b"MJPG");
+    out.extend_from_slice(&image_size.to_le_bytes());
+    out.extend_from_slice(&[0; 16]);
+    end_chunk(&mut out, strf);
+    end_chunk(&mut out, strl);
+    end_chunk(&mut out, hdrl);
+
+    // Verificar que el chunk RIFF entra en u32 antes de copiar los frames:
+    // LIST movi, un chunk "00dc" por frame (alineado a 2) e idx1
+    let frames_size = jpegs
+        .iter()
+        .try_fold(0usize, |total, jpeg| total.checked_add(8 + jpeg.len() + jpeg.len() % 2));
+    let riff_size = frames_size
+        .and_then(|n| n.checked_add(out.len() - riff + 12))
+        .and_then(|n| n.checked_add(8 + 16 * jpegs.len()));
+    if riff_size.and_then(|n| u32::try_from(n).ok()).is_none() {
+        return Err(DicomError::validation("AVI de más de 4 GB"));
+    }
+
+    // Los offsets del índice son relativos al identificador "movi"
+    let movi = begin_list(&mut out, b"movi");
+    let movi_start = movi + 4;
+    let mut index = Vec::with_capacity(jpegs.len());
+    for jpeg in &jpegs {
+        index.push(((out.len() - movi_start) as u32, jpeg.len() as u32));
+        let chunk = begin_chunk(&mut out, b"00dc");
+        out.extend_from_slice(jpeg);
+        end_chunk(&mut out, chunk);
+    }
+    end_chunk(&mut out, movi);
+
+    let idx1 = begin_chunk(&mut out, b"idx1");
+    for (offset, size) in index {
+        out.extend_from_slice(b"00dc");
+        for value in [AVI_KEYFRAME, offset, size] {
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    end_chunk(&mut out, idx1);
+    end_chunk(&mut out, riff);
+    Ok(out)
+}
+
+/// Escribir el header de un chunk RIFF; devuelve dónde empieza su contenido
+fn begin_chunk(out: &mut Vec<u8>, id: &[u8; 4]) -> usize {
+    out.extend_from_slice(id);
+    out.extend_from_slice(&[0; 4]);
+    out.len()
+}
+
+fn begin_list(out: &mut Vec<u8>, list_type: &[u8; 4]) -> usize {
+    let start = begin_chunk(out, b"LIST");
+    out.extend_from_slice(list_type);
+    start
+}
+
+/// Completar el tamaño del chunk y alinear a 2 bytes
+fn end_chunk(out: &mut Vec<u8>, start: usize) {
+    let size = (out.len() - start) as u32;
+    out[start - 4..start].copy_from_slice(&size.to_le_bytes());
+    if out.len() % 2 == 1 {
+        out.push(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decimate_keeps_total_duration() {
+        let selected: Vec<(u32, f64)> = (0..10).map(|n| (n, 40.0)).collect();
+        let kept = decimate(&selected, 4);
+
+        assert_eq!(kept.iter().map(|(n, _)| *n).collect::<Vec<_>>(), [0, 3, 6, 9]);
+        assert_eq!(kept.iter().map(|(_, d)| *d).collect::<Vec<_>>(), [120.0, 120.0, 120.0, 40.0]);
+        assert_eq!(decimate(&selected, 20).len(), 10);
+    }
+
+    #[test]
+    fn test_avi_rejects_image_size_overflow() {
+        let frames = std::iter::empty::<Result<(RenderedFrame, f64)>>();
+        assert!(matches!(
+            encode_avi(frames, 65535, 65535, 90),
+            Err(DicomError::ValidationError(m)) if m.contains("65535x65535")
+        ));
+    }
+
+    #[test]
+    fn test_riff_chunks_are_padded() {
+        let mut out = Vec::new();
+        let chunk = begin_chunk(&mut out, b"test");
+        out.extend_from_slice(&[1, 2, 3]);
+        end_chunk(&mut out, chunk);
+
+        assert_eq!(out, [b't', b'e', b's', b't', 3, 0, 0, 0, 1, 2, 3, 0]);
+    }
+}
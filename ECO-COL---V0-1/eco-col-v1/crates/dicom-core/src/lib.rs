@@ -5,9 +5,24 @@
 //! ## Características
 //! 
 //! - ✅ Parsing de archivos DICOM (Explicit/Implicit VR)
+//! - ✅ Escritura y transcodificación (Implicit, Explicit y Deflated)
 //! - ✅ Lazy loading de pixel data
-//! - ✅ Extracción de metadata
-//! - ✅ Validación robusta
+//! - ✅ Parsing desde readers en memoria y streams async (C-STORE, uploads)
+//! - ✅ Ingesta en paralelo de directorios (Patient > Study > Series)
+//! - ✅ Lectura y escritura de DICOMDIR (CD/USB, perfil STD-GEN-CD)
+//! - ✅ Decodificación de pixel data encapsulado (RLE, JPEG, JPEG-LS, JPEG 2000)
+//! - ✅ Renderizado para visualización (rescale, VOI LUT, ventanas, color)
+//! - ✅ Exportación a PNG/JPEG, miniaturas y hojas de contactos de cine loops
+//! - ✅ Cine loops animados (GIF, APNG, MJPEG AVI) con el timing del data set
+//! - ✅ Extracción de metadata y acceso tipado a cualquier atributo
+//! - ✅ Specific Character Set (incluye ISO 2022) y nombres de persona estructurados
+//! - ✅ Checksums SHA-256 del archivo y de cada frame
+//! - ✅ Validación robusta y conformidad de IODs de ultrasonido (PS3.3)
+//! - ✅ Modo recuperación para archivos mal formados (con advertencias)
+//! - ✅ Anonimización (PS3.15 Basic Profile con opciones)
+//! - ✅ Validación y generación de UIDs (raíz propia o 2.25)
+//! - ✅ Calibración de regiones de ultrasonido (mm, mm², cm/s)
+//! - ✅ Enmascarado de anotaciones incrustadas (regiones de ultrasonido)
 //! - ✅ Performance optimizada (<100ms para 500MB)
 //! 
 //! ## Uso Básico
@@ -25,16 +40,42 @@
 //! ```
 
 pub mod parser;
+pub mod batch;
+pub mod dicomdir;
+pub mod writer;
 pub mod metadata;
+pub mod attribute;
+pub mod charset;
+pub mod person_name;
 pub mod pixel;
+pub mod codec;
+#[cfg(feature = "export")]
+pub mod export;
 pub mod validation;
+pub mod conformance;
+pub mod uid;
+pub mod checksum;
+pub mod anonymizer;
+pub mod ultrasound;
+pub mod redaction;
 pub mod error;
 
+mod reader;
+
 // Re-exports
 pub use parser::{DicomParser, ParseOptions};
+pub use batch::{DirectoryScan, SkipReason};
+pub use dicomdir::{DicomDir, DicomDirOptions, DicomDirWriter, DirectoryRecord, RecordType};
+pub use writer::{DicomWriter, WriteOptions};
 pub use metadata::{DicomInstance, DicomMetadata};
-pub use pixel::{PixelData, PixelDataDescriptor};
-pub use error::{DicomError, Result};
+pub use attribute::{AttributeKey, FromElement};
+pub use person_name::PersonName;
+pub use ultrasound::{PhysicalUnits, UltrasoundRegion};
+pub use pixel::{PixelData, PixelDataDescriptor, PixelDataLocation, RenderOptions, RenderedFrame, Voi, VoiFunction, Window};
+pub use uid::UidGenerator;
+pub use checksum::Checksums;
+pub use conformance::{ConformanceReport, Finding, Iod, Severity};
+pub use error::{DicomError, ParseWarning, Result};
 
 #[cfg(test)]
 mod tests {
@@ -1,9 +1,20 @@
 //! Estructuras de metadata DICOM
 
+use dicom::dictionary_std::tags;
+use dicom::object::InMemDicomObject;
 use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::BufReader;
 use std::path::PathBuf;
 
-use crate::pixel::PixelDataDescriptor;
+use crate::attribute::{AttributeKey, Element, FromElement};
+use crate::checksum::Checksums;
+use crate::codec::CodecRegistry;
+use crate::conformance::{self, ConformanceReport};
+use crate::error::{DicomError, ParseWarning, Result};
+use crate::person_name::PersonName;
+use crate::pixel::{PixelData, PixelDataDescriptor};
+use crate::ultrasound::UltrasoundRegion;
 
 /// Instancia DICOM completa
 #[derive(Debug, Clone)]
@@ -13,17 +24,37 @@ pub struct DicomInstance {
     
     /// Metadata DICOM
     pub metadata: DicomMetadata,
+
+    /// Data set completo hasta Pixel Data (sin File Meta ni píxeles)
+    pub dataset: InMemDicomObject,
+
+    /// Regiones de ultrasonido con su calibración (vacío si no hay)
+    pub regions: Vec<UltrasoundRegion>,
     
     /// Descriptor de pixel data (lazy)
     pub pixel_descriptor: Option<PixelDataDescriptor>,
+
+    /// Pixel data en memoria (solo con `ParseOptions::load_pixel_data`)
+    pub pixel_data: Option<PixelData>,
+
+    /// Problemas tolerados en modo recuperación (vacío en modo estricto)
+    pub warnings: Vec<ParseWarning>,
+
+    /// Hashes del archivo y de cada frame (solo con `ParseOptions::validate_checksums`)
+    pub checksums: Option<Checksums>,
 }
 
 impl DicomInstance {
-    /// Obtener nombre del paciente
+    /// Obtener nombre del paciente (valor DICOM decodificado)
     pub fn patient_name(&self) -> &str {
         &self.metadata.patient_name
     }
 
+    /// Obtener nombre del paciente por componentes
+    pub fn patient_person_name(&self) -> &PersonName {
+        &self.metadata.patient_person_name
+    }
+
     /// Obtener modalidad
     pub fn modality(&self) -> &str {
         &self.metadata.modality
@@ -43,6 +74,126 @@ impl DicomInstance {
     pub fn instance_uid(&self) -> &str {
         &self.metadata.sop_instance_uid
     }
+
+    // ============================================
+    // Acceso genérico al data set
+    // ============================================
+
+    /// Elemento por tag o keyword (None si no está o la keyword no existe)
+    pub fn element(&self, key: impl AttributeKey) -> Option<&Element> {
+        self.dataset.element(key.to_tag().ok()?).ok()
+    }
+
+    /// Valor tipado por tag o keyword, p.ej. `instance.get::<f64>("FrameTime")`
+    ///
+    /// None si el atributo no está o no se puede convertir a `T`.
+    pub fn get<T: FromElement>(&self, key: impl AttributeKey) -> Option<T> {
+        self.element(key).and_then(T::from_element)
+    }
+
+    /// Igual que [`get`](Self::get) pero distinguiendo la causa del fallo
+    pub fn try_get<T: FromElement>(&self, key: impl AttributeKey) -> Result<T> {
+        let tag = key.to_tag()?;
+        let element = self
+            .dataset
+            .element(tag)
+            .map_err(|_| DicomError::MissingRequiredTag(tag.to_string()))?;
+
+        T::from_element(element).ok_or_else(|| {
+            DicomError::parse(format!(
+                "No se pudo convertir {} ({}) a {}",
+                tag,
+                element.vr(),
+                std::any::type_name::<T>()
+            ))
+        })
+    }
+
+    /// Iterar sobre todos los elementos de primer nivel, en orden de tag
+    pub fn elements(&self) -> impl Iterator<Item = &Element> + '_ {
+        self.dataset.iter()
+    }
+
+    /// Body Part Examined (0018,0015)
+    pub fn body_part_examined(&self) -> Option<String> {
+        self.get::<String>(tags::BODY_PART_EXAMINED).filter(|s| !s.is_empty())
+    }
+
+    /// Frames por segundo de un cine
+    ///
+    /// Usa Cine Rate, luego Recommended Display Frame Rate y por último Frame Time.
+    pub fn frame_rate(&self) -> Option<f64> {
+        self.get::<f64>(tags::CINE_RATE)
+            .or_else(|| self.get::<f64>(tags::RECOMMENDED_DISPLAY_FRAME_RATE))
+            .or_else(|| self.get::<f64>(tags::FRAME_TIME).filter(|&ms| ms > 0.0).map(|ms| 1000.0 / ms))
+            .filter(|&rate| rate > 0.0)
+    }
+
+    /// Región de ultrasonido que contiene el píxel (x, y)
+    ///
+    /// Si varias regiones se superponen (p.ej. color flow sobre modo B), se
+    /// devuelve la primera declarada.
+    pub fn region_at(&self, x: u32, y: u32) -> Option<&UltrasoundRegion> {
+        self.regions.iter().find(|region| region.contains(x, y))
+    }
+
+    /// Verificar los hashes calculados al parsear contra los almacenados
+    pub fn verify_checksums(&self, expected: &Checksums) -> Result<()> {
+        self.checksums
+            .as_ref()
+            .ok_or_else(|| DicomError::validation("Checksums no calculados (ParseOptions::validate_checksums)"))?
+            .verify(expected)
+    }
+
+    /// Validar la conformidad del data set con su IOD
+    pub fn conformance(&self) -> ConformanceReport {
+        conformance::validate_iod(&self.dataset)
+    }
+
+    /// Obtener pixel data cargado (None si se usó lazy loading)
+    pub fn pixel_data(&self) -> Option<&PixelData> {
+        self.pixel_data.as_ref()
+    }
+
+    /// Cargar todos los frames desde el archivo original
+    ///
+    /// Si los píxeles ya se cargaron durante el parsing, se devuelve una copia.
+    pub fn load_pixels(&self) -> Result<PixelData> {
+        self.load_pixels_with(CodecRegistry::builtin())
+    }
+
+    /// Cargar todos los frames decodificando con un registro de codecs propio
+    pub fn load_pixels_with(&self, codecs: &CodecRegistry) -> Result<PixelData> {
+        if let Some(pixel_data) = &self.pixel_data {
+            return Ok(pixel_data.clone());
+        }
+
+        let descriptor = self.descriptor()?;
+        let mut reader = BufReader::new(File::open(&self.file_path)?);
+        descriptor.read_pixels_with(&mut reader, &self.metadata.transfer_syntax_uid, codecs)
+    }
+
+    /// Cargar un solo frame desde el archivo original (seek + read)
+    pub fn load_frame(&self, n: u32) -> Result<Vec<u8>> {
+        self.load_frame_with(n, CodecRegistry::builtin())
+    }
+
+    /// Cargar un solo frame decodificando con un registro de codecs propio
+    pub fn load_frame_with(&self, n: u32, codecs: &CodecRegistry) -> Result<Vec<u8>> {
+        if let Some(frame) = self.pixel_data.as_ref().and_then(|p| p.frame(n)) {
+            return Ok(frame.to_vec());
+        }
+
+        let descriptor = self.descriptor()?;
+        let mut reader = BufReader::new(File::open(&self.file_path)?);
+        descriptor.read_frame_with(&mut reader, n, &self.metadata.transfer_syntax_uid, codecs)
+    }
+
+    fn descriptor(&self) -> Result<&PixelDataDescriptor> {
+        self.pixel_descriptor
+            .as_ref()
+            .ok_or_else(|| DicomError::MissingRequiredTag("PixelData".to_string()))
+    }
 }
 
 /// Metadata DICOM jerárquico (Patient > Study > Series > Instance)
@@ -53,6 +204,9 @@ pub struct DicomMetadata {
     // ============================================
     pub patient_id: String,
     pub patient_name: String,
+    /// `patient_name` separado en grupos y componentes
+    #[serde(default)]
+    pub patient_person_name: PersonName,
     pub patient_birth_date: Option<String>,
     pub patient_sex: Option<String>,
 
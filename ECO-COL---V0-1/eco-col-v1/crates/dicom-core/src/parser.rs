@@ -1,30 +1,56 @@
 //! Parser DICOM principal
 
-use crate::error::{DicomError, Result};
+use crate::batch::{self, DirectoryScan, FileError};
+use crate::checksum::{self, Checksums};
+use crate::conformance;
+use crate::dicomdir::DicomDir;
+use crate::error::{DicomError, ParseWarning, Result};
 use crate::metadata::{DicomInstance, DicomMetadata};
-use crate::pixel::PixelDataDescriptor;
+use crate::person_name::PersonName;
+use crate::pixel::{
+    Lut, PaletteColorLut, PixelDataDescriptor, PixelDataLocation, PixelPresentation, VoiFunction, Window,
+};
+use crate::reader;
+use crate::ultrasound;
 use crate::validation;
 
-use dicom::object::{DefaultDicomObject, InMemDicomObject};
+use dicom::encoding::{TransferSyntax, TransferSyntaxIndex};
+use dicom::core::value::{PrimitiveValue, Value};
+use dicom::object::{DefaultDicomObject, FileMetaTable, FileMetaTableBuilder, InMemDicomObject};
 use dicom::dictionary_std::tags;
+use dicom::dictionary_std::uids;
+use dicom::transfer_syntax::TransferSyntaxRegistry;
 use std::path::Path;
 use std::fs::File;
-use std::io::{BufReader, Read, Seek, SeekFrom};
+use flate2::read::DeflateDecoder;
+use std::io::{BufReader, Cursor, Read, Seek, SeekFrom};
+use tokio::io::{AsyncRead, AsyncReadExt};
 
 /// Opciones de parsing
 #[derive(Debug, Clone)]
 pub struct ParseOptions {
-    /// Validar checksums de pixel data
+    /// Calcular SHA-256 del archivo y de cada frame (`DicomInstance::checksums`)
     pub validate_checksums: bool,
     
     /// Cargar pixel data en memoria (false = lazy loading)
     pub load_pixel_data: bool,
     
     /// Validar estrictamente el estándar DICOM
+    ///
+    /// Los errores del reporte de conformidad (`conformance::validate_iod`)
+    /// abortan el parsing; sin esta opción se consultan con
+    /// `DicomInstance::conformance`.
     pub strict_validation: bool,
     
     /// Tamaño máximo de archivo en bytes (0 = sin límite)
     pub max_file_size: u64,
+
+    /// Modo recuperación para archivos mal formados
+    ///
+    /// Acepta archivos sin preamble, sin "DICM" o sin File Meta (deduciendo
+    /// la transfer syntax), con longitudes impares o con pixel data truncado.
+    /// Los problemas quedan en `DicomInstance::warnings` en vez de abortar.
+    pub recovery: bool,
 }
 
 impl Default for ParseOptions {
@@ -34,10 +60,26 @@ impl Default for ParseOptions {
             load_pixel_data: false,      // Lazy loading por defecto
             strict_validation: true,
             max_file_size: 2_000_000_000, // 2GB por defecto
+            recovery: false,
         }
     }
 }
 
+/// Tamaño del primer bloque leído en `parse_stream` (se duplica si el data
+/// set no entra)
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Qué hacer con Pixel Data al terminar de leer el data set
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PixelDataMode {
+    /// Registrar la ubicación para cargar después (lazy loading)
+    Locate,
+    /// Cargar los píxeles en memoria
+    Load,
+    /// Detenerse en el header de Pixel Data (el resto puede no haber llegado)
+    Header,
+}
+
 /// Parser DICOM principal
 pub struct DicomParser {
     options: ParseOptions,
@@ -71,49 +113,310 @@ impl DicomParser {
             )));
         }
 
-        // Validar tamaño del archivo
-        let metadata = std::fs::metadata(path)?;
-        if self.options.max_file_size > 0 && metadata.len() > self.options.max_file_size {
+        let file = File::open(path)?;
+        self.parse_source(path, BufReader::new(file), self.pixel_mode())
+    }
+
+    /// Parsear un archivo y verificar sus hashes contra los almacenados
+    ///
+    /// Los hashes se calculan aunque `validate_checksums` esté desactivado;
+    /// una diferencia es `CorruptedPixelData`.
+    pub fn parse_file_verified(&self, path: &Path, expected: &Checksums) -> Result<DicomInstance> {
+        let parser = DicomParser::with_options(ParseOptions {
+            validate_checksums: true,
+            ..self.options.clone()
+        });
+        let instance = parser.parse_file(path)?;
+        instance.verify_checksums(expected)?;
+        Ok(instance)
+    }
+
+    /// Parsear un objeto DICOM desde cualquier fuente con seek (buffer en
+    /// memoria, blob de la base de datos, ...)
+    ///
+    /// El objeto debe empezar en el offset 0 de `reader`. La instancia queda
+    /// sin `file_path`, así que con lazy loading los píxeles se leen después
+    /// con `PixelDataDescriptor::read_pixels` sobre el mismo reader.
+    pub fn parse_reader<R: Read + Seek>(&self, reader: R) -> Result<DicomInstance> {
+        self.parse_source(Path::new(""), reader, self.pixel_mode())
+    }
+
+    /// Parsear un objeto DICOM a medida que llega (payload de C-STORE,
+    /// upload HTTP)
+    ///
+    /// Con lazy loading solo se leen bytes de `reader` hasta el header de
+    /// Pixel Data: los píxeles no se cargan en memoria y el descriptor queda
+    /// sin ubicación (`pixel_descriptor` es None si el objeto no trae Pixel
+    /// Data). El resto del stream queda sin consumir, salvo los bytes que ya
+    /// entraron en el último bloque leído. Con `load_pixel_data` se lee el
+    /// objeto completo.
+    pub async fn parse_stream<R: AsyncRead + Unpin>(&self, mut reader: R) -> Result<DicomInstance> {
+        let mut buffer = Vec::new();
+
+        if self.options.load_pixel_data {
+            reader.read_to_end(&mut buffer).await?;
+            self.check_size(buffer.len() as u64)?;
+            return self.parse_reader(Cursor::new(buffer));
+        }
+
+        // Se lee por bloques y se reintenta el parsing sobre lo recibido
+        // hasta llegar a Pixel Data o al fin del stream
+        let mut chunk = STREAM_CHUNK_SIZE;
+        loop {
+            let mut exhausted = false;
+            let target = buffer.len() + chunk;
+            buffer.reserve(chunk);
+            while buffer.len() < target {
+                if reader.read_buf(&mut buffer).await? == 0 {
+                    exhausted = true;
+                    break;
+                }
+            }
+            self.check_size(buffer.len() as u64)?;
+
+            let result = self.parse_source(Path::new(""), Cursor::new(&buffer), PixelDataMode::Header);
+            match result {
+                Ok(instance) if exhausted || instance.pixel_descriptor.is_some() => return Ok(instance),
+                Err(e) if exhausted => return Err(e),
+                // Data set incompleto: pedir más bytes
+                _ => chunk *= 2,
+            }
+        }
+    }
+
+    /// Parsear en paralelo todos los archivos de un directorio (recursivo)
+    ///
+    /// Pensado para exports de equipos o USB sin DICOMDIR. Los archivos sin
+    /// "DICM" se ignoran con su motivo y los errores se acumulan por archivo;
+    /// solo falla si `dir` no es un directorio.
+    pub fn parse_directory(&self, dir: &Path) -> Result<DirectoryScan> {
+        batch::parse_directory(self, dir, self.options.recovery)
+    }
+
+    /// Parsear en paralelo los archivos referenciados por un DICOMDIR
+    ///
+    /// Los archivos faltantes o ilegibles quedan en `DirectoryScan::errors`,
+    /// igual que los File IDs inválidos (con el path del DICOMDIR), que no
+    /// se abren.
+    pub fn parse_dicomdir(&self, path: &Path) -> Result<DirectoryScan> {
+        let dicomdir = DicomDir::open(path)?;
+        let mut scan = batch::parse_files(self, dicomdir.referenced_files());
+        scan.errors.extend(dicomdir.invalid_file_ids().into_iter().map(|problem| FileError {
+            path: path.to_path_buf(),
+            error: DicomError::validation(problem),
+        }));
+        Ok(scan)
+    }
+
+    /// Qué hacer con Pixel Data según las opciones
+    fn pixel_mode(&self) -> PixelDataMode {
+        if self.options.load_pixel_data {
+            PixelDataMode::Load
+        } else {
+            PixelDataMode::Locate
+        }
+    }
+
+    /// Validar el tamaño máximo del objeto
+    fn check_size(&self, size: u64) -> Result<()> {
+        if self.options.max_file_size > 0 && size > self.options.max_file_size {
             return Err(DicomError::validation(format!(
                 "Archivo demasiado grande: {} bytes (máximo: {})",
-                metadata.len(),
-                self.options.max_file_size
+                size, self.options.max_file_size
             )));
         }
+        Ok(())
+    }
 
-        // Abrir archivo
-        let file = File::open(path)?;
-        let mut reader = BufReader::new(file);
+    /// Parsear preamble, File Meta y data set desde `reader`
+    fn parse_source<R: Read + Seek>(&self, path: &Path, mut reader: R, mode: PixelDataMode) -> Result<DicomInstance> {
+        // Validar tamaño del objeto
+        let size = reader.seek(SeekFrom::End(0))?;
+        self.check_size(size)?;
+        reader.seek(SeekFrom::Start(0))?;
+
+        // Validar magic bytes DICOM (en modo recuperación se aceptan archivos
+        // sin preamble y data sets sin File Meta)
+        let mut warnings = Vec::new();
+        let meta = match self.validate_magic_bytes(&mut reader) {
+            Ok(()) => Some(
+                FileMetaTable::from_reader(&mut reader)
+                    .map_err(|e| DicomError::parse(format!("Error parsing File Meta: {}", e)))?,
+            ),
+            Err(e) if !self.options.recovery => return Err(e),
+            Err(_) => {
+                warnings.push(ParseWarning::MissingPreamble);
+                self.recover_file_meta(&mut reader)?
+            }
+        };
+        let position = reader.stream_position()?;
+
+        let ts_uid = match &meta {
+            Some(meta) => meta.transfer_syntax().trim_end_matches(['\0', ' ']).to_string(),
+            None => {
+                let uid = guess_transfer_syntax(&mut reader, position)?;
+                warnings.push(ParseWarning::MissingFileMeta);
+                warnings.push(ParseWarning::GuessedTransferSyntax { uid: uid.to_string() });
+                uid.to_string()
+            }
+        };
 
-        // Validar magic bytes DICOM
-        self.validate_magic_bytes(&mut reader)?;
+        // Deflated: el data set completo está comprimido, así que se infla en
+        // memoria y los píxeles se cargan siempre (los offsets no sirven sobre
+        // el archivo)
+        if ts_uid == uids::DEFLATED_EXPLICIT_VR_LITTLE_ENDIAN {
+            let mut inflated = Vec::new();
+            DeflateDecoder::new(&mut reader)
+                .read_to_end(&mut inflated)
+                .map_err(|e| DicomError::parse(format!("Error inflating data set: {}", e)))?;
+
+            let ts = TransferSyntaxRegistry
+                .get(uids::EXPLICIT_VR_LITTLE_ENDIAN)
+                .ok_or_else(|| DicomError::UnsupportedTransferSyntax(ts_uid.clone()))?;
+            let mode = match mode {
+                PixelDataMode::Header => PixelDataMode::Header,
+                _ => PixelDataMode::Load,
+            };
+            let mut instance = self.parse_dataset(path, Cursor::new(inflated), meta, ts, 0, mode, warnings)?;
+
+            if let Some(descriptor) = instance.pixel_descriptor.as_mut() {
+                descriptor.location = None;
+            }
+            if let Some(pixel_data) = instance.pixel_data.as_mut() {
+                pixel_data.descriptor.location = None;
+            }
+            return self.with_checksums(instance, &mut reader, mode);
+        }
+
+        let ts = TransferSyntaxRegistry
+            .get(&ts_uid)
+            .ok_or_else(|| DicomError::UnsupportedTransferSyntax(ts_uid.clone()))?;
+
+        let instance = self.parse_dataset(path, &mut reader, meta, ts, position, mode, warnings)?;
+        self.with_checksums(instance, &mut reader, mode)
+    }
+
+    /// Calcular los hashes si `validate_checksums` está activo
+    ///
+    /// No aplica al leer solo hasta Pixel Data: el objeto está incompleto.
+    fn with_checksums<R: Read + Seek>(
+        &self,
+        mut instance: DicomInstance,
+        reader: &mut R,
+        mode: PixelDataMode,
+    ) -> Result<DicomInstance> {
+        if self.options.validate_checksums && mode != PixelDataMode::Header {
+            instance.checksums = Some(checksum::compute(reader, &instance)?);
+        }
+        Ok(instance)
+    }
 
-        // Parsear con dicom-rs
-        let obj = InMemDicomObject::read_from(&mut reader)
-            .map_err(|e| DicomError::parse(format!("Error parsing DICOM: {}", e)))?;
+    /// Parsear el data set que sigue al File Meta group
+    ///
+    /// Sin File Meta (modo recuperación) se genera uno a partir del data set.
+    #[allow(clippy::too_many_arguments)]
+    fn parse_dataset<R: Read + Seek>(
+        &self,
+        path: &Path,
+        mut reader: R,
+        meta: Option<FileMetaTable>,
+        ts: &TransferSyntax,
+        position: u64,
+        mode: PixelDataMode,
+        mut warnings: Vec<ParseWarning>,
+    ) -> Result<DicomInstance> {
+        let recovery = self.options.recovery;
+
+        // Leer el data set hasta Pixel Data, sin cargar los píxeles
+        let locate_pixels = mode != PixelDataMode::Header;
+        let head = reader::read_until_pixel_data(&mut reader, ts, position, recovery, locate_pixels)?;
+        warnings.extend(head.warnings);
+        let meta = match meta {
+            Some(meta) => meta,
+            None => generate_file_meta(&head.dataset, ts.uid())?,
+        };
+        let obj = head.dataset.with_exact_meta(meta);
 
         // Extraer metadata
-        let metadata = self.extract_metadata(&obj)?;
+        let metadata = self.extract_metadata_with(&obj, &self.get_transfer_syntax(&obj), &mut warnings)?;
+
+        // Extraer descriptor de pixel data (y los píxeles si se pidieron)
+        // (solo hasta el header, el descriptor existe si se llegó a Pixel Data)
+        let mut pixel_descriptor = match self.extract_pixel_descriptor(&obj, head.pixel_location) {
+            Ok(_) if !locate_pixels && !head.has_pixel_data => None,
+            Ok(descriptor) => Some(descriptor),
+            // Sin Pixel Data ni Image Pixel (SR, KO, PDF encapsulado)
+            Err(_) if !head.has_pixel_data && obj.element(tags::ROWS).is_err() => None,
+            Err(e) if recovery => {
+                warnings.push(ParseWarning::UnreadablePixelData { message: e.to_string() });
+                None
+            }
+            Err(e) => return Err(e),
+        };
+        if let (Some(descriptor), true) = (pixel_descriptor.as_mut(), recovery) {
+            let end = reader.seek(SeekFrom::End(0))?;
+            warnings.extend(discard_truncated_pixels(descriptor, end));
+        }
 
-        // Extraer descriptor de pixel data (sin cargar datos)
-        let pixel_descriptor = if self.options.load_pixel_data {
-            Some(self.extract_pixel_data(&obj)?)
-        } else {
-            Some(self.extract_pixel_descriptor(&obj)?)
+        let pixel_data = match (&pixel_descriptor, mode) {
+            (Some(descriptor), PixelDataMode::Load) => match descriptor.read_pixels(&mut reader, &metadata.transfer_syntax_uid) {
+                Ok(pixel_data) => Some(pixel_data),
+                Err(e) if recovery => {
+                    warnings.push(ParseWarning::UnreadablePixelData { message: e.to_string() });
+                    None
+                }
+                Err(e) => return Err(e),
+            },
+            _ => None,
         };
 
         // Validar si está habilitado
         if self.options.strict_validation {
-            validation::validate_instance(&metadata)?;
+            let conformance = validation::validate_instance(&metadata)
+                .and_then(|()| conformance::validate_iod(&obj).ensure_conformant());
+            match conformance {
+                Ok(()) => {}
+                Err(e) if recovery => warnings.push(ParseWarning::Validation { message: e.to_string() }),
+                Err(e) => return Err(e),
+            }
         }
 
         Ok(DicomInstance {
             file_path: path.to_path_buf(),
             metadata,
+            regions: ultrasound::ultrasound_regions(&obj),
+            dataset: obj.into_inner(),
             pixel_descriptor,
+            pixel_data,
+            warnings,
+            checksums: None,
         })
     }
 
+    /// Buscar un File Meta group sin preamble (modo recuperación)
+    ///
+    /// Devuelve None si el archivo empieza directamente con el data set; el
+    /// reader queda posicionado al inicio del data set.
+    fn recover_file_meta<R: Read + Seek>(&self, reader: &mut R) -> Result<Option<FileMetaTable>> {
+        reader.seek(SeekFrom::Start(0))?;
+        let mut start = [0u8; 4];
+        let found = reader.read(&mut start)?;
+        reader.seek(SeekFrom::Start(0))?;
+
+        let meta = if found == 4 && &start == b"DICM" {
+            // "DICM" sin los 128 bytes de preamble
+            FileMetaTable::from_reader(&mut *reader)
+        } else if found >= 2 && u16::from_le_bytes([start[0], start[1]]) == 0x0002 {
+            // File Meta sin "DICM": se antepone para que dicom-rs lo acepte
+            FileMetaTable::from_reader(Read::chain(Cursor::new(*b"DICM"), &mut *reader))
+        } else {
+            return Ok(None);
+        };
+
+        meta.map(Some)
+            .map_err(|e| DicomError::parse(format!("Error parsing File Meta: {}", e)))
+    }
+
     /// Validar magic bytes "DICM" en posición 128
     fn validate_magic_bytes<R: Read + Seek>(&self, reader: &mut R) -> Result<()> {
         // DICOM tiene 128 bytes de preamble, luego "DICM"
@@ -126,44 +429,70 @@ impl DicomParser {
             return Err(DicomError::InvalidMagicBytes);
         }
 
-        // Volver al inicio para parsing completo
-        reader.seek(SeekFrom::Start(0))?;
+        // Volver a "DICM": dicom-rs lee el meta group a partir de ahí
+        reader.seek(SeekFrom::Start(128))?;
         
         Ok(())
     }
 
     /// Extraer metadata del objeto DICOM
-    fn extract_metadata(&self, obj: &DefaultDicomObject) -> Result<DicomMetadata> {
+    pub(crate) fn extract_metadata(&self, obj: &InMemDicomObject, transfer_syntax_uid: &str) -> Result<DicomMetadata> {
+        self.extract_metadata_with(obj, transfer_syntax_uid, &mut Vec::new())
+    }
+
+    /// Extraer metadata; en modo recuperación los atributos requeridos que
+    /// faltan quedan vacíos y se registran en `warnings`
+    fn extract_metadata_with(
+        &self,
+        obj: &InMemDicomObject,
+        transfer_syntax_uid: &str,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Result<DicomMetadata> {
+        let mut required = |tag: dicom::core::Tag| match self.get_string(obj, tag) {
+            Ok(value) => Ok(value),
+            Err(_) if self.options.recovery => {
+                warnings.push(ParseWarning::MissingAttribute { tag: tag.to_string() });
+                Ok(String::new())
+            }
+            Err(e) => Err(e),
+        };
+
+        let patient_id = required(tags::PATIENT_ID)?;
+        let patient_name = required(tags::PATIENT_NAME)?;
         Ok(DicomMetadata {
             // Patient Level
-            patient_id: self.get_string(obj, tags::PATIENT_ID)?,
-            patient_name: self.get_string(obj, tags::PATIENT_NAME)?,
+            patient_id,
+            patient_person_name: PersonName::parse(&patient_name),
+            patient_name,
             patient_birth_date: self.get_string_opt(obj, tags::PATIENT_BIRTH_DATE),
             patient_sex: self.get_string_opt(obj, tags::PATIENT_SEX),
 
             // Study Level
-            study_instance_uid: self.get_string(obj, tags::STUDY_INSTANCE_UID)?,
+            study_instance_uid: required(tags::STUDY_INSTANCE_UID)?,
             study_date: self.get_string_opt(obj, tags::STUDY_DATE),
             study_time: self.get_string_opt(obj, tags::STUDY_TIME),
             study_description: self.get_string_opt(obj, tags::STUDY_DESCRIPTION),
             accession_number: self.get_string_opt(obj, tags::ACCESSION_NUMBER),
 
             // Series Level
-            series_instance_uid: self.get_string(obj, tags::SERIES_INSTANCE_UID)?,
+            series_instance_uid: required(tags::SERIES_INSTANCE_UID)?,
             series_number: self.get_integer_opt(obj, tags::SERIES_NUMBER),
-            modality: self.get_string(obj, tags::MODALITY)?,
+            modality: required(tags::MODALITY)?,
             series_description: self.get_string_opt(obj, tags::SERIES_DESCRIPTION),
 
             // Instance Level
-            sop_instance_uid: self.get_string(obj, tags::SOP_INSTANCE_UID)?,
+            sop_instance_uid: required(tags::SOP_INSTANCE_UID)?,
             instance_number: self.get_integer_opt(obj, tags::INSTANCE_NUMBER),
-            transfer_syntax_uid: self.get_string(obj, tags::TRANSFER_SYNTAX_UID)
-                .unwrap_or_else(|_| "1.2.840.10008.1.2.1".to_string()), // Default: Explicit VR Little Endian
+            transfer_syntax_uid: transfer_syntax_uid.to_string(),
         })
     }
 
     /// Extraer descriptor de pixel data (sin cargar píxeles)
-    fn extract_pixel_descriptor(&self, obj: &DefaultDicomObject) -> Result<PixelDataDescriptor> {
+    fn extract_pixel_descriptor(
+        &self,
+        obj: &DefaultDicomObject,
+        location: Option<PixelDataLocation>,
+    ) -> Result<PixelDataDescriptor> {
         Ok(PixelDataDescriptor {
             rows: self.get_integer(obj, tags::ROWS)? as u32,
             columns: self.get_integer(obj, tags::COLUMNS)? as u32,
@@ -173,21 +502,91 @@ impl DicomParser {
             photometric_interpretation: self.get_string(obj, tags::PHOTOMETRIC_INTERPRETATION)?,
             samples_per_pixel: self.get_integer(obj, tags::SAMPLES_PER_PIXEL)? as u16,
             pixel_representation: self.get_integer(obj, tags::PIXEL_REPRESENTATION)? as u16,
+
+            // Multi-frame (cine loops de ultrasonido)
+            number_of_frames: self.get_integer_opt(obj, tags::NUMBER_OF_FRAMES)
+                .filter(|&frames| frames > 0)
+                .unwrap_or(1) as u32,
+            frame_time: self.get_float_opt(obj, tags::FRAME_TIME),
+            frame_time_vector: self.get_floats_opt(obj, tags::FRAME_TIME_VECTOR),
+            cine_rate: self.get_integer_opt(obj, tags::CINE_RATE)
+                .filter(|&rate| rate > 0)
+                .map(|rate| rate as u32),
+
+            planar_configuration: self.get_integer_opt(obj, tags::PLANAR_CONFIGURATION).unwrap_or(0) as u16,
+            presentation: self.extract_presentation(obj),
+
+            location,
         })
     }
 
-    /// Extraer pixel data completo (carga en memoria)
-    fn extract_pixel_data(&self, _obj: &DefaultDicomObject) -> Result<PixelDataDescriptor> {
-        // Por ahora, solo extraemos el descriptor
-        // La carga real de píxeles se implementará cuando sea necesario
-        self.extract_pixel_descriptor(_obj)
+    /// Extraer rescale, ventanas, LUTs y paleta de color
+    fn extract_presentation(&self, obj: &DefaultDicomObject) -> PixelPresentation {
+        // Con píxeles con signo, el primer valor mapeado de las LUTs es SS
+        let signed = self.get_integer_opt(obj, tags::PIXEL_REPRESENTATION) == Some(1);
+
+        let lut_items = |tag| {
+            obj.element(tag)
+                .ok()
+                .and_then(|e| e.items())
+                .map(|items| {
+                    items
+                        .iter()
+                        .filter_map(|item| self.get_lut(item, tags::LUT_DESCRIPTOR, tags::LUT_DATA, signed))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default()
+        };
+
+        let centers = self.get_floats_opt(obj, tags::WINDOW_CENTER).unwrap_or_default();
+        let widths = self.get_floats_opt(obj, tags::WINDOW_WIDTH).unwrap_or_default();
+
+        let palette = (|| {
+            Some(PaletteColorLut {
+                red: self.get_lut(
+                    obj,
+                    tags::RED_PALETTE_COLOR_LOOKUP_TABLE_DESCRIPTOR,
+                    tags::RED_PALETTE_COLOR_LOOKUP_TABLE_DATA,
+                    signed,
+                )?,
+                green: self.get_lut(
+                    obj,
+                    tags::GREEN_PALETTE_COLOR_LOOKUP_TABLE_DESCRIPTOR,
+                    tags::GREEN_PALETTE_COLOR_LOOKUP_TABLE_DATA,
+                    signed,
+                )?,
+                blue: self.get_lut(
+                    obj,
+                    tags::BLUE_PALETTE_COLOR_LOOKUP_TABLE_DESCRIPTOR,
+                    tags::BLUE_PALETTE_COLOR_LOOKUP_TABLE_DATA,
+                    signed,
+                )?,
+            })
+        })();
+
+        PixelPresentation {
+            rescale_slope: self.get_float_opt(obj, tags::RESCALE_SLOPE).unwrap_or(1.0),
+            rescale_intercept: self.get_float_opt(obj, tags::RESCALE_INTERCEPT).unwrap_or(0.0),
+            modality_lut: lut_items(tags::MODALITY_LUT_SEQUENCE).into_iter().next(),
+            windows: centers
+                .into_iter()
+                .zip(widths)
+                .map(|(center, width)| Window { center, width })
+                .collect(),
+            voi_function: self
+                .get_string_opt(obj, tags::VOILUT_FUNCTION)
+                .and_then(|code| VoiFunction::from_code(&code))
+                .unwrap_or_default(),
+            voi_luts: lut_items(tags::VOILUT_SEQUENCE),
+            palette,
+        }
     }
 
     // ============================================
     // Utilidades para extraer tags
     // ============================================
 
-    fn get_string(&self, obj: &DefaultDicomObject, tag: dicom::core::Tag) -> Result<String> {
+    fn get_string(&self, obj: &InMemDicomObject, tag: dicom::core::Tag) -> Result<String> {
         obj.element(tag)
             .map_err(|_| DicomError::MissingRequiredTag(format!("{:?}", tag)))?
             .to_str()
@@ -195,25 +594,85 @@ impl DicomParser {
             .map_err(|e| DicomError::parse(format!("Error converting tag {:?}: {}", tag, e)))
     }
 
-    fn get_string_opt(&self, obj: &DefaultDicomObject, tag: dicom::core::Tag) -> Option<String> {
+    fn get_transfer_syntax(&self, obj: &DefaultDicomObject) -> String {
+        let ts = obj.meta().transfer_syntax().trim_end_matches(['\0', ' ']);
+        if ts.is_empty() {
+            // Default: Explicit VR Little Endian
+            uids::EXPLICIT_VR_LITTLE_ENDIAN.to_string()
+        } else {
+            ts.to_string()
+        }
+    }
+
+    fn get_string_opt(&self, obj: &InMemDicomObject, tag: dicom::core::Tag) -> Option<String> {
         obj.element(tag)
             .ok()
             .and_then(|e| e.to_str().ok())
             .map(|s| s.to_string())
     }
 
-    fn get_integer(&self, obj: &DefaultDicomObject, tag: dicom::core::Tag) -> Result<i32> {
+    fn get_integer(&self, obj: &InMemDicomObject, tag: dicom::core::Tag) -> Result<i32> {
         obj.element(tag)
             .map_err(|_| DicomError::MissingRequiredTag(format!("{:?}", tag)))?
             .to_int::<i32>()
             .map_err(|e| DicomError::parse(format!("Error converting tag {:?}: {}", tag, e)))
     }
 
-    fn get_integer_opt(&self, obj: &DefaultDicomObject, tag: dicom::core::Tag) -> Option<i32> {
+    fn get_integer_opt(&self, obj: &InMemDicomObject, tag: dicom::core::Tag) -> Option<i32> {
         obj.element(tag)
             .ok()
             .and_then(|e| e.to_int::<i32>().ok())
     }
+
+    fn get_float_opt(&self, obj: &InMemDicomObject, tag: dicom::core::Tag) -> Option<f64> {
+        obj.element(tag)
+            .ok()
+            .and_then(|e| e.to_float64().ok())
+    }
+
+    fn get_floats_opt(&self, obj: &InMemDicomObject, tag: dicom::core::Tag) -> Option<Vec<f64>> {
+        obj.element(tag)
+            .ok()
+            .and_then(|e| e.to_multi_float64().ok())
+            .filter(|values| !values.is_empty())
+    }
+
+    /// Leer una LUT a partir de su descriptor (entradas, primer valor, bits) y datos
+    fn get_lut(
+        &self,
+        obj: &InMemDicomObject,
+        descriptor_tag: dicom::core::Tag,
+        data_tag: dicom::core::Tag,
+        signed: bool,
+    ) -> Option<Lut> {
+        let descriptor = obj.element(descriptor_tag).ok()?.to_multi_int::<i32>().ok()?;
+        let [entries, first_mapped, bits_per_entry] = descriptor[..] else {
+            return None;
+        };
+
+        let data: Vec<u16> = match obj.element(data_tag).ok()?.value() {
+            Value::Primitive(PrimitiveValue::U16(values)) => values.to_vec(),
+            // OB u otro VR: palabras de 16 bits en little endian
+            Value::Primitive(value) => value
+                .to_bytes()
+                .chunks_exact(2)
+                .map(|b| u16::from_le_bytes([b[0], b[1]]))
+                .collect(),
+            _ => return None,
+        };
+
+        // 0 entradas significa 65536
+        let entries = if entries == 0 { 65536 } else { entries as usize };
+        if data.len() < entries {
+            return None;
+        }
+
+        Some(Lut {
+            first_mapped: if signed { first_mapped as u16 as i16 as i32 } else { first_mapped as u16 as i32 },
+            bits_per_entry: bits_per_entry as u16,
+            data: data[..entries].to_vec(),
+        })
+    }
 }
 
 impl Default for DicomParser {
@@ -222,6 +681,96 @@ impl Default for DicomParser {
     }
 }
 
+/// Deducir la transfer syntax de un data set sin File Meta a partir del
+/// primer elemento: VR explícito si los bytes 4-5 son un VR válido, y little
+/// endian si el grupo leído así es el menor
+fn guess_transfer_syntax<R: Read + Seek>(reader: &mut R, position: u64) -> Result<&'static str> {
+    let mut first = [0u8; 6];
+    reader.read_exact(&mut first).map_err(|_| DicomError::InvalidMagicBytes)?;
+    reader.seek(SeekFrom::Start(position))?;
+
+    let group_le = u16::from_le_bytes([first[0], first[1]]);
+    let group_be = u16::from_be_bytes([first[0], first[1]]);
+    let explicit = dicom::core::VR::from_binary([first[4], first[5]]).is_some();
+
+    // Un grupo 0 o impar en ambos órdenes no parece un data set DICOM
+    if group_le.min(group_be) == 0 || (group_le % 2 == 1 && group_be % 2 == 1) {
+        return Err(DicomError::InvalidMagicBytes);
+    }
+
+    match (explicit, group_le <= group_be) {
+        (true, true) => Ok(uids::EXPLICIT_VR_LITTLE_ENDIAN),
+        (false, true) => Ok(uids::IMPLICIT_VR_LITTLE_ENDIAN),
+        // Explicit VR Big Endian (retirado)
+        (true, false) => Ok("1.2.840.10008.1.2.2"),
+        // No existe Implicit VR Big Endian
+        (false, false) => Err(DicomError::InvalidMagicBytes),
+    }
+}
+
+/// Generar el File Meta de un data set que no lo trae
+fn generate_file_meta(dataset: &InMemDicomObject, transfer_syntax_uid: &str) -> Result<FileMetaTable> {
+    let uid = |tag| {
+        dataset
+            .element(tag)
+            .ok()
+            .and_then(|e| e.to_str().ok())
+            .map(|s| s.trim_end_matches(['\0', ' ']).to_string())
+            .unwrap_or_default()
+    };
+
+    FileMetaTableBuilder::new()
+        .transfer_syntax(transfer_syntax_uid)
+        .media_storage_sop_class_uid(uid(tags::SOP_CLASS_UID))
+        .media_storage_sop_instance_uid(uid(tags::SOP_INSTANCE_UID))
+        .build()
+        .map_err(|e| DicomError::parse(format!("Error generating File Meta: {}", e)))
+}
+
+/// Descartar los frames (o fragmentos) que quedan más allá del fin del archivo
+fn discard_truncated_pixels(descriptor: &mut PixelDataDescriptor, end: u64) -> Option<ParseWarning> {
+    let frame_size = descriptor.frame_size_bytes() as u64;
+
+    match descriptor.location.as_mut()? {
+        PixelDataLocation::Native { offset, length } => {
+            let available = end.saturating_sub(*offset);
+            if available >= *length {
+                return None;
+            }
+
+            let expected = *length;
+            let frames = available.checked_div(frame_size).unwrap_or(0);
+            *length = frames * frame_size;
+            descriptor.number_of_frames = frames as u32;
+            Some(ParseWarning::TruncatedPixelData { expected, available })
+        }
+        PixelDataLocation::Encapsulated { offset_table, fragments } => {
+            let complete = fragments
+                .iter()
+                .take_while(|f| f.offset + u64::from(f.length) <= end)
+                .count();
+            if complete == fragments.len() {
+                return None;
+            }
+
+            let start = fragments[0].offset;
+            let last = fragments[fragments.len() - 1];
+            let expected = last.offset + u64::from(last.length) - start;
+            fragments.truncate(complete);
+
+            // Caso habitual en ultrasonido: un fragmento por frame
+            if descriptor.number_of_frames as usize > complete {
+                descriptor.number_of_frames = complete as u32;
+                offset_table.truncate(complete);
+            }
+            Some(ParseWarning::TruncatedPixelData {
+                expected,
+                available: end.saturating_sub(start),
+            })
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -239,6 +788,7 @@ mod tests {
             load_pixel_data: true,
             strict_validation: false,
             max_file_size: 1_000_000,
+            recovery: true,
         };
         
         let parser = DicomParser::with_options(options);
@@ -0,0 +1,196 @@
+//! Nombres de persona (VR PN, PS3.5 §6.2.1)
+//!
+//! Un valor PN tiene hasta tres grupos separados por `=` (alfabético,
+//! ideográfico y fonético) y cada grupo hasta cinco componentes separados
+//! por `^`: apellido, nombre, segundo nombre, prefijo y sufijo.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Componentes de un grupo del nombre
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct NameComponents {
+    pub family: String,
+    pub given: String,
+    pub middle: String,
+    pub prefix: String,
+    pub suffix: String,
+}
+
+impl NameComponents {
+    /// Leer un grupo "Apellido^Nombre^Segundo^Prefijo^Sufijo"
+    pub fn parse(group: &str) -> Self {
+        let mut parts = group.split('^').map(|part| part.trim().to_string());
+        Self {
+            family: parts.next().unwrap_or_default(),
+            given: parts.next().unwrap_or_default(),
+            middle: parts.next().unwrap_or_default(),
+            prefix: parts.next().unwrap_or_default(),
+            suffix: parts.next().unwrap_or_default(),
+        }
+    }
+
+    /// Verificar si todos los componentes están vacíos
+    pub fn is_empty(&self) -> bool {
+        self.components().iter().all(|c| c.is_empty())
+    }
+
+    fn components(&self) -> [&str; 5] {
+        [&self.family, &self.given, &self.middle, &self.prefix, &self.suffix]
+    }
+
+    /// Codificación DICOM sin los `^` finales sobrantes
+    fn to_dicom(&self) -> String {
+        let components = self.components();
+        let used = components.iter().rposition(|c| !c.is_empty()).map_or(0, |i| i + 1);
+        components[..used].join("^")
+    }
+
+    /// Nombre legible: "Prefijo Nombre Segundo Apellido Sufijo"
+    fn full_name(&self) -> String {
+        [&self.prefix, &self.given, &self.middle, &self.family, &self.suffix]
+            .iter()
+            .filter(|c| !c.is_empty())
+            .map(|c| c.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Nombre de persona estructurado
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub struct PersonName {
+    /// Representación alfabética (la única usada en la mayoría de los estudios)
+    pub alphabetic: NameComponents,
+
+    /// Representación ideográfica (kanji, hanja, ...)
+    pub ideographic: NameComponents,
+
+    /// Representación fonética (hiragana, hangul, ...)
+    pub phonetic: NameComponents,
+}
+
+impl PersonName {
+    /// Leer un valor PN ya decodificado
+    pub fn parse(value: &str) -> Self {
+        let mut groups = value.trim_end_matches(['\0', ' ']).split('=').map(NameComponents::parse);
+        Self {
+            alphabetic: groups.next().unwrap_or_default(),
+            ideographic: groups.next().unwrap_or_default(),
+            phonetic: groups.next().unwrap_or_default(),
+        }
+    }
+
+    /// Apellido (grupo alfabético)
+    pub fn family(&self) -> &str {
+        &self.alphabetic.family
+    }
+
+    /// Nombre (grupo alfabético)
+    pub fn given(&self) -> &str {
+        &self.alphabetic.given
+    }
+
+    /// Segundo nombre (grupo alfabético)
+    pub fn middle(&self) -> &str {
+        &self.alphabetic.middle
+    }
+
+    /// Prefijo, p.ej. "Dr." (grupo alfabético)
+    pub fn prefix(&self) -> &str {
+        &self.alphabetic.prefix
+    }
+
+    /// Sufijo (grupo alfabético)
+    pub fn suffix(&self) -> &str {
+        &self.alphabetic.suffix
+    }
+
+    /// Verificar si el nombre está vacío
+    pub fn is_empty(&self) -> bool {
+        self.alphabetic.is_empty() && self.ideographic.is_empty() && self.phonetic.is_empty()
+    }
+
+    /// Nombre legible para pantalla ("Juan Carlos Pérez")
+    ///
+    /// Si no hay grupo alfabético se usa el ideográfico y luego el fonético.
+    pub fn full_name(&self) -> String {
+        [&self.alphabetic, &self.ideographic, &self.phonetic]
+            .into_iter()
+            .find(|group| !group.is_empty())
+            .map(NameComponents::full_name)
+            .unwrap_or_default()
+    }
+
+    /// Codificación DICOM ("Apellido^Nombre=...") sin separadores sobrantes
+    pub fn to_dicom(&self) -> String {
+        let groups = [&self.alphabetic, &self.ideographic, &self.phonetic].map(NameComponents::to_dicom);
+        let used = groups.iter().rposition(|g| !g.is_empty()).map_or(0, |i| i + 1);
+        groups[..used].join("=")
+    }
+}
+
+impl fmt::Display for PersonName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_dicom())
+    }
+}
+
+impl From<String> for PersonName {
+    fn from(value: String) -> Self {
+        Self::parse(&value)
+    }
+}
+
+impl From<PersonName> for String {
+    fn from(name: PersonName) -> Self {
+        name.to_dicom()
+    }
+}
+
+/// Comparar con la codificación DICOM
+impl PartialEq<str> for PersonName {
+    fn eq(&self, other: &str) -> bool {
+        self.to_dicom() == other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_alphabetic() {
+        let name = PersonName::parse("PÉREZ GÓMEZ^JUAN^CARLOS^DR.^ ");
+        assert_eq!(name.family(), "PÉREZ GÓMEZ");
+        assert_eq!(name.given(), "JUAN");
+        assert_eq!(name.middle(), "CARLOS");
+        assert_eq!(name.prefix(), "DR.");
+        assert_eq!(name.full_name(), "DR. JUAN CARLOS PÉREZ GÓMEZ");
+        assert_eq!(name.to_dicom(), "PÉREZ GÓMEZ^JUAN^CARLOS^DR.");
+    }
+
+    #[test]
+    fn test_parse_groups() {
+        let name = PersonName::parse("Yamada^Tarou=山田^太郎=やまだ^たろう");
+        assert_eq!(name.ideographic.family, "山田");
+        assert_eq!(name.phonetic.given, "たろう");
+        assert_eq!(name.to_string(), "Yamada^Tarou=山田^太郎=やまだ^たろう");
+
+        let ideographic_only = PersonName::parse("=洪^吉洞");
+        assert!(ideographic_only.alphabetic.is_empty());
+        assert_eq!(ideographic_only.full_name(), "吉洞 洪");
+    }
+
+    #[test]
+    fn test_empty_and_serde() {
+        assert!(PersonName::parse("").is_empty());
+        assert_eq!(PersonName::default(), *"");
+
+        let name = PersonName::parse("NUÑEZ^MARÍA");
+        let json = serde_json::to_string(&name).unwrap();
+        assert_eq!(json, "\"NUÑEZ^MARÍA\"");
+        assert_eq!(serde_json::from_str::<PersonName>(&json).unwrap(), name);
+    }
+}
@@ -1,6 +1,20 @@
 //! Estructuras para pixel data
 
+use crate::codec::{self, CodecRegistry};
+use crate::error::{DicomError, Result};
+
+use dicom::dictionary_std::tags;
 use serde::{Deserialize, Serialize};
+use std::io::{Read, Seek, SeekFrom};
+
+mod render;
+
+pub use render::{
+    Lut, PaletteColorLut, PixelPresentation, RenderOptions, RenderedFrame, Voi, VoiFunction, Window,
+};
+
+/// Explicit VR Big Endian: retirada del estándar, pero equipos viejos aún la envían
+const EXPLICIT_VR_BIG_ENDIAN: &str = "1.2.840.10008.1.2.2";
 
 /// Descriptor de pixel data (sin los píxeles en sí)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,13 +42,316 @@ pub struct PixelDataDescriptor {
     
     /// Representación de píxel (0 = unsigned, 1 = signed)
     pub pixel_representation: u16,
+
+    /// Número de frames (1 para imágenes simples, N para cine loops)
+    #[serde(default = "default_number_of_frames")]
+    pub number_of_frames: u32,
+
+    /// Tiempo nominal entre frames en ms (0018,1063)
+    #[serde(default)]
+    pub frame_time: Option<f64>,
+
+    /// Tiempo entre cada frame y el anterior en ms (0018,1065)
+    #[serde(default)]
+    pub frame_time_vector: Option<Vec<f64>>,
+
+    /// Frames por segundo recomendados para reproducción (0018,0040)
+    #[serde(default)]
+    pub cine_rate: Option<u32>,
+
+    /// Configuración planar (0 = muestras intercaladas, 1 = un plano por color)
+    #[serde(default)]
+    pub planar_configuration: u16,
+
+    /// Atributos de presentación (rescale, ventanas, LUTs, paleta)
+    #[serde(default)]
+    pub presentation: PixelPresentation,
+
+    /// Ubicación de los píxeles en el archivo (para lazy loading)
+    #[serde(default)]
+    pub location: Option<PixelDataLocation>,
+}
+
+fn default_number_of_frames() -> u32 {
+    1
+}
+
+/// Ubicación del valor de Pixel Data (7FE0,0010) dentro del archivo
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PixelDataLocation {
+    /// Pixel data nativo: un solo bloque contiguo con todos los frames
+    Native {
+        /// Offset absoluto del primer byte del valor
+        offset: u64,
+        /// Longitud del valor en bytes
+        length: u64,
+    },
+
+    /// Pixel data encapsulado en fragmentos (JPEG, RLE, ...)
+    Encapsulated {
+        /// Basic Offset Table (vacía si el archivo no la incluye)
+        offset_table: Vec<u32>,
+        /// Fragmentos en orden, sin contar la Basic Offset Table
+        fragments: Vec<Fragment>,
+    },
+}
+
+/// Fragmento de pixel data encapsulado
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fragment {
+    /// Offset absoluto del primer byte del fragmento
+    pub offset: u64,
+    /// Longitud del fragmento en bytes
+    pub length: u32,
 }
 
 impl PixelDataDescriptor {
-    /// Calcular tamaño total en bytes del pixel data
+    /// Calcular tamaño en bytes de un solo frame
+    ///
+    /// Satura en `usize::MAX` si las dimensiones desbordan; para leer o
+    /// reservar memoria usar [`checked_frame_size_bytes`](Self::checked_frame_size_bytes).
+    pub fn frame_size_bytes(&self) -> usize {
+        self.checked_frame_size_bytes().unwrap_or(usize::MAX)
+    }
+
+    /// Tamaño de un frame, con error si Rows x Columns x muestras desborda
+    pub fn checked_frame_size_bytes(&self) -> Result<usize> {
+        self.frame_size_with(&self.photometric_interpretation)
+            .ok_or_else(|| self.size_overflow())
+    }
+
+    /// Tamaño de un frame si los píxeles estuvieran en `photometric_interpretation`
+    pub(crate) fn frame_size_with(&self, photometric_interpretation: &str) -> Option<usize> {
+        let pixels = (self.rows as usize).checked_mul(self.columns as usize)?;
+        let samples = match photometric_interpretation.trim() {
+            // YBR_FULL_422 nativo: Cb y Cr se comparten entre cada par de píxeles
+            "YBR_FULL_422" => pixels.checked_mul(2)?,
+            _ => pixels.checked_mul(self.samples_per_pixel as usize)?,
+        };
+        // Con bits_allocated = 1 los píxeles van empaquetados en bytes
+        Some(samples.checked_mul(self.bits_allocated as usize)?.div_ceil(8))
+    }
+
+    /// Calcular tamaño total en bytes del pixel data (todos los frames)
+    ///
+    /// Satura igual que [`frame_size_bytes`](Self::frame_size_bytes).
     pub fn total_size_bytes(&self) -> usize {
-        let bytes_per_pixel = (self.bits_allocated / 8) as usize;
-        (self.rows as usize) * (self.columns as usize) * bytes_per_pixel * (self.samples_per_pixel as usize)
+        self.checked_total_size_bytes().unwrap_or(usize::MAX)
+    }
+
+    /// Tamaño total, con error si desborda
+    pub fn checked_total_size_bytes(&self) -> Result<usize> {
+        self.checked_frame_size_bytes()?
+            .checked_mul(self.number_of_frames.max(1) as usize)
+            .ok_or_else(|| self.size_overflow())
+    }
+
+    fn size_overflow(&self) -> DicomError {
+        DicomError::validation(format!(
+            "Pixel data de {}x{}x{} muestras de {} bits y {} frames desborda el tamaño direccionable",
+            self.rows, self.columns, self.samples_per_pixel, self.bits_allocated, self.number_of_frames
+        ))
+    }
+
+    /// Verificar si es un objeto multi-frame (cine loop)
+    pub fn is_multi_frame(&self) -> bool {
+        self.number_of_frames > 1
+    }
+
+    /// Duración en ms del frame `n` (tiempo desde el frame anterior)
+    ///
+    /// Usa Frame Time Vector si existe; si no, Frame Time; y como último
+    /// recurso Cine Rate. El primer valor del vector es 0 por definición.
+    pub fn frame_duration_ms(&self, n: u32) -> Option<f64> {
+        if n >= self.number_of_frames.max(1) {
+            return None;
+        }
+
+        if let Some(vector) = &self.frame_time_vector {
+            if let Some(&time) = vector.get(n as usize) {
+                return Some(time);
+            }
+        }
+
+        self.frame_time
+            .or_else(|| self.cine_rate.filter(|&rate| rate > 0).map(|rate| 1000.0 / rate as f64))
+    }
+
+    /// Leer todos los frames desde el archivo usando `location`
+    ///
+    /// El pixel data encapsulado se decodifica con los codecs incluidos
+    /// (ver [`CodecRegistry::builtin`]).
+    pub fn read_pixels<R: Read + Seek>(&self, reader: &mut R, transfer_syntax_uid: &str) -> Result<PixelData> {
+        self.read_pixels_with(reader, transfer_syntax_uid, CodecRegistry::builtin())
+    }
+
+    /// Leer todos los frames, decodificando con el registro indicado
+    pub fn read_pixels_with<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        transfer_syntax_uid: &str,
+        codecs: &CodecRegistry,
+    ) -> Result<PixelData> {
+        if let Some(PixelDataLocation::Encapsulated { offset_table, fragments }) = &self.location {
+            let decoder = codecs
+                .decoder(transfer_syntax_uid)
+                .ok_or_else(|| DicomError::UnsupportedTransferSyntax(transfer_syntax_uid.to_string()))?;
+
+            // Reservar según lo declarado solo hasta lo que ocupan los fragmentos;
+            // si los frames descomprimidos son mayores el vector crece al decodificar
+            let stored: u64 = fragments.iter().map(|f| u64::from(f.length)).sum();
+            let total = self.checked_total_size_bytes()?;
+            let mut data = Vec::with_capacity(total.min(usize::try_from(stored).unwrap_or(usize::MAX)));
+            for n in 0..self.number_of_frames.max(1) {
+                let frame = self.read_encapsulated_frame(reader, offset_table, fragments, n, transfer_syntax_uid, codecs)?;
+                data.extend_from_slice(&frame);
+            }
+
+            // Los píxeles ya no están encapsulados y pueden cambiar de espacio de color;
+            // los codecs siempre entregan las muestras intercaladas
+            let mut descriptor = self.clone();
+            descriptor.photometric_interpretation = decoder.output_photometric_interpretation(self);
+            descriptor.planar_configuration = 0;
+            return Ok(PixelData { descriptor, data });
+        }
+
+        let (offset, _) = self.native_location(transfer_syntax_uid)?;
+        let total = self.checked_total_size_bytes()?;
+        ensure_available(reader, offset, total)?;
+
+        let mut data = vec![0; total];
+        reader.seek(SeekFrom::Start(offset))?;
+        read_pixel_bytes(reader, &mut data)?;
+        self.to_little_endian(&mut data, transfer_syntax_uid);
+
+        Ok(PixelData {
+            descriptor: self.clone(),
+            data,
+        })
+    }
+
+    /// Leer un solo frame desde el archivo usando `location`
+    pub fn read_frame<R: Read + Seek>(&self, reader: &mut R, n: u32, transfer_syntax_uid: &str) -> Result<Vec<u8>> {
+        self.read_frame_with(reader, n, transfer_syntax_uid, CodecRegistry::builtin())
+    }
+
+    /// Leer un solo frame, decodificando con el registro indicado
+    pub fn read_frame_with<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        n: u32,
+        transfer_syntax_uid: &str,
+        codecs: &CodecRegistry,
+    ) -> Result<Vec<u8>> {
+        if n >= self.number_of_frames.max(1) {
+            return Err(DicomError::validation(format!(
+                "Frame {} fuera de rango (total: {})",
+                n, self.number_of_frames
+            )));
+        }
+
+        if let Some(PixelDataLocation::Encapsulated { offset_table, fragments }) = &self.location {
+            if !codecs.supports(transfer_syntax_uid) {
+                return Err(DicomError::UnsupportedTransferSyntax(transfer_syntax_uid.to_string()));
+            }
+            return self.read_encapsulated_frame(reader, offset_table, fragments, n, transfer_syntax_uid, codecs);
+        }
+
+        let (offset, _) = self.native_location(transfer_syntax_uid)?;
+        let frame_size = self.checked_frame_size_bytes()?;
+        let start = frame_offset(offset, n, frame_size)?;
+        ensure_available(reader, start, frame_size)?;
+
+        let mut data = vec![0; frame_size];
+        reader.seek(SeekFrom::Start(start))?;
+        read_pixel_bytes(reader, &mut data)?;
+        self.to_little_endian(&mut data, transfer_syntax_uid);
+
+        Ok(data)
+    }
+
+    /// Leer los fragmentos del frame `n` y decodificarlos
+    fn read_encapsulated_frame<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        offset_table: &[u32],
+        fragments: &[Fragment],
+        n: u32,
+        transfer_syntax_uid: &str,
+        codecs: &CodecRegistry,
+    ) -> Result<Vec<u8>> {
+        let data = self.read_fragments(reader, offset_table, fragments, n)?;
+        codecs.decode_frame(transfer_syntax_uid, &data, self)
+    }
+
+    /// Concatenar los fragmentos del frame `n` sin decodificar
+    fn read_fragments<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        offset_table: &[u32],
+        fragments: &[Fragment],
+        n: u32,
+    ) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        for fragment in codec::frame_fragments(offset_table, fragments, self.number_of_frames, n)? {
+            let start = data.len();
+            data.resize(start + fragment.length as usize, 0);
+            reader.seek(SeekFrom::Start(fragment.offset))?;
+            read_pixel_bytes(reader, &mut data[start..])?;
+        }
+        Ok(data)
+    }
+
+    /// Leer el frame `n` tal como está almacenado (sin decodificar ni
+    /// convertir el orden de bytes)
+    pub fn read_stored_frame<R: Read + Seek>(&self, reader: &mut R, n: u32) -> Result<Vec<u8>> {
+        if n >= self.number_of_frames.max(1) {
+            return Err(DicomError::validation(format!(
+                "Frame {} fuera de rango (total: {})",
+                n, self.number_of_frames
+            )));
+        }
+
+        match &self.location {
+            Some(PixelDataLocation::Encapsulated { offset_table, fragments }) => {
+                self.read_fragments(reader, offset_table, fragments, n)
+            }
+            Some(PixelDataLocation::Native { offset, .. }) => {
+                let frame_size = self.checked_frame_size_bytes()?;
+                let start = frame_offset(*offset, n, frame_size)?;
+                ensure_available(reader, start, frame_size)?;
+
+                let mut data = vec![0; frame_size];
+                reader.seek(SeekFrom::Start(start))?;
+                read_pixel_bytes(reader, &mut data)?;
+                Ok(data)
+            }
+            None => Err(DicomError::MissingRequiredTag(format!("{:?}", tags::PIXEL_DATA))),
+        }
+    }
+
+    /// Obtener offset y longitud de pixel data nativo, validando el tamaño
+    fn native_location(&self, transfer_syntax_uid: &str) -> Result<(u64, u64)> {
+        match &self.location {
+            Some(PixelDataLocation::Native { offset, length }) => {
+                if *length < self.checked_total_size_bytes()? as u64 {
+                    return Err(DicomError::CorruptedPixelData);
+                }
+                Ok((*offset, *length))
+            }
+            Some(PixelDataLocation::Encapsulated { .. }) => {
+                Err(DicomError::UnsupportedTransferSyntax(transfer_syntax_uid.to_string()))
+            }
+            None => Err(DicomError::MissingRequiredTag(format!("{:?}", tags::PIXEL_DATA))),
+        }
+    }
+
+    /// Convertir a little endian si la transfer syntax es big endian
+    fn to_little_endian(&self, data: &mut [u8], transfer_syntax_uid: &str) {
+        let word_size = (self.bits_allocated / 8) as usize;
+        if transfer_syntax_uid == EXPLICIT_VR_BIG_ENDIAN && word_size > 1 {
+            data.chunks_exact_mut(word_size).for_each(|word| word.reverse());
+        }
     }
 
     /// Verificar si es imagen monocromática
@@ -48,7 +365,9 @@ impl PixelDataDescriptor {
     }
 }
 
-/// Pixel data cargado en memoria
+/// Pixel data cargado en memoria (bytes nativos en little endian)
+///
+/// Los frames están concatenados en `data`, en el orden del archivo.
 #[derive(Debug, Clone)]
 pub struct PixelData {
     pub descriptor: PixelDataDescriptor,
@@ -57,35 +376,309 @@ pub struct PixelData {
 
 impl PixelData {
     /// Crear pixel data vacío con descriptor
+    ///
+    /// # Panics
+    ///
+    /// Si el tamaño desborda; con dimensiones leídas de un archivo usar
+    /// [`try_new`](Self::try_new).
     pub fn new(descriptor: PixelDataDescriptor) -> Self {
-        let size = descriptor.total_size_bytes();
-        Self {
+        Self::try_new(descriptor).expect("Dimensiones de pixel data fuera de rango")
+    }
+
+    /// Crear pixel data vacío, con error si el tamaño desborda
+    pub fn try_new(descriptor: PixelDataDescriptor) -> Result<Self> {
+        let size = descriptor.checked_total_size_bytes()?;
+        Ok(Self {
             descriptor,
             data: vec![0; size],
-        }
+        })
+    }
+
+    /// Número de frames disponibles
+    pub fn number_of_frames(&self) -> u32 {
+        self.descriptor.number_of_frames.max(1)
     }
 
-    /// Obtener píxel en posición (x, y)
-    pub fn get_pixel(&self, x: u32, y: u32) -> Option<u16> {
-        if x >= self.descriptor.columns || y >= self.descriptor.rows {
+    /// Obtener los bytes del frame `n` (empezando en 0)
+    pub fn frame(&self, n: u32) -> Option<&[u8]> {
+        if n >= self.number_of_frames() {
             return None;
         }
 
-        let bytes_per_pixel = (self.descriptor.bits_allocated / 8) as usize;
-        let offset = ((y * self.descriptor.columns + x) as usize) * bytes_per_pixel;
+        let size = self.descriptor.checked_frame_size_bytes().ok()?;
+        let start = (n as usize).checked_mul(size)?;
+        self.data.get(start..start.checked_add(size)?)
+    }
+
+    /// Iterar sobre los frames en orden
+    pub fn frames(&self) -> Frames<'_> {
+        Frames {
+            pixel_data: self,
+            next: 0,
+        }
+    }
 
-        if offset + bytes_per_pixel > self.data.len() {
+    /// Obtener el valor del píxel (x, y) de un frame monocromo
+    ///
+    /// Con más de una muestra por píxel o más de 16 bits devuelve None; para
+    /// esos casos usar [`get_sample`](Self::get_sample).
+    pub fn get_pixel(&self, frame: u32, x: u32, y: u32) -> Option<u16> {
+        if self.descriptor.samples_per_pixel > 1 || self.descriptor.bits_allocated > 16 {
             return None;
         }
+        self.get_sample(frame, x, y, 0).ok().map(|value| value as u16)
+    }
 
-        // Leer valor (asumiendo little endian)
-        if bytes_per_pixel == 2 {
-            Some(u16::from_le_bytes([
-                self.data[offset],
-                self.data[offset + 1],
-            ]))
-        } else {
-            Some(self.data[offset] as u16)
+    /// Obtener la muestra `sample` (0 = gris/R/Y, 1 = G/Cb, 2 = B/Cr) del
+    /// píxel (x, y) del frame indicado
+    ///
+    /// Respeta Planar Configuration y el submuestreo de YBR_FULL_422. Los
+    /// valores con signo se devuelven con sus bits sin extender.
+    pub fn get_sample(&self, frame: u32, x: u32, y: u32, sample: u16) -> Result<u32> {
+        let d = &self.descriptor;
+        let samples = d.samples_per_pixel.max(1);
+        if x >= d.columns || y >= d.rows || sample >= samples {
+            return Err(DicomError::validation(format!(
+                "Muestra {} del píxel ({}, {}) fuera de una imagen de {}x{} con {} muestras",
+                sample, x, y, d.columns, d.rows, samples
+            )));
         }
+        let data = self.frame(frame).ok_or_else(|| {
+            DicomError::validation(format!("Frame {} fuera de rango (total: {})", frame, self.number_of_frames()))
+        })?;
+
+        let (columns, sample, samples) = (d.columns as usize, sample as usize, samples as usize);
+        let pixel = (y as usize).checked_mul(columns).and_then(|o| o.checked_add(x as usize));
+        let index = pixel.and_then(|pixel| {
+            if d.photometric_interpretation.trim() == "YBR_FULL_422" {
+                // Cada par de píxeles ocupa Y1 Y2 Cb Cr
+                let pair = (pixel / 2).checked_mul(4)?;
+                Some(if sample == 0 { pair + pixel % 2 } else { pair + 1 + sample })
+            } else if d.planar_configuration == 1 {
+                (d.rows as usize).checked_mul(columns)?.checked_mul(sample)?.checked_add(pixel)
+            } else {
+                pixel.checked_mul(samples)?.checked_add(sample)
+            }
+        });
+        let index = index.ok_or_else(|| DicomError::validation("Offset de píxel fuera de rango"))?;
+
+        let value = match d.bits_allocated {
+            // Empaquetado desde el bit menos significativo (PS3.5 §8.1.1)
+            1 => data.get(index / 8).map(|byte| u32::from((byte >> (index % 8)) & 1)),
+            8 => data.get(index).map(|&byte| u32::from(byte)),
+            16 => index
+                .checked_mul(2)
+                .and_then(|start| data.get(start..start.checked_add(2)?))
+                .map(|b| u32::from(u16::from_le_bytes([b[0], b[1]]))),
+            32 => index
+                .checked_mul(4)
+                .and_then(|start| data.get(start..start.checked_add(4)?))
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+            bits => {
+                return Err(DicomError::validation(format!("Bits Allocated {} no soportado", bits)));
+            }
+        };
+        value.ok_or(DicomError::CorruptedPixelData)
+    }
+}
+
+/// Leer exactamente `buf.len()` bytes; un archivo truncado es pixel data corrupto
+fn read_pixel_bytes<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<()> {
+    reader.read_exact(buf).map_err(|e| match e.kind() {
+        std::io::ErrorKind::UnexpectedEof => DicomError::CorruptedPixelData,
+        _ => DicomError::Io(e),
+    })
+}
+
+/// Offset absoluto del frame `n` de pixel data nativo
+fn frame_offset(offset: u64, n: u32, frame_size: usize) -> Result<u64> {
+    (frame_size as u64)
+        .checked_mul(u64::from(n))
+        .and_then(|start| start.checked_add(offset))
+        .ok_or(DicomError::CorruptedPixelData)
+}
+
+/// Verificar que el archivo tiene `len` bytes desde `offset` antes de
+/// reservar memoria para leerlos
+fn ensure_available<R: Seek>(reader: &mut R, offset: u64, len: usize) -> Result<()> {
+    let end = reader.seek(SeekFrom::End(0))?;
+    match offset.checked_add(len as u64) {
+        Some(needed) if needed <= end => Ok(()),
+        _ => Err(DicomError::CorruptedPixelData),
+    }
+}
+
+/// Iterador sobre los frames de un `PixelData`
+pub struct Frames<'a> {
+    pixel_data: &'a PixelData,
+    next: u32,
+}
+
+impl<'a> Iterator for Frames<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let frame = self.pixel_data.frame(self.next)?;
+        self.next += 1;
+        Some(frame)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.pixel_data.number_of_frames().saturating_sub(self.next) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Frames<'_> {}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Descriptor de un solo frame sin signo (también lo usan los codecs)
+    pub(crate) fn descriptor(rows: u32, columns: u32, bits_allocated: u16, samples_per_pixel: u16) -> PixelDataDescriptor {
+        PixelDataDescriptor {
+            rows,
+            columns,
+            bits_allocated,
+            bits_stored: bits_allocated,
+            high_bit: bits_allocated - 1,
+            photometric_interpretation: if samples_per_pixel == 3 { "RGB" } else { "MONOCHROME2" }.to_string(),
+            samples_per_pixel,
+            pixel_representation: 0,
+            number_of_frames: 1,
+            frame_time: None,
+            frame_time_vector: None,
+            cine_rate: None,
+            planar_configuration: 0,
+            presentation: PixelPresentation::default(),
+            location: None,
+        }
+    }
+
+    fn cine_descriptor(frames: u32) -> PixelDataDescriptor {
+        PixelDataDescriptor {
+            number_of_frames: frames,
+            frame_time: Some(33.3),
+            ..descriptor(2, 2, 8, 1)
+        }
+    }
+
+    #[test]
+    fn test_frame_sizes() {
+        let descriptor = cine_descriptor(3);
+        assert_eq!(descriptor.frame_size_bytes(), 4);
+        assert_eq!(descriptor.total_size_bytes(), 12);
+        assert!(descriptor.is_multi_frame());
+    }
+
+    #[test]
+    fn test_frame_access() {
+        let mut pixel_data = PixelData::new(cine_descriptor(3));
+        pixel_data.data = (0..12).collect();
+
+        assert_eq!(pixel_data.frame(1), Some(&[4u8, 5, 6, 7][..]));
+        assert_eq!(pixel_data.frame(3), None);
+        assert_eq!(pixel_data.get_pixel(2, 1, 1), Some(11));
+        assert_eq!(pixel_data.frames().count(), 3);
+    }
+
+    #[test]
+    fn test_huge_dimensions_fail_without_allocating() {
+        let file = vec![0u8; 64];
+        const EXTREMES: [u32; 5] = [0, 1, 2, 65535, u32::MAX];
+        let cases = EXTREMES
+            .into_iter()
+            .flat_map(|r| EXTREMES.into_iter().flat_map(move |c| EXTREMES.into_iter().map(move |f| (r, c, f))));
+
+        for (rows, columns, frames) in cases {
+            let descriptor = PixelDataDescriptor {
+                number_of_frames: frames,
+                location: Some(PixelDataLocation::Native {
+                    offset: 8,
+                    length: u64::MAX,
+                }),
+                ..descriptor(rows, columns, 16, 3)
+            };
+            let fits = descriptor.checked_total_size_bytes().is_ok_and(|size| size <= 56);
+
+            let mut reader = std::io::Cursor::new(&file);
+            let last = frames.saturating_sub(1);
+            assert_eq!(descriptor.read_pixels(&mut reader, "1.2.840.10008.1.2.1").is_ok(), fits);
+            if let Ok(frame) = descriptor.read_frame(&mut reader, last, "1.2.840.10008.1.2.1") {
+                assert!(frame.len() <= 56);
+            }
+            if let Ok(frame) = descriptor.read_stored_frame(&mut reader, last) {
+                assert!(frame.len() <= 56);
+            }
+            if descriptor.checked_total_size_bytes().is_err() {
+                assert!(PixelData::try_new(descriptor).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn test_rgb_samples_interleaved_and_planar() {
+        // 2x1 RGB: píxel 0 = (1, 2, 3), píxel 1 = (4, 5, 6)
+        let mut interleaved = PixelData::new(descriptor(1, 2, 8, 3));
+        interleaved.data = vec![1, 2, 3, 4, 5, 6];
+        let mut planar = PixelData::new(PixelDataDescriptor {
+            planar_configuration: 1,
+            ..descriptor(1, 2, 8, 3)
+        });
+        planar.data = vec![1, 4, 2, 5, 3, 6];
+
+        for pixel_data in [&interleaved, &planar] {
+            let pixel = |x| (0..3).map(|s| pixel_data.get_sample(0, x, 0, s).unwrap()).collect::<Vec<_>>();
+            assert_eq!(pixel(0), [1, 2, 3]);
+            assert_eq!(pixel(1), [4, 5, 6]);
+            assert!(pixel_data.get_sample(0, 0, 0, 3).is_err());
+            assert_eq!(pixel_data.get_pixel(0, 0, 0), None);
+        }
+    }
+
+    #[test]
+    fn test_multi_frame_rgb_samples() {
+        let mut pixel_data = PixelData::new(PixelDataDescriptor {
+            number_of_frames: 3,
+            ..descriptor(2, 2, 16, 3)
+        });
+        // Frame n, píxel p, muestra s = 100n + 10p + s
+        pixel_data.data = (0..3u16)
+            .flat_map(|n| (0..4u16).flat_map(move |p| (0..3u16).map(move |s| 100 * n + 10 * p + s)))
+            .flat_map(u16::to_le_bytes)
+            .collect();
+
+        assert_eq!(pixel_data.get_sample(2, 1, 1, 2).unwrap(), 232);
+        assert_eq!(pixel_data.get_sample(1, 0, 1, 0).unwrap(), 120);
+        assert!(pixel_data.get_sample(3, 0, 0, 0).is_err());
+    }
+
+    #[test]
+    fn test_sample_bit_depths() {
+        let mut packed = PixelData::new(descriptor(1, 10, 1, 1));
+        packed.data = vec![0b0000_0100, 0b0000_0010];
+        assert_eq!(packed.get_pixel(0, 2, 0), Some(1));
+        assert_eq!(packed.get_pixel(0, 9, 0), Some(1));
+        assert_eq!(packed.get_pixel(0, 8, 0), Some(0));
+
+        let mut wide = PixelData::new(descriptor(1, 1, 32, 1));
+        wide.data = 70_000u32.to_le_bytes().to_vec();
+        assert_eq!(wide.get_sample(0, 0, 0, 0).unwrap(), 70_000);
+        assert_eq!(wide.get_pixel(0, 0, 0), None);
+
+        let mut odd = PixelData::new(descriptor(1, 1, 8, 1));
+        odd.descriptor.bits_allocated = 12;
+        assert!(odd.get_sample(0, 0, 0, 0).is_err());
+    }
+
+    #[test]
+    fn test_frame_duration() {
+        let mut descriptor = cine_descriptor(3);
+        assert_eq!(descriptor.frame_duration_ms(2), Some(33.3));
+        assert_eq!(descriptor.frame_duration_ms(3), None);
+
+        descriptor.frame_time_vector = Some(vec![0.0, 40.0, 20.0]);
+        assert_eq!(descriptor.frame_duration_ms(1), Some(40.0));
     }
 }
@@ -0,0 +1,618 @@
+//! Pipeline de visualización de pixel data
+//!
+//! Convierte los valores almacenados en un buffer de 8 bits listo para
+//! mostrar, en el orden de PS3.4 N.2:
+//!
+//! 1. Bits almacenados: máscara de Bits Stored / High Bit y extensión de signo
+//! 2. Transformación de modalidad: Modality LUT o Rescale Slope/Intercept
+//! 3. Transformación VOI: VOI LUT o ventana (LINEAR, LINEAR_EXACT, SIGMOID)
+//! 4. MONOCHROME1 se invierte para que 0 sea negro
+//!
+//! Las imágenes a color (RGB, YBR_FULL, YBR_FULL_422 y PALETTE COLOR) se
+//! convierten a RGB de 8 bits.
+
+use super::{PixelData, PixelDataDescriptor};
+use crate::error::{DicomError, Result};
+
+use serde::{Deserialize, Serialize};
+
+/// Tabla de búsqueda (Modality LUT, VOI LUT o paleta de color)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Lut {
+    /// Primer valor de entrada mapeado (segundo valor de LUT Descriptor)
+    pub first_mapped: i32,
+
+    /// Bits por entrada (tercer valor de LUT Descriptor)
+    pub bits_per_entry: u16,
+
+    /// Entradas de la tabla (LUT Data)
+    pub data: Vec<u16>,
+}
+
+impl Lut {
+    /// Buscar un valor; fuera de rango se usa la primera o la última entrada
+    pub fn lookup(&self, value: i32) -> u16 {
+        if self.data.is_empty() {
+            return 0;
+        }
+        let index = (value as i64 - self.first_mapped as i64).clamp(0, self.data.len() as i64 - 1);
+        self.data[index as usize]
+    }
+
+    /// Valor máximo que puede tener una entrada
+    pub fn max_output(&self) -> u32 {
+        (1u32 << self.bits_per_entry.clamp(1, 16)) - 1
+    }
+
+    /// Buscar un valor y escalarlo a 8 bits
+    fn lookup_u8(&self, value: i32) -> u8 {
+        to_u8(self.lookup(value) as f64, self.max_output() as f64)
+    }
+}
+
+/// Paleta de color para PALETTE COLOR
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaletteColorLut {
+    pub red: Lut,
+    pub green: Lut,
+    pub blue: Lut,
+}
+
+/// Ventana VOI (Window Center / Window Width)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Window {
+    pub center: f64,
+    pub width: f64,
+}
+
+/// Función VOI LUT (0028,1056)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VoiFunction {
+    #[default]
+    Linear,
+    LinearExact,
+    Sigmoid,
+}
+
+impl VoiFunction {
+    /// Interpretar el valor de VOI LUT Function
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.trim() {
+            "LINEAR" => Some(Self::Linear),
+            "LINEAR_EXACT" => Some(Self::LinearExact),
+            "SIGMOID" => Some(Self::Sigmoid),
+            _ => None,
+        }
+    }
+}
+
+impl Window {
+    /// Aplicar la ventana a un valor de modalidad, con salida 0..=255
+    ///
+    /// Fórmulas de PS3.3 C.11.2.1.2 y C.11.2.1.3.
+    pub fn apply(&self, value: f64, function: VoiFunction) -> u8 {
+        let (c, w) = (self.center, self.width);
+        let y = match function {
+            VoiFunction::Linear => {
+                let w = w.max(1.0);
+                if value <= c - 0.5 - (w - 1.0) / 2.0 {
+                    0.0
+                } else if value > c - 0.5 + (w - 1.0) / 2.0 {
+                    255.0
+                } else {
+                    ((value - (c - 0.5)) / (w - 1.0) + 0.5) * 255.0
+                }
+            }
+            VoiFunction::LinearExact => {
+                let w = w.max(f64::MIN_POSITIVE);
+                if value <= c - w / 2.0 {
+                    0.0
+                } else if value > c + w / 2.0 {
+                    255.0
+                } else {
+                    ((value - c) / w + 0.5) * 255.0
+                }
+            }
+            VoiFunction::Sigmoid => 255.0 / (1.0 + (-4.0 * (value - c) / w.max(f64::MIN_POSITIVE)).exp()),
+        };
+        y.round().clamp(0.0, 255.0) as u8
+    }
+
+    /// Ventana que mapea `[min, max]` exactamente a `[0, 255]` (con LINEAR_EXACT)
+    pub fn from_range(min: f64, max: f64) -> Self {
+        Self {
+            center: (min + max) / 2.0,
+            width: (max - min).max(1.0),
+        }
+    }
+}
+
+/// Atributos que definen cómo mostrar los valores almacenados
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PixelPresentation {
+    /// Rescale Slope (0028,1053)
+    pub rescale_slope: f64,
+
+    /// Rescale Intercept (0028,1052)
+    pub rescale_intercept: f64,
+
+    /// Modality LUT Sequence (0028,3000); tiene prioridad sobre el rescale
+    pub modality_lut: Option<Lut>,
+
+    /// Pares Window Center / Window Width (0028,1050 y 0028,1051)
+    pub windows: Vec<Window>,
+
+    /// VOI LUT Function (0028,1056)
+    pub voi_function: VoiFunction,
+
+    /// VOI LUT Sequence (0028,3010)
+    pub voi_luts: Vec<Lut>,
+
+    /// Paleta para PALETTE COLOR (0028,1101-1103 y 0028,1201-1203)
+    pub palette: Option<PaletteColorLut>,
+}
+
+impl Default for PixelPresentation {
+    fn default() -> Self {
+        Self {
+            rescale_slope: 1.0,
+            rescale_intercept: 0.0,
+            modality_lut: None,
+            windows: Vec::new(),
+            voi_function: VoiFunction::default(),
+            voi_luts: Vec::new(),
+            palette: None,
+        }
+    }
+}
+
+/// Transformación VOI a usar al renderizar
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Voi {
+    /// Primera ventana del archivo; si no hay, la primera VOI LUT; si
+    /// tampoco, el rango completo de valores posibles
+    #[default]
+    Auto,
+
+    /// Ventana explícita (p.ej. ajustada por el usuario)
+    Window(Window),
+
+    /// VOI LUT del archivo por índice
+    Lut(usize),
+
+    /// Ventana calculada con el mínimo y máximo del frame
+    MinMax,
+}
+
+/// Opciones de renderizado
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RenderOptions {
+    /// Transformación VOI (solo imágenes monocromáticas)
+    pub voi: Voi,
+
+    /// Función de la ventana; None usa la VOI LUT Function del archivo
+    pub function: Option<VoiFunction>,
+}
+
+/// Frame listo para mostrar, con 8 bits por muestra
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderedFrame {
+    pub width: u32,
+    pub height: u32,
+
+    /// 1 para escala de grises, 3 para RGB
+    pub samples_per_pixel: u16,
+
+    /// Muestras intercaladas por píxel, fila por fila
+    pub data: Vec<u8>,
+}
+
+impl RenderedFrame {
+    /// Convertir a RGB de 8 bits (replicando el gris en los tres canales)
+    pub fn into_rgb8(self) -> Vec<u8> {
+        if self.samples_per_pixel == 3 {
+            return self.data;
+        }
+        self.data.iter().flat_map(|&v| [v, v, v]).collect()
+    }
+}
+
+impl PixelData {
+    /// Valores almacenados del frame `n`, enmascarados a Bits Stored y con
+    /// signo extendido si Pixel Representation = 1
+    ///
+    /// Devuelve una muestra por elemento, en el orden en que están en el frame.
+    pub fn stored_values(&self, n: u32) -> Result<Vec<i32>> {
+        let frame = self.frame(n).ok_or_else(|| {
+            DicomError::validation(format!("Frame {} fuera de rango (total: {})", n, self.number_of_frames()))
+        })?;
+        stored_values(&self.descriptor, frame)
+    }
+
+    /// Valores de modalidad del frame `n` (tras Modality LUT o rescale)
+    pub fn modality_values(&self, n: u32) -> Result<Vec<f64>> {
+        let presentation = &self.descriptor.presentation;
+        let stored = self.stored_values(n)?;
+
+        Ok(match &presentation.modality_lut {
+            Some(lut) => stored.iter().map(|&v| lut.lookup(v) as f64).collect(),
+            None => stored
+                .iter()
+                .map(|&v| v as f64 * presentation.rescale_slope + presentation.rescale_intercept)
+                .collect(),
+        })
+    }
+
+    /// Renderizar el frame `n` como buffer de 8 bits listo para mostrar
+    pub fn render_frame(&self, n: u32, options: &RenderOptions) -> Result<RenderedFrame> {
+        let descriptor = &self.descriptor;
+        let (samples_per_pixel, data) = match descriptor.photometric_interpretation.trim() {
+            "MONOCHROME1" | "MONOCHROME2" => (1, self.render_monochrome(n, options)?),
+            "RGB" => (3, self.render_rgb(n)?),
+            "YBR_FULL" => {
+                let ybr = self.render_rgb(n)?;
+                (3, ybr.chunks_exact(3).flat_map(|p| ybr_to_rgb(p[0], p[1], p[2])).collect())
+            }
+            "YBR_FULL_422" => (3, self.render_ybr_422(n)?),
+            "PALETTE COLOR" => (3, self.render_palette(n)?),
+            other => {
+                return Err(DicomError::validation(format!(
+                    "Interpretación fotométrica no soportada para renderizar: {}",
+                    other
+                )))
+            }
+        };
+
+        Ok(RenderedFrame {
+            width: descriptor.columns,
+            height: descriptor.rows,
+            samples_per_pixel,
+            data,
+        })
+    }
+
+    fn render_monochrome(&self, n: u32, options: &RenderOptions) -> Result<Vec<u8>> {
+        let descriptor = &self.descriptor;
+        let presentation = &descriptor.presentation;
+        let values = self.modality_values(n)?;
+        let function = options.function.unwrap_or(presentation.voi_function);
+
+        let window = |window: Window, function| values.iter().map(|&v| window.apply(v, function)).collect();
+        let lut = |lut: &Lut| values.iter().map(|&v| lut.lookup_u8(v.round() as i32)).collect();
+
+        let mut output: Vec<u8> = match options.voi {
+            Voi::Window(w) => window(w, function),
+            Voi::Lut(index) => lut(presentation.voi_luts.get(index).ok_or_else(|| {
+                DicomError::validation(format!("VOI LUT {} no existe", index))
+            })?),
+            Voi::MinMax => {
+                let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+                let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+                window(Window::from_range(min, max), VoiFunction::LinearExact)
+            }
+            Voi::Auto => match (presentation.windows.first(), presentation.voi_luts.first()) {
+                (Some(&w), _) => window(w, function),
+                (None, Some(voi_lut)) => lut(voi_lut),
+                (None, None) => {
+                    let (min, max) = modality_range(descriptor);
+                    window(Window::from_range(min, max), VoiFunction::LinearExact)
+                }
+            },
+        };
+
+        // MONOCHROME1: el valor mínimo se muestra blanco
+        if descriptor.photometric_interpretation.trim() == "MONOCHROME1" {
+            output.iter_mut().for_each(|v| *v = 255 - *v);
+        }
+
+        Ok(output)
+    }
+
+    /// Muestras de color a 8 bits, intercaladas por píxel
+    fn render_rgb(&self, n: u32) -> Result<Vec<u8>> {
+        let descriptor = &self.descriptor;
+        if descriptor.samples_per_pixel != 3 {
+            return Err(DicomError::validation(format!(
+                "{} requiere 3 muestras por píxel",
+                descriptor.photometric_interpretation
+            )));
+        }
+
+        let stored = self.stored_values(n)?;
+        let max = ((1u64 << descriptor.bits_stored) - 1) as f64;
+        let pixels = stored.len() / 3;
+
+        let sample = |pixel: usize, channel: usize| match descriptor.planar_configuration {
+            // Planar Configuration 1: RRR... GGG... BBB...
+            1 => stored[channel * pixels + pixel],
+            _ => stored[pixel * 3 + channel],
+        };
+
+        Ok((0..pixels)
+            .flat_map(|pixel| (0..3).map(move |channel| (pixel, channel)))
+            .map(|(pixel, channel)| to_u8(sample(pixel, channel) as f64, max))
+            .collect())
+    }
+
+    /// YBR_FULL_422 nativo: cada par de píxeles se guarda como Y1 Y2 Cb Cr
+    fn render_ybr_422(&self, n: u32) -> Result<Vec<u8>> {
+        let descriptor = &self.descriptor;
+        if descriptor.samples_per_pixel != 3 || descriptor.columns % 2 != 0 {
+            return Err(DicomError::validation(
+                "YBR_FULL_422 requiere 3 muestras por píxel y un número par de columnas",
+            ));
+        }
+
+        let stored = self.stored_values(n)?;
+        let max = ((1u64 << descriptor.bits_stored) - 1) as f64;
+
+        Ok(stored
+            .chunks_exact(4)
+            .flat_map(|group| {
+                let [y1, y2, cb, cr] = [group[0], group[1], group[2], group[3]].map(|v| to_u8(v as f64, max));
+                ybr_to_rgb(y1, cb, cr).into_iter().chain(ybr_to_rgb(y2, cb, cr))
+            })
+            .collect())
+    }
+
+    fn render_palette(&self, n: u32) -> Result<Vec<u8>> {
+        let palette = self
+            .descriptor
+            .presentation
+            .palette
+            .as_ref()
+            .ok_or_else(|| DicomError::MissingRequiredTag("Palette Color Lookup Table".to_string()))?;
+
+        Ok(self
+            .stored_values(n)?
+            .into_iter()
+            .flat_map(|v| [palette.red.lookup_u8(v), palette.green.lookup_u8(v), palette.blue.lookup_u8(v)])
+            .collect())
+    }
+}
+
+/// Extraer las muestras de un frame según Bits Allocated/Stored y High Bit
+fn stored_values(descriptor: &PixelDataDescriptor, frame: &[u8]) -> Result<Vec<i32>> {
+    let bits_allocated = descriptor.bits_allocated;
+    let bits_stored = descriptor.bits_stored;
+    let high_bit = descriptor.high_bit;
+
+    if bits_stored == 0 || bits_stored > bits_allocated || high_bit >= bits_allocated || high_bit + 1 < bits_stored
+    {
+        return Err(DicomError::validation(format!(
+            "Combinación inválida: Bits Allocated {}, Bits Stored {}, High Bit {}",
+            bits_allocated, bits_stored, high_bit
+        )));
+    }
+
+    let raw: Vec<u32> = match bits_allocated {
+        // Bits empaquetados: el primer píxel va en el bit menos significativo
+        1 => {
+            let count = descriptor.rows as usize * descriptor.columns as usize;
+            (0..count).map(|i| u32::from(frame[i / 8] >> (i % 8) & 1)).collect()
+        }
+        8 => frame.iter().map(|&b| u32::from(b)).collect(),
+        16 => frame
+            .chunks_exact(2)
+            .map(|b| u32::from(u16::from_le_bytes([b[0], b[1]])))
+            .collect(),
+        32 => frame
+            .chunks_exact(4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect(),
+        other => {
+            return Err(DicomError::validation(format!(
+                "Bits Allocated {} no soportado",
+                other
+            )))
+        }
+    };
+
+    let shift = high_bit + 1 - bits_stored;
+    let mask = if bits_stored >= 32 { u32::MAX } else { (1u32 << bits_stored) - 1 };
+    let signed = descriptor.pixel_representation == 1;
+
+    Ok(raw
+        .into_iter()
+        .map(|word| {
+            let value = (word >> shift) & mask;
+            if signed && bits_stored < 32 && value >> (bits_stored - 1) & 1 == 1 {
+                (value as i64 - (1i64 << bits_stored)) as i32
+            } else {
+                value as i32
+            }
+        })
+        .collect())
+}
+
+/// Rango de valores de modalidad posible según Bits Stored
+fn modality_range(descriptor: &PixelDataDescriptor) -> (f64, f64) {
+    let presentation = &descriptor.presentation;
+    if let Some(lut) = &presentation.modality_lut {
+        return (0.0, lut.max_output() as f64);
+    }
+
+    let bits = descriptor.bits_stored.min(32) as i32;
+    let (min, max) = if descriptor.pixel_representation == 1 {
+        (-(2f64.powi(bits - 1)), 2f64.powi(bits - 1) - 1.0)
+    } else {
+        (0.0, 2f64.powi(bits) - 1.0)
+    };
+
+    let a = min * presentation.rescale_slope + presentation.rescale_intercept;
+    let b = max * presentation.rescale_slope + presentation.rescale_intercept;
+    (a.min(b), a.max(b))
+}
+
+/// Escalar un valor en `[0, max]` a 8 bits
+fn to_u8(value: f64, max: f64) -> u8 {
+    if max == 255.0 {
+        return value.clamp(0.0, 255.0) as u8;
+    }
+    (value * 255.0 / max).round().clamp(0.0, 255.0) as u8
+}
+
+/// YCbCr (full range, PS3.3 C.7.6.3.1.2) a RGB
+fn ybr_to_rgb(y: u8, cb: u8, cr: u8) -> [u8; 3] {
+    let (y, cb, cr) = (y as f64, cb as f64 - 128.0, cr as f64 - 128.0);
+    [
+        y + 1.402 * cr,
+        y - 0.344136 * cb - 0.714136 * cr,
+        y + 1.772 * cb,
+    ]
+    .map(|v| v.round().clamp(0.0, 255.0) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel::tests::descriptor;
+
+    fn pixel_data(descriptor: PixelDataDescriptor, data: Vec<u8>) -> PixelData {
+        PixelData { descriptor, data }
+    }
+
+    fn words(values: &[u16]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn test_stored_values_mask_and_sign_extend() {
+        // 12 bits con signo en 16, con bits de overlay por encima de High Bit
+        let mut descriptor = descriptor(1, 3, 16, 1);
+        descriptor.bits_stored = 12;
+        descriptor.high_bit = 11;
+        descriptor.pixel_representation = 1;
+
+        let pixels = pixel_data(descriptor, words(&[0xF001, 0x0800, 0x07FF]));
+        assert_eq!(pixels.stored_values(0).unwrap(), vec![1, -2048, 2047]);
+    }
+
+    #[test]
+    fn test_stored_values_with_high_bit_offset() {
+        // Bits Stored 8 en los bits 4..=11
+        let mut descriptor = descriptor(1, 1, 16, 1);
+        descriptor.bits_stored = 8;
+        descriptor.high_bit = 11;
+
+        let pixels = pixel_data(descriptor, words(&[0xF7A3]));
+        assert_eq!(pixels.stored_values(0).unwrap(), vec![0x7A]);
+    }
+
+    #[test]
+    fn test_rescale_and_linear_window() {
+        // CT: HU = valor - 1024, ventana de abdomen (40/400)
+        let mut descriptor = descriptor(1, 3, 16, 1);
+        descriptor.presentation.rescale_intercept = -1024.0;
+        descriptor.presentation.windows = vec![Window { center: 40.0, width: 400.0 }];
+
+        let pixels = pixel_data(descriptor, words(&[0, 1064, 2000]));
+        assert_eq!(pixels.modality_values(0).unwrap(), vec![-1024.0, 40.0, 976.0]);
+
+        let frame = pixels.render_frame(0, &RenderOptions::default()).unwrap();
+        assert_eq!(frame.samples_per_pixel, 1);
+        assert_eq!(frame.data, vec![0, 128, 255]);
+    }
+
+    #[test]
+    fn test_window_functions() {
+        let window = Window { center: 100.0, width: 50.0 };
+
+        assert_eq!(window.apply(75.0, VoiFunction::LinearExact), 0);
+        assert_eq!(window.apply(100.0, VoiFunction::LinearExact), 128);
+        assert_eq!(window.apply(125.0, VoiFunction::LinearExact), 255);
+
+        // SIGMOID nunca satura del todo y pasa por la mitad en el centro
+        assert_eq!(window.apply(100.0, VoiFunction::Sigmoid), 128);
+        assert!(window.apply(80.0, VoiFunction::Sigmoid) > 0);
+        assert!(window.apply(120.0, VoiFunction::Sigmoid) < 255);
+    }
+
+    #[test]
+    fn test_monochrome1_is_inverted() {
+        let mut descriptor = descriptor(1, 3, 8, 1);
+        descriptor.photometric_interpretation = "MONOCHROME1".to_string();
+
+        let frame = pixel_data(descriptor, vec![0, 100, 255])
+            .render_frame(0, &RenderOptions::default())
+            .unwrap();
+        assert_eq!(frame.data, vec![255, 155, 0]);
+    }
+
+    #[test]
+    fn test_voi_lut_and_min_max() {
+        let mut descriptor = descriptor(1, 3, 8, 1);
+        descriptor.presentation.voi_luts = vec![Lut {
+            first_mapped: 10,
+            bits_per_entry: 8,
+            data: vec![0, 100, 200],
+        }];
+        let pixels = pixel_data(descriptor, vec![5, 11, 12]);
+
+        // Auto usa la VOI LUT; fuera de rango toma la entrada extrema
+        let frame = pixels.render_frame(0, &RenderOptions::default()).unwrap();
+        assert_eq!(frame.data, vec![0, 100, 200]);
+
+        let options = RenderOptions { voi: Voi::MinMax, ..Default::default() };
+        assert_eq!(pixels.render_frame(0, &options).unwrap().data, vec![0, 219, 255]);
+    }
+
+    #[test]
+    fn test_ybr_full_to_rgb() {
+        let mut descriptor = descriptor(1, 2, 8, 3);
+        descriptor.photometric_interpretation = "YBR_FULL".to_string();
+
+        // Gris medio y rojo puro (Y=76, Cb=85, Cr=255)
+        let frame = pixel_data(descriptor, vec![128, 128, 128, 76, 85, 255])
+            .render_frame(0, &RenderOptions::default())
+            .unwrap();
+        assert_eq!(frame.samples_per_pixel, 3);
+        assert_eq!(&frame.data[..3], &[128, 128, 128]);
+        assert!(frame.data[3] >= 253 && frame.data[4] <= 2 && frame.data[5] <= 2);
+    }
+
+    #[test]
+    fn test_ybr_full_422_shares_chroma() {
+        let mut descriptor = descriptor(1, 2, 8, 3);
+        descriptor.photometric_interpretation = "YBR_FULL_422".to_string();
+        assert_eq!(descriptor.frame_size_bytes(), 4);
+
+        let frame = pixel_data(descriptor, vec![50, 200, 128, 128])
+            .render_frame(0, &RenderOptions::default())
+            .unwrap();
+        assert_eq!(frame.data, vec![50, 50, 50, 200, 200, 200]);
+    }
+
+    #[test]
+    fn test_planar_rgb_is_interleaved() {
+        let mut descriptor = descriptor(1, 2, 8, 3);
+        descriptor.planar_configuration = 1;
+
+        let frame = pixel_data(descriptor, vec![1, 2, 3, 4, 5, 6])
+            .render_frame(0, &RenderOptions::default())
+            .unwrap();
+        assert_eq!(frame.data, vec![1, 3, 5, 2, 4, 6]);
+    }
+
+    #[test]
+    fn test_palette_color() {
+        let lut = |data: Vec<u16>| Lut {
+            first_mapped: 0,
+            bits_per_entry: 16,
+            data,
+        };
+        let mut descriptor = descriptor(1, 2, 8, 1);
+        descriptor.photometric_interpretation = "PALETTE COLOR".to_string();
+        descriptor.presentation.palette = Some(PaletteColorLut {
+            red: lut(vec![0, 65535]),
+            green: lut(vec![65535, 0]),
+            blue: lut(vec![0, 0]),
+        });
+
+        let frame = pixel_data(descriptor, vec![0, 1])
+            .render_frame(0, &RenderOptions::default())
+            .unwrap();
+        assert_eq!(frame.data, vec![0, 255, 0, 255, 0, 0]);
+        assert_eq!(frame.into_rgb8().len(), 6);
+    }
+}
@@ -0,0 +1,406 @@
+//! Lectura del data set elemento por elemento
+//!
+//! A diferencia de `InMemDicomObject::from_reader`, este lector se detiene
+//! al encontrar Pixel Data (7FE0,0010) y solo registra dónde están los
+//! píxeles en el archivo, sin leerlos.
+//!
+//! En modo tolerante (`lenient`) los errores de lectura cortan el data set
+//! en vez de abortar, y quedan registrados como [`ParseWarning`].
+
+use crate::charset::{self, CharacterSet};
+use crate::error::{DicomError, ParseWarning, Result};
+use crate::pixel::{Fragment, PixelDataLocation};
+
+use dicom::core::value::{DataSetSequence, PrimitiveValue};
+use dicom::core::{DataElement, DataElementHeader, Length, Tag, VR};
+use dicom::core::header::{HasLength, SequenceItemHeader};
+use dicom::dictionary_std::tags;
+use dicom::encoding::TransferSyntax;
+use dicom::object::InMemDicomObject;
+use dicom::parser::{DynStatefulDecoder, StatefulDecode, StatefulDecoder};
+use std::cell::Cell;
+use std::io::Read;
+use std::rc::Rc;
+
+/// Resultado de leer el data set hasta Pixel Data
+pub(crate) struct DataSetHead {
+    /// Elementos anteriores a Pixel Data
+    pub dataset: InMemDicomObject,
+
+    /// Ubicación de Pixel Data, si el data set lo contiene y se pidió ubicarlo
+    pub pixel_location: Option<PixelDataLocation>,
+
+    /// Se llegó al header de Pixel Data
+    pub has_pixel_data: bool,
+
+    /// Problemas tolerados (solo en modo tolerante)
+    pub warnings: Vec<ParseWarning>,
+
+    /// Offset de cada item de Directory Record Sequence (DICOMDIR), que es
+    /// como se referencian los registros entre sí
+    pub record_offsets: Vec<u64>,
+}
+
+/// Estado compartido mientras se lee un data set
+struct ReadContext {
+    lenient: bool,
+    warnings: Vec<ParseWarning>,
+    record_offsets: Vec<u64>,
+}
+
+/// Leer el data set desde `source` hasta encontrar Pixel Data
+///
+/// `position` es el offset absoluto de `source` dentro del archivo, para
+/// que las ubicaciones registradas sirvan para hacer seek después. Sin
+/// `locate_pixels` la lectura termina en el header de Pixel Data, sin
+/// recorrer los fragmentos (útil cuando el resto del objeto aún no llegó).
+pub(crate) fn read_until_pixel_data<S: Read>(
+    source: S,
+    ts: &TransferSyntax,
+    position: u64,
+    lenient: bool,
+    locate_pixels: bool,
+) -> Result<DataSetHead> {
+    let consumed = Rc::new(Cell::new(0));
+    let source = CountingReader {
+        inner: source,
+        count: Rc::clone(&consumed),
+    };
+    let mut decoder = StatefulDecoder::new_with_ts(source, ts, position)
+        .map_err(|_| DicomError::UnsupportedTransferSyntax(ts.uid().to_string()))?;
+
+    let mut ctx = ReadContext {
+        lenient,
+        warnings: Vec::new(),
+        record_offsets: Vec::new(),
+    };
+    let mut dataset = InMemDicomObject::new_empty();
+    let mut charset = CharacterSet::default();
+    let mut pixel_location = None;
+    let mut has_pixel_data = false;
+
+    loop {
+        let offset = decoder.position();
+        let before = consumed.get();
+        let header = match decoder.decode_header() {
+            Ok(header) => header,
+            // Fin del archivo sin Pixel Data (p.ej. SR o presentation state),
+            // solo si no se llegó a leer nada del siguiente header
+            Err(e) if is_eof(&e) && consumed.get() == before => break,
+            Err(_) if lenient => {
+                ctx.warnings.push(ParseWarning::TruncatedDataSet { offset });
+                break;
+            }
+            Err(e) if is_eof(&e) => {
+                return Err(DicomError::parse(format!(
+                    "Header de elemento truncado en el offset {} ({} bytes)",
+                    offset,
+                    consumed.get() - before
+                )));
+            }
+            Err(e) => return Err(DicomError::parse(format!("Error reading element header: {}", e))),
+        };
+
+        if header.tag == tags::PIXEL_DATA {
+            has_pixel_data = true;
+            if locate_pixels {
+                pixel_location = Some(read_pixel_location(&mut decoder, &header, &mut ctx)?);
+            }
+            break;
+        }
+
+        match read_element(&mut decoder, header, &charset, &mut ctx) {
+            Ok(element) => dataset.put(element),
+            Err(_) if lenient => {
+                ctx.warnings.push(ParseWarning::TruncatedDataSet { offset });
+                break;
+            }
+            Err(e) => return Err(e),
+        };
+        if header.tag == tags::SPECIFIC_CHARACTER_SET {
+            charset = CharacterSet::from_dataset(&dataset);
+        }
+    }
+
+    Ok(DataSetHead {
+        dataset,
+        pixel_location,
+        has_pixel_data,
+        warnings: ctx.warnings,
+        record_offsets: ctx.record_offsets,
+    })
+}
+
+/// Lector que cuenta los bytes entregados al decoder, para distinguir el
+/// fin del archivo entre dos elementos de un header cortado
+struct CountingReader<S> {
+    inner: S,
+    count: Rc<Cell<u64>>,
+}
+
+impl<S: Read> Read for CountingReader<S> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count.set(self.count.get() + n as u64);
+        Ok(n)
+    }
+}
+
+/// Leer un elemento completo (incluyendo secuencias anidadas)
+///
+/// Los VR de texto se decodifican con `charset` (ver [`charset`]).
+fn read_element<S: Read>(
+    decoder: &mut DynStatefulDecoder<S>,
+    header: DataElementHeader,
+    charset: &CharacterSet,
+    ctx: &mut ReadContext,
+) -> Result<DataElement<InMemDicomObject>> {
+    // UN con longitud indefinida también se codifica como secuencia
+    if header.vr == VR::SQ || header.length().is_undefined() {
+        let items = read_items(decoder, header.length(), charset, ctx)?;
+        if header.tag == tags::DIRECTORY_RECORD_SEQUENCE {
+            ctx.record_offsets = items.iter().map(|(offset, _)| *offset).collect();
+        }
+        let items: Vec<InMemDicomObject> = items.into_iter().map(|(_, item)| item).collect();
+        return Ok(DataElement::new(
+            header.tag,
+            VR::SQ,
+            DataSetSequence::new(items, header.length()),
+        ));
+    }
+
+    let read_error = |e| DicomError::parse(format!("Error reading value of {:?}: {}", header.tag, e));
+
+    if ctx.lenient {
+        if let Some(length) = header.length().get().filter(|len| len % 2 == 1) {
+            ctx.warnings.push(ParseWarning::OddLength {
+                tag: header.tag.to_string(),
+                length,
+            });
+
+            // Un valor binario de longitud impar desalinea la lectura por
+            // palabras: se conservan los bytes tal cual como UN
+            if is_binary(header.vr) {
+                let value = decoder.read_value_bytes(&header).map_err(read_error)?;
+                return Ok(DataElement::new(header.tag, VR::UN, value));
+            }
+        }
+    }
+
+    let value = if charset::uses_character_set(header.vr) {
+        match decoder.read_value_bytes(&header).map_err(read_error)? {
+            PrimitiveValue::U8(bytes) => charset.decode_value(header.vr, &bytes),
+            other => other,
+        }
+    } else {
+        decoder.read_value_preserved(&header).map_err(read_error)?
+    };
+
+    Ok(DataElement::new(header.tag, header.vr, value))
+}
+
+/// Leer los items de una secuencia, con el offset del header de cada uno
+fn read_items<S: Read>(
+    decoder: &mut DynStatefulDecoder<S>,
+    length: Length,
+    charset: &CharacterSet,
+    ctx: &mut ReadContext,
+) -> Result<Vec<(u64, InMemDicomObject)>> {
+    let end = length.get().map(|len| decoder.position() + u64::from(len));
+    let mut items = Vec::new();
+
+    while end.map_or(true, |end| decoder.position() < end) {
+        let offset = decoder.position();
+        match decode_item_header(decoder)? {
+            SequenceItemHeader::Item { len } => items.push((offset, read_item(decoder, len, charset, ctx)?)),
+            SequenceItemHeader::SequenceDelimiter => break,
+            SequenceItemHeader::ItemDelimiter => {
+                return Err(DicomError::parse("Item delimiter outside of an item"));
+            }
+        }
+    }
+
+    Ok(items)
+}
+
+/// Leer los elementos de un item de secuencia
+///
+/// Un item puede declarar su propio Specific Character Set, que aplica solo
+/// dentro de él.
+fn read_item<S: Read>(
+    decoder: &mut DynStatefulDecoder<S>,
+    length: Length,
+    charset: &CharacterSet,
+    ctx: &mut ReadContext,
+) -> Result<InMemDicomObject> {
+    let end = length.get().map(|len| decoder.position() + u64::from(len));
+    let mut item = InMemDicomObject::new_empty();
+    let mut charset = charset.clone();
+
+    while end.map_or(true, |end| decoder.position() < end) {
+        let header = decoder
+            .decode_header()
+            .map_err(|e| DicomError::parse(format!("Error reading element header: {}", e)))?;
+
+        if header.tag == Tag(0xFFFE, 0xE00D) {
+            break;
+        }
+
+        item.put(read_element(decoder, header, &charset, ctx)?);
+        if header.tag == tags::SPECIFIC_CHARACTER_SET {
+            charset = CharacterSet::from_dataset(&item);
+        }
+    }
+
+    Ok(item)
+}
+
+/// Registrar la ubicación de Pixel Data sin leer los píxeles
+///
+/// En modo tolerante un error entre fragmentos corta la lista; los que
+/// quedan más allá del fin del archivo los descarta el parser.
+fn read_pixel_location<S: Read>(
+    decoder: &mut DynStatefulDecoder<S>,
+    header: &DataElementHeader,
+    ctx: &mut ReadContext,
+) -> Result<PixelDataLocation> {
+    if let Some(length) = header.length().get() {
+        return Ok(PixelDataLocation::Native {
+            offset: decoder.position(),
+            length: u64::from(length),
+        });
+    }
+
+    // Encapsulado: el primer item es la Basic Offset Table
+    let mut offset_table = Vec::new();
+    let mut fragments = Vec::new();
+    let mut first = true;
+
+    loop {
+        let item = match decode_item_header(decoder) {
+            Ok(item) => item,
+            Err(_) if ctx.lenient => break,
+            Err(e) => return Err(e),
+        };
+
+        match item {
+            SequenceItemHeader::Item { len } => {
+                let length = len
+                    .get()
+                    .ok_or_else(|| DicomError::parse("Pixel data fragment with undefined length"))?;
+
+                if first {
+                    decoder
+                        .read_u32_to_vec(length, &mut offset_table)
+                        .map_err(|_| DicomError::CorruptedPixelData)?;
+                    first = false;
+                } else {
+                    fragments.push(Fragment {
+                        offset: decoder.position(),
+                        length,
+                    });
+                    decoder.skip_bytes(length).map_err(|_| DicomError::CorruptedPixelData)?;
+                }
+            }
+            SequenceItemHeader::SequenceDelimiter => break,
+            SequenceItemHeader::ItemDelimiter => return Err(DicomError::CorruptedPixelData),
+        }
+    }
+
+    Ok(PixelDataLocation::Encapsulated {
+        offset_table,
+        fragments,
+    })
+}
+
+fn decode_item_header<S: Read>(decoder: &mut DynStatefulDecoder<S>) -> Result<SequenceItemHeader> {
+    decoder
+        .decode_item_header()
+        .map_err(|e| DicomError::parse(format!("Error reading item header: {}", e)))
+}
+
+/// VR cuyo valor se lee como palabras de 2, 4 u 8 bytes
+fn is_binary(vr: VR) -> bool {
+    matches!(
+        vr,
+        VR::AT | VR::US | VR::SS | VR::UL | VR::SL | VR::FL | VR::FD | VR::SV | VR::UV
+            | VR::OW | VR::OF | VR::OD | VR::OL | VR::OV
+    )
+}
+
+/// Verificar si el error viene de llegar al final de la fuente
+fn is_eof(error: &(dyn std::error::Error + 'static)) -> bool {
+    let mut current = Some(error);
+    while let Some(e) = current {
+        if let Some(io) = e.downcast_ref::<std::io::Error>() {
+            return io.kind() == std::io::ErrorKind::UnexpectedEof;
+        }
+        current = e.source();
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dicom::transfer_syntax::entries::EXPLICIT_VR_LITTLE_ENDIAN;
+
+    #[test]
+    fn test_reads_sequence_and_stops_at_pixel_data() {
+        #[rustfmt::skip]
+        let bytes: Vec<u8> = [
+            // (0008,0060) CS "US"
+            &[0x08, 0x00, 0x60, 0x00, b'C', b'S', 2, 0, b'U', b'S'][..],
+            // (0018,6011) SQ longitud indefinida, un item indefinido con (0018,6018) UL 10
+            &[0x18, 0x00, 0x11, 0x60, b'S', b'Q', 0, 0, 0xFF, 0xFF, 0xFF, 0xFF],
+            &[0xFE, 0xFF, 0x00, 0xE0, 0xFF, 0xFF, 0xFF, 0xFF],
+            &[0x18, 0x00, 0x18, 0x60, b'U', b'L', 4, 0, 10, 0, 0, 0],
+            &[0xFE, 0xFF, 0x0D, 0xE0, 0, 0, 0, 0],
+            &[0xFE, 0xFF, 0xDD, 0xE0, 0, 0, 0, 0],
+            // (7FE0,0010) OB longitud 4
+            &[0xE0, 0x7F, 0x10, 0x00, b'O', b'B', 0, 0, 4, 0, 0, 0, 1, 2, 3, 4],
+        ]
+        .concat();
+
+        let head = read_until_pixel_data(&bytes[..], &EXPLICIT_VR_LITTLE_ENDIAN.erased(), 100, false, true).unwrap();
+
+        assert_eq!(head.dataset.element(tags::MODALITY).unwrap().to_str().unwrap(), "US");
+        let regions = head.dataset.element(tags::SEQUENCE_OF_ULTRASOUND_REGIONS).unwrap();
+        assert_eq!(regions.items().unwrap().len(), 1);
+
+        // 100 + 10 + 12 + 8 + 12 + 8 + 8 + 12 = 170
+        assert_eq!(
+            head.pixel_location,
+            Some(PixelDataLocation::Native { offset: 170, length: 4 })
+        );
+    }
+
+    #[test]
+    fn test_lenient_odd_length_and_truncation() {
+        #[rustfmt::skip]
+        let bytes: Vec<u8> = [
+            // (0028,0010) US con longitud impar 3
+            &[0x28, 0x00, 0x10, 0x00, b'U', b'S', 3, 0, 2, 0, 9][..],
+            // (0028,0011) US 4
+            &[0x28, 0x00, 0x11, 0x00, b'U', b'S', 2, 0, 4, 0],
+            // (0028,0030) DS declarado con 8 bytes pero el archivo termina antes
+            &[0x28, 0x00, 0x30, 0x00, b'D', b'S', 8, 0, b'0', b'.'],
+        ]
+        .concat();
+        let ts = EXPLICIT_VR_LITTLE_ENDIAN.erased();
+
+        assert!(read_until_pixel_data(&bytes[..], &ts, 0, false, true).is_err());
+
+        let head = read_until_pixel_data(&bytes[..], &ts, 0, true, true).unwrap();
+        assert_eq!(head.dataset.element(tags::ROWS).unwrap().vr(), VR::UN);
+        assert_eq!(head.dataset.element(tags::COLUMNS).unwrap().to_int::<u16>().unwrap(), 4);
+        assert!(head.dataset.element(tags::PIXEL_SPACING).is_err());
+        assert_eq!(
+            head.warnings,
+            vec![
+                ParseWarning::OddLength { tag: tags::ROWS.to_string(), length: 3 },
+                ParseWarning::TruncatedDataSet { offset: 21 },
+            ]
+        );
+    }
+}
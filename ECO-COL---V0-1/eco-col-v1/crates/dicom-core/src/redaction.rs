@@ -0,0 +1,353 @@
+//! Enmascarado de anotaciones incrustadas en los píxeles
+//!
+//! Los equipos de ultrasonido suelen escribir nombre del paciente, fecha e
+//! institución en un banner de la imagen. Anonimizar el data set no basta:
+//! hay que tapar esos píxeles antes de compartir el estudio.
+
+use crate::error::{DicomError, Result};
+use crate::metadata::DicomInstance;
+use crate::pixel::{PixelData, PixelDataDescriptor};
+use crate::ultrasound::UltrasoundRegion;
+
+use dicom::core::{DataElement, PrimitiveValue, VR};
+use dicom::dictionary_std::tags;
+use dicom::object::InMemDicomObject;
+use serde::{Deserialize, Serialize};
+
+/// Rectángulo en píxeles (esquina superior izquierda y tamaño)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    /// Verificar si el píxel (x, y) está dentro del rectángulo
+    pub fn contains(&self, x: u32, y: u32) -> bool {
+        x >= self.x && y >= self.y && x - self.x < self.width && y - self.y < self.height
+    }
+}
+
+/// Opciones de enmascarado
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactionOptions {
+    /// Rectángulos a tapar (p.ej. el banner superior con nombre y fecha)
+    pub rectangles: Vec<Rect>,
+
+    /// Tapar todo lo que quede fuera de las regiones de ultrasonido declaradas
+    pub mask_outside_regions: bool,
+
+    /// No tocar imágenes con Burned In Annotation (0028,0301) = NO
+    ///
+    /// Desactivado por defecto: muchos equipos declaran NO aunque tengan texto.
+    pub trust_burned_in_flag: bool,
+}
+
+impl Default for RedactionOptions {
+    fn default() -> Self {
+        Self {
+            rectangles: Vec::new(),
+            mask_outside_regions: true,
+            trust_burned_in_flag: false,
+        }
+    }
+}
+
+/// Valor de Burned In Annotation (None si no está o no es YES/NO)
+pub fn burned_in_annotation(dataset: &InMemDicomObject) -> Option<bool> {
+    let value = dataset.element(tags::BURNED_IN_ANNOTATION).ok()?.to_str().ok()?;
+    match value.trim() {
+        "YES" => Some(true),
+        "NO" => Some(false),
+        _ => None,
+    }
+}
+
+/// Enmascarador de píxeles
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    options: RedactionOptions,
+}
+
+impl Redactor {
+    /// Crear enmascarador con las opciones indicadas
+    pub fn new(options: RedactionOptions) -> Self {
+        Self { options }
+    }
+
+    /// Devolver una copia de la instancia con los píxeles enmascarados
+    ///
+    /// Los píxeles quedan decodificados en memoria (`pixel_data`) y Burned In
+    /// Annotation pasa a NO. El ícono (Icon Image Sequence) es una copia
+    /// reducida de la imagen con el mismo texto, así que se elimina.
+    pub fn redact(&self, instance: &DicomInstance) -> Result<DicomInstance> {
+        if self.options.trust_burned_in_flag && burned_in_annotation(&instance.dataset) == Some(false) {
+            return Ok(instance.clone());
+        }
+
+        let mut pixel_data = instance.load_pixels()?;
+        self.mask(&mut pixel_data, &instance.regions)?;
+
+        let mut dataset = instance.dataset.clone();
+        dataset.remove_element(tags::ICON_IMAGE_SEQUENCE);
+        dataset.put(DataElement::new(
+            tags::BURNED_IN_ANNOTATION,
+            VR::CS,
+            PrimitiveValue::from("NO"),
+        ));
+
+        Ok(DicomInstance {
+            file_path: instance.file_path.clone(),
+            metadata: instance.metadata.clone(),
+            dataset,
+            regions: instance.regions.clone(),
+            warnings: instance.warnings.clone(),
+            checksums: None,
+            pixel_descriptor: Some(pixel_data.descriptor.clone()),
+            pixel_data: Some(pixel_data),
+        })
+    }
+
+    /// Tapar en todos los frames los píxeles que cubren las opciones
+    pub fn mask(&self, pixel_data: &mut PixelData, regions: &[UltrasoundRegion]) -> Result<()> {
+        if self.options.mask_outside_regions && regions.is_empty() {
+            return Err(DicomError::validation(
+                "No hay regiones de ultrasonido declaradas para enmascarar fuera de ellas",
+            ));
+        }
+
+        let descriptor = &pixel_data.descriptor;
+        let black = black_samples(descriptor)?;
+        let bytes_per_sample = (descriptor.bits_allocated / 8) as usize;
+        let samples = descriptor.samples_per_pixel as usize;
+        let (columns, rows) = (descriptor.columns, descriptor.rows);
+        let pixels = columns as usize * rows as usize;
+        let planar = descriptor.planar_configuration == 1;
+        // YBR_FULL_422 nativo: cada par de píxeles se guarda como Y1 Y2 Cb Cr
+        let pairs = descriptor.photometric_interpretation.trim() == "YBR_FULL_422";
+        let frame_size = descriptor.checked_frame_size_bytes()?;
+
+        let masked: Vec<usize> = (0..rows)
+            .flat_map(|y| (0..columns).map(move |x| (x, y)))
+            .filter(|&(x, y)| {
+                self.options.rectangles.iter().any(|rect| rect.contains(x, y))
+                    || (self.options.mask_outside_regions && !regions.iter().any(|r| r.contains(x, y)))
+            })
+            .map(|(x, y)| y as usize * columns as usize + x as usize)
+            .collect();
+
+        // Índices de las muestras a tapar y su valor; con 4:2:2 se tapa el par
+        // completo para no dejar la crominancia compartida con un píxel visible
+        let writes: Vec<(usize, u16)> = if pairs {
+            let mut pairs: Vec<usize> = masked.iter().map(|pixel| pixel / 2).collect();
+            pairs.dedup();
+            let pair_black = [black[0], black[0], black[1], black[2]];
+            pairs
+                .iter()
+                .flat_map(|pair| pair_black.iter().enumerate().map(move |(i, &value)| (pair * 4 + i, value)))
+                .collect()
+        } else {
+            masked
+                .iter()
+                .flat_map(|&pixel| {
+                    black.iter().enumerate().map(move |(sample, &value)| {
+                        (if planar { sample * pixels + pixel } else { pixel * samples + sample }, value)
+                    })
+                })
+                .collect()
+        };
+
+        for frame in pixel_data.data.chunks_exact_mut(frame_size) {
+            for &(index, value) in &writes {
+                let bytes = value.to_le_bytes();
+                frame[index * bytes_per_sample..(index + 1) * bytes_per_sample]
+                    .copy_from_slice(&bytes[..bytes_per_sample]);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Palabra almacenada que se ve negra, para cada muestra del píxel
+fn black_samples(descriptor: &PixelDataDescriptor) -> Result<Vec<u16>> {
+    if !matches!(descriptor.bits_allocated, 8 | 16) {
+        return Err(DicomError::validation(format!(
+            "Enmascarado con Bits Allocated {} no soportado",
+            descriptor.bits_allocated
+        )));
+    }
+
+    let bits = descriptor.bits_stored.clamp(1, 16) as u32;
+    let shift = (descriptor.high_bit + 1).saturating_sub(descriptor.bits_stored) as u32;
+    let mask = ((1u32 << bits) - 1) as u16;
+    let signed = descriptor.pixel_representation == 1;
+    // Valores mínimo y máximo como palabra almacenada (complemento a 2 si hay signo)
+    let (min, max) = if signed { (1u16 << (bits - 1), mask >> 1) } else { (0, mask) };
+    let stored = |value: u16| (value & mask) << shift;
+
+    Ok(match descriptor.photometric_interpretation.trim() {
+        "MONOCHROME2" => vec![stored(min)],
+        "MONOCHROME1" => vec![stored(max)],
+        "RGB" => vec![stored(0); 3],
+        // Y = 0 y crominancia en el punto medio
+        "YBR_FULL" | "YBR_FULL_422" => vec![stored(0), stored(1 << (bits - 1)), stored(1 << (bits - 1))],
+        "PALETTE COLOR" => vec![stored(palette_black(descriptor)?)],
+        other => {
+            return Err(DicomError::validation(format!(
+                "Enmascarado no soportado para {}",
+                other
+            )))
+        }
+    })
+}
+
+/// Índice de la paleta que se ve más oscuro (idealmente R = G = B = 0)
+fn palette_black(descriptor: &PixelDataDescriptor) -> Result<u16> {
+    let palette = descriptor
+        .presentation
+        .palette
+        .as_ref()
+        .ok_or_else(|| DicomError::MissingRequiredTag("Palette Color Lookup Table".to_string()))?;
+
+    let first = palette.red.first_mapped;
+    let entries = palette.red.data.len().max(1) as i32;
+    let brightness = |value: i32| {
+        [&palette.red, &palette.green, &palette.blue]
+            .iter()
+            .map(|lut| lut.lookup(value) as f64 / lut.max_output() as f64)
+            .sum::<f64>()
+    };
+    let darkest = (first..first + entries)
+        .min_by(|&a, &b| brightness(a).total_cmp(&brightness(b)))
+        .unwrap_or(first);
+    Ok(darkest as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel::tests::descriptor;
+
+    fn region(x0: u32, y0: u32, x1: u32, y1: u32) -> UltrasoundRegion {
+        UltrasoundRegion {
+            spatial_format: 1,
+            data_type: 1,
+            flags: 0,
+            min_x0: x0,
+            min_y0: y0,
+            max_x1: x1,
+            max_y1: y1,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_mask_outside_regions() {
+        let mut pixel_data = PixelData {
+            descriptor: descriptor(3, 3, 8, 1),
+            data: vec![9; 9],
+        };
+        Redactor::default().mask(&mut pixel_data, &[region(1, 1, 2, 2)]).unwrap();
+        assert_eq!(pixel_data.data, vec![0, 0, 0, 0, 9, 9, 0, 9, 9]);
+    }
+
+    #[test]
+    fn test_mask_rectangles_in_every_frame() {
+        let mut descriptor = descriptor(2, 2, 16, 1);
+        descriptor.number_of_frames = 2;
+        descriptor.photometric_interpretation = "MONOCHROME1".to_string();
+        descriptor.bits_stored = 12;
+        descriptor.high_bit = 11;
+        let mut pixel_data = PixelData {
+            descriptor,
+            data: vec![1; 16],
+        };
+
+        let redactor = Redactor::new(RedactionOptions {
+            rectangles: vec![Rect { x: 0, y: 0, width: 2, height: 1 }],
+            mask_outside_regions: false,
+            trust_burned_in_flag: false,
+        });
+        redactor.mask(&mut pixel_data, &[]).unwrap();
+
+        // MONOCHROME1: negro es el valor máximo (0x0FFF)
+        let frame = [0xFF, 0x0F, 0xFF, 0x0F, 1, 1, 1, 1];
+        assert_eq!(pixel_data.data, [frame, frame].concat());
+    }
+
+    #[test]
+    fn test_mask_ybr_planar() {
+        let mut descriptor = descriptor(1, 2, 8, 3);
+        descriptor.photometric_interpretation = "YBR_FULL".to_string();
+        descriptor.planar_configuration = 1;
+        let mut pixel_data = PixelData {
+            descriptor,
+            data: vec![200, 201, 50, 51, 60, 61],
+        };
+
+        Redactor::default().mask(&mut pixel_data, &[region(1, 0, 1, 0)]).unwrap();
+        assert_eq!(pixel_data.data, vec![0, 201, 128, 51, 128, 61]);
+    }
+
+    #[test]
+    fn test_mask_ybr_422_pairs() {
+        let mut descriptor = descriptor(1, 4, 8, 3);
+        descriptor.photometric_interpretation = "YBR_FULL_422".to_string();
+        let mut pixel_data = PixelData {
+            descriptor,
+            // Y1 Y2 Cb Cr de los pares (0, 1) y (2, 3)
+            data: vec![200, 201, 50, 60, 202, 203, 70, 80],
+        };
+
+        // Solo el píxel 1 queda fuera de la región: se tapa todo su par
+        Redactor::default().mask(&mut pixel_data, &[region(2, 0, 3, 0)]).unwrap();
+        assert_eq!(pixel_data.data, vec![0, 0, 128, 128, 202, 203, 70, 80]);
+    }
+
+    #[test]
+    fn test_mask_palette_color() {
+        use crate::pixel::{Lut, PaletteColorLut};
+
+        let lut = |data: Vec<u16>| Lut {
+            first_mapped: 10,
+            bits_per_entry: 16,
+            data,
+        };
+        let mut descriptor = descriptor(1, 2, 8, 1);
+        descriptor.photometric_interpretation = "PALETTE COLOR".to_string();
+        descriptor.presentation.palette = Some(PaletteColorLut {
+            red: lut(vec![65535, 10, 20]),
+            green: lut(vec![65535, 0, 30]),
+            blue: lut(vec![65535, 0, 0]),
+        });
+        let mut pixel_data = PixelData {
+            descriptor,
+            data: vec![10, 10],
+        };
+
+        // Ninguna entrada es (0, 0, 0); la más oscura es el índice 11
+        Redactor::default().mask(&mut pixel_data, &[region(1, 0, 1, 0)]).unwrap();
+        assert_eq!(pixel_data.data, vec![11, 10]);
+
+        pixel_data.descriptor.presentation.palette = None;
+        assert!(Redactor::default().mask(&mut pixel_data, &[region(1, 0, 1, 0)]).is_err());
+    }
+
+    #[test]
+    fn test_missing_regions() {
+        let mut pixel_data = PixelData::new(descriptor(2, 2, 8, 1));
+        assert!(Redactor::default().mask(&mut pixel_data, &[]).is_err());
+    }
+
+    #[test]
+    fn test_burned_in_annotation() {
+        let mut dataset = InMemDicomObject::new_empty();
+        assert_eq!(burned_in_annotation(&dataset), None);
+        dataset.put(DataElement::new(tags::BURNED_IN_ANNOTATION, VR::CS, PrimitiveValue::from("YES")));
+        assert_eq!(burned_in_annotation(&dataset), Some(true));
+    }
+}
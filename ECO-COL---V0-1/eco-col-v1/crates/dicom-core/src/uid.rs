@@ -0,0 +1,200 @@
+//! UIDs (PS3.5 §9)
+//!
+//! Validación del formato y generación de UIDs nuevos para estudios,
+//! reportes y copias anonimizadas. Con una raíz de organización los UIDs
+//! quedan bajo ella; sin raíz se usa "2.25" seguido de un UUID en decimal
+//! (PS3.5 §B.2).
+
+use crate::error::{DicomError, Result};
+
+use sha2::{Digest, Sha256};
+
+/// Longitud máxima de un UID
+pub const MAX_UID_LENGTH: usize = 64;
+
+/// Raíz de los UIDs derivados de un UUID
+pub const UUID_ROOT: &str = "2.25";
+
+/// Dígitos mínimos del sufijo bajo una raíz propia (~66 bits)
+const MIN_SUFFIX_DIGITS: usize = 20;
+
+// ============================================
+// Validación
+// ============================================
+
+/// Validar un UID según PS3.5 §9.1
+///
+/// Solo dígitos y puntos, hasta 64 caracteres, sin componentes vacíos y sin
+/// ceros a la izquierda (salvo el componente "0"). Se admite el `\0` de
+/// relleno a longitud par.
+pub fn validate_uid(uid: &str) -> Result<()> {
+    match problem(uid) {
+        Some(message) => Err(DicomError::validation(message)),
+        None => Ok(()),
+    }
+}
+
+/// Verificar si el UID es válido (ver [`validate_uid`])
+pub fn is_valid_uid(uid: &str) -> bool {
+    problem(uid).is_none()
+}
+
+/// Descripción del problema del UID (None si es válido)
+pub(crate) fn problem(uid: &str) -> Option<String> {
+    let uid = uid.strip_suffix('\0').unwrap_or(uid);
+
+    if uid.is_empty() {
+        return Some("UID vacío".to_string());
+    }
+    if uid.len() > MAX_UID_LENGTH {
+        return Some(format!(
+            "UID de {} caracteres (máximo {}): {}",
+            uid.len(),
+            MAX_UID_LENGTH,
+            uid
+        ));
+    }
+
+    for component in uid.split('.') {
+        if component.is_empty() {
+            return Some(format!("UID con un componente vacío: {}", uid));
+        }
+        if !component.bytes().all(|b| b.is_ascii_digit()) {
+            return Some(format!("UID con caracteres inválidos: {}", uid));
+        }
+        if component.len() > 1 && component.starts_with('0') {
+            return Some(format!("UID con un componente con cero a la izquierda: {}", uid));
+        }
+    }
+
+    None
+}
+
+// ============================================
+// Generación
+// ============================================
+
+/// Generador de UIDs
+///
+/// `generate` produce UIDs aleatorios; `derive` produce siempre el mismo UID
+/// para la misma clave (remapeo de UIDs en anonimización).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UidGenerator {
+    /// Raíz de la organización (None = "2.25")
+    root: Option<String>,
+}
+
+impl UidGenerator {
+    /// Crear generador bajo la raíz de la organización
+    ///
+    /// La raíz debe ser un UID válido y dejar lugar para al menos 20 dígitos.
+    pub fn new(root: &str) -> Result<Self> {
+        let root = root.trim_end_matches(['\0', ' ', '.']);
+        validate_uid(root)?;
+
+        if root.len() + 1 + MIN_SUFFIX_DIGITS > MAX_UID_LENGTH {
+            return Err(DicomError::validation(format!(
+                "Raíz de UID demasiado larga ({} caracteres, máximo {}): {}",
+                root.len(),
+                MAX_UID_LENGTH - 1 - MIN_SUFFIX_DIGITS,
+                root
+            )));
+        }
+
+        Ok(Self {
+            root: Some(root.to_string()),
+        })
+    }
+
+    /// Generador bajo "2.25" (sin raíz propia)
+    pub fn uuid() -> Self {
+        Self::default()
+    }
+
+    /// Raíz usada para los UIDs generados
+    pub fn root(&self) -> &str {
+        self.root.as_deref().unwrap_or(UUID_ROOT)
+    }
+
+    /// UID nuevo y único
+    pub fn generate(&self) -> String {
+        self.with_suffix(uuid::Uuid::new_v4().as_u128())
+    }
+
+    /// UID determinístico para `key` (SHA-256)
+    ///
+    /// Bajo "2.25" el sufijo es un UUID versión 8 (RFC 9562) derivado del hash.
+    pub fn derive(&self, key: &[u8]) -> String {
+        let digest = Sha256::digest(key);
+
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&digest[..16]);
+        // UUID versión 8, variante RFC 4122
+        bytes[6] = (bytes[6] & 0x0F) | 0x80;
+        bytes[8] = (bytes[8] & 0x3F) | 0x80;
+
+        self.with_suffix(u128::from_be_bytes(bytes))
+    }
+
+    /// Agregar el sufijo a la raíz, recortado a los dígitos disponibles
+    fn with_suffix(&self, value: u128) -> String {
+        let root = self.root();
+        let digits = MAX_UID_LENGTH - root.len() - 1;
+
+        // u128::MAX tiene 39 dígitos
+        let suffix = match u32::try_from(digits).ok().and_then(|d| 10u128.checked_pow(d)) {
+            Some(limit) => value % limit,
+            None => value,
+        };
+        format!("{}.{}", root, suffix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_uid() {
+        assert!(is_valid_uid("1.2.840.10008.5.1.4.1.1.6.1"));
+        assert!(is_valid_uid("1.2.0.3\0"));
+        assert!(is_valid_uid(&format!("1.{}", "2".repeat(62))));
+
+        assert!(!is_valid_uid(&format!("1.{}", "2".repeat(63))));
+        assert!(!is_valid_uid("1..2"));
+        assert!(!is_valid_uid("1.2."));
+        assert!(!is_valid_uid("1.02.3"));
+        assert!(!is_valid_uid("1.2.3 "));
+        assert!(matches!(validate_uid(""), Err(DicomError::ValidationError(_))));
+    }
+
+    #[test]
+    fn test_generate_under_root() {
+        let generator = UidGenerator::new("1.2.826.0.1.3680043.10.1234").unwrap();
+        let first = generator.generate();
+        assert!(first.starts_with("1.2.826.0.1.3680043.10.1234."));
+        assert!(first.len() <= MAX_UID_LENGTH);
+        assert!(is_valid_uid(&first));
+        assert_ne!(first, generator.generate());
+
+        assert!(UidGenerator::new("1.02").is_err());
+        assert!(UidGenerator::new(&format!("1.{}", "2".repeat(45))).is_err());
+    }
+
+    #[test]
+    fn test_uuid_fallback_and_derive() {
+        let generator = UidGenerator::uuid();
+        assert_eq!(generator.root(), "2.25");
+        assert!(is_valid_uid(&generator.generate()));
+
+        let uid = generator.derive(b"1.2.3");
+        assert_eq!(uid, generator.derive(b"1.2.3"));
+        assert_ne!(uid, generator.derive(b"1.2.4"));
+        assert!(uid.starts_with("2.25.") && is_valid_uid(&uid));
+
+        let rooted = UidGenerator::new("1.2.3.").unwrap();
+        assert_eq!(rooted.root(), "1.2.3");
+        assert_eq!(rooted.derive(b"x"), rooted.derive(b"x"));
+        assert!(is_valid_uid(&rooted.derive(b"x")));
+    }
+}
@@ -0,0 +1,343 @@
+//! Regiones de ultrasonido (Sequence of Ultrasound Regions, 0018,6011)
+//!
+//! Cada región declara qué parte de la imagen contiene datos de ultrasonido
+//! (modo B, M, Doppler, ...). Todo lo que queda fuera suele ser texto o
+//! gráficos del equipo.
+
+use dicom::dictionary_std::tags;
+use dicom::object::InMemDicomObject;
+use serde::{Deserialize, Serialize};
+
+/// Unidades físicas de un eje (Physical Units X/Y Direction, 0018,6024 y 0018,6026)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PhysicalUnits {
+    #[default]
+    None,
+    Percent,
+    Decibel,
+    Centimeter,
+    Seconds,
+    Hertz,
+    DecibelPerSecond,
+    CentimeterPerSecond,
+    SquareCentimeter,
+    SquareCentimeterPerSecond,
+    CubicCentimeter,
+    CubicCentimeterPerSecond,
+    Degrees,
+    /// Código no definido por el estándar
+    Unknown(u16),
+}
+
+impl PhysicalUnits {
+    /// Convertir desde el código DICOM
+    pub fn from_code(code: u16) -> Self {
+        match code {
+            0x0000 => Self::None,
+            0x0001 => Self::Percent,
+            0x0002 => Self::Decibel,
+            0x0003 => Self::Centimeter,
+            0x0004 => Self::Seconds,
+            0x0005 => Self::Hertz,
+            0x0006 => Self::DecibelPerSecond,
+            0x0007 => Self::CentimeterPerSecond,
+            0x0008 => Self::SquareCentimeter,
+            0x0009 => Self::SquareCentimeterPerSecond,
+            0x000A => Self::CubicCentimeter,
+            0x000B => Self::CubicCentimeterPerSecond,
+            0x000C => Self::Degrees,
+            other => Self::Unknown(other),
+        }
+    }
+
+    /// Símbolo para mostrar en pantalla
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::None | Self::Unknown(_) => "",
+            Self::Percent => "%",
+            Self::Decibel => "dB",
+            Self::Centimeter => "cm",
+            Self::Seconds => "s",
+            Self::Hertz => "Hz",
+            Self::DecibelPerSecond => "dB/s",
+            Self::CentimeterPerSecond => "cm/s",
+            Self::SquareCentimeter => "cm²",
+            Self::SquareCentimeterPerSecond => "cm²/s",
+            Self::CubicCentimeter => "cm³",
+            Self::CubicCentimeterPerSecond => "cm³/s",
+            Self::Degrees => "°",
+        }
+    }
+}
+
+/// Región de ultrasonido declarada por el equipo
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct UltrasoundRegion {
+    /// Region Spatial Format (0018,6012): 1 = 2D, 2 = M-mode, 3 = espectral, ...
+    pub spatial_format: u16,
+
+    /// Region Data Type (0018,6014): 1 = tejido, 2 = color flow, 3 = PW, ...
+    pub data_type: u16,
+
+    /// Region Flags (0018,6016)
+    pub flags: u32,
+
+    /// Esquina superior izquierda, inclusiva (0018,6018 y 0018,601A)
+    pub min_x0: u32,
+    pub min_y0: u32,
+
+    /// Esquina inferior derecha, inclusiva (0018,601C y 0018,601E)
+    pub max_x1: u32,
+    pub max_y1: u32,
+
+    /// Unidades físicas de cada eje (0018,6024 y 0018,6026)
+    pub physical_units_x: PhysicalUnits,
+    pub physical_units_y: PhysicalUnits,
+
+    /// Tamaño físico de un píxel en cada eje (0018,602C y 0018,602E)
+    pub physical_delta_x: f64,
+    pub physical_delta_y: f64,
+
+    /// Píxel de referencia relativo a la esquina de la región (0018,6020 y 0018,6022)
+    ///
+    /// En Doppler espectral, Y0 marca la línea base (velocidad cero).
+    pub reference_pixel_x0: Option<i32>,
+    pub reference_pixel_y0: Option<i32>,
+
+    /// Valor físico en el píxel de referencia (0018,6028 y 0018,602A)
+    pub reference_value_x: f64,
+    pub reference_value_y: f64,
+}
+
+impl UltrasoundRegion {
+    /// Verificar si el píxel (x, y) está dentro de la región
+    pub fn contains(&self, x: u32, y: u32) -> bool {
+        (self.min_x0..=self.max_x1).contains(&x) && (self.min_y0..=self.max_y1).contains(&y)
+    }
+
+    /// Ancho en píxeles
+    pub fn width(&self) -> u32 {
+        self.max_x1.saturating_sub(self.min_x0) + 1
+    }
+
+    /// Alto en píxeles
+    pub fn height(&self) -> u32 {
+        self.max_y1.saturating_sub(self.min_y0) + 1
+    }
+
+    /// Píxel de referencia en coordenadas de la imagen
+    pub fn reference_pixel(&self) -> (i64, i64) {
+        (
+            self.min_x0 as i64 + self.reference_pixel_x0.unwrap_or(0) as i64,
+            self.min_y0 as i64 + self.reference_pixel_y0.unwrap_or(0) as i64,
+        )
+    }
+
+    /// Verificar si la región permite medir distancias (ambos ejes en cm)
+    pub fn is_spatially_calibrated(&self) -> bool {
+        self.physical_units_x == PhysicalUnits::Centimeter
+            && self.physical_units_y == PhysicalUnits::Centimeter
+            && self.physical_delta_x != 0.0
+            && self.physical_delta_y != 0.0
+    }
+
+    // ============================================
+    // Calibración
+    // ============================================
+
+    /// Convertir un punto de la imagen a valores físicos (unidades de cada eje)
+    ///
+    /// None si el punto está fuera de la región o un eje no tiene calibración.
+    pub fn to_physical(&self, x: f64, y: f64) -> Option<(f64, f64)> {
+        if !self.covers(x, y)
+            || self.physical_units_x == PhysicalUnits::None
+            || self.physical_units_y == PhysicalUnits::None
+        {
+            return None;
+        }
+
+        let (ref_x, ref_y) = self.reference_pixel();
+        Some((
+            (x - ref_x as f64) * self.physical_delta_x + self.reference_value_x,
+            (y - ref_y as f64) * self.physical_delta_y + self.reference_value_y,
+        ))
+    }
+
+    /// Distancia en mm entre dos puntos de la región
+    pub fn length_mm(&self, from: (f64, f64), to: (f64, f64)) -> Option<f64> {
+        if !self.is_spatially_calibrated() || !self.covers(from.0, from.1) || !self.covers(to.0, to.1) {
+            return None;
+        }
+
+        let dx = (to.0 - from.0) * self.physical_delta_x;
+        let dy = (to.1 - from.1) * self.physical_delta_y;
+        Some(dx.hypot(dy) * 10.0)
+    }
+
+    /// Área en mm² de un polígono cerrado con vértices dentro de la región
+    pub fn area_mm2(&self, polygon: &[(f64, f64)]) -> Option<f64> {
+        if !self.is_spatially_calibrated() || polygon.len() < 3 {
+            return None;
+        }
+        if !polygon.iter().all(|&(x, y)| self.covers(x, y)) {
+            return None;
+        }
+
+        // Fórmula del área de Gauss (shoelace) en píxeles²
+        let twice_area: f64 = polygon
+            .iter()
+            .zip(polygon.iter().cycle().skip(1))
+            .map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+            .sum();
+        let pixel_area = (self.physical_delta_x * self.physical_delta_y).abs();
+        Some(twice_area.abs() / 2.0 * pixel_area * 100.0)
+    }
+
+    /// Velocidad en cm/s de una fila de un espectro Doppler
+    pub fn velocity_cm_s(&self, y: f64) -> Option<f64> {
+        if self.physical_units_y != PhysicalUnits::CentimeterPerSecond
+            || y < self.min_y0 as f64
+            || y > self.max_y1 as f64
+        {
+            return None;
+        }
+
+        let (_, ref_y) = self.reference_pixel();
+        Some((y - ref_y as f64) * self.physical_delta_y + self.reference_value_y)
+    }
+
+    /// Verificar si un punto con coordenadas fraccionarias cae en la región
+    fn covers(&self, x: f64, y: f64) -> bool {
+        x >= self.min_x0 as f64 && x <= self.max_x1 as f64 && y >= self.min_y0 as f64 && y <= self.max_y1 as f64
+    }
+}
+
+/// Leer las regiones de ultrasonido del data set (vacío si no hay)
+///
+/// Los items sin las cuatro coordenadas de la región se ignoran.
+pub fn ultrasound_regions(dataset: &InMemDicomObject) -> Vec<UltrasoundRegion> {
+    let items = match dataset.element(tags::SEQUENCE_OF_ULTRASOUND_REGIONS) {
+        Ok(element) => element.items().unwrap_or_default(),
+        Err(_) => return Vec::new(),
+    };
+
+    items
+        .iter()
+        .filter_map(|item| {
+            let int = |tag| item.element(tag).ok()?.to_int::<u32>().ok();
+            let signed = |tag| item.element(tag).ok()?.to_int::<i32>().ok();
+            let float = |tag| item.element(tag).ok()?.to_float64().ok();
+
+            Some(UltrasoundRegion {
+                spatial_format: int(tags::REGION_SPATIAL_FORMAT).unwrap_or(0) as u16,
+                data_type: int(tags::REGION_DATA_TYPE).unwrap_or(0) as u16,
+                flags: int(tags::REGION_FLAGS).unwrap_or(0),
+                min_x0: int(tags::REGION_LOCATION_MIN_X0)?,
+                min_y0: int(tags::REGION_LOCATION_MIN_Y0)?,
+                max_x1: int(tags::REGION_LOCATION_MAX_X1)?,
+                max_y1: int(tags::REGION_LOCATION_MAX_Y1)?,
+                physical_units_x: PhysicalUnits::from_code(int(tags::PHYSICAL_UNITS_X_DIRECTION).unwrap_or(0) as u16),
+                physical_units_y: PhysicalUnits::from_code(int(tags::PHYSICAL_UNITS_Y_DIRECTION).unwrap_or(0) as u16),
+                physical_delta_x: float(tags::PHYSICAL_DELTA_X).unwrap_or(0.0),
+                physical_delta_y: float(tags::PHYSICAL_DELTA_Y).unwrap_or(0.0),
+                reference_pixel_x0: signed(tags::REFERENCE_PIXEL_X0),
+                reference_pixel_y0: signed(tags::REFERENCE_PIXEL_Y0),
+                reference_value_x: float(tags::REFERENCE_PIXEL_PHYSICAL_VALUE_X).unwrap_or(0.0),
+                reference_value_y: float(tags::REFERENCE_PIXEL_PHYSICAL_VALUE_Y).unwrap_or(0.0),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use dicom::core::value::DataSetSequence;
+    use dicom::core::{DataElement, PrimitiveValue, VR};
+
+    /// Item de Sequence of Ultrasound Regions con la ubicación indicada
+    pub(crate) fn region_item(x0: u32, y0: u32, x1: u32, y1: u32) -> InMemDicomObject {
+        InMemDicomObject::from_element_iter([
+            DataElement::new(tags::REGION_SPATIAL_FORMAT, VR::US, PrimitiveValue::from(1u16)),
+            DataElement::new(tags::REGION_DATA_TYPE, VR::US, PrimitiveValue::from(1u16)),
+            DataElement::new(tags::REGION_FLAGS, VR::UL, PrimitiveValue::from(0u32)),
+            DataElement::new(tags::REGION_LOCATION_MIN_X0, VR::UL, PrimitiveValue::from(x0)),
+            DataElement::new(tags::REGION_LOCATION_MIN_Y0, VR::UL, PrimitiveValue::from(y0)),
+            DataElement::new(tags::REGION_LOCATION_MAX_X1, VR::UL, PrimitiveValue::from(x1)),
+            DataElement::new(tags::REGION_LOCATION_MAX_Y1, VR::UL, PrimitiveValue::from(y1)),
+        ])
+    }
+
+    pub(crate) fn with_regions(items: Vec<InMemDicomObject>) -> InMemDicomObject {
+        InMemDicomObject::from_element_iter([DataElement::new(
+            tags::SEQUENCE_OF_ULTRASOUND_REGIONS,
+            VR::SQ,
+            DataSetSequence::from(items),
+        )])
+    }
+
+    #[test]
+    fn test_parse_regions() {
+        let mut incomplete = region_item(0, 0, 1, 1);
+        incomplete.remove_element(tags::REGION_LOCATION_MAX_Y1);
+        let dataset = with_regions(vec![region_item(10, 20, 109, 219), incomplete]);
+
+        let regions = ultrasound_regions(&dataset);
+        assert_eq!(regions.len(), 1);
+        assert_eq!((regions[0].width(), regions[0].height()), (100, 200));
+        assert!(regions[0].contains(10, 219));
+        assert!(!regions[0].contains(110, 20));
+    }
+
+    /// Agregar calibración a un item de región
+    fn calibrate(item: &mut InMemDicomObject, units: (u16, u16), delta: (f64, f64), reference: (i32, i32)) {
+        item.put(DataElement::new(tags::PHYSICAL_UNITS_X_DIRECTION, VR::US, PrimitiveValue::from(units.0)));
+        item.put(DataElement::new(tags::PHYSICAL_UNITS_Y_DIRECTION, VR::US, PrimitiveValue::from(units.1)));
+        item.put(DataElement::new(tags::PHYSICAL_DELTA_X, VR::FD, PrimitiveValue::from(delta.0)));
+        item.put(DataElement::new(tags::PHYSICAL_DELTA_Y, VR::FD, PrimitiveValue::from(delta.1)));
+        item.put(DataElement::new(tags::REFERENCE_PIXEL_X0, VR::SL, PrimitiveValue::from(reference.0)));
+        item.put(DataElement::new(tags::REFERENCE_PIXEL_Y0, VR::SL, PrimitiveValue::from(reference.1)));
+    }
+
+    #[test]
+    fn test_spatial_calibration() {
+        let mut item = region_item(100, 50, 499, 449);
+        calibrate(&mut item, (3, 3), (0.01, 0.02), (0, 0));
+        let region = ultrasound_regions(&with_regions(vec![item])).remove(0);
+
+        assert_eq!(region.physical_units_x, PhysicalUnits::Centimeter);
+        assert!(region.is_spatially_calibrated());
+        let (x, y) = region.to_physical(200.0, 150.0).unwrap();
+        assert!((x - 1.0).abs() < 1e-9 && (y - 2.0).abs() < 1e-9);
+
+        // 30 px en X (3 mm) y 20 px en Y (4 mm)
+        let length = region.length_mm((100.0, 50.0), (130.0, 70.0)).unwrap();
+        assert!((length - 5.0).abs() < 1e-9);
+        assert!(region.length_mm((100.0, 50.0), (600.0, 70.0)).is_none());
+
+        // Cuadrado de 100 x 100 px = 10 mm x 20 mm
+        let square = [(100.0, 50.0), (200.0, 50.0), (200.0, 150.0), (100.0, 150.0)];
+        assert!((region.area_mm2(&square).unwrap() - 200.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_doppler_velocity() {
+        let mut item = region_item(0, 300, 639, 479);
+        item.put(DataElement::new(tags::REGION_SPATIAL_FORMAT, VR::US, PrimitiveValue::from(3u16)));
+        // Línea base 100 px bajo el borde superior, 0.5 cm/s por píxel hacia arriba
+        calibrate(&mut item, (4, 7), (0.01, -0.5), (0, 100));
+        let region = ultrasound_regions(&with_regions(vec![item])).remove(0);
+
+        assert_eq!(region.reference_pixel(), (0, 400));
+        assert_eq!(region.velocity_cm_s(400.0), Some(0.0));
+        assert_eq!(region.velocity_cm_s(340.0), Some(30.0));
+        assert_eq!(region.velocity_cm_s(450.0), Some(-25.0));
+        assert!(region.velocity_cm_s(200.0).is_none());
+        assert!(region.length_mm((0.0, 300.0), (10.0, 300.0)).is_none());
+    }
+
+    #[test]
+    fn test_no_regions() {
+        assert!(ultrasound_regions(&InMemDicomObject::new_empty()).is_empty());
+    }
+}
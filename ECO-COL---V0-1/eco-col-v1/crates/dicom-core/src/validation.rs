@@ -2,6 +2,7 @@
 
 use crate::error::{DicomError, Result};
 use crate::metadata::DicomMetadata;
+use crate::uid;
 
 /// Validar que una instancia DICOM tiene todos los campos requeridos
 pub fn validate_instance(metadata: &DicomMetadata) -> Result<()> {
@@ -23,29 +24,12 @@ pub fn validate_instance(metadata: &DicomMetadata) -> Result<()> {
     Ok(())
 }
 
-/// Validar formato de UID DICOM
+/// Validar formato de UID DICOM (PS3.5 §9, ver [`uid::validate_uid`])
 fn validate_uid(uid: &str, name: &str) -> Result<()> {
-    if uid.is_empty() {
-        return Err(DicomError::validation(format!("{} no puede estar vacío", name)));
+    match uid::problem(uid) {
+        Some(problem) => Err(DicomError::validation(format!("{}: {}", name, problem))),
+        None => Ok(()),
     }
-
-    // UID debe contener solo números y puntos
-    if !uid.chars().all(|c| c.is_ascii_digit() || c == '.') {
-        return Err(DicomError::validation(format!(
-            "{} contiene caracteres inválidos: {}",
-            name, uid
-        )));
-    }
-
-    // UID no debe empezar ni terminar con punto
-    if uid.starts_with('.') || uid.ends_with('.') {
-        return Err(DicomError::validation(format!(
-            "{} no debe empezar ni terminar con punto: {}",
-            name, uid
-        )));
-    }
-
-    Ok(())
 }
 
 #[cfg(test)]
@@ -71,4 +55,11 @@ mod tests {
     fn test_invalid_uid_dots() {
         assert!(validate_uid(".1.2.3.", "Test UID").is_err());
     }
+
+    #[test]
+    fn test_invalid_uid_components_and_length() {
+        assert!(validate_uid("1..2", "Test UID").is_err());
+        assert!(validate_uid("1.2.03", "Test UID").is_err());
+        assert!(validate_uid(&"1.2".repeat(22), "Test UID").is_err());
+    }
 }
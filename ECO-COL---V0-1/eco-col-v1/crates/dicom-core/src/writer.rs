@@ -0,0 +1,325 @@
+//! Escritura de archivos DICOM Part 10
+//!
+//! Genera el archivo completo a partir de un `DicomInstance`: preamble de
+//! 128 bytes, "DICM", File Meta group regenerado y el data set codificado
+//! en la transfer syntax elegida (transcodificando si hace falta).
+
+use crate::error::{DicomError, Result};
+use crate::metadata::DicomInstance;
+
+use dicom::core::value::{DataSetSequence, PrimitiveValue, Value};
+use dicom::core::{DataElement, Length, Tag, VR};
+use dicom::dictionary_std::{tags, uids};
+use dicom::encoding::text::SpecificCharacterSet;
+use dicom::encoding::TransferSyntaxIndex;
+use dicom::object::{FileMetaTableBuilder, InMemDicomObject};
+use dicom::transfer_syntax::TransferSyntaxRegistry;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Implementation Class UID de ECO-COL (0002,0012)
+pub const IMPLEMENTATION_CLASS_UID: &str = "2.25.151935882654191792079661466317042542301";
+
+/// Implementation Version Name de ECO-COL (0002,0013)
+pub const IMPLEMENTATION_VERSION_NAME: &str = concat!("ECOCOL_", env!("CARGO_PKG_VERSION"));
+
+/// Transfer syntaxes que el writer sabe codificar
+pub const SUPPORTED_TRANSFER_SYNTAXES: &[&str] = &[
+    uids::IMPLICIT_VR_LITTLE_ENDIAN,
+    uids::EXPLICIT_VR_LITTLE_ENDIAN,
+    uids::DEFLATED_EXPLICIT_VR_LITTLE_ENDIAN,
+];
+
+/// Opciones de escritura
+#[derive(Debug, Clone)]
+pub struct WriteOptions {
+    /// Transfer syntax de salida (ver [`SUPPORTED_TRANSFER_SYNTAXES`])
+    pub transfer_syntax_uid: String,
+
+    /// Source Application Entity Title (0002,0016)
+    pub source_ae_title: Option<String>,
+
+    /// Nivel de compresión para Deflated Explicit VR Little Endian (0-9)
+    pub deflate_level: u32,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self {
+            transfer_syntax_uid: uids::EXPLICIT_VR_LITTLE_ENDIAN.to_string(),
+            source_ae_title: None,
+            deflate_level: 6,
+        }
+    }
+}
+
+/// Writer DICOM principal
+pub struct DicomWriter {
+    options: WriteOptions,
+}
+
+impl DicomWriter {
+    /// Crear writer con opciones por defecto (Explicit VR Little Endian)
+    pub fn new() -> Self {
+        Self {
+            options: WriteOptions::default(),
+        }
+    }
+
+    /// Crear writer con opciones custom
+    pub fn with_options(options: WriteOptions) -> Self {
+        Self { options }
+    }
+
+    /// Crear writer para una transfer syntax
+    pub fn with_transfer_syntax(transfer_syntax_uid: &str) -> Self {
+        Self::with_options(WriteOptions {
+            transfer_syntax_uid: transfer_syntax_uid.to_string(),
+            ..WriteOptions::default()
+        })
+    }
+
+    /// Escribir la instancia como archivo Part 10 en `path`
+    pub fn write_file(&self, instance: &DicomInstance, path: &Path) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(instance, &mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Escribir la instancia como archivo Part 10 en `to`
+    ///
+    /// Si los píxeles no están en memoria se leen del archivo original; el
+    /// pixel data encapsulado se decodifica, porque la salida siempre es nativa.
+    pub fn write<W: Write>(&self, instance: &DicomInstance, mut to: W) -> Result<()> {
+        let ts_uid = self.options.transfer_syntax_uid.as_str();
+        if !SUPPORTED_TRANSFER_SYNTAXES.contains(&ts_uid) {
+            return Err(DicomError::UnsupportedTransferSyntax(ts_uid.to_string()));
+        }
+
+        let dataset = self.build_dataset(instance)?;
+        let meta = self.build_meta(&dataset)?;
+
+        // Preamble + "DICM" + File Meta (siempre Explicit VR Little Endian)
+        to.write_all(&[0u8; 128])?;
+        to.write_all(b"DICM")?;
+        meta.write(&mut to)
+            .map_err(|e| DicomError::internal(format!("Error writing File Meta: {}", e)))?;
+
+        let charset = character_set(&dataset).unwrap_or_default();
+        if ts_uid == uids::DEFLATED_EXPLICIT_VR_LITTLE_ENDIAN {
+            // Deflate "raw" (RFC 1951, sin header zlib) del data set Explicit VR LE
+            let mut encoder = DeflateEncoder::new(to, Compression::new(self.options.deflate_level.min(9)));
+            write_dataset(&dataset, &mut encoder, uids::EXPLICIT_VR_LITTLE_ENDIAN, charset)?;
+            encoder.finish()?;
+        } else {
+            write_dataset(&dataset, &mut to, ts_uid, charset)?;
+        }
+
+        Ok(())
+    }
+
+    /// Data set de salida: el original con Pixel Data nativo y Group Lengths recalculados
+    fn build_dataset(&self, instance: &DicomInstance) -> Result<InMemDicomObject> {
+        let mut dataset = with_undefined_lengths(&instance.dataset);
+
+        // Los textos ya están decodificados: si el charset declarado no se
+        // puede codificar (p.ej. ISO 2022 multivaluado) se escribe en UTF-8
+        if character_set(&dataset).is_none() {
+            dataset.put(DataElement::new(
+                tags::SPECIFIC_CHARACTER_SET,
+                VR::CS,
+                PrimitiveValue::from("ISO_IR 192"),
+            ));
+        }
+
+        if instance.pixel_descriptor.is_some() {
+            let pixel_data = instance.load_pixels()?;
+            let descriptor = &pixel_data.descriptor;
+
+            // El codec puede haber cambiado el espacio de color (p.ej. YBR_FULL_422 -> RGB)
+            dataset.put(DataElement::new(
+                tags::PHOTOMETRIC_INTERPRETATION,
+                VR::CS,
+                PrimitiveValue::from(descriptor.photometric_interpretation.as_str()),
+            ));
+            if descriptor.samples_per_pixel > 1 {
+                dataset.put(DataElement::new(
+                    tags::PLANAR_CONFIGURATION,
+                    VR::US,
+                    PrimitiveValue::from(descriptor.planar_configuration),
+                ));
+            }
+
+            // Los valores siempre tienen longitud par
+            let mut data = pixel_data.data;
+            if data.len() % 2 == 1 {
+                data.push(0);
+            }
+            let vr = if descriptor.bits_allocated > 8 { VR::OW } else { VR::OB };
+            dataset.put(DataElement::new(tags::PIXEL_DATA, vr, PrimitiveValue::from(data)));
+        }
+
+        self.update_group_lengths(&mut dataset)?;
+        Ok(dataset)
+    }
+
+    /// Recalcular los Group Length (gggg,0000) presentes en el data set
+    ///
+    /// Son opcionales fuera del File Meta y no se agregan si no estaban, pero
+    /// los que vienen del original quedan desactualizados al transcodificar.
+    fn update_group_lengths(&self, dataset: &mut InMemDicomObject) -> Result<()> {
+        let groups: Vec<u16> = dataset
+            .iter()
+            .map(|e| e.header().tag)
+            .filter(|tag| tag.element() == 0x0000)
+            .map(|tag| tag.group())
+            .collect();
+        if groups.is_empty() {
+            return Ok(());
+        }
+
+        // En Deflated el data set se codifica como Explicit VR LE antes de comprimir
+        let ts_uid = match self.options.transfer_syntax_uid.as_str() {
+            uids::DEFLATED_EXPLICIT_VR_LITTLE_ENDIAN => uids::EXPLICIT_VR_LITTLE_ENDIAN,
+            uid => uid,
+        };
+
+        // Los subconjuntos por grupo se codifican con el charset del data set completo
+        let charset = character_set(dataset).unwrap_or_default();
+        let mut by_group: BTreeMap<u16, InMemDicomObject> = BTreeMap::new();
+        for element in dataset.iter() {
+            let tag = element.header().tag;
+            if groups.contains(&tag.group()) && tag.element() != 0x0000 {
+                by_group
+                    .entry(tag.group())
+                    .or_insert_with(InMemDicomObject::new_empty)
+                    .put(element.clone());
+            }
+        }
+
+        for group in groups {
+            let mut encoded = Vec::new();
+            if let Some(elements) = by_group.get(&group) {
+                write_dataset(elements, &mut encoded, ts_uid, charset)?;
+            }
+            dataset.put(DataElement::new(
+                Tag(group, 0x0000),
+                VR::UL,
+                PrimitiveValue::from(encoded.len() as u32),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// File Meta group regenerado para el data set de salida
+    fn build_meta(&self, dataset: &InMemDicomObject) -> Result<dicom::object::FileMetaTable> {
+        let uid = |tag| {
+            dataset
+                .element(tag)
+                .ok()
+                .and_then(|e| e.to_str().ok())
+                .map(|s| s.trim_end_matches(['\0', ' ']).to_string())
+                .ok_or_else(|| DicomError::MissingRequiredTag(format!("{:?}", tag)))
+        };
+
+        let mut builder = FileMetaTableBuilder::new()
+            .media_storage_sop_class_uid(uid(tags::SOP_CLASS_UID)?)
+            .media_storage_sop_instance_uid(uid(tags::SOP_INSTANCE_UID)?)
+            .transfer_syntax(self.options.transfer_syntax_uid.as_str())
+            .implementation_class_uid(IMPLEMENTATION_CLASS_UID)
+            .implementation_version_name(IMPLEMENTATION_VERSION_NAME);
+        if let Some(ae_title) = &self.options.source_ae_title {
+            builder = builder.source_application_entity_title(ae_title.as_str());
+        }
+
+        builder
+            .build()
+            .map_err(|e| DicomError::internal(format!("Error building File Meta: {}", e)))
+    }
+}
+
+impl Default for DicomWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Copia del data set con secuencias e items de longitud indefinida
+///
+/// Las longitudes leídas del archivo original dejan de ser válidas al cambiar
+/// de transfer syntax o al modificar items; con longitud indefinida el
+/// encoder escribe delimitadores y no hace falta recalcularlas.
+fn with_undefined_lengths(dataset: &InMemDicomObject) -> InMemDicomObject {
+    InMemDicomObject::from_element_iter(dataset.iter().map(|element| match element.value() {
+        Value::Sequence(sequence) => DataElement::new(
+            element.header().tag,
+            VR::SQ,
+            DataSetSequence::new(
+                sequence.items().iter().map(with_undefined_lengths).collect::<Vec<_>>(),
+                Length::UNDEFINED,
+            ),
+        ),
+        _ => element.clone(),
+    }))
+}
+
+/// Specific Character Set (0008,0005) del data set
+///
+/// None si dicom-rs no sabe codificarlo (ISO 2022 multivaluado, ISO_IR 126, ...).
+fn character_set(dataset: &InMemDicomObject) -> Option<SpecificCharacterSet> {
+    let terms = match dataset.element(tags::SPECIFIC_CHARACTER_SET) {
+        Ok(element) => element.to_multi_str().map(|terms| terms.to_vec()).unwrap_or_default(),
+        Err(_) => return Some(SpecificCharacterSet::default()),
+    };
+
+    match terms.as_slice() {
+        [] => Some(SpecificCharacterSet::default()),
+        [term] if matches!(term.trim(), "" | "ISO_IR 6") => Some(SpecificCharacterSet::default()),
+        [term] => SpecificCharacterSet::from_code(term.trim()),
+        _ => None,
+    }
+}
+
+/// Codificar el data set (sin File Meta) con la transfer syntax dada
+pub(crate) fn write_dataset<W: Write>(
+    dataset: &InMemDicomObject,
+    to: W,
+    ts_uid: &str,
+    charset: SpecificCharacterSet,
+) -> Result<()> {
+    let ts = TransferSyntaxRegistry
+        .get(ts_uid)
+        .ok_or_else(|| DicomError::UnsupportedTransferSyntax(ts_uid.to_string()))?;
+
+    dataset
+        .write_dataset_with_ts_cs(to, ts, charset)
+        .map_err(|e| DicomError::internal(format!("Error writing data set: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_length_is_recomputed() {
+        let mut dataset = InMemDicomObject::new_empty();
+        dataset.put(DataElement::new(Tag(0x0009, 0x0000), VR::UL, PrimitiveValue::from(999u32)));
+        dataset.put(DataElement::new(Tag(0x0009, 0x1010), VR::OB, PrimitiveValue::from(vec![1u8, 2, 3, 4])));
+
+        // Implicit VR: tag (4) + longitud (4) + valor (4)
+        DicomWriter::with_transfer_syntax(uids::IMPLICIT_VR_LITTLE_ENDIAN)
+            .update_group_lengths(&mut dataset)
+            .unwrap();
+        assert_eq!(dataset.element(Tag(0x0009, 0x0000)).unwrap().to_int::<u32>().unwrap(), 12);
+
+        // Explicit VR con OB: tag (4) + VR (2) + reservado (2) + longitud (4) + valor (4)
+        DicomWriter::new().update_group_lengths(&mut dataset).unwrap();
+        assert_eq!(dataset.element(Tag(0x0009, 0x0000)).unwrap().to_int::<u32>().unwrap(), 16);
+    }
+}
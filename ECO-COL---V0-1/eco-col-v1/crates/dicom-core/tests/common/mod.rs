@@ -0,0 +1,133 @@
+//! Utilidades compartidas para los tests de integración
+//!
+//! Genera archivos DICOM sintéticos con dicom-rs para no depender de
+//! fixtures binarios en el repositorio.
+
+#![allow(dead_code)]
+
+use dicom::core::value::{PixelFragmentSequence, Value};
+use dicom::core::{DataElement, PrimitiveValue, VR};
+use dicom::dictionary_std::{tags, uids};
+use dicom::object::{FileMetaTableBuilder, InMemDicomObject};
+use std::path::{Path, PathBuf};
+
+pub const STUDY_UID: &str = "1.2.826.0.1.3680043.8.498.1";
+pub const SERIES_UID: &str = "1.2.826.0.1.3680043.8.498.1.1";
+pub const INSTANCE_UID: &str = "1.2.826.0.1.3680043.8.498.1.1.1";
+
+/// Objeto DICOM mínimo de ultrasonido monocromático de 8 bits
+pub fn sample_object(rows: u16, columns: u16, pixels: Vec<u8>) -> InMemDicomObject {
+    let mut obj = InMemDicomObject::new_empty();
+    let mut put_str = |tag, vr, value: &str| {
+        obj.put(DataElement::new(tag, vr, PrimitiveValue::from(value)));
+    };
+    put_str(tags::SOP_CLASS_UID, VR::UI, uids::ULTRASOUND_IMAGE_STORAGE);
+    put_str(tags::SOP_INSTANCE_UID, VR::UI, INSTANCE_UID);
+    put_str(tags::STUDY_DATE, VR::DA, "20260115");
+    put_str(tags::MODALITY, VR::CS, "US");
+    put_str(tags::PATIENT_NAME, VR::PN, "PEREZ^JUAN");
+    put_str(tags::PATIENT_ID, VR::LO, "CC123456");
+    put_str(tags::PATIENT_SEX, VR::CS, "M");
+    put_str(tags::STUDY_INSTANCE_UID, VR::UI, STUDY_UID);
+    put_str(tags::SERIES_INSTANCE_UID, VR::UI, SERIES_UID);
+    put_str(tags::PHOTOMETRIC_INTERPRETATION, VR::CS, "MONOCHROME2");
+
+    let mut put_us = |tag, value: u16| {
+        obj.put(DataElement::new(tag, VR::US, PrimitiveValue::from(value)));
+    };
+    put_us(tags::SAMPLES_PER_PIXEL, 1);
+    put_us(tags::ROWS, rows);
+    put_us(tags::COLUMNS, columns);
+    put_us(tags::BITS_ALLOCATED, 8);
+    put_us(tags::BITS_STORED, 8);
+    put_us(tags::HIGH_BIT, 7);
+    put_us(tags::PIXEL_REPRESENTATION, 0);
+
+    obj.put(DataElement::new(
+        tags::PIXEL_DATA,
+        VR::OB,
+        PrimitiveValue::from(pixels),
+    ));
+    obj
+}
+
+/// Objeto sin píxeles (SR, KO, PDF encapsulado) con las claves de paciente,
+/// estudio y serie de [`sample_object`]
+pub fn sample_document(sop_class: &str, modality: &str) -> InMemDicomObject {
+    let mut obj = sample_object(2, 2, vec![0; 4]);
+    for tag in [
+        tags::SAMPLES_PER_PIXEL,
+        tags::PHOTOMETRIC_INTERPRETATION,
+        tags::ROWS,
+        tags::COLUMNS,
+        tags::BITS_ALLOCATED,
+        tags::BITS_STORED,
+        tags::HIGH_BIT,
+        tags::PIXEL_REPRESENTATION,
+        tags::PIXEL_DATA,
+    ] {
+        obj.remove_element(tag);
+    }
+    obj.put(DataElement::new(tags::SOP_CLASS_UID, VR::UI, PrimitiveValue::from(sop_class)));
+    obj.put(DataElement::new(tags::MODALITY, VR::CS, PrimitiveValue::from(modality)));
+    obj
+}
+
+/// Cine loop de `frames` frames con Frame Time de 40 ms (25 fps)
+pub fn sample_cine_object(rows: u16, columns: u16, frames: u32, pixels: Vec<u8>) -> InMemDicomObject {
+    let mut obj = sample_object(rows, columns, pixels);
+    obj.put(DataElement::new(
+        tags::SOP_CLASS_UID,
+        VR::UI,
+        PrimitiveValue::from(uids::ULTRASOUND_MULTI_FRAME_IMAGE_STORAGE),
+    ));
+    obj.put(DataElement::new(
+        tags::NUMBER_OF_FRAMES,
+        VR::IS,
+        PrimitiveValue::from(frames.to_string()),
+    ));
+    obj.put(DataElement::new(tags::FRAME_TIME, VR::DS, PrimitiveValue::from("40")));
+    obj.put(DataElement::new(
+        tags::FRAME_INCREMENT_POINTER,
+        VR::AT,
+        PrimitiveValue::from(tags::FRAME_TIME),
+    ));
+    obj
+}
+
+/// Región de ultrasonido 2D calibrada en cm (0,05 cm por píxel)
+pub fn region_item(x0: u32, y0: u32, x1: u32, y1: u32) -> InMemDicomObject {
+    InMemDicomObject::from_element_iter([
+        DataElement::new(tags::REGION_SPATIAL_FORMAT, VR::US, PrimitiveValue::from(1u16)),
+        DataElement::new(tags::REGION_DATA_TYPE, VR::US, PrimitiveValue::from(1u16)),
+        DataElement::new(tags::REGION_FLAGS, VR::UL, PrimitiveValue::from(0u32)),
+        DataElement::new(tags::REGION_LOCATION_MIN_X0, VR::UL, PrimitiveValue::from(x0)),
+        DataElement::new(tags::REGION_LOCATION_MIN_Y0, VR::UL, PrimitiveValue::from(y0)),
+        DataElement::new(tags::REGION_LOCATION_MAX_X1, VR::UL, PrimitiveValue::from(x1)),
+        DataElement::new(tags::REGION_LOCATION_MAX_Y1, VR::UL, PrimitiveValue::from(y1)),
+        DataElement::new(tags::PHYSICAL_UNITS_X_DIRECTION, VR::US, PrimitiveValue::from(3u16)),
+        DataElement::new(tags::PHYSICAL_UNITS_Y_DIRECTION, VR::US, PrimitiveValue::from(3u16)),
+        DataElement::new(tags::PHYSICAL_DELTA_X, VR::FD, PrimitiveValue::from(0.05)),
+        DataElement::new(tags::PHYSICAL_DELTA_Y, VR::FD, PrimitiveValue::from(0.05)),
+    ])
+}
+
+/// Reemplaza Pixel Data por una secuencia de fragmentos encapsulados
+pub fn encapsulate(mut obj: InMemDicomObject, fragments: Vec<Vec<u8>>) -> InMemDicomObject {
+    obj.put(DataElement::new(
+        tags::PIXEL_DATA,
+        VR::OB,
+        Value::PixelSequence(PixelFragmentSequence::new(Vec::<u32>::new(), fragments)),
+    ));
+    obj
+}
+
+/// Escribe el objeto como archivo Part 10 con la transfer syntax dada
+pub fn write_file(dir: &Path, name: &str, obj: InMemDicomObject, transfer_syntax: &str) -> PathBuf {
+    let path = dir.join(name);
+    let file = obj
+        .with_meta(FileMetaTableBuilder::new().transfer_syntax(transfer_syntax))
+        .expect("meta group válido");
+    file.write_to_file(&path).expect("escritura del archivo de prueba");
+    path
+}
@@ -1,6 +1,20 @@
 //! Tests de integración para dicom-core
 
-use dicom_core::{DicomParser, ParseOptions};
+mod common;
+
+use dicom::dictionary_std::uids;
+use dicom::core::value::DataSetSequence;
+use dicom::core::{DataElement, PrimitiveValue, VR};
+use dicom::dictionary_std::tags;
+use dicom::object::InMemDicomObject;
+use std::io::Cursor;
+use dicom_core::anonymizer::{AnonymizationProfile, Anonymizer, UidRemapper};
+use dicom_core::checksum;
+use dicom_core::codec::CodecRegistry;
+use dicom_core::redaction::{self, RedactionOptions, Redactor};
+use dicom_core::{
+    Checksums, DicomDir, DicomDirOptions, DicomDirWriter, DicomError, DicomParser, DicomWriter, Iod, RecordType, SkipReason, ParseOptions, ParseWarning, PersonName, PhysicalUnits, PixelDataLocation, RenderOptions, Voi, VoiFunction,
+};
 
 #[test]
 fn test_parser_with_default_options() {
@@ -15,12 +29,1166 @@ fn test_parser_with_custom_options() {
         load_pixel_data: false,
         strict_validation: true,
         max_file_size: 1_000_000_000,
+        recovery: false,
     };
 
     let parser = DicomParser::with_options(options);
     assert!(parser.is_ready());
 }
 
+fn pixel_options() -> ParseOptions {
+    ParseOptions {
+        load_pixel_data: true,
+        ..ParseOptions::default()
+    }
+}
+
+#[test]
+fn test_load_pixel_data_explicit_vr() {
+    let dir = tempfile::tempdir().unwrap();
+    let pixels: Vec<u8> = (0..12).collect();
+    let path = common::write_file(
+        dir.path(),
+        "explicit.dcm",
+        common::sample_object(3, 4, pixels.clone()),
+        uids::EXPLICIT_VR_LITTLE_ENDIAN,
+    );
+
+    let instance = DicomParser::with_options(pixel_options()).parse_file(&path).unwrap();
+    assert_eq!(instance.metadata.transfer_syntax_uid, uids::EXPLICIT_VR_LITTLE_ENDIAN);
+
+    let pixel_data = instance.pixel_data().expect("pixel data cargado");
+    assert_eq!(pixel_data.data, pixels);
+    assert_eq!(pixel_data.get_pixel(0, 1, 2), Some(9));
+}
+
+#[test]
+fn test_load_pixel_data_implicit_vr() {
+    let dir = tempfile::tempdir().unwrap();
+    let pixels: Vec<u8> = (100..106).collect();
+    let path = common::write_file(
+        dir.path(),
+        "implicit.dcm",
+        common::sample_object(2, 3, pixels.clone()),
+        uids::IMPLICIT_VR_LITTLE_ENDIAN,
+    );
+
+    let instance = DicomParser::with_options(pixel_options()).parse_file(&path).unwrap();
+    assert_eq!(instance.metadata.transfer_syntax_uid, uids::IMPLICIT_VR_LITTLE_ENDIAN);
+    assert_eq!(instance.pixel_data().unwrap().data, pixels);
+}
+
+#[test]
+fn test_lazy_loading_skips_pixel_data() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = common::write_file(
+        dir.path(),
+        "lazy.dcm",
+        common::sample_object(2, 2, vec![0; 4]),
+        uids::EXPLICIT_VR_LITTLE_ENDIAN,
+    );
+
+    let instance = DicomParser::new().parse_file(&path).unwrap();
+    assert!(instance.pixel_data().is_none());
+    assert_eq!(instance.pixel_descriptor.unwrap().total_size_bytes(), 4);
+}
+
+#[test]
+fn test_load_cine_loop_frames() {
+    let dir = tempfile::tempdir().unwrap();
+    // 3 frames de 2x2, cada frame con un valor constante
+    let pixels: Vec<u8> = (0..3).flat_map(|frame| vec![frame * 10; 4]).collect();
+    let path = common::write_file(
+        dir.path(),
+        "cine.dcm",
+        common::sample_cine_object(2, 2, 3, pixels),
+        uids::EXPLICIT_VR_LITTLE_ENDIAN,
+    );
+
+    let instance = DicomParser::with_options(pixel_options()).parse_file(&path).unwrap();
+    let pixel_data = instance.pixel_data().unwrap();
+    assert_eq!(pixel_data.number_of_frames(), 3);
+    assert_eq!(pixel_data.descriptor.frame_time, Some(40.0));
+    assert_eq!(pixel_data.descriptor.frame_duration_ms(1), Some(40.0));
+    assert_eq!(pixel_data.get_pixel(2, 1, 1), Some(20));
+
+    let firsts: Vec<u8> = pixel_data.frames().map(|frame| frame[0]).collect();
+    assert_eq!(firsts, vec![0, 10, 20]);
+}
+
+#[test]
+fn test_lazy_frame_loading_from_offsets() {
+    let dir = tempfile::tempdir().unwrap();
+    let pixels: Vec<u8> = (0..3).flat_map(|frame| vec![frame * 10; 4]).collect();
+    let path = common::write_file(
+        dir.path(),
+        "cine.dcm",
+        common::sample_cine_object(2, 2, 3, pixels.clone()),
+        uids::EXPLICIT_VR_LITTLE_ENDIAN,
+    );
+
+    let instance = DicomParser::new().parse_file(&path).unwrap();
+    let descriptor = instance.pixel_descriptor.as_ref().unwrap();
+    match descriptor.location {
+        Some(PixelDataLocation::Native { offset, length }) => {
+            // Pixel data es el último elemento del archivo
+            assert_eq!(length, 12);
+            assert_eq!(offset + length, std::fs::metadata(&path).unwrap().len());
+        }
+        ref other => panic!("ubicación inesperada: {:?}", other),
+    }
+
+    assert_eq!(instance.load_frame(2).unwrap(), vec![20; 4]);
+    assert!(instance.load_frame(3).is_err());
+    assert_eq!(instance.load_pixels().unwrap().data, pixels);
+}
+
+#[test]
+fn test_lazy_parse_does_not_read_pixel_data() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = common::write_file(
+        dir.path(),
+        "cut.dcm",
+        common::sample_object(4, 4, vec![7; 16]),
+        uids::EXPLICIT_VR_LITTLE_ENDIAN,
+    );
+
+    // Cortar el archivo a la mitad del pixel data
+    let len = std::fs::metadata(&path).unwrap().len();
+    std::fs::OpenOptions::new().write(true).open(&path).unwrap().set_len(len - 8).unwrap();
+
+    let instance = DicomParser::new().parse_file(&path).unwrap();
+    assert!(matches!(instance.load_pixels(), Err(DicomError::CorruptedPixelData)));
+}
+
+#[test]
+fn test_encapsulated_fragment_locations() {
+    let dir = tempfile::tempdir().unwrap();
+    let obj = common::encapsulate(
+        common::sample_object(2, 2, vec![]),
+        vec![vec![1, 2, 3, 4], vec![5, 6]],
+    );
+    let path = common::write_file(dir.path(), "jpeg.dcm", obj, uids::JPEG_BASELINE8_BIT);
+
+    let instance = DicomParser::new().parse_file(&path).unwrap();
+    match &instance.pixel_descriptor.as_ref().unwrap().location {
+        Some(PixelDataLocation::Encapsulated { offset_table, fragments }) => {
+            assert!(offset_table.is_empty());
+            assert_eq!(fragments.len(), 2);
+            assert_eq!(fragments[0].length, 4);
+            // Cada fragmento va precedido por 8 bytes de item header
+            assert_eq!(fragments[1].offset, fragments[0].offset + 4 + 8);
+        }
+        other => panic!("ubicación inesperada: {:?}", other),
+    }
+}
+
+/// Frame RLE de un segmento con los bytes como literal PackBits
+fn rle_frame(pixels: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0u8; 64];
+    frame[0] = 1;
+    frame[4] = 64;
+    frame.push(pixels.len() as u8 - 1);
+    frame.extend_from_slice(pixels);
+    // Los fragmentos deben tener longitud par
+    if frame.len() % 2 == 1 {
+        frame.push(0);
+    }
+    frame
+}
+
+#[test]
+fn test_decode_rle_cine_loop() {
+    let dir = tempfile::tempdir().unwrap();
+    let obj = common::encapsulate(
+        common::sample_cine_object(2, 2, 2, vec![]),
+        vec![rle_frame(&[1, 2, 3, 4]), rle_frame(&[5, 6, 7, 8])],
+    );
+    let path = common::write_file(dir.path(), "rle.dcm", obj, uids::RLE_LOSSLESS);
+
+    let instance = DicomParser::with_options(pixel_options()).parse_file(&path).unwrap();
+    let pixel_data = instance.pixel_data().unwrap();
+    assert_eq!(pixel_data.data, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+
+    // Lazy: un solo frame desde los fragmentos
+    let instance = DicomParser::new().parse_file(&path).unwrap();
+    assert_eq!(instance.load_frame(1).unwrap(), vec![5, 6, 7, 8]);
+}
+
+#[test]
+fn test_encapsulated_without_codec_is_unsupported() {
+    let dir = tempfile::tempdir().unwrap();
+    let obj = common::encapsulate(common::sample_object(2, 2, vec![]), vec![rle_frame(&[1, 2, 3, 4])]);
+    let path = common::write_file(dir.path(), "rle.dcm", obj, uids::RLE_LOSSLESS);
+
+    let instance = DicomParser::new().parse_file(&path).unwrap();
+    let result = instance.load_pixels_with(&CodecRegistry::new());
+    assert!(matches!(result, Err(DicomError::UnsupportedTransferSyntax(_))));
+}
+
+#[test]
+fn test_render_with_rescale_window_and_voi_lut() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut obj = common::sample_object(1, 3, vec![0, 100, 200]);
+    let mut put = |tag, vr, value: PrimitiveValue| {
+        obj.put(DataElement::new(tag, vr, value));
+    };
+    put(tags::RESCALE_SLOPE, VR::DS, PrimitiveValue::from("2"));
+    put(tags::RESCALE_INTERCEPT, VR::DS, PrimitiveValue::from("-100"));
+    put(tags::WINDOW_CENTER, VR::DS, PrimitiveValue::from("100\\0"));
+    put(tags::WINDOW_WIDTH, VR::DS, PrimitiveValue::from("200\\100"));
+    put(tags::VOILUT_FUNCTION, VR::CS, PrimitiveValue::from("LINEAR_EXACT"));
+
+    let voi_lut = InMemDicomObject::from_element_iter([
+        DataElement::new(tags::LUT_DESCRIPTOR, VR::US, PrimitiveValue::from([3u16, 0, 8])),
+        DataElement::new(tags::LUT_DATA, VR::OW, PrimitiveValue::from([10u16, 20, 30])),
+    ]);
+    obj.put(DataElement::new(
+        tags::VOILUT_SEQUENCE,
+        VR::SQ,
+        dicom::core::value::DataSetSequence::from(vec![voi_lut]),
+    ));
+    let path = common::write_file(dir.path(), "render.dcm", obj, uids::IMPLICIT_VR_LITTLE_ENDIAN);
+
+    let instance = DicomParser::with_options(pixel_options()).parse_file(&path).unwrap();
+    let pixel_data = instance.pixel_data().unwrap();
+    let presentation = &pixel_data.descriptor.presentation;
+    assert_eq!(presentation.windows.len(), 2);
+    assert_eq!(presentation.voi_function, VoiFunction::LinearExact);
+    assert_eq!(pixel_data.modality_values(0).unwrap(), vec![-100.0, 100.0, 300.0]);
+
+    // Primera ventana (100/200) sobre los valores de modalidad
+    let frame = pixel_data.render_frame(0, &RenderOptions::default()).unwrap();
+    assert_eq!(frame.data, vec![0, 128, 255]);
+
+    let options = RenderOptions { voi: Voi::Lut(0), ..Default::default() };
+    assert_eq!(pixel_data.render_frame(0, &options).unwrap().data, vec![10, 30, 30]);
+}
+
+#[test]
+fn test_write_round_trip_all_transfer_syntaxes() {
+    let dir = tempfile::tempdir().unwrap();
+    let pixels: Vec<u8> = (0..12).collect();
+    let path = common::write_file(
+        dir.path(),
+        "source.dcm",
+        common::sample_cine_object(2, 2, 3, pixels.clone()),
+        uids::IMPLICIT_VR_LITTLE_ENDIAN,
+    );
+    let source = DicomParser::new().parse_file(&path).unwrap();
+
+    for ts in [
+        uids::IMPLICIT_VR_LITTLE_ENDIAN,
+        uids::EXPLICIT_VR_LITTLE_ENDIAN,
+        uids::DEFLATED_EXPLICIT_VR_LITTLE_ENDIAN,
+    ] {
+        let output = dir.path().join(format!("{}.dcm", ts));
+        DicomWriter::with_transfer_syntax(ts).write_file(&source, &output).unwrap();
+
+        let bytes = std::fs::read(&output).unwrap();
+        assert_eq!(&bytes[128..132], b"DICM");
+
+        let copy = DicomParser::new().parse_file(&output).unwrap();
+        assert_eq!(copy.metadata.transfer_syntax_uid, ts);
+        assert_eq!(copy.patient_name(), source.patient_name());
+        assert_eq!(copy.instance_uid(), common::INSTANCE_UID);
+        assert_eq!(copy.pixel_descriptor.as_ref().unwrap().number_of_frames, 3);
+        assert_eq!(copy.load_pixels().unwrap().data, pixels);
+        assert_eq!(copy.load_frame(2).unwrap(), vec![8, 9, 10, 11]);
+        assert_eq!(copy.dataset.element(tags::FRAME_TIME).unwrap().to_float64().unwrap(), 40.0);
+    }
+}
+
+#[test]
+fn test_write_transcodes_sequences() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut obj = common::sample_object(1, 2, vec![10, 20]);
+    let item = InMemDicomObject::from_element_iter([
+        DataElement::new(tags::LUT_DESCRIPTOR, VR::US, PrimitiveValue::from([2u16, 0, 8])),
+        DataElement::new(tags::LUT_DATA, VR::OW, PrimitiveValue::from([0u16, 255])),
+        DataElement::new(tags::LUT_EXPLANATION, VR::LO, PrimitiveValue::from("FULL")),
+    ]);
+    obj.put(DataElement::new(
+        tags::VOILUT_SEQUENCE,
+        VR::SQ,
+        // Item (8) + elementos (14 + 16 + 12) + delimitador (8) en Explicit VR
+        dicom::core::value::DataSetSequence::new(vec![item], dicom::core::Length(58)),
+    ));
+    let path = common::write_file(dir.path(), "explicit.dcm", obj, uids::EXPLICIT_VR_LITTLE_ENDIAN);
+    let source = DicomParser::new().parse_file(&path).unwrap();
+
+    let output = dir.path().join("implicit.dcm");
+    DicomWriter::with_transfer_syntax(uids::IMPLICIT_VR_LITTLE_ENDIAN)
+        .write_file(&source, &output)
+        .unwrap();
+
+    let copy = DicomParser::with_options(pixel_options()).parse_file(&output).unwrap();
+    let pixel_data = copy.pixel_data().unwrap();
+    assert_eq!(pixel_data.data, vec![10, 20]);
+    assert_eq!(pixel_data.descriptor.presentation.voi_luts[0].data, vec![0, 255]);
+}
+
+#[test]
+fn test_write_decodes_encapsulated_pixel_data() {
+    let dir = tempfile::tempdir().unwrap();
+    let obj = common::encapsulate(
+        common::sample_cine_object(2, 2, 2, vec![]),
+        vec![rle_frame(&[1, 2, 3, 4]), rle_frame(&[5, 6, 7, 8])],
+    );
+    let path = common::write_file(dir.path(), "rle.dcm", obj, uids::RLE_LOSSLESS);
+    let source = DicomParser::new().parse_file(&path).unwrap();
+
+    let output = dir.path().join("native.dcm");
+    DicomWriter::new().write_file(&source, &output).unwrap();
+
+    let copy = DicomParser::new().parse_file(&output).unwrap();
+    assert_eq!(copy.metadata.transfer_syntax_uid, uids::EXPLICIT_VR_LITTLE_ENDIAN);
+    assert!(matches!(
+        copy.pixel_descriptor.as_ref().unwrap().location,
+        Some(PixelDataLocation::Native { length: 8, .. })
+    ));
+    assert_eq!(copy.load_frame(1).unwrap(), vec![5, 6, 7, 8]);
+}
+
+#[test]
+fn test_write_unsupported_transfer_syntax() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = common::write_file(
+        dir.path(),
+        "source.dcm",
+        common::sample_object(2, 2, vec![0; 4]),
+        uids::EXPLICIT_VR_LITTLE_ENDIAN,
+    );
+    let source = DicomParser::new().parse_file(&path).unwrap();
+
+    let result = DicomWriter::with_transfer_syntax(uids::JPEG_BASELINE8_BIT).write(&source, Vec::new());
+    assert!(matches!(result, Err(DicomError::UnsupportedTransferSyntax(_))));
+}
+
+#[test]
+fn test_anonymize_and_write() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = common::write_file(
+        dir.path(),
+        "source.dcm",
+        common::sample_object(2, 2, vec![1, 2, 3, 4]),
+        uids::EXPLICIT_VR_LITTLE_ENDIAN,
+    );
+    let source = DicomParser::new().parse_file(&path).unwrap();
+
+    let profile = AnonymizationProfile::from_json(r#"{ "patient_id": "ANON-0001" }"#).unwrap();
+    let uids = UidRemapper::load_or_create(&dir.path().join("salt.hex")).unwrap();
+    let anonymized = Anonymizer::new(profile, uids.clone()).unwrap().anonymize(&source).unwrap();
+    assert_eq!(anonymized.patient_name(), "");
+    assert_eq!(anonymized.study_uid(), uids.remap(common::STUDY_UID));
+
+    let output = dir.path().join("anon.dcm");
+    DicomWriter::new().write_file(&anonymized, &output).unwrap();
+
+    let copy = DicomParser::new().parse_file(&output).unwrap();
+    assert_eq!(copy.metadata.patient_id, "ANON-0001");
+    assert_eq!(copy.metadata.study_date.as_deref(), Some(""));
+    assert_eq!(copy.instance_uid(), uids.remap(common::INSTANCE_UID));
+    assert_eq!(copy.load_pixels().unwrap().data, vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn test_anonymize_without_required_attributes() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = common::write_file(
+        dir.path(),
+        "source.dcm",
+        common::sample_object(2, 2, vec![1, 2, 3, 4]),
+        uids::EXPLICIT_VR_LITTLE_ENDIAN,
+    );
+    let source = DicomParser::new().parse_file(&path).unwrap();
+
+    // El perfil quita atributos requeridos por el IOD; la salida se acepta igual
+    let profile = AnonymizationProfile::from_json(r#"{ "overrides": { "PatientID": "X", "Modality": "X" } }"#).unwrap();
+    let anonymized = Anonymizer::new(profile, UidRemapper::random()).unwrap().anonymize(&source).unwrap();
+    assert_eq!(anonymized.metadata.patient_id, "");
+    assert!(anonymized.dataset.element(tags::MODALITY).is_err());
+}
+
+#[test]
+fn test_redact_outside_ultrasound_regions() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut obj = common::sample_object(2, 3, vec![9; 6]);
+    obj.put(DataElement::new(tags::BURNED_IN_ANNOTATION, VR::CS, PrimitiveValue::from("YES")));
+    let region = common::region_item(1, 1, 2, 1);
+    obj.put(DataElement::new(
+        tags::SEQUENCE_OF_ULTRASOUND_REGIONS,
+        VR::SQ,
+        DataSetSequence::from(vec![region]),
+    ));
+    let icon = InMemDicomObject::from_element_iter([DataElement::new(
+        tags::ROWS,
+        VR::US,
+        PrimitiveValue::from(64u16),
+    )]);
+    obj.put(DataElement::new(tags::ICON_IMAGE_SEQUENCE, VR::SQ, DataSetSequence::from(vec![icon])));
+    let path = common::write_file(dir.path(), "banner.dcm", obj, uids::EXPLICIT_VR_LITTLE_ENDIAN);
+    let source = DicomParser::new().parse_file(&path).unwrap();
+
+    let redacted = Redactor::new(RedactionOptions::default()).redact(&source).unwrap();
+    let output = dir.path().join("redacted.dcm");
+    DicomWriter::new().write_file(&redacted, &output).unwrap();
+
+    let copy = DicomParser::new().parse_file(&output).unwrap();
+    assert_eq!(copy.regions.len(), 1);
+    assert_eq!(redaction::burned_in_annotation(&copy.dataset), Some(false));
+    assert!(copy.dataset.element(tags::ICON_IMAGE_SEQUENCE).is_err());
+    assert_eq!(copy.load_pixels().unwrap().data, vec![0, 0, 0, 0, 9, 9]);
+}
+
+#[test]
+fn test_region_calibration() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut obj = common::sample_object(4, 4, vec![0; 16]);
+    let region = common::region_item(0, 0, 3, 3);
+    obj.put(DataElement::new(
+        tags::SEQUENCE_OF_ULTRASOUND_REGIONS,
+        VR::SQ,
+        DataSetSequence::from(vec![region]),
+    ));
+    let path = common::write_file(dir.path(), "calibrated.dcm", obj, uids::IMPLICIT_VR_LITTLE_ENDIAN);
+
+    let instance = DicomParser::new().parse_file(&path).unwrap();
+    let region = instance.region_at(1, 1).unwrap();
+    assert_eq!(region.physical_units_x, PhysicalUnits::Centimeter);
+    let length = region.length_mm((0.0, 0.0), (3.0, 0.0)).unwrap();
+    assert!((length - 1.5).abs() < 1e-9);
+    assert!(instance.region_at(4, 0).is_none());
+}
+
+#[test]
+fn test_generic_attribute_access() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut obj = common::sample_cine_object(2, 2, 2, vec![0; 8]);
+    obj.put(DataElement::new(tags::BODY_PART_EXAMINED, VR::CS, PrimitiveValue::from("ABDOMEN ")));
+    let path = common::write_file(dir.path(), "cine.dcm", obj, uids::EXPLICIT_VR_LITTLE_ENDIAN);
+    let instance = DicomParser::new().parse_file(&path).unwrap();
+
+    assert_eq!(instance.get::<f64>("FrameTime"), Some(40.0));
+    assert_eq!(instance.get::<u16>(tags::ROWS), Some(2));
+    assert_eq!(instance.get::<String>("(0008,0060)").as_deref(), Some("US"));
+    assert_eq!(instance.body_part_examined().as_deref(), Some("ABDOMEN"));
+    assert_eq!(instance.frame_rate(), Some(25.0));
+
+    assert!(matches!(instance.try_get::<f64>("CineRate"), Err(DicomError::MissingRequiredTag(_))));
+    assert!(matches!(instance.try_get::<f64>("NoExiste"), Err(DicomError::InvalidTag(_))));
+    assert!(matches!(instance.try_get::<u16>("PatientName"), Err(DicomError::ParseError(_))));
+
+    // Los elementos salen en orden y sin Pixel Data
+    let seen: Vec<_> = instance.elements().map(|e| e.header().tag).collect();
+    assert!(seen.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(seen.contains(&tags::NUMBER_OF_FRAMES));
+    assert!(!seen.contains(&tags::PIXEL_DATA));
+}
+
+/// Reemplaza Patient Name por bytes ya codificados con el charset indicado
+fn with_raw_name(charset: &[&str], name: &[u8]) -> InMemDicomObject {
+    let mut obj = common::sample_object(2, 2, vec![0; 4]);
+    obj.put(DataElement::new(
+        tags::SPECIFIC_CHARACTER_SET,
+        VR::CS,
+        PrimitiveValue::Strs(charset.iter().map(|t| t.to_string()).collect()),
+    ));
+    let mut bytes = name.to_vec();
+    if bytes.len() % 2 == 1 {
+        bytes.push(b' ');
+    }
+    obj.put(DataElement::new(tags::PATIENT_NAME, VR::PN, PrimitiveValue::from(bytes)));
+    obj
+}
+
+#[test]
+fn test_latin1_patient_name() {
+    let dir = tempfile::tempdir().unwrap();
+    let obj = with_raw_name(&["ISO_IR 100"], b"NU\xD1EZ P\xC9REZ^MAR\xCDA JOS\xC9");
+    let path = common::write_file(dir.path(), "latin1.dcm", obj, uids::EXPLICIT_VR_LITTLE_ENDIAN);
+
+    let instance = DicomParser::new().parse_file(&path).unwrap();
+    assert_eq!(instance.patient_name(), "NUÑEZ PÉREZ^MARÍA JOSÉ");
+    assert_eq!(instance.patient_person_name().family(), "NUÑEZ PÉREZ");
+    assert_eq!(instance.patient_person_name().given(), "MARÍA JOSÉ");
+}
+
+#[test]
+fn test_iso2022_patient_name_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let name = [
+        &b"Yamada^Tarou="[..],
+        b"\x1B$B\x3B\x33\x45\x44\x1B(B^\x1B$B\x42\x40\x4F\x3A\x1B(B",
+    ]
+    .concat();
+    let obj = with_raw_name(&["", "ISO 2022 IR 87"], &name);
+    let path = common::write_file(dir.path(), "iso2022.dcm", obj, uids::EXPLICIT_VR_LITTLE_ENDIAN);
+
+    let instance = DicomParser::new().parse_file(&path).unwrap();
+    assert_eq!(instance.patient_person_name().ideographic.family, "山田");
+    assert_eq!(instance.get::<PersonName>("PatientName").unwrap().given(), "Tarou");
+
+    // ISO 2022 no se puede volver a codificar: la copia se escribe en UTF-8
+    let output = dir.path().join("utf8.dcm");
+    DicomWriter::new().write_file(&instance, &output).unwrap();
+    let copy = DicomParser::new().parse_file(&output).unwrap();
+    assert_eq!(copy.get::<String>("SpecificCharacterSet").as_deref(), Some("ISO_IR 192"));
+    assert_eq!(copy.patient_name(), instance.patient_name());
+}
+
+#[test]
+fn test_parse_reader_from_memory() {
+    let dir = tempfile::tempdir().unwrap();
+    let pixels: Vec<u8> = (0..3).flat_map(|frame| vec![frame * 10; 4]).collect();
+    let path = common::write_file(
+        dir.path(),
+        "cine.dcm",
+        common::sample_cine_object(2, 2, 3, pixels.clone()),
+        uids::EXPLICIT_VR_LITTLE_ENDIAN,
+    );
+    let bytes = std::fs::read(&path).unwrap();
+
+    let mut reader = Cursor::new(bytes);
+    let instance = DicomParser::new().parse_reader(&mut reader).unwrap();
+    assert_eq!(instance.study_uid(), common::STUDY_UID);
+    assert!(instance.file_path.as_os_str().is_empty());
+
+    // Lazy loading: los offsets se usan sobre el mismo reader
+    let descriptor = instance.pixel_descriptor.as_ref().unwrap();
+    let pixel_data = descriptor.read_pixels(&mut reader, uids::EXPLICIT_VR_LITTLE_ENDIAN).unwrap();
+    assert_eq!(pixel_data.data, pixels);
+
+    let loaded = DicomParser::with_options(pixel_options())
+        .parse_reader(Cursor::new(reader.into_inner()))
+        .unwrap();
+    assert_eq!(loaded.pixel_data().unwrap().data, pixels);
+}
+
+#[tokio::test]
+async fn test_parse_stream_stops_at_pixel_data() {
+    let dir = tempfile::tempdir().unwrap();
+    // 300 frames encapsulados de 1 KiB: bastante más que el primer bloque
+    let fragments: Vec<Vec<u8>> = (0..300).map(|_| vec![7; 1024]).collect();
+    let cine = common::encapsulate(common::sample_cine_object(32, 32, 300, Vec::new()), fragments);
+    let path = common::write_file(dir.path(), "cine.dcm", cine, uids::RLE_LOSSLESS);
+    let bytes = std::fs::read(&path).unwrap();
+
+    let mut stream = Cursor::new(bytes.clone());
+    let instance = DicomParser::new().parse_stream(&mut stream).await.unwrap();
+    assert!((stream.position() as usize) < bytes.len());
+    assert_eq!(instance.metadata.sop_instance_uid, common::INSTANCE_UID);
+    assert_eq!(instance.frame_rate(), Some(25.0));
+
+    let descriptor = instance.pixel_descriptor.as_ref().unwrap();
+    assert_eq!(descriptor.number_of_frames, 300);
+    assert_eq!(descriptor.location, None);
+    assert!(instance.pixel_data.is_none());
+}
+
+#[tokio::test]
+async fn test_parse_stream_small_object() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = common::write_file(
+        dir.path(),
+        "us.dcm",
+        common::sample_object(2, 2, vec![1, 2, 3, 4]),
+        uids::IMPLICIT_VR_LITTLE_ENDIAN,
+    );
+    let bytes = std::fs::read(&path).unwrap();
+
+    let instance = DicomParser::new().parse_stream(&bytes[..]).await.unwrap();
+    assert_eq!(instance.patient_person_name().family(), "PEREZ");
+    assert!(instance.pixel_descriptor.is_some());
+
+    let instance = DicomParser::with_options(pixel_options()).parse_stream(&bytes[..]).await.unwrap();
+    assert_eq!(instance.pixel_data().unwrap().data, vec![1, 2, 3, 4]);
+
+    let small = ParseOptions { max_file_size: 64, ..ParseOptions::default() };
+    assert!(DicomParser::with_options(small).parse_stream(&bytes[..]).await.is_err());
+}
+
+#[test]
+fn test_conformance_report_and_strict_validation() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut obj = common::sample_object(2, 2, vec![0; 4]);
+    obj.put(DataElement::new(tags::PATIENT_SEX, VR::CS, PrimitiveValue::from("X")));
+    let path = common::write_file(dir.path(), "sex.dcm", obj, uids::EXPLICIT_VR_LITTLE_ENDIAN);
+
+    let strict = DicomParser::new().parse_file(&path);
+    assert!(matches!(strict, Err(DicomError::ValidationError(ref m)) if m.contains("PatientSex")));
+
+    let lenient = ParseOptions { strict_validation: false, ..ParseOptions::default() };
+    let instance = DicomParser::with_options(lenient).parse_file(&path).unwrap();
+    let report = instance.conformance();
+    assert_eq!(report.iod, Some(Iod::UltrasoundImage));
+    let errors: Vec<&str> = report.errors().map(|f| f.keyword.as_str()).collect();
+    assert_eq!(errors, vec!["PatientSex"]);
+    // Type 2 ausentes en el objeto de prueba: solo advertencias
+    assert!(report.warnings().any(|f| f.keyword == "StudyTime"));
+
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["findings"][0]["severity"], "warning");
+
+    let instance = DicomParser::with_options(recovery_options()).parse_file(&path).unwrap();
+    assert!(matches!(instance.warnings[..], [ParseWarning::Validation { .. }]));
+}
+
+#[test]
+fn test_strict_validation_accepts_non_image_sop_classes() {
+    let dir = tempfile::tempdir().unwrap();
+    for (name, sop_class, modality) in [
+        ("sr.dcm", uids::COMPREHENSIVE_SR_STORAGE, "SR"),
+        ("ko.dcm", uids::KEY_OBJECT_SELECTION_DOCUMENT_STORAGE, "KO"),
+        ("pdf.dcm", uids::ENCAPSULATED_PDF_STORAGE, "DOC"),
+    ] {
+        let obj = common::sample_document(sop_class, modality);
+        let path = common::write_file(dir.path(), name, obj, uids::EXPLICIT_VR_LITTLE_ENDIAN);
+
+        let instance = DicomParser::new().parse_file(&path).unwrap();
+        assert!(instance.conformance().is_conformant(), "{}", name);
+        assert!(instance.pixel_descriptor.is_none());
+    }
+}
+
+#[test]
+fn test_checksums_for_file_and_frames() {
+    let dir = tempfile::tempdir().unwrap();
+    let pixels: Vec<u8> = (0..3).flat_map(|frame| vec![frame * 10; 4]).collect();
+    let path = common::write_file(
+        dir.path(),
+        "cine.dcm",
+        common::sample_cine_object(2, 2, 3, pixels),
+        uids::EXPLICIT_VR_LITTLE_ENDIAN,
+    );
+    let bytes = std::fs::read(&path).unwrap();
+
+    assert!(DicomParser::new().parse_file(&path).unwrap().checksums.is_none());
+
+    let options = ParseOptions { validate_checksums: true, ..ParseOptions::default() };
+    let instance = DicomParser::with_options(options).parse_file(&path).unwrap();
+    let checksums = instance.checksums.clone().unwrap();
+    assert_eq!(checksums.file_sha256, checksum::sha256_hex(&bytes));
+    assert_eq!(checksums.frame_sha256[2], checksum::sha256_hex(&[20; 4]));
+    assert_eq!(checksums.frame_sha256.len(), 3);
+    assert!(instance.verify_checksums(&checksums).is_ok());
+
+    // Un byte alterado en el último frame
+    let mut damaged = bytes.clone();
+    *damaged.last_mut().unwrap() = 99;
+    std::fs::write(&path, damaged).unwrap();
+
+    let frames_only = Checksums { file_sha256: String::new(), ..checksums.clone() };
+    let result = DicomParser::new().parse_file_verified(&path, &frames_only);
+    assert!(matches!(result, Err(DicomError::CorruptedPixelData)));
+    let mut expected = frames_only;
+    expected.frame_sha256[2] = checksum::sha256_hex(&[20, 20, 20, 99]);
+    assert!(DicomParser::new().parse_file_verified(&path, &expected).is_ok());
+}
+
+#[test]
+fn test_checksums_of_encapsulated_frames() {
+    let dir = tempfile::tempdir().unwrap();
+    let fragments = vec![rle_frame(&[1, 2, 3, 4]), rle_frame(&[5, 6, 7, 8])];
+    let cine = common::encapsulate(common::sample_cine_object(2, 2, 2, Vec::new()), fragments.clone());
+    let path = common::write_file(dir.path(), "rle.dcm", cine, uids::RLE_LOSSLESS);
+
+    let options = ParseOptions { validate_checksums: true, ..ParseOptions::default() };
+    let instance = DicomParser::with_options(options).parse_file(&path).unwrap();
+    let expected: Vec<String> = fragments.iter().map(|f| checksum::sha256_hex(f)).collect();
+    assert_eq!(instance.checksums.unwrap().frame_sha256, expected);
+}
+
+fn recovery_options() -> ParseOptions {
+    ParseOptions {
+        recovery: true,
+        ..ParseOptions::default()
+    }
+}
+
+#[test]
+fn test_recover_raw_dataset_without_file_meta() {
+    use dicom::encoding::TransferSyntaxIndex;
+    use dicom::transfer_syntax::TransferSyntaxRegistry;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("raw.dcm");
+    let ts = TransferSyntaxRegistry.get(uids::IMPLICIT_VR_LITTLE_ENDIAN).unwrap();
+    let mut bytes = Vec::new();
+    common::sample_object(2, 2, vec![1, 2, 3, 4])
+        .write_dataset_with_ts(&mut bytes, ts)
+        .unwrap();
+    std::fs::write(&path, bytes).unwrap();
+
+    let strict = DicomParser::new().parse_file(&path);
+    assert!(matches!(strict, Err(DicomError::InvalidMagicBytes)));
+
+    let instance = DicomParser::with_options(recovery_options()).parse_file(&path).unwrap();
+    assert_eq!(instance.metadata.transfer_syntax_uid, uids::IMPLICIT_VR_LITTLE_ENDIAN);
+    assert_eq!(instance.instance_uid(), common::INSTANCE_UID);
+    assert_eq!(instance.load_pixels().unwrap().data, vec![1, 2, 3, 4]);
+    assert_eq!(
+        instance.warnings,
+        vec![
+            ParseWarning::MissingPreamble,
+            ParseWarning::MissingFileMeta,
+            ParseWarning::GuessedTransferSyntax {
+                uid: uids::IMPLICIT_VR_LITTLE_ENDIAN.to_string()
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_recover_missing_preamble() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = common::write_file(
+        dir.path(),
+        "full.dcm",
+        common::sample_object(2, 2, vec![1, 2, 3, 4]),
+        uids::EXPLICIT_VR_LITTLE_ENDIAN,
+    );
+    let full = std::fs::read(&path).unwrap();
+
+    // Sin preamble, y sin preamble ni "DICM"
+    for (name, skip) in [("no_preamble.dcm", 128), ("no_magic.dcm", 132)] {
+        let damaged = dir.path().join(name);
+        std::fs::write(&damaged, &full[skip..]).unwrap();
+
+        let instance = DicomParser::with_options(recovery_options()).parse_file(&damaged).unwrap();
+        assert_eq!(instance.warnings, vec![ParseWarning::MissingPreamble]);
+        assert_eq!(instance.study_uid(), common::STUDY_UID);
+        assert_eq!(instance.load_pixels().unwrap().data, vec![1, 2, 3, 4]);
+    }
+}
+
+#[test]
+fn test_recover_truncated_cine() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut obj = common::sample_cine_object(2, 2, 3, (0..12).collect());
+    obj.remove_element(tags::PATIENT_ID);
+    let path = common::write_file(dir.path(), "cine.dcm", obj, uids::EXPLICIT_VR_LITTLE_ENDIAN);
+
+    // Cortar a mitad del tercer frame
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::write(&path, &bytes[..bytes.len() - 3]).unwrap();
+
+    let instance = DicomParser::with_options(recovery_options()).parse_file(&path).unwrap();
+    assert!(instance.warnings.contains(&ParseWarning::TruncatedPixelData { expected: 12, available: 9 }));
+    assert!(instance.warnings.contains(&ParseWarning::MissingAttribute {
+        tag: tags::PATIENT_ID.to_string()
+    }));
+    assert!(instance
+        .warnings
+        .iter()
+        .any(|w| matches!(w, ParseWarning::Validation { .. })));
+
+    let pixels = instance.load_pixels().unwrap();
+    assert_eq!(pixels.descriptor.number_of_frames, 2);
+    assert_eq!(pixels.data, (0..8).collect::<Vec<u8>>());
+}
+
+#[test]
+fn test_file_truncated_inside_element_header() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = common::write_file(
+        dir.path(),
+        "cut.dcm",
+        common::sample_object(2, 2, vec![0; 4]),
+        uids::EXPLICIT_VR_LITTLE_ENDIAN,
+    );
+
+    // Cortar 3 bytes dentro del tag de Pixel Data (E0 7F 10 00)
+    let bytes = std::fs::read(&path).unwrap();
+    let header = bytes.windows(4).rposition(|w| w == [0xE0, 0x7F, 0x10, 0x00]).unwrap();
+    std::fs::write(&path, &bytes[..header + 3]).unwrap();
+
+    let options = ParseOptions {
+        strict_validation: false,
+        ..ParseOptions::default()
+    };
+    assert!(matches!(
+        DicomParser::with_options(options.clone()).parse_file(&path),
+        Err(DicomError::ParseError(_))
+    ));
+
+    let instance = DicomParser::with_options(recovery_options()).parse_file(&path).unwrap();
+    assert!(instance
+        .warnings
+        .iter()
+        .any(|w| matches!(w, ParseWarning::TruncatedDataSet { .. })));
+
+    // Cortado justo antes del tag: fin normal de un objeto sin Pixel Data
+    std::fs::write(&path, &bytes[..header]).unwrap();
+    let instance = DicomParser::with_options(options).parse_file(&path).unwrap();
+    assert!(instance.warnings.is_empty());
+}
+
+#[test]
+fn test_truncated_pixel_data_is_corrupted() {
+    let dir = tempfile::tempdir().unwrap();
+    // 3x3 declarado pero solo 4 bytes de píxeles
+    let path = common::write_file(
+        dir.path(),
+        "truncated.dcm",
+        common::sample_object(3, 3, vec![0; 4]),
+        uids::EXPLICIT_VR_LITTLE_ENDIAN,
+    );
+
+    let result = DicomParser::with_options(pixel_options()).parse_file(&path);
+    assert!(matches!(result, Err(DicomError::CorruptedPixelData)));
+}
+
+fn numbered(series_uid: &str, series: i32, instance_uid: &str, instance: i32) -> InMemDicomObject {
+    let mut obj = common::sample_object(2, 2, vec![0; 4]);
+    obj.put(DataElement::new(tags::SERIES_INSTANCE_UID, VR::UI, PrimitiveValue::from(series_uid)));
+    obj.put(DataElement::new(tags::SOP_INSTANCE_UID, VR::UI, PrimitiveValue::from(instance_uid)));
+    obj.put(DataElement::new(tags::SERIES_NUMBER, VR::IS, PrimitiveValue::from(series.to_string())));
+    obj.put(DataElement::new(tags::INSTANCE_NUMBER, VR::IS, PrimitiveValue::from(instance.to_string())));
+    obj
+}
+
+#[test]
+fn test_parse_directory_groups_and_collects_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let nested = dir.path().join("EXPORT").join("0001");
+    std::fs::create_dir_all(&nested).unwrap();
+    let ts = uids::EXPLICIT_VR_LITTLE_ENDIAN;
+
+    // Dos series del mismo estudio, con nombres sin extensión y en desorden
+    common::write_file(dir.path(), "IM2", numbered("1.2.3.2", 2, "1.2.3.2.1", 1), ts);
+    common::write_file(&nested, "IM0", numbered("1.2.3.1", 1, "1.2.3.1.2", 2), ts);
+    let first = common::write_file(&nested, "IM1", numbered("1.2.3.1", 1, "1.2.3.1.1", 1), ts);
+
+    // Archivo DICOM truncado a mitad del File Meta
+    let bytes = std::fs::read(&first).unwrap();
+    std::fs::write(nested.join("IM9"), &bytes[..150]).unwrap();
+
+    std::fs::write(dir.path().join("README.TXT"), "Exportado por el equipo").unwrap();
+    std::fs::write(dir.path().join("DICOMDIR"), &bytes).unwrap();
+    std::fs::write(nested.join(".DS_Store"), [0u8; 16]).unwrap();
+
+    let scan = DicomParser::new().parse_directory(dir.path()).unwrap();
+
+    assert_eq!(scan.patients.len(), 1);
+    let patient = &scan.patients[0];
+    assert_eq!(patient.patient_id, "CC123456");
+    assert_eq!(patient.studies.len(), 1);
+
+    let series = &patient.studies[0].series;
+    assert_eq!(series.len(), 2);
+    assert_eq!(series[0].series_number, Some(1));
+    let uids: Vec<&str> = series[0].instances.iter().map(|i| i.metadata.sop_instance_uid.as_str()).collect();
+    assert_eq!(uids, ["1.2.3.1.1", "1.2.3.1.2"]);
+    assert_eq!(series[1].instances.len(), 1);
+    assert_eq!(scan.instance_count(), 3);
+
+    let mut skipped: Vec<(String, SkipReason)> = scan
+        .skipped
+        .iter()
+        .map(|s| (s.path.file_name().unwrap().to_string_lossy().into_owned(), s.reason))
+        .collect();
+    skipped.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        skipped,
+        [
+            (".DS_Store".to_string(), SkipReason::Hidden),
+            ("DICOMDIR".to_string(), SkipReason::Dicomdir),
+            ("README.TXT".to_string(), SkipReason::NotDicom),
+        ]
+    );
+
+    assert_eq!(scan.errors.len(), 1);
+    assert!(scan.errors[0].path.ends_with("IM9"));
+}
+
+#[test]
+fn test_parse_directory_requires_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    let result = DicomParser::new().parse_directory(&dir.path().join("no-existe"));
+    assert!(matches!(result, Err(DicomError::Io(_))));
+}
+
+#[test]
+fn test_dicomdir_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let media = dir.path().join("DICOM").join("ST000001");
+    std::fs::create_dir_all(&media).unwrap();
+    let ts = uids::EXPLICIT_VR_LITTLE_ENDIAN;
+
+    let parser = DicomParser::new();
+    let instances: Vec<_> = [
+        ("IM000002", numbered("1.2.3.1", 1, "1.2.3.1.2", 2)),
+        ("IM000001", numbered("1.2.3.1", 1, "1.2.3.1.1", 1)),
+        ("IM000003", numbered("1.2.3.2", 2, "1.2.3.2.1", 1)),
+    ]
+    .into_iter()
+    .map(|(name, obj)| parser.parse_file(&common::write_file(&media, name, obj, ts)).unwrap())
+    .collect();
+
+    let writer = DicomDirWriter::with_options(DicomDirOptions {
+        file_set_id: Some("ECOCOL_REF".to_string()),
+        general_purpose_cd: true,
+        ..DicomDirOptions::default()
+    });
+    let path = writer.write_file(dir.path(), &instances).unwrap();
+
+    let dicomdir = DicomDir::open(&path).unwrap();
+    assert_eq!(dicomdir.file_set_id.as_deref(), Some("ECOCOL_REF"));
+    assert_eq!(dicomdir.records.len(), 1);
+    let patient = &dicomdir.records[0];
+    assert_eq!(patient.record_type, RecordType::Patient);
+    assert_eq!(patient.get::<String>("PatientID").as_deref(), Some("CC123456"));
+
+    let series = &patient.children[0].children;
+    assert_eq!(series.len(), 2);
+    let images = &series[0].children;
+    assert_eq!(images[0].file_id, ["DICOM", "ST000001", "IM000001"]);
+    assert_eq!(images[1].referenced_sop_instance_uid.as_deref(), Some("1.2.3.1.2"));
+    assert_eq!(images[0].referenced_transfer_syntax_uid.as_deref(), Some(ts));
+
+    let scan = parser.parse_dicomdir(&path).unwrap();
+    assert!(scan.errors.is_empty());
+    assert_eq!(scan.instance_count(), 3);
+
+    // Un archivo referenciado que ya no está queda como error
+    std::fs::remove_file(media.join("IM000003")).unwrap();
+    let scan = parser.parse_dicomdir(&path).unwrap();
+    assert_eq!(scan.instance_count(), 2);
+    assert_eq!(scan.errors.len(), 1);
+}
+
+#[test]
+fn test_dicomdir_record_type_by_sop_class() {
+    let dir = tempfile::tempdir().unwrap();
+    let parser = DicomParser::new();
+    let mut sr = common::sample_document(uids::COMPREHENSIVE_SR_STORAGE, "SR");
+    sr.put(DataElement::new(tags::SOP_INSTANCE_UID, VR::UI, PrimitiveValue::from("1.2.3.9")));
+    sr.put(DataElement::new(tags::COMPLETION_FLAG, VR::CS, PrimitiveValue::from("COMPLETE")));
+    let instances: Vec<_> = [("IM1", common::sample_object(2, 2, vec![0; 4])), ("SR1", sr)]
+        .into_iter()
+        .map(|(name, obj)| {
+            parser
+                .parse_file(&common::write_file(dir.path(), name, obj, uids::EXPLICIT_VR_LITTLE_ENDIAN))
+                .unwrap()
+        })
+        .collect();
+
+    let path = DicomDirWriter::new().write_file(dir.path(), &instances).unwrap();
+    let dicomdir = DicomDir::open(&path).unwrap();
+    let leaves = &dicomdir.records[0].children[0].children[0].children;
+    let types: Vec<&str> = leaves.iter().map(|record| record.record_type.as_str()).collect();
+    assert_eq!(types, ["IMAGE", "SR DOCUMENT"]);
+    assert_eq!(leaves[1].get::<String>("CompletionFlag").as_deref(), Some("COMPLETE"));
+    assert_eq!(parser.parse_dicomdir(&path).unwrap().instance_count(), 2);
+}
+
+#[test]
+fn test_dicomdir_skips_invalid_file_ids() {
+    let dir = tempfile::tempdir().unwrap();
+    let media = dir.path().join("DICOM");
+    std::fs::create_dir_all(&media).unwrap();
+    let parser = DicomParser::new();
+    let instances: Vec<_> = [
+        ("IM000001", numbered("1.2.3.1", 1, "1.2.3.1.1", 1)),
+        ("IM000002", numbered("1.2.3.1", 1, "1.2.3.1.2", 2)),
+    ]
+    .into_iter()
+    .map(|(name, obj)| {
+        parser
+            .parse_file(&common::write_file(&media, name, obj, uids::EXPLICIT_VR_LITTLE_ENDIAN))
+            .unwrap()
+    })
+    .collect();
+    let path = DicomDirWriter::new().write_file(dir.path(), &instances).unwrap();
+
+    // Un medio que referencia un archivo fuera de su directorio (mismo largo)
+    let mut bytes = std::fs::read(&path).unwrap();
+    let original = br"DICOM\IM000002";
+    let at = bytes.windows(original.len()).position(|w| w == original).unwrap();
+    bytes[at..at + original.len()].copy_from_slice(br"..\..\..\IM002");
+    std::fs::write(&path, bytes).unwrap();
+
+    let dicomdir = DicomDir::open(&path).unwrap();
+    assert_eq!(dicomdir.referenced_files(), [media.join("IM000001")]);
+    assert_eq!(dicomdir.invalid_file_ids().len(), 1);
+
+    let scan = parser.parse_dicomdir(&path).unwrap();
+    assert_eq!(scan.instance_count(), 1);
+    assert_eq!(scan.errors.len(), 1);
+    assert_eq!(scan.errors[0].path, path);
+    assert!(matches!(&scan.errors[0].error, DicomError::ValidationError(m) if m.contains(r"..\..\..\IM002")));
+}
+
+#[test]
+fn test_dicomdir_general_purpose_cd_profile() {
+    let dir = tempfile::tempdir().unwrap();
+    let parser = DicomParser::new();
+    let implicit = common::write_file(
+        dir.path(),
+        "IM1",
+        common::sample_object(2, 2, vec![0; 4]),
+        uids::IMPLICIT_VR_LITTLE_ENDIAN,
+    );
+    let instances = vec![parser.parse_file(&implicit).unwrap()];
+
+    // Sin el perfil se acepta cualquier transfer syntax
+    assert!(DicomDirWriter::new().write_file(dir.path(), &instances).is_ok());
+
+    let profile = DicomDirWriter::with_options(DicomDirOptions {
+        general_purpose_cd: true,
+        ..DicomDirOptions::default()
+    });
+    assert!(matches!(
+        profile.write_file(dir.path(), &instances),
+        Err(DicomError::ValidationError(_))
+    ));
+
+    // Los File IDs en minúsculas no son válidos en ningún medio
+    let lowercase = common::write_file(
+        dir.path(),
+        "im2.dcm",
+        common::sample_object(2, 2, vec![0; 4]),
+        uids::EXPLICIT_VR_LITTLE_ENDIAN,
+    );
+    let instances = vec![parser.parse_file(&lowercase).unwrap()];
+    assert!(DicomDirWriter::new().write_file(dir.path(), &instances).is_err());
+}
+
+#[cfg(feature = "export")]
+fn decode_png(bytes: &[u8]) -> (png::OutputInfo, Vec<u8>) {
+    let mut reader = png::Decoder::new(bytes).read_info().unwrap();
+    let mut data = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut data).unwrap();
+    data.truncate(info.buffer_size());
+    (info, data)
+}
+
+#[cfg(feature = "export")]
+#[test]
+fn test_export_frame_and_contact_sheet() {
+    use dicom_core::export::{ContactSheetOptions, ExportOptions, ImageFormat};
+
+    let dir = tempfile::tempdir().unwrap();
+    // 10 frames de 4x4, cada uno con un valor constante
+    let pixels: Vec<u8> = (0..10u8).flat_map(|n| [n * 20; 16]).collect();
+    let obj = common::sample_cine_object(4, 4, 10, pixels);
+    let path = common::write_file(dir.path(), "cine.dcm", obj, uids::EXPLICIT_VR_LITTLE_ENDIAN);
+    let instance = DicomParser::new().parse_file(&path).unwrap();
+
+    let options = ExportOptions {
+        size: Some((8, 6)),
+        ..ExportOptions::default()
+    };
+    let (info, data) = decode_png(&instance.export_frame(3, &options).unwrap());
+    assert_eq!((info.width, info.height), (6, 6));
+    assert_eq!(info.color_type, png::ColorType::Grayscale);
+    assert!(data.iter().all(|&v| v == 60));
+
+    let jpeg = instance
+        .export_frame(0, &ExportOptions {
+            format: ImageFormat::Jpeg { quality: 90 },
+            ..ExportOptions::default()
+        })
+        .unwrap();
+    assert_eq!(&jpeg[..2], [0xFF, 0xD8]);
+
+    let sheet = instance
+        .contact_sheet(&ContactSheetOptions {
+            frames: 4,
+            columns: Some(2),
+            tile_size: (4, 4),
+            spacing: 1,
+            background: 255,
+            ..ContactSheetOptions::default()
+        })
+        .unwrap();
+    let (info, data) = decode_png(&sheet);
+    assert_eq!((info.width, info.height), (11, 11));
+    let at = |x: usize, y: usize| data[y * 11 + x];
+    assert_eq!(at(0, 0), 255);
+    // Frames 0, 3, 6 y 9
+    assert_eq!([at(1, 1), at(6, 1), at(1, 6), at(9, 9)], [0, 60, 120, 180]);
+
+    // Dimensiones que no entran en u32
+    for (tile_size, spacing) in [((u32::MAX, 4), 1), ((4, 4), u32::MAX)] {
+        let huge = ContactSheetOptions {
+            frames: 4,
+            tile_size,
+            spacing,
+            ..ContactSheetOptions::default()
+        };
+        assert!(matches!(instance.contact_sheet(&huge), Err(DicomError::ValidationError(_))));
+    }
+}
+
+#[cfg(feature = "export")]
+#[test]
+fn test_export_cine_timing_and_containers() {
+    use dicom_core::export::{CineExportOptions, CineFormat};
+
+    let dir = tempfile::tempdir().unwrap();
+    let pixels: Vec<u8> = (0..6u8).flat_map(|n| [n * 40; 16]).collect();
+    let mut obj = common::sample_cine_object(4, 4, 6, pixels);
+    // Ritmo irregular: el frame 2 dura 100 ms
+    obj.put(DataElement::new(
+        tags::FRAME_TIME_VECTOR,
+        VR::DS,
+        PrimitiveValue::from("0\\40\\40\\100\\40\\40"),
+    ));
+    let path = common::write_file(dir.path(), "cine.dcm", obj, uids::EXPLICIT_VR_LITTLE_ENDIAN);
+    let instance = DicomParser::new().parse_file(&path).unwrap();
+    assert_eq!(instance.playback_delays_ms().unwrap(), [40.0, 40.0, 100.0, 40.0, 40.0, 40.0]);
+
+    let gif = instance
+        .export_cine(&CineExportOptions {
+            frames: Some(1..5),
+            max_frames: Some(2),
+            ..CineExportOptions::default()
+        })
+        .unwrap();
+    let mut options = gif::DecodeOptions::new();
+    options.set_color_output(gif::ColorOutput::Indexed);
+    let mut decoder = options.read_info(gif.as_slice()).unwrap();
+    let mut frames = Vec::new();
+    while let Some(frame) = decoder.read_next_frame().unwrap() {
+        frames.push((frame.delay, frame.buffer[0]));
+    }
+    // Frames 1 y 4; el primero absorbe la duración de 2 y 3
+    assert_eq!(frames, [(18, 40), (4, 160)]);
+
+    let apng = instance
+        .export_cine(&CineExportOptions {
+            format: CineFormat::Apng,
+            max_size: Some((2, 2)),
+            ..CineExportOptions::default()
+        })
+        .unwrap();
+    let reader = png::Decoder::new(apng.as_slice()).read_info().unwrap();
+    let info = reader.info();
+    assert_eq!((info.width, info.height), (2, 2));
+    assert_eq!(info.animation_control.unwrap().num_frames, 6);
+
+    let avi = instance
+        .export_cine(&CineExportOptions {
+            format: CineFormat::MjpegAvi { quality: 80 },
+            ..CineExportOptions::default()
+        })
+        .unwrap();
+    assert_eq!((&avi[..4], &avi[8..12]), (&b"RIFF"[..], &b"AVI "[..]));
+    assert_eq!(u32::from_le_bytes(avi[4..8].try_into().unwrap()) as usize, avi.len() - 8);
+    assert_eq!(avi.windows(4).filter(|w| w == b"00dc").count(), 12);
+    // 300 ms en 6 frames = 50 ms por frame
+    assert_eq!(u32::from_le_bytes(avi[32..36].try_into().unwrap()), 50_000);
+
+    assert!(instance
+        .export_cine(&CineExportOptions {
+            frames: Some(6..9),
+            ..CineExportOptions::default()
+        })
+        .is_err());
+}
+
 // Nota: Para tests con archivos DICOM reales, necesitarás agregar
 // fixtures en tests/fixtures/ y descomentar los siguientes tests
 
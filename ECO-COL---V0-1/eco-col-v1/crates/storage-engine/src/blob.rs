@@ -0,0 +1,332 @@
+//! Almacenamiento de archivos DICOM direccionado por contenido
+//!
+//! Cada objeto se guarda una sola vez con su SHA-256 como nombre, en dos
+//! niveles de directorios con los primeros bytes del hash
+//! (`ab/cd/abcd…ef.dcm`) para no juntar miles de archivos en una carpeta.
+//! La escritura va primero a `tmp/` y se publica con un rename atómico: un
+//! corte de energía deja a lo sumo un temporal, nunca un objeto a medias.
+
+use crate::error::{Result, StorageError};
+use dicom_core::{Checksums, DicomInstance, DicomParser};
+
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Directorio de escrituras en curso, dentro de la raíz
+const TMP_DIR: &str = "tmp";
+
+/// Extensión de los objetos (PS3.10 no exige ninguna; ayuda a las herramientas)
+const EXTENSION: &str = "dcm";
+
+/// Contador para nombres de temporales únicos dentro del proceso
+static TMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Objeto guardado
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredBlob {
+    /// SHA-256 en hexadecimal (minúsculas)
+    pub sha256: String,
+
+    /// Ubicación definitiva
+    pub path: PathBuf,
+
+    pub size: u64,
+
+    /// El contenido ya estaba guardado y no se escribió de nuevo
+    pub deduplicated: bool,
+}
+
+/// Almacén de objetos bajo un directorio raíz
+#[derive(Debug, Clone)]
+pub struct BlobStore {
+    root: PathBuf,
+}
+
+impl BlobStore {
+    /// Abrir (o crear) el almacén en `root`
+    pub fn open(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        std::fs::create_dir_all(root.join(TMP_DIR))?;
+        Ok(Self { root })
+    }
+
+    /// Directorio raíz
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Ubicación del objeto con hash `sha256` (exista o no)
+    pub fn path_for(&self, sha256: &str) -> Result<PathBuf> {
+        if sha256.len() != 64 || !sha256.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b)) {
+            return Err(StorageError::invalid_instance(format!("SHA-256 inválido: {:?}", sha256)));
+        }
+        Ok(self
+            .root
+            .join(&sha256[..2])
+            .join(&sha256[2..4])
+            .join(format!("{}.{}", sha256, EXTENSION)))
+    }
+
+    /// Verificar si el objeto existe
+    pub fn contains(&self, sha256: &str) -> bool {
+        self.path_for(sha256).map(|path| path.is_file()).unwrap_or(false)
+    }
+
+    // ============================================
+    // Escritura
+    // ============================================
+
+    /// Guardar una copia del archivo en `source`
+    pub fn put_file(&self, source: &Path) -> Result<StoredBlob> {
+        self.put_reader(File::open(source)?)
+    }
+
+    /// Guardar bytes en memoria (p.ej. un payload de C-STORE)
+    pub fn put_bytes(&self, bytes: &[u8]) -> Result<StoredBlob> {
+        self.put_reader(bytes)
+    }
+
+    /// Copiar `reader` a un temporal calculando el hash y publicarlo
+    pub fn put_reader(&self, mut reader: impl Read) -> Result<StoredBlob> {
+        let tmp = self.root.join(TMP_DIR).join(format!(
+            "{}-{}.tmp",
+            std::process::id(),
+            TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let written = (|| -> Result<(u64, String)> {
+            let mut file = File::create(&tmp)?;
+            let mut hashing = HashingWriter::new(&mut file);
+            let size = std::io::copy(&mut reader, &mut hashing)?;
+            let sha256 = hashing.finish();
+            file.sync_all()?;
+            Ok((size, sha256))
+        })();
+        let (size, sha256) = match written {
+            Ok(written) => written,
+            Err(e) => {
+                let _ = std::fs::remove_file(&tmp);
+                return Err(e);
+            }
+        };
+
+        let path = self.path_for(&sha256)?;
+        if path.is_file() {
+            std::fs::remove_file(&tmp)?;
+            return Ok(StoredBlob {
+                sha256,
+                path,
+                size,
+                deduplicated: true,
+            });
+        }
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // Con dos escrituras simultáneas del mismo contenido el segundo
+        // rename reemplaza un archivo idéntico
+        std::fs::rename(&tmp, &path)?;
+
+        Ok(StoredBlob {
+            sha256,
+            path,
+            size,
+            deduplicated: false,
+        })
+    }
+
+    // ============================================
+    // Lectura
+    // ============================================
+
+    /// Leer un objeto verificando su hash
+    pub fn read(&self, sha256: &str) -> Result<Vec<u8>> {
+        let path = self.path_for(sha256)?;
+        let bytes = std::fs::read(&path)?;
+        check(&path, sha256, &hex(&Sha256::digest(&bytes)))?;
+        Ok(bytes)
+    }
+
+    /// Verificar el hash de un objeto sin cargarlo en memoria
+    pub fn verify(&self, sha256: &str) -> Result<()> {
+        let path = self.path_for(sha256)?;
+        let (_, actual) = sha256_file(&path)?;
+        check(&path, sha256, &actual)
+    }
+
+    /// Parsear un objeto verificando su hash
+    ///
+    /// Una diferencia es `DicomError::CorruptedPixelData`, como en
+    /// [`DicomParser::parse_file_verified`].
+    pub fn parse(&self, parser: &DicomParser, sha256: &str) -> Result<DicomInstance> {
+        let expected = Checksums {
+            file_sha256: sha256.to_string(),
+            frame_sha256: Vec::new(),
+        };
+        Ok(parser.parse_file_verified(&self.path_for(sha256)?, &expected)?)
+    }
+
+    /// Todos los objetos guardados, en orden
+    ///
+    /// Solo incluye archivos con la ubicación que les corresponde; los
+    /// temporales y archivos ajenos no se listan.
+    pub fn list(&self) -> Result<Vec<StoredBlob>> {
+        let mut blobs = Vec::new();
+        for path in files_under(&self.root, 2)? {
+            let Some(sha256) = path.file_stem().and_then(|s| s.to_str()) else { continue };
+            if self.path_for(sha256).ok().as_ref() == Some(&path) {
+                blobs.push(StoredBlob {
+                    sha256: sha256.to_string(),
+                    size: path.metadata()?.len(),
+                    path,
+                    deduplicated: false,
+                });
+            }
+        }
+        Ok(blobs)
+    }
+}
+
+/// Archivos a exactamente `depth` niveles de directorio bajo `dir`
+fn files_under(dir: &Path, depth: usize) -> Result<Vec<PathBuf>> {
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<_>>()?;
+    entries.sort();
+
+    let mut files = Vec::new();
+    for path in entries {
+        if depth == 0 {
+            if path.is_file() {
+                files.push(path);
+            }
+        } else if path.is_dir() && path.file_name() != Some(TMP_DIR.as_ref()) {
+            files.extend(files_under(&path, depth - 1)?);
+        }
+    }
+    Ok(files)
+}
+
+/// `ChecksumMismatch` si `actual` no es `expected`
+pub(crate) fn check(path: &Path, expected: &str, actual: &str) -> Result<()> {
+    if expected.eq_ignore_ascii_case(actual) {
+        Ok(())
+    } else {
+        Err(StorageError::ChecksumMismatch {
+            path: path.to_path_buf(),
+            expected: expected.to_string(),
+            actual: actual.to_string(),
+        })
+    }
+}
+
+/// Tamaño y SHA-256 de un archivo, leyéndolo en streaming
+pub(crate) fn sha256_file(path: &Path) -> std::io::Result<(u64, String)> {
+    sha256_reader(File::open(path)?)
+}
+
+/// Tamaño y SHA-256 de todo lo que entrega `reader`
+pub(crate) fn sha256_reader(mut reader: impl Read) -> std::io::Result<(u64, String)> {
+    let mut hashing = HashingWriter::new(std::io::sink());
+    let size = std::io::copy(&mut reader, &mut hashing)?;
+    Ok((size, hashing.finish()))
+}
+
+fn hex(digest: &[u8]) -> String {
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Writer que calcula el SHA-256 de lo que pasa por él
+struct HashingWriter<W> {
+    inner: W,
+    hasher: Sha256,
+}
+
+impl<W: Write> HashingWriter<W> {
+    fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+        }
+    }
+
+    fn finish(self) -> String {
+        hex(&self.hasher.finalize())
+    }
+}
+
+/// Reader que calcula el SHA-256 de lo que se lee a través de él
+pub(crate) struct HashingReader<R> {
+    inner: R,
+    hasher: Sha256,
+}
+
+impl<R: Read> HashingReader<R> {
+    pub(crate) fn new(inner: R) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+        }
+    }
+
+    pub(crate) fn finish(self) -> String {
+        hex(&self.hasher.finalize())
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_put_deduplicates_and_verifies() {
+        let dir = tempfile::tempdir().unwrap();
+        let blobs = BlobStore::open(dir.path()).unwrap();
+
+        let first = blobs.put_bytes(b"DICM payload").unwrap();
+        let second = blobs.put_bytes(b"DICM payload").unwrap();
+        assert!(!first.deduplicated);
+        assert!(second.deduplicated);
+        assert_eq!(first.path, second.path);
+        assert!(first.path.starts_with(dir.path().join(&first.sha256[..2]).join(&first.sha256[2..4])));
+        assert_eq!(blobs.read(&first.sha256).unwrap(), b"DICM payload");
+        assert_eq!(blobs.list().unwrap().len(), 1);
+        assert_eq!(std::fs::read_dir(dir.path().join(TMP_DIR)).unwrap().count(), 0);
+
+        std::fs::write(&first.path, b"DICM pay1oad").unwrap();
+        assert!(matches!(blobs.read(&first.sha256), Err(StorageError::ChecksumMismatch { .. })));
+        assert!(blobs.verify(&first.sha256).is_err());
+    }
+
+    #[test]
+    fn test_path_for_rejects_invalid_hashes() {
+        let blobs = BlobStore { root: PathBuf::from("/blobs") };
+        assert!(blobs.path_for("../../etc/passwd").is_err());
+        assert!(blobs.path_for(&"A".repeat(64)).is_err());
+        assert!(blobs.path_for(&"a".repeat(64)).is_ok());
+    }
+}
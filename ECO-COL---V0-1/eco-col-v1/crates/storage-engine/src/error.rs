@@ -0,0 +1,50 @@
+//! Error types del storage engine
+
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Resultado genérico para operaciones de almacenamiento
+pub type Result<T> = std::result::Result<T, StorageError>;
+
+/// Errores del índice de estudios
+#[derive(Error, Debug)]
+pub enum StorageError {
+    #[error("Error de base de datos: {0}")]
+    Database(#[from] rusqlite::Error),
+
+    #[error("Error DICOM: {0}")]
+    Dicom(#[from] dicom_core::DicomError),
+
+    #[error("Error de I/O: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("SHA-256 de {path:?} no coincide: esperado {expected}, calculado {actual}")]
+    ChecksumMismatch {
+        path: PathBuf,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("La base tiene el esquema v{found}, más nuevo que el soportado (v{supported})")]
+    SchemaTooNew { found: u32, supported: u32 },
+
+    #[error("Error en la migración v{version} ({name}): {error}")]
+    Migration {
+        version: u32,
+        name: &'static str,
+        error: rusqlite::Error,
+    },
+
+    #[error("Configuración inválida: {0}")]
+    InvalidConfig(String),
+
+    #[error("Instancia inválida para el índice: {0}")]
+    InvalidInstance(String),
+}
+
+impl StorageError {
+    /// Crea un error de instancia inválida con mensaje custom
+    pub fn invalid_instance(msg: impl Into<String>) -> Self {
+        StorageError::InvalidInstance(msg.into())
+    }
+}
@@ -0,0 +1,124 @@
+//! Verificación de consistencia entre el índice y los archivos
+//!
+//! Compara cada fila de `instances` con su archivo (existencia y SHA-256)
+//! y busca objetos del [`BlobStore`] que ninguna fila referencia. Solo
+//! reporta: no borra ni corrige nada.
+
+use crate::blob::{sha256_file, BlobStore};
+use crate::error::Result;
+use crate::store::StudyStore;
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+/// Inconsistencia encontrada
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FsckIssue {
+    /// La fila apunta a un archivo que no existe
+    MissingFile { sop_instance_uid: String, path: PathBuf },
+
+    /// El contenido del archivo no coincide con `instances.file_sha256`
+    ChecksumMismatch {
+        sop_instance_uid: String,
+        path: PathBuf,
+        expected: String,
+        actual: String,
+    },
+
+    /// El tamaño no coincide con `instances.file_size_bytes`
+    SizeMismatch {
+        sop_instance_uid: String,
+        path: PathBuf,
+        expected: u64,
+        actual: u64,
+    },
+
+    /// Objeto del almacén sin fila en `instances`
+    Orphan { path: PathBuf },
+}
+
+/// Resultado de [`StudyStore::fsck`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FsckReport {
+    /// Filas de `instances` verificadas
+    pub instances_checked: usize,
+
+    /// Objetos encontrados en el almacén
+    pub blobs_checked: usize,
+
+    pub issues: Vec<FsckIssue>,
+}
+
+impl FsckReport {
+    /// Verificar si no hay inconsistencias
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl StudyStore {
+    /// Verificar las filas de `instances` contra los archivos y los objetos
+    /// de `blobs` contra las filas
+    ///
+    /// Las instancias registradas con [`StudyStore::ingest`] fuera del
+    /// almacén también se verifican.
+    pub fn fsck(&self, blobs: &BlobStore) -> Result<FsckReport> {
+        let mut report = FsckReport::default();
+
+        let mut stmt = self
+            .connection()
+            .prepare("SELECT sop_instance_uid, file_path, file_size_bytes, file_sha256 FROM instances ORDER BY file_path")?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    PathBuf::from(row.get::<_, String>(1)?),
+                    row.get::<_, u64>(2)?,
+                    row.get::<_, String>(3)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut referenced = HashSet::new();
+        for (sop_instance_uid, path, expected_size, expected_sha256) in rows {
+            report.instances_checked += 1;
+            referenced.insert(path.clone());
+
+            if !path.is_file() {
+                report.issues.push(FsckIssue::MissingFile { sop_instance_uid, path });
+                continue;
+            }
+
+            let (size, sha256) = sha256_file(&path)?;
+            if !sha256.eq_ignore_ascii_case(&expected_sha256) {
+                report.issues.push(FsckIssue::ChecksumMismatch {
+                    sop_instance_uid,
+                    path,
+                    expected: expected_sha256,
+                    actual: sha256,
+                });
+            } else if size != expected_size {
+                report.issues.push(FsckIssue::SizeMismatch {
+                    sop_instance_uid,
+                    path,
+                    expected: expected_size,
+                    actual: size,
+                });
+            }
+        }
+
+        for blob in blobs.list()? {
+            report.blobs_checked += 1;
+            if !referenced.contains(&blob.path) {
+                report.issues.push(FsckIssue::Orphan { path: blob.path });
+            }
+        }
+
+        if !report.is_clean() {
+            tracing::warn!("fsck: {} inconsistencias", report.issues.len());
+        }
+        Ok(report)
+    }
+}
@@ -1,10 +1,60 @@
-//! ECO-COL V1 - Crate Library
-//! FASE 0 - Estructura inicial
+//! # Storage Engine
+//!
+//! Índice local de estudios DICOM sobre SQLite (`sql/schema.sql`).
+//!
+//! ## Características
+//!
+//! - ✅ Esquema con WAL y foreign keys, migraciones versionadas (`user_version`)
+//! - ✅ Ingesta de instancias en Patient > Study > Series > Instance (upsert)
+//! - ✅ Consultas tipadas por paciente, rango de fechas y Accession Number
+//! - ✅ Almacén de archivos por SHA-256 (deduplicado, escritura atómica)
+//! - ✅ fsck: consistencia entre `instances` y los archivos en disco
+//! - ✅ Retención: archivo en bundles `.tar.gz`, borrado programado y auditoría
+//! - ✅ Cuota de disco con marcas alta/baja y desalojo por último acceso
+//!
+//! ## Uso Básico
+//!
+//! ```rust,no_run
+//! use dicom_core::DicomParser;
+//! use storage_engine::StudyStore;
+//! use std::path::Path;
+//!
+//! let mut store = StudyStore::open(Path::new("eco-col.db"))?;
+//! let instance = DicomParser::new().parse_file(Path::new("study.dcm"))?;
+//! store.ingest(&instance)?;
+//!
+//! for study in store.studies_by_patient(&instance.metadata.patient_id)? {
+//!     println!("{} {:?}", study.study_instance_uid, study.study_date);
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+pub mod store;
+pub mod records;
+pub mod blob;
+pub mod fsck;
+pub mod lifecycle;
+pub mod migrate;
+pub mod quota;
+pub mod error;
+
+// Re-exports
+pub use store::StudyStore;
+pub use blob::{BlobStore, StoredBlob};
+pub use fsck::{FsckIssue, FsckReport};
+pub use migrate::{MigrationOutcome, SCHEMA_VERSION};
+pub use quota::{QuotaManager, QuotaOptions, QuotaReport, StorageUsage};
+pub use lifecycle::{LifecycleAction, LifecycleOptions, LifecycleReport, LifecycleWorker, RetentionHold};
+pub use records::{InstanceRecord, PatientRecord, SeriesRecord, StudyRecord};
+pub use error::{Result, StorageError};
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
     #[test]
-    fn it_works() {
-        assert_eq!(2 + 2, 4);
+    fn test_store_creation() {
+        let store = StudyStore::open_in_memory().unwrap();
+        assert!(store.patient("CC123456").unwrap().is_none());
     }
 }
@@ -0,0 +1,534 @@
+//! Ciclo de vida de los estudios: retención, archivo y borrado
+//!
+//! La expiración se fija al ingerir (`retention_expires_at`, con
+//! `system_config.retention_days`). Cada corrida de [`LifecycleWorker`]:
+//!
+//! 1. Borra las instancias de los estudios archivados cuyo
+//!    `deletion_scheduled_at` ya pasó: primero las filas de `series` e
+//!    `instances` y después los archivos importados al
+//!    [`BlobStore`](crate::BlobStore); los registrados con
+//!    [`StudyStore::ingest`] son del llamador y no se tocan. El estudio
+//!    (con sus informes, anotaciones y asignaciones) y el paciente se
+//!    conservan, con
+//!    `is_archived = 1` y la ruta del bundle en `archive_bundle_path`.
+//! 2. Archiva los estudios expirados en un bundle `.tar.gz` con manifest y
+//!    programa su borrado tras el período de gracia.
+//!
+//! El SHA-256 de cada archivo se compara con `instances.file_sha256` al
+//! escribir el bundle, y el bundle se vuelve a leer y verificar antes de
+//! renombrarlo y antes de borrar. Ante una diferencia no se borra nada.
+//!
+//! Un estudio protegido (`is_protected`) o sin informe final
+//! (`has_completed_report`) nunca se archiva ni se borra. Cada acción queda
+//! en `audit_log`; en modo dry-run solo se calcula el plan.
+
+use crate::blob::{check, sha256_reader, HashingReader};
+use crate::error::{Result, StorageError};
+use crate::records::{PatientRecord, StudyRecord, PATIENT_COLUMNS, STUDY_COLUMNS};
+use crate::store::StudyStore;
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Usuario registrado en `audit_log` por defecto
+pub const LIFECYCLE_USER: &str = "lifecycle";
+
+/// Opciones del worker
+#[derive(Debug, Clone)]
+pub struct LifecycleOptions {
+    /// Directorio de los bundles (`<Study Instance UID>.tar.gz`)
+    pub archive_dir: PathBuf,
+
+    /// Tiempo entre el archivo y el borrado de los archivos originales
+    pub deletion_grace: Duration,
+
+    /// Calcular las acciones sin archivar, borrar ni escribir en la base
+    pub dry_run: bool,
+
+    /// `audit_log.user_id` de las acciones
+    pub user_id: String,
+}
+
+impl LifecycleOptions {
+    pub fn new(archive_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            archive_dir: archive_dir.into(),
+            deletion_grace: Duration::from_secs(7 * 86_400),
+            dry_run: false,
+            user_id: LIFECYCLE_USER.to_string(),
+        }
+    }
+}
+
+/// Motivo para no archivar ni borrar un estudio expirado
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionHold {
+    /// Marcado como protegido (`is_protected`)
+    Protected,
+    /// Sin informe final (`has_completed_report`)
+    NoFinalReport,
+}
+
+/// Acción realizada (o planificada en dry-run) sobre un estudio
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum LifecycleAction {
+    /// Bundle creado y borrado programado
+    Archived {
+        study_instance_uid: String,
+        bundle: PathBuf,
+        instances: usize,
+        deletion_scheduled_at: i64,
+    },
+
+    /// Series e instancias eliminadas, con los archivos del
+    /// [`BlobStore`](crate::BlobStore) (el estudio queda archivado)
+    Purged {
+        study_instance_uid: String,
+        files_removed: usize,
+        bytes_freed: u64,
+    },
+
+    /// Estudio expirado que se conserva
+    Held {
+        study_instance_uid: String,
+        reason: RetentionHold,
+    },
+
+    /// No se pudo crear o verificar el bundle; no se borra nada y se
+    /// reintenta en la próxima corrida
+    Failed { study_instance_uid: String, error: String },
+}
+
+/// Resultado de una corrida
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LifecycleReport {
+    pub dry_run: bool,
+    pub actions: Vec<LifecycleAction>,
+}
+
+/// Worker de retención; llamar a [`run`](Self::run) periódicamente
+#[derive(Debug, Clone)]
+pub struct LifecycleWorker {
+    options: LifecycleOptions,
+}
+
+impl LifecycleWorker {
+    pub fn new(options: LifecycleOptions) -> Self {
+        Self { options }
+    }
+
+    pub fn options(&self) -> &LifecycleOptions {
+        &self.options
+    }
+
+    /// Ejecutar una corrida con la hora actual
+    pub fn run(&self, store: &mut StudyStore) -> Result<LifecycleReport> {
+        self.run_at(store, chrono::Utc::now().timestamp())
+    }
+
+    /// Ejecutar una corrida como si fuera `now` (segundos Unix)
+    pub fn run_at(&self, store: &mut StudyStore, now: i64) -> Result<LifecycleReport> {
+        let mut report = LifecycleReport {
+            dry_run: self.options.dry_run,
+            actions: Vec::new(),
+        };
+
+        let due = query_studies(
+            store.connection(),
+            "is_archived = 1 AND deletion_scheduled_at IS NOT NULL AND deletion_scheduled_at <= ?1",
+            now,
+        )?;
+        for study in due {
+            let action = match hold(&study) {
+                Some(reason) => held(study, reason),
+                None => self.purge(store.connection_mut(), study, PurgeCause::Retention)?,
+            };
+            report.actions.push(action);
+        }
+
+        let expired = query_studies(store.connection(), "is_archived = 0 AND retention_expires_at <= ?1", now)?;
+        for study in expired {
+            let action = match hold(&study) {
+                Some(reason) => held(study, reason),
+                None => self.archive(store.connection_mut(), study, now)?,
+            };
+            report.actions.push(action);
+        }
+
+        if !report.actions.is_empty() {
+            tracing::info!(
+                "Ciclo de vida{}: {} acciones",
+                if report.dry_run { " (dry-run)" } else { "" },
+                report.actions.len()
+            );
+        }
+        Ok(report)
+    }
+
+    // ============================================
+    // Archivo
+    // ============================================
+
+    pub(crate) fn archive(&self, conn: &mut Connection, study: StudyRecord, now: i64) -> Result<LifecycleAction> {
+        let uid = study.study_instance_uid.clone();
+        let bundle = match self.bundle_path(&uid) {
+            Ok(bundle) => bundle,
+            Err(e) => return self.failed(conn, uid, "study_archive_failed", "Error al crear el bundle", e),
+        };
+        let files = study_files(conn, &uid)?;
+        let deletion_scheduled_at = now + self.options.deletion_grace.as_secs() as i64;
+
+        if self.options.dry_run {
+            return Ok(LifecycleAction::Archived {
+                study_instance_uid: uid,
+                bundle,
+                instances: files.len(),
+                deletion_scheduled_at,
+            });
+        }
+
+        if let Err(e) = write_bundle(conn, &study, &files, &bundle) {
+            return self.failed(conn, uid, "study_archive_failed", "Error al crear el bundle", e);
+        }
+
+        let tx = conn.transaction()?;
+        tx.execute(
+            "UPDATE studies SET is_archived = 1, archived_at = ?2, deletion_scheduled_at = ?3,
+                 archive_bundle_path = ?4
+             WHERE study_instance_uid = ?1",
+            params![uid, now, deletion_scheduled_at, bundle.to_string_lossy()],
+        )?;
+        audit(
+            &tx,
+            &self.options.user_id,
+            "study_archived",
+            "data_modification",
+            &uid,
+            &format!("{} instancias archivadas en {}", files.len(), bundle.display()),
+        )?;
+        tx.commit()?;
+
+        Ok(LifecycleAction::Archived {
+            study_instance_uid: uid,
+            bundle,
+            instances: files.len(),
+            deletion_scheduled_at,
+        })
+    }
+
+    // ============================================
+    // Borrado
+    // ============================================
+
+    pub(crate) fn purge(&self, conn: &mut Connection, study: StudyRecord, cause: PurgeCause) -> Result<LifecycleAction> {
+        let uid = study.study_instance_uid;
+        // Estudios archivados antes de guardar la ruta: la ubicación por defecto
+        let bundle = match study.archive_bundle_path.map_or_else(|| self.bundle_path(&uid), Ok) {
+            Ok(bundle) => bundle,
+            Err(e) => return self.failed(conn, uid, "study_purge_failed", "Bundle no verificado, no se borra", e),
+        };
+        let files = study_files(conn, &uid)?;
+
+        if self.options.dry_run {
+            let owned = files.iter().filter(|f| f.store_owned);
+            return Ok(LifecycleAction::Purged {
+                study_instance_uid: uid,
+                files_removed: owned.clone().count(),
+                bytes_freed: owned.map(|f| f.size).sum(),
+            });
+        }
+
+        if let Err(e) = verify_bundle(&bundle, &files) {
+            return self.failed(conn, uid, "study_purge_failed", "Bundle no verificado, no se borra", e);
+        }
+
+        // Las filas se borran antes que los archivos: un corte deja archivos
+        // sin fila (que fsck reporta), nunca filas sin archivo
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM series WHERE study_instance_uid = ?1", [&uid])?;
+        tx.execute(
+            "UPDATE studies SET is_archived = 1, deletion_scheduled_at = NULL, archive_bundle_path = ?2
+             WHERE study_instance_uid = ?1",
+            params![uid, bundle.to_string_lossy()],
+        )?;
+        audit(
+            &tx,
+            &self.options.user_id,
+            cause.event_type(),
+            "data_modification",
+            &uid,
+            &format!("{} instancias eliminadas {}", files.len(), cause.description()),
+        )?;
+        tx.commit()?;
+
+        let mut files_removed = 0;
+        let mut bytes_freed = 0;
+        // Los archivos registrados con `ingest` son del llamador
+        for file in files.iter().filter(|f| f.store_owned) {
+            match std::fs::remove_file(&file.path) {
+                Ok(()) => {
+                    files_removed += 1;
+                    bytes_freed += file.size;
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => tracing::warn!("No se pudo borrar {:?}: {}", file.path, e),
+            }
+        }
+
+        Ok(LifecycleAction::Purged {
+            study_instance_uid: uid,
+            files_removed,
+            bytes_freed,
+        })
+    }
+
+    /// Bundle por defecto de un estudio
+    fn bundle_path(&self, study_uid: &str) -> Result<PathBuf> {
+        Ok(self.options.archive_dir.join(format!("{}.tar.gz", path_component(study_uid)?)))
+    }
+
+    /// Registrar un archivo o borrado fallido
+    fn failed(
+        &self,
+        conn: &Connection,
+        uid: String,
+        event_type: &str,
+        context: &str,
+        error: StorageError,
+    ) -> Result<LifecycleAction> {
+        tracing::warn!("{} ({}): {}", context, uid, error);
+        let error = error.to_string();
+        audit(conn, &self.options.user_id, event_type, "system", &uid, &format!("{}: {}", context, error))?;
+        Ok(LifecycleAction::Failed {
+            study_instance_uid: uid,
+            error,
+        })
+    }
+}
+
+/// Motivo del borrado, para `audit_log`
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum PurgeCause {
+    /// Venció el período de gracia tras el archivo
+    Retention,
+    /// Desalojo por falta de espacio (ver [`quota`](crate::quota))
+    Quota,
+}
+
+impl PurgeCause {
+    fn event_type(self) -> &'static str {
+        match self {
+            PurgeCause::Retention => "study_purged",
+            PurgeCause::Quota => "study_evicted",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            PurgeCause::Retention => "tras el período de gracia",
+            PurgeCause::Quota => "para liberar espacio (cuota de disco)",
+        }
+    }
+}
+
+fn hold(study: &StudyRecord) -> Option<RetentionHold> {
+    if study.is_protected {
+        Some(RetentionHold::Protected)
+    } else if !study.has_completed_report {
+        Some(RetentionHold::NoFinalReport)
+    } else {
+        None
+    }
+}
+
+fn held(study: StudyRecord, reason: RetentionHold) -> LifecycleAction {
+    LifecycleAction::Held {
+        study_instance_uid: study.study_instance_uid,
+        reason,
+    }
+}
+
+fn query_studies(conn: &Connection, filter: &str, now: i64) -> Result<Vec<StudyRecord>> {
+    let sql = format!(
+        "SELECT {} FROM studies WHERE {} ORDER BY retention_expires_at, study_instance_uid",
+        STUDY_COLUMNS, filter
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map([now], StudyRecord::from_row)?;
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
+
+/// Registrar una acción en `audit_log`
+fn audit(conn: &Connection, user_id: &str, event_type: &str, category: &str, study_uid: &str, description: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO audit_log (event_type, event_category, user_id, description, entity_type, entity_id)
+         VALUES (?1, ?2, ?3, ?4, 'study', ?5)",
+        params![event_type, category, user_id, description, study_uid],
+    )?;
+    Ok(())
+}
+
+// ============================================
+// Bundle
+// ============================================
+
+/// Archivo de una instancia del estudio
+#[derive(Debug, Serialize)]
+struct StudyFile {
+    series_instance_uid: String,
+    sop_instance_uid: String,
+    path: PathBuf,
+    size: u64,
+    sha256: String,
+    /// Solo se borran al purgar los archivos importados al BlobStore
+    #[serde(skip)]
+    store_owned: bool,
+}
+
+/// UID usable como nombre de archivo: solo dígitos y puntos
+///
+/// `ingest` ya rechaza UIDs no conformes; esto cubre filas anteriores.
+fn path_component(uid: &str) -> Result<&str> {
+    if !uid.bytes().any(|b| b.is_ascii_digit()) || !uid.bytes().all(|b| b.is_ascii_digit() || b == b'.') {
+        return Err(StorageError::invalid_instance(format!("UID no apto como nombre de archivo: {:?}", uid)));
+    }
+    Ok(uid)
+}
+
+impl StudyFile {
+    /// Nombre dentro del bundle
+    fn entry_name(&self) -> String {
+        format!("{}/{}.dcm", self.series_instance_uid, self.sop_instance_uid)
+    }
+}
+
+fn study_files(conn: &Connection, study_uid: &str) -> Result<Vec<StudyFile>> {
+    let mut stmt = conn.prepare(
+        "SELECT i.series_instance_uid, i.sop_instance_uid, i.file_path, i.file_size_bytes, i.file_sha256,
+                i.store_owned
+         FROM instances i JOIN series s ON s.series_instance_uid = i.series_instance_uid
+         WHERE s.study_instance_uid = ?1
+         ORDER BY s.series_number, i.series_instance_uid, i.instance_number, i.sop_instance_uid",
+    )?;
+    let rows = stmt.query_map([study_uid], |row| {
+        Ok(StudyFile {
+            series_instance_uid: row.get(0)?,
+            sop_instance_uid: row.get(1)?,
+            path: PathBuf::from(row.get::<_, String>(2)?),
+            size: row.get(3)?,
+            sha256: row.get(4)?,
+            store_owned: row.get(5)?,
+        })
+    })?;
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
+
+/// Contenido de `manifest.json` dentro del bundle
+#[derive(Serialize)]
+struct Manifest<'a> {
+    patient: Option<PatientRecord>,
+    study: &'a StudyRecord,
+    instances: Vec<ManifestEntry<'a>>,
+}
+
+#[derive(Serialize)]
+struct ManifestEntry<'a> {
+    entry: String,
+    #[serde(flatten)]
+    file: &'a StudyFile,
+}
+
+/// Escribir el bundle en un temporal y renombrarlo al terminar
+///
+/// Cada archivo se hashea mientras se copia al tar; el temporal se vuelve
+/// a leer y verificar antes del rename.
+fn write_bundle(conn: &Connection, study: &StudyRecord, files: &[StudyFile], bundle: &Path) -> Result<()> {
+    for file in files {
+        path_component(&file.series_instance_uid)?;
+        path_component(&file.sop_instance_uid)?;
+    }
+    let patient = conn
+        .query_row(
+            &format!("SELECT {} FROM patients WHERE patient_id = ?1", PATIENT_COLUMNS),
+            [&study.patient_id],
+            PatientRecord::from_row,
+        )
+        .ok();
+    let manifest = Manifest {
+        patient,
+        study,
+        instances: files
+            .iter()
+            .map(|file| ManifestEntry {
+                entry: file.entry_name(),
+                file,
+            })
+            .collect(),
+    };
+    let manifest = serde_json::to_vec_pretty(&manifest).map_err(std::io::Error::from)?;
+
+    if let Some(parent) = bundle.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp = bundle.with_extension("gz.tmp");
+    let written = (|| -> Result<()> {
+        let mut tar = tar::Builder::new(GzEncoder::new(File::create(&tmp)?, Compression::default()));
+
+        let mut header = tar::Header::new_gnu();
+        header.set_size(manifest.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(study.created_at.max(0) as u64);
+        header.set_cksum();
+        tar.append_data(&mut header, "manifest.json", manifest.as_slice())?;
+
+        for file in files {
+            let source = File::open(&file.path)?;
+            let mut header = tar::Header::new_gnu();
+            header.set_metadata(&source.metadata()?);
+            let mut hashing = HashingReader::new(source);
+            tar.append_data(&mut header, file.entry_name(), &mut hashing)?;
+            check(&file.path, &file.sha256, &hashing.finish())?;
+        }
+        tar.into_inner()?.finish()?.sync_all()?;
+        verify_bundle(&tmp, files)
+    })();
+
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e);
+    }
+    std::fs::rename(&tmp, bundle)?;
+    Ok(())
+}
+
+/// Releer el bundle y comparar cada entrada con `instances.file_sha256`
+fn verify_bundle(bundle: &Path, files: &[StudyFile]) -> Result<()> {
+    let mut pending: BTreeMap<String, &StudyFile> = files.iter().map(|file| (file.entry_name(), file)).collect();
+
+    let mut archive = tar::Archive::new(GzDecoder::new(File::open(bundle)?));
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().into_owned();
+        let Some(file) = pending.remove(&name) else { continue };
+        let (_, actual) = sha256_reader(&mut entry)?;
+        check(&bundle.join(&name), &file.sha256, &actual)?;
+    }
+
+    match pending.into_keys().next() {
+        Some(name) => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("{} no está en el bundle {}", name, bundle.display()),
+        )
+        .into()),
+        None => Ok(()),
+    }
+}
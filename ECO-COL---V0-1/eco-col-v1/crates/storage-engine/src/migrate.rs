@@ -0,0 +1,170 @@
+//! Migraciones versionadas del esquema
+//!
+//! La versión de la base es `PRAGMA user_version`. La v1 es
+//! `sql/schema.sql` tal cual (el mismo script que usa el instalador) y cada
+//! cambio posterior es un script en `sql/migrations/`, aplicado en orden y
+//! en su propia transacción junto con el nuevo `user_version`. Las líneas
+//! `PRAGMA` de un script se ejecutan antes de abrir su transacción.
+//!
+//! Las bases creadas antes de las migraciones tienen `user_version = 0`;
+//! como `schema.sql` es idempotente se actualizan desde cero sin perder
+//! datos.
+
+use crate::error::{Result, StorageError};
+
+use rusqlite::Connection;
+use std::path::PathBuf;
+
+/// Migración embebida en el binario
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    sql: &'static str,
+}
+
+/// Migraciones en orden; la versión de cada una es su posición + 1
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial",
+        sql: include_str!("../../../sql/schema.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "study_query_indexes",
+        sql: include_str!("../../../sql/migrations/0002_study_query_indexes.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "study_archive_bundle",
+        sql: include_str!("../../../sql/migrations/0003_study_archive_bundle.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "instance_store_owned",
+        sql: include_str!("../../../sql/migrations/0004_instance_store_owned.sql"),
+    },
+];
+
+/// Versión del esquema que espera este binario
+pub const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/// Pragmas de `schema.sql` que no persisten en el archivo y hay que
+/// aplicar en cada conexión (WAL sí persiste; repetirlo no cuesta nada)
+const CONNECTION_PRAGMAS: &str = "PRAGMA journal_mode = WAL;
+PRAGMA synchronous = NORMAL;
+PRAGMA foreign_keys = ON;";
+
+/// Resultado de [`migrate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationOutcome {
+    /// Versión al abrir
+    pub from: u32,
+
+    /// Versión final (siempre [`SCHEMA_VERSION`])
+    pub to: u32,
+
+    /// Copia de la base tomada antes de migrar
+    pub backup: Option<PathBuf>,
+}
+
+/// Versión del esquema de la base
+pub fn schema_version(conn: &Connection) -> Result<u32> {
+    Ok(conn.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+}
+
+/// Llevar la base a [`SCHEMA_VERSION`]
+///
+/// Antes de modificar una base con datos se guarda una copia con
+/// `VACUUM INTO` junto al archivo (`<base>.v<versión>-<timestamp>.bak`).
+/// Una base más nueva que el binario se rechaza sin tocarla.
+pub fn migrate(conn: &mut Connection) -> Result<MigrationOutcome> {
+    let from = schema_version(conn)?;
+    if from > SCHEMA_VERSION {
+        return Err(StorageError::SchemaTooNew {
+            found: from,
+            supported: SCHEMA_VERSION,
+        });
+    }
+
+    conn.execute_batch(CONNECTION_PRAGMAS)?;
+
+    let mut outcome = MigrationOutcome {
+        from,
+        to: from,
+        backup: None,
+    };
+    if from == SCHEMA_VERSION {
+        return Ok(outcome);
+    }
+
+    outcome.backup = backup(conn, from)?;
+
+    for migration in &MIGRATIONS[from as usize..] {
+        let failed = |error| StorageError::Migration {
+            version: migration.version,
+            name: migration.name,
+            error,
+        };
+
+        // SQLite no permite cambiar algunos pragmas dentro de una transacción
+        let (pragmas, body): (Vec<&str>, Vec<&str>) = migration
+            .sql
+            .lines()
+            .partition(|line| line.trim_start().to_ascii_uppercase().starts_with("PRAGMA"));
+        conn.execute_batch(&pragmas.join("\n")).map_err(failed)?;
+
+        let tx = conn.transaction()?;
+        tx.execute_batch(&body.join("\n")).map_err(failed)?;
+        tx.pragma_update(None, "user_version", migration.version)?;
+        tx.commit()?;
+
+        tracing::info!("Esquema migrado a v{} ({})", migration.version, migration.name);
+        outcome.to = migration.version;
+    }
+
+    Ok(outcome)
+}
+
+/// Copiar la base antes de migrar, si es un archivo y tiene tablas
+fn backup(conn: &Connection, version: u32) -> Result<Option<PathBuf>> {
+    let Some(path) = conn.path().filter(|p| !p.is_empty()) else {
+        return Ok(None);
+    };
+    let tables: u32 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_schema WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+        [],
+        |row| row.get(0),
+    )?;
+    if tables == 0 {
+        return Ok(None);
+    }
+
+    let backup = PathBuf::from(format!("{}.v{}-{}.bak", path, version, chrono::Utc::now().timestamp()));
+    conn.execute("VACUUM INTO ?1", [backup.to_string_lossy()])?;
+    tracing::info!("Copia de seguridad antes de migrar: {:?}", backup);
+    Ok(Some(backup))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrations_are_numbered_in_order() {
+        for (i, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version as usize, i + 1, "{}", migration.name);
+        }
+    }
+
+    #[test]
+    fn test_migrate_is_idempotent() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        let first = migrate(&mut conn).unwrap();
+        assert_eq!((first.from, first.to, first.backup), (0, SCHEMA_VERSION, None));
+
+        let second = migrate(&mut conn).unwrap();
+        assert_eq!((second.from, second.to), (SCHEMA_VERSION, SCHEMA_VERSION));
+    }
+}
@@ -0,0 +1,205 @@
+//! Cuota de disco y desalojo por falta de espacio
+//!
+//! El uso se calcula con `instances.file_size_bytes`: cubre solo las
+//! instancias vivas. Los bundles del archivo (`LifecycleOptions::archive_dir`)
+//! no cuentan, aunque desalojar un estudio sin archivar escriba uno; el
+//! directorio de archivo debe dimensionarse aparte. Desalojar instancias
+//! registradas con `ingest` baja el uso pero no borra sus archivos, que son
+//! del llamador. Cuando el uso supera la
+//! marca alta, [`QuotaManager`] desaloja estudios hasta bajar de la marca
+//! baja, en este orden:
+//!
+//! 1. Estudios ya archivados (el bundle conserva los datos).
+//! 2. Estudios con informe final aún sin archivar: se archivan y se borran.
+//!
+//! Dentro de cada grupo se desaloja primero el paciente accedido hace más
+//! tiempo (`patients.last_accessed`). Los estudios protegidos y los que no
+//! tienen informe final nunca se desalojan. El archivo, el borrado, la
+//! auditoría y el dry-run son los del [`LifecycleWorker`].
+
+use crate::error::{Result, StorageError};
+use crate::lifecycle::{LifecycleAction, LifecycleOptions, LifecycleWorker, PurgeCause};
+use crate::records::{StudyRecord, STUDY_COLUMNS};
+use crate::store::StudyStore;
+
+use serde::{Deserialize, Serialize};
+
+const GB: u64 = 1024 * 1024 * 1024;
+
+/// Opciones de la cuota
+#[derive(Debug, Clone)]
+pub struct QuotaOptions {
+    /// Uso (bytes) a partir del cual se desaloja
+    pub high_watermark: u64,
+
+    /// Uso (bytes) al que se baja al desalojar
+    pub low_watermark: u64,
+
+    /// Archivo, auditoría y dry-run
+    pub lifecycle: LifecycleOptions,
+}
+
+impl QuotaOptions {
+    /// Marcas por defecto para el objetivo de diseño: 100 estudios de
+    /// 500 MB por ciclo (50 GB), bajando al 80%
+    pub fn new(lifecycle: LifecycleOptions) -> Self {
+        Self {
+            high_watermark: 50 * GB,
+            low_watermark: 40 * GB,
+            lifecycle,
+        }
+    }
+}
+
+/// Uso actual del almacenamiento (solo instancias vivas, sin bundles)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageUsage {
+    /// Suma de `instances.file_size_bytes`
+    pub bytes: u64,
+    pub instances: u64,
+    /// Estudios con al menos una instancia (los ya borrados no cuentan)
+    pub studies: u64,
+}
+
+/// Resultado de [`QuotaManager::enforce`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QuotaReport {
+    pub dry_run: bool,
+
+    /// Uso antes de desalojar
+    pub usage_before: u64,
+
+    /// Uso después de desalojar (estimado en dry-run)
+    pub usage_after: u64,
+
+    /// Archivos y borrados realizados
+    pub actions: Vec<LifecycleAction>,
+
+    /// Bytes que faltaron para llegar a la marca baja (todo lo que quedaba
+    /// estaba protegido o sin informe final)
+    pub shortfall: u64,
+}
+
+impl StudyStore {
+    /// Uso del almacenamiento según el índice
+    pub fn storage_usage(&self) -> Result<StorageUsage> {
+        Ok(self.connection().query_row(
+            "SELECT COALESCE(SUM(i.file_size_bytes), 0), COUNT(*), COUNT(DISTINCT s.study_instance_uid)
+             FROM instances i JOIN series s ON s.series_instance_uid = i.series_instance_uid",
+            [],
+            |row| {
+                Ok(StorageUsage {
+                    bytes: row.get(0)?,
+                    instances: row.get(1)?,
+                    studies: row.get(2)?,
+                })
+            },
+        )?)
+    }
+}
+
+/// Control de la cuota; llamar a [`enforce`](Self::enforce) tras cada
+/// ingesta o periódicamente
+#[derive(Debug, Clone)]
+pub struct QuotaManager {
+    high_watermark: u64,
+    low_watermark: u64,
+    lifecycle: LifecycleWorker,
+}
+
+impl QuotaManager {
+    /// Falla si la marca baja supera a la alta
+    pub fn new(options: QuotaOptions) -> Result<Self> {
+        if options.low_watermark > options.high_watermark {
+            return Err(StorageError::InvalidConfig(format!(
+                "Marca baja ({} bytes) mayor que la alta ({} bytes)",
+                options.low_watermark, options.high_watermark
+            )));
+        }
+        Ok(Self {
+            high_watermark: options.high_watermark,
+            low_watermark: options.low_watermark,
+            lifecycle: LifecycleWorker::new(options.lifecycle),
+        })
+    }
+
+    /// Verificar si el uso supera la marca alta
+    pub fn under_pressure(&self, store: &StudyStore) -> Result<bool> {
+        Ok(store.storage_usage()?.bytes > self.high_watermark)
+    }
+
+    /// Desalojar si el uso supera la marca alta
+    pub fn enforce(&self, store: &mut StudyStore) -> Result<QuotaReport> {
+        self.enforce_at(store, chrono::Utc::now().timestamp())
+    }
+
+    /// Igual que [`enforce`](Self::enforce), como si fuera `now` (segundos Unix)
+    pub fn enforce_at(&self, store: &mut StudyStore, now: i64) -> Result<QuotaReport> {
+        let usage = store.storage_usage()?.bytes;
+        let mut report = QuotaReport {
+            dry_run: self.lifecycle.options().dry_run,
+            usage_before: usage,
+            usage_after: usage,
+            ..QuotaReport::default()
+        };
+        if usage <= self.high_watermark {
+            return Ok(report);
+        }
+
+        for (study, bytes) in eviction_candidates(store)? {
+            if report.usage_after <= self.low_watermark {
+                break;
+            }
+
+            let uid = study.study_instance_uid.clone();
+            if !study.is_archived {
+                let archived = self.lifecycle.archive(store.connection_mut(), study.clone(), now)?;
+                let failed = matches!(archived, LifecycleAction::Failed { .. });
+                report.actions.push(archived);
+                if failed {
+                    continue;
+                }
+            }
+
+            let purged = self.lifecycle.purge(store.connection_mut(), study, PurgeCause::Quota)?;
+            let failed = matches!(purged, LifecycleAction::Failed { .. });
+            report.actions.push(purged);
+            if failed {
+                continue;
+            }
+            report.usage_after = report.usage_after.saturating_sub(bytes);
+            tracing::info!("Estudio {} desalojado: {} bytes", uid, bytes);
+        }
+
+        report.shortfall = report.usage_after.saturating_sub(self.low_watermark);
+        if report.shortfall > 0 {
+            tracing::warn!(
+                "Cuota: faltan {} bytes para la marca baja y no quedan estudios desalojables",
+                report.shortfall
+            );
+        }
+        Ok(report)
+    }
+}
+
+/// Estudios desalojables en orden, con sus bytes según el índice
+///
+/// Los estudios ya desalojados (archivados sin series) no cuentan.
+fn eviction_candidates(store: &StudyStore) -> Result<Vec<(StudyRecord, u64)>> {
+    let sql = format!(
+        "SELECT {},
+             (SELECT COALESCE(SUM(i.file_size_bytes), 0) FROM instances i
+              JOIN series s ON s.series_instance_uid = i.series_instance_uid
+              WHERE s.study_instance_uid = studies.study_instance_uid) AS bytes
+         FROM studies
+         WHERE is_protected = 0 AND (is_archived = 1 OR has_completed_report = 1)
+             AND EXISTS (SELECT 1 FROM series WHERE series.study_instance_uid = studies.study_instance_uid)
+         ORDER BY is_archived DESC,
+             (SELECT last_accessed FROM patients WHERE patients.patient_id = studies.patient_id),
+             study_date, study_instance_uid",
+        STUDY_COLUMNS
+    );
+    let mut stmt = store.connection().prepare(&sql)?;
+    let rows = stmt.query_map([], |row| Ok((StudyRecord::from_row(row)?, row.get("bytes")?)))?;
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
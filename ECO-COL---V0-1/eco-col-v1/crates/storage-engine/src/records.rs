@@ -0,0 +1,169 @@
+//! Filas tipadas de las tablas de `sql/schema.sql`
+//!
+//! Los timestamps son segundos Unix (`unixepoch()` en SQLite). Las fechas y
+//! horas DICOM se guardan tal cual llegan (DA "YYYYMMDD", TM "HHMMSS.FFFFFF").
+
+use dicom_core::PersonName;
+
+use rusqlite::Row;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Fila de `patients`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PatientRecord {
+    pub patient_id: String,
+    pub patient_name: PersonName,
+    pub patient_birth_date: Option<String>,
+    pub patient_sex: Option<String>,
+    pub created_at: i64,
+    pub last_accessed: i64,
+}
+
+/// Fila de `studies`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StudyRecord {
+    pub study_instance_uid: String,
+    pub patient_id: String,
+    /// None si el estudio llegó sin Study Date
+    pub study_date: Option<String>,
+    pub study_time: Option<String>,
+    pub study_description: Option<String>,
+    pub accession_number: Option<String>,
+    pub referring_physician: Option<PersonName>,
+    pub retention_expires_at: i64,
+    pub is_archived: bool,
+    pub archived_at: Option<i64>,
+    pub deletion_scheduled_at: Option<i64>,
+    /// Bundle `.tar.gz` del archivo (ver [`lifecycle`](crate::lifecycle))
+    pub archive_bundle_path: Option<PathBuf>,
+    pub has_completed_report: bool,
+    pub is_protected: bool,
+    pub created_at: i64,
+}
+
+/// Fila de `series`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeriesRecord {
+    pub series_instance_uid: String,
+    pub study_instance_uid: String,
+    pub modality: String,
+    pub series_number: Option<i32>,
+    pub series_description: Option<String>,
+    pub body_part_examined: Option<String>,
+    pub frame_rate: Option<f64>,
+    pub number_of_instances: u32,
+    pub created_at: i64,
+}
+
+/// Fila de `instances`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstanceRecord {
+    pub sop_instance_uid: String,
+    pub series_instance_uid: String,
+    pub instance_number: Option<i32>,
+    pub transfer_syntax_uid: String,
+    pub rows: u32,
+    pub columns: u32,
+    pub bits_allocated: u16,
+    pub bits_stored: u16,
+    pub photometric_interpretation: String,
+    pub file_path: PathBuf,
+    pub file_size_bytes: u64,
+    pub file_sha256: String,
+    /// El archivo está en el [`BlobStore`](crate::BlobStore) y se borra al purgar
+    pub store_owned: bool,
+    pub created_at: i64,
+}
+
+// ============================================
+// Lectura de filas
+// ============================================
+
+pub(crate) const PATIENT_COLUMNS: &str =
+    "patient_id, patient_name, patient_birth_date, patient_sex, created_at, last_accessed";
+
+pub(crate) const STUDY_COLUMNS: &str = "study_instance_uid, patient_id, study_date, study_time, \
+     study_description, accession_number, referring_physician, retention_expires_at, is_archived, \
+     archived_at, deletion_scheduled_at, archive_bundle_path, has_completed_report, is_protected, created_at";
+
+pub(crate) const SERIES_COLUMNS: &str = "series_instance_uid, study_instance_uid, modality, series_number, \
+     series_description, body_part_examined, frame_rate, number_of_instances, created_at";
+
+pub(crate) const INSTANCE_COLUMNS: &str = "sop_instance_uid, series_instance_uid, instance_number, \
+     transfer_syntax_uid, rows, columns, bits_allocated, bits_stored, photometric_interpretation, \
+     file_path, file_size_bytes, file_sha256, store_owned, created_at";
+
+impl PatientRecord {
+    pub(crate) fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            patient_id: row.get("patient_id")?,
+            patient_name: PersonName::parse(&row.get::<_, String>("patient_name")?),
+            patient_birth_date: row.get("patient_birth_date")?,
+            patient_sex: row.get("patient_sex")?,
+            created_at: row.get("created_at")?,
+            last_accessed: row.get("last_accessed")?,
+        })
+    }
+}
+
+impl StudyRecord {
+    pub(crate) fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            study_instance_uid: row.get("study_instance_uid")?,
+            patient_id: row.get("patient_id")?,
+            study_date: Some(row.get::<_, String>("study_date")?).filter(|d| !d.is_empty()),
+            study_time: row.get("study_time")?,
+            study_description: row.get("study_description")?,
+            accession_number: row.get("accession_number")?,
+            referring_physician: row
+                .get::<_, Option<String>>("referring_physician")?
+                .map(|name| PersonName::parse(&name)),
+            retention_expires_at: row.get("retention_expires_at")?,
+            is_archived: row.get("is_archived")?,
+            archived_at: row.get("archived_at")?,
+            deletion_scheduled_at: row.get("deletion_scheduled_at")?,
+            archive_bundle_path: row.get::<_, Option<String>>("archive_bundle_path")?.map(PathBuf::from),
+            has_completed_report: row.get("has_completed_report")?,
+            is_protected: row.get("is_protected")?,
+            created_at: row.get("created_at")?,
+        })
+    }
+}
+
+impl SeriesRecord {
+    pub(crate) fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            series_instance_uid: row.get("series_instance_uid")?,
+            study_instance_uid: row.get("study_instance_uid")?,
+            modality: row.get("modality")?,
+            series_number: row.get("series_number")?,
+            series_description: row.get("series_description")?,
+            body_part_examined: row.get("body_part_examined")?,
+            frame_rate: row.get("frame_rate")?,
+            number_of_instances: row.get::<_, Option<u32>>("number_of_instances")?.unwrap_or(0),
+            created_at: row.get("created_at")?,
+        })
+    }
+}
+
+impl InstanceRecord {
+    pub(crate) fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            sop_instance_uid: row.get("sop_instance_uid")?,
+            series_instance_uid: row.get("series_instance_uid")?,
+            instance_number: row.get("instance_number")?,
+            transfer_syntax_uid: row.get("transfer_syntax_uid")?,
+            rows: row.get("rows")?,
+            columns: row.get("columns")?,
+            bits_allocated: row.get("bits_allocated")?,
+            bits_stored: row.get("bits_stored")?,
+            photometric_interpretation: row.get("photometric_interpretation")?,
+            file_path: PathBuf::from(row.get::<_, String>("file_path")?),
+            file_size_bytes: row.get("file_size_bytes")?,
+            file_sha256: row.get("file_sha256")?,
+            store_owned: row.get("store_owned")?,
+            created_at: row.get("created_at")?,
+        })
+    }
+}
@@ -0,0 +1,497 @@
+//! Índice de estudios en SQLite
+//!
+//! [`StudyStore`] migra la base al esquema actual (WAL, foreign keys) y registra
+//! cada instancia en los cuatro niveles Patient > Study > Series > Instance.
+//! Los niveles superiores se crean o actualizan (upsert) con los datos de
+//! la instancia más reciente; un atributo vacío no pisa uno ya conocido.
+
+use crate::blob::{check, sha256_file, BlobStore, StoredBlob};
+use crate::error::{Result, StorageError};
+use crate::migrate;
+use crate::records::{
+    InstanceRecord, PatientRecord, SeriesRecord, StudyRecord, INSTANCE_COLUMNS, PATIENT_COLUMNS, SERIES_COLUMNS,
+    STUDY_COLUMNS,
+};
+use dicom_core::{DicomInstance, PersonName};
+
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, OptionalExtension, Params, Transaction};
+use std::path::{Path, PathBuf};
+
+/// Retención si `system_config` no define `retention_days`
+pub const DEFAULT_RETENTION_DAYS: i64 = 15;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Índice de estudios sobre una base SQLite
+pub struct StudyStore {
+    conn: Connection,
+}
+
+impl StudyStore {
+    /// Abrir (o crear) la base en `path` y migrarla a la versión actual
+    pub fn open(path: &Path) -> Result<Self> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    /// Base temporal en memoria (sin WAL)
+    pub fn open_in_memory() -> Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    /// Migrar una conexión existente (ver [`migrate`](crate::migrate))
+    pub fn with_connection(mut conn: Connection) -> Result<Self> {
+        migrate::migrate(&mut conn)?;
+        Ok(Self { conn })
+    }
+
+    /// Versión del esquema (`PRAGMA user_version`)
+    pub fn schema_version(&self) -> Result<u32> {
+        migrate::schema_version(&self.conn)
+    }
+
+    /// Conexión subyacente, para consultas que el store no cubre
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    pub(crate) fn connection_mut(&mut self) -> &mut Connection {
+        &mut self.conn
+    }
+
+    /// Días de retención configurados (`system_config.retention_days`)
+    pub fn retention_days(&self) -> Result<i64> {
+        retention_days(&self.conn)
+    }
+
+    // ============================================
+    // Ingesta
+    // ============================================
+
+    /// Registrar una instancia y sus niveles superiores
+    ///
+    /// Volver a ingerir la misma instancia actualiza su fila (p.ej. si el
+    /// archivo se movió). El archivo sigue siendo del llamador: el borrado
+    /// del ciclo de vida elimina la fila pero no el archivo (ver
+    /// [`import`](Self::import)).
+    pub fn ingest(&mut self, instance: &DicomInstance) -> Result<()> {
+        self.ingest_all([instance]).map(|_| ())
+    }
+
+    /// Registrar varias instancias en una sola transacción
+    ///
+    /// Si alguna falla no se registra ninguna.
+    pub fn ingest_all<'a>(&mut self, instances: impl IntoIterator<Item = &'a DicomInstance>) -> Result<usize> {
+        // Tamaño y hash se calculan antes de abrir la transacción
+        let files = instances
+            .into_iter()
+            .map(|instance| Ok((instance, FileInfo::of(instance)?)))
+            .collect::<Result<Vec<_>>>()?;
+        self.register(&files)
+    }
+
+    /// Copiar una instancia al [`BlobStore`] y registrarla con su ubicación ahí
+    ///
+    /// La copia es del store: el ciclo de vida la borra al purgar el estudio.
+    pub fn import(&mut self, blobs: &BlobStore, instance: &DicomInstance) -> Result<StoredBlob> {
+        let mut stored = self.import_all(blobs, [instance])?;
+        Ok(stored.remove(0))
+    }
+
+    /// Copiar varias instancias al [`BlobStore`] y registrarlas en una sola
+    /// transacción
+    ///
+    /// Si una copia, un checksum o el registro fallan, se eliminan los
+    /// objetos que creó esta llamada; los que ya estaban en el store (p.ej.
+    /// de una importación anterior) se conservan.
+    pub fn import_all<'a>(
+        &mut self,
+        blobs: &BlobStore,
+        instances: impl IntoIterator<Item = &'a DicomInstance>,
+    ) -> Result<Vec<StoredBlob>> {
+        let mut stored = Vec::new();
+        let imported = (|| -> Result<()> {
+            let mut files = Vec::new();
+            for instance in instances {
+                let blob = blobs.put_file(&instance.file_path)?;
+                let file = FileInfo {
+                    path: blob.path.clone(),
+                    size: blob.size,
+                    sha256: blob.sha256.clone(),
+                    owned: true,
+                };
+                stored.push(blob);
+
+                // El archivo cambió desde que se parseó
+                if let Some(checksums) = instance.checksums.as_ref().filter(|c| !c.file_sha256.is_empty()) {
+                    check(&instance.file_path, &checksums.file_sha256, &file.sha256)?;
+                }
+                files.push((instance, file));
+            }
+            self.register(&files).map(|_| ())
+        })();
+
+        if let Err(e) = imported {
+            for blob in stored.iter().filter(|blob| !blob.deduplicated) {
+                if let Err(e) = std::fs::remove_file(&blob.path) {
+                    tracing::warn!("No se pudo borrar {:?}: {}", blob.path, e);
+                }
+            }
+            return Err(e);
+        }
+        Ok(stored)
+    }
+
+    fn register(&mut self, files: &[(&DicomInstance, FileInfo)]) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let retention = retention_days(&tx)?;
+        for (instance, file) in files {
+            ingest_instance(&tx, instance, file, retention)?;
+        }
+        tx.commit()?;
+
+        tracing::debug!("{} instancias registradas en el índice", files.len());
+        Ok(files.len())
+    }
+
+    // ============================================
+    // Retención
+    // ============================================
+
+    /// Proteger un estudio del archivo y borrado automáticos
+    ///
+    /// Devuelve false si el estudio no existe.
+    pub fn set_protected(&self, study_instance_uid: &str, protected: bool) -> Result<bool> {
+        self.set_study_flag("is_protected", study_instance_uid, protected)
+    }
+
+    /// Marcar que el estudio tiene (o no) informe final firmado
+    ///
+    /// Sin informe final el estudio no se archiva ni se borra.
+    pub fn set_report_completed(&self, study_instance_uid: &str, completed: bool) -> Result<bool> {
+        self.set_study_flag("has_completed_report", study_instance_uid, completed)
+    }
+
+    /// Registrar que se abrió un estudio (`patients.last_accessed`)
+    ///
+    /// El desalojo por cuota empieza por los pacientes accedidos hace más
+    /// tiempo.
+    pub fn mark_accessed(&self, study_instance_uid: &str) -> Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE patients SET last_accessed = unixepoch()
+             WHERE patient_id = (SELECT patient_id FROM studies WHERE study_instance_uid = ?1)",
+            [study_instance_uid],
+        )?;
+        Ok(updated > 0)
+    }
+
+    fn set_study_flag(&self, column: &str, study_instance_uid: &str, value: bool) -> Result<bool> {
+        let sql = format!("UPDATE studies SET {} = ?2 WHERE study_instance_uid = ?1", column);
+        Ok(self.conn.execute(&sql, params![study_instance_uid, value])? > 0)
+    }
+
+    // ============================================
+    // Consultas
+    // ============================================
+
+    /// Paciente por Patient ID
+    pub fn patient(&self, patient_id: &str) -> Result<Option<PatientRecord>> {
+        let sql = format!("SELECT {} FROM patients WHERE patient_id = ?1", PATIENT_COLUMNS);
+        Ok(self
+            .conn
+            .query_row(&sql, [patient_id], PatientRecord::from_row)
+            .optional()?)
+    }
+
+    /// Estudio por Study Instance UID
+    pub fn study(&self, study_instance_uid: &str) -> Result<Option<StudyRecord>> {
+        let sql = format!("SELECT {} FROM studies WHERE study_instance_uid = ?1", STUDY_COLUMNS);
+        Ok(self
+            .conn
+            .query_row(&sql, [study_instance_uid], StudyRecord::from_row)
+            .optional()?)
+    }
+
+    /// Estudios de un paciente, del más reciente al más antiguo
+    pub fn studies_by_patient(&self, patient_id: &str) -> Result<Vec<StudyRecord>> {
+        self.query_studies("patient_id = ?1 ORDER BY study_date DESC, study_time DESC", [patient_id])
+    }
+
+    /// Estudios con Study Date entre `from` y `to` (ambos incluidos)
+    ///
+    /// Los estudios sin Study Date no se incluyen.
+    pub fn studies_by_date_range(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<StudyRecord>> {
+        let (from, to) = (dicom_date(from), dicom_date(to));
+        self.query_studies(
+            "study_date <> '' AND study_date BETWEEN ?1 AND ?2 ORDER BY study_date, study_time",
+            [from, to],
+        )
+    }
+
+    /// Estudios con un Accession Number
+    ///
+    /// Puede haber más de uno: el número lo asigna cada RIS.
+    pub fn studies_by_accession(&self, accession_number: &str) -> Result<Vec<StudyRecord>> {
+        self.query_studies("accession_number = ?1 ORDER BY study_date DESC", [accession_number])
+    }
+
+    /// Series de un estudio, por Series Number
+    pub fn series_for_study(&self, study_instance_uid: &str) -> Result<Vec<SeriesRecord>> {
+        let sql = format!(
+            "SELECT {} FROM series WHERE study_instance_uid = ?1 \
+             ORDER BY series_number IS NULL, series_number, series_instance_uid",
+            SERIES_COLUMNS
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map([study_instance_uid], SeriesRecord::from_row)?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Instancias de una serie, por Instance Number
+    pub fn instances_for_series(&self, series_instance_uid: &str) -> Result<Vec<InstanceRecord>> {
+        let sql = format!(
+            "SELECT {} FROM instances WHERE series_instance_uid = ?1 \
+             ORDER BY instance_number IS NULL, instance_number, file_path",
+            INSTANCE_COLUMNS
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map([series_instance_uid], InstanceRecord::from_row)?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    fn query_studies<P: Params>(&self, filter: &str, params: P) -> Result<Vec<StudyRecord>> {
+        let sql = format!("SELECT {} FROM studies WHERE {}", STUDY_COLUMNS, filter);
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params, StudyRecord::from_row)?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+}
+
+// ============================================
+// Upserts
+// ============================================
+
+/// Datos del archivo que no están en el data set
+struct FileInfo {
+    path: PathBuf,
+    size: u64,
+    sha256: String,
+    /// Copia en el [`BlobStore`] (se borra al purgar)
+    owned: bool,
+}
+
+impl FileInfo {
+    /// Usa el hash calculado por el parser si está disponible
+    fn of(instance: &DicomInstance) -> Result<Self> {
+        let path = instance.file_path.clone();
+        match &instance.checksums {
+            Some(checksums) if !checksums.file_sha256.is_empty() => Ok(Self {
+                size: std::fs::metadata(&path)?.len(),
+                sha256: checksums.file_sha256.clone(),
+                path,
+                owned: false,
+            }),
+            _ => {
+                let (size, sha256) = sha256_file(&path)?;
+                Ok(Self {
+                    path,
+                    size,
+                    sha256,
+                    owned: false,
+                })
+            }
+        }
+    }
+}
+
+fn ingest_instance(tx: &Transaction, instance: &DicomInstance, file: &FileInfo, retention_days: i64) -> Result<()> {
+    let metadata = &instance.metadata;
+    let descriptor = instance
+        .pixel_descriptor
+        .as_ref()
+        .or(instance.pixel_data.as_ref().map(|p| &p.descriptor))
+        .ok_or_else(|| StorageError::invalid_instance(format!("{} sin Pixel Data", metadata.sop_instance_uid)))?;
+    for (name, uid) in [
+        ("Patient ID", &metadata.patient_id),
+        ("Study Instance UID", &metadata.study_instance_uid),
+        ("Series Instance UID", &metadata.series_instance_uid),
+        ("SOP Instance UID", &metadata.sop_instance_uid),
+    ] {
+        if uid.is_empty() {
+            return Err(StorageError::invalid_instance(format!("{} vacío en {:?}", name, instance.file_path)));
+        }
+    }
+    // Los UIDs terminan en rutas del bundle: solo se aceptan conformes
+    for (name, uid) in [
+        ("Study Instance UID", &metadata.study_instance_uid),
+        ("Series Instance UID", &metadata.series_instance_uid),
+        ("SOP Instance UID", &metadata.sop_instance_uid),
+    ] {
+        if let Err(e) = dicom_core::uid::validate_uid(uid) {
+            return Err(StorageError::invalid_instance(format!("{} no conforme en {:?}: {}", name, instance.file_path, e)));
+        }
+    }
+
+    // El CHECK de la tabla solo admite M, F y O
+    let sex = metadata
+        .patient_sex
+        .as_deref()
+        .map(str::trim)
+        .filter(|sex| matches!(*sex, "M" | "F" | "O"));
+
+    tx.execute(
+        "INSERT INTO patients (patient_id, patient_name, patient_birth_date, patient_sex)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT (patient_id) DO UPDATE SET
+             patient_name = CASE WHEN excluded.patient_name <> '' THEN excluded.patient_name ELSE patient_name END,
+             patient_birth_date = COALESCE(excluded.patient_birth_date, patient_birth_date),
+             patient_sex = COALESCE(excluded.patient_sex, patient_sex),
+             last_accessed = unixepoch()",
+        params![
+            metadata.patient_id,
+            metadata.patient_name,
+            non_empty(&metadata.patient_birth_date),
+            sex,
+        ],
+    )?;
+
+    let referring = instance
+        .get::<PersonName>("ReferringPhysicianName")
+        .filter(|name| !name.is_empty())
+        .map(|name| name.to_dicom());
+    tx.execute(
+        "INSERT INTO studies (study_instance_uid, patient_id, study_date, study_time, study_description,
+                              accession_number, referring_physician, retention_expires_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, unixepoch() + ?8)
+         ON CONFLICT (study_instance_uid) DO UPDATE SET
+             study_date = CASE WHEN excluded.study_date <> '' THEN excluded.study_date ELSE study_date END,
+             study_time = COALESCE(excluded.study_time, study_time),
+             study_description = COALESCE(excluded.study_description, study_description),
+             accession_number = COALESCE(excluded.accession_number, accession_number),
+             referring_physician = COALESCE(excluded.referring_physician, referring_physician)",
+        params![
+            metadata.study_instance_uid,
+            metadata.patient_id,
+            non_empty(&metadata.study_date).unwrap_or_default(),
+            non_empty(&metadata.study_time),
+            non_empty(&metadata.study_description),
+            non_empty(&metadata.accession_number),
+            referring,
+            retention_days * SECONDS_PER_DAY,
+        ],
+    )?;
+
+    tx.execute(
+        "INSERT INTO series (series_instance_uid, study_instance_uid, modality, series_number,
+                             series_description, body_part_examined, frame_rate)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         ON CONFLICT (series_instance_uid) DO UPDATE SET
+             modality = CASE WHEN excluded.modality <> '' THEN excluded.modality ELSE modality END,
+             series_number = COALESCE(excluded.series_number, series_number),
+             series_description = COALESCE(excluded.series_description, series_description),
+             body_part_examined = COALESCE(excluded.body_part_examined, body_part_examined),
+             frame_rate = COALESCE(excluded.frame_rate, frame_rate)",
+        params![
+            metadata.series_instance_uid,
+            metadata.study_instance_uid,
+            metadata.modality,
+            metadata.series_number,
+            non_empty(&metadata.series_description),
+            instance.body_part_examined(),
+            instance.frame_rate(),
+        ],
+    )?;
+
+    tx.execute(
+        "INSERT INTO instances (sop_instance_uid, series_instance_uid, instance_number, transfer_syntax_uid,
+                                rows, columns, bits_allocated, bits_stored, photometric_interpretation,
+                                file_path, file_size_bytes, file_sha256, store_owned)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+         ON CONFLICT (sop_instance_uid) DO UPDATE SET
+             series_instance_uid = excluded.series_instance_uid,
+             instance_number = excluded.instance_number,
+             transfer_syntax_uid = excluded.transfer_syntax_uid,
+             rows = excluded.rows,
+             columns = excluded.columns,
+             bits_allocated = excluded.bits_allocated,
+             bits_stored = excluded.bits_stored,
+             photometric_interpretation = excluded.photometric_interpretation,
+             file_path = excluded.file_path,
+             file_size_bytes = excluded.file_size_bytes,
+             file_sha256 = excluded.file_sha256,
+             store_owned = excluded.store_owned",
+        params![
+            metadata.sop_instance_uid,
+            metadata.series_instance_uid,
+            metadata.instance_number,
+            metadata.transfer_syntax_uid,
+            descriptor.rows,
+            descriptor.columns,
+            descriptor.bits_allocated,
+            descriptor.bits_stored,
+            descriptor.photometric_interpretation,
+            file.path.to_string_lossy(),
+            file.size,
+            file.sha256,
+            file.owned,
+        ],
+    )?;
+
+    tx.execute(
+        "UPDATE series SET number_of_instances =
+             (SELECT COUNT(*) FROM instances WHERE instances.series_instance_uid = series.series_instance_uid)
+         WHERE series_instance_uid = ?1",
+        [&metadata.series_instance_uid],
+    )?;
+
+    Ok(())
+}
+
+fn retention_days(conn: &Connection) -> Result<i64> {
+    let value: Option<String> = conn
+        .query_row(
+            "SELECT config_value FROM system_config WHERE config_key = 'retention_days'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(value
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(DEFAULT_RETENTION_DAYS))
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// Fecha en formato DA ("YYYYMMDD"), comparable como texto
+fn dicom_date(date: NaiveDate) -> String {
+    date.format("%Y%m%d").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_is_idempotent() {
+        let store = StudyStore::open_in_memory().unwrap();
+        let conn = store.conn;
+        let store = StudyStore::with_connection(conn).unwrap();
+
+        assert_eq!(store.schema_version().unwrap(), migrate::SCHEMA_VERSION);
+        assert_eq!(store.retention_days().unwrap(), 15);
+        let foreign_keys: bool = store
+            .connection()
+            .query_row("PRAGMA foreign_keys", [], |row| row.get(0))
+            .unwrap();
+        assert!(foreign_keys);
+    }
+
+    #[test]
+    fn test_dicom_date_sorts_as_text() {
+        let date = |y, m, d| dicom_date(NaiveDate::from_ymd_opt(y, m, d).unwrap());
+        assert_eq!(date(2026, 1, 5), "20260105");
+        assert!(date(2025, 12, 31) < date(2026, 1, 1));
+    }
+}
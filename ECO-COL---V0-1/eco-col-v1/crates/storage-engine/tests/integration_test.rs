@@ -0,0 +1,768 @@
+//! Tests de integración del índice de estudios
+
+use chrono::NaiveDate;
+use dicom::core::{DataElement, PrimitiveValue, VR};
+use dicom::dictionary_std::{tags, uids};
+use dicom::object::{FileMetaTableBuilder, InMemDicomObject};
+use dicom_core::{DicomInstance, DicomParser, ParseOptions};
+use std::path::{Path, PathBuf};
+use storage_engine::{StorageError, StudyStore};
+
+/// Instancia de ultrasonido de 2x2 escrita en `dir` y parseada
+fn instance(dir: &Path, attrs: &[(dicom::core::Tag, VR, &str)]) -> DicomInstance {
+    let options = ParseOptions {
+        validate_checksums: true,
+        ..ParseOptions::default()
+    };
+    DicomParser::with_options(options).parse_file(&write_instance(dir, attrs)).unwrap()
+}
+
+/// Escribir la instancia de ultrasonido en `dir`
+fn write_instance(dir: &Path, attrs: &[(dicom::core::Tag, VR, &str)]) -> PathBuf {
+    let mut obj = InMemDicomObject::new_empty();
+    for (tag, vr, value) in [
+        (tags::SOP_CLASS_UID, VR::UI, uids::ULTRASOUND_IMAGE_STORAGE),
+        (tags::MODALITY, VR::CS, "US"),
+        (tags::PHOTOMETRIC_INTERPRETATION, VR::CS, "MONOCHROME2"),
+    ]
+    .iter()
+    .chain(attrs)
+    {
+        obj.put(DataElement::new(*tag, *vr, PrimitiveValue::from(*value)));
+    }
+    for (tag, value) in [
+        (tags::SAMPLES_PER_PIXEL, 1u16),
+        (tags::ROWS, 2),
+        (tags::COLUMNS, 2),
+        (tags::BITS_ALLOCATED, 8),
+        (tags::BITS_STORED, 8),
+        (tags::HIGH_BIT, 7),
+        (tags::PIXEL_REPRESENTATION, 0),
+    ] {
+        obj.put(DataElement::new(tag, VR::US, PrimitiveValue::from(value)));
+    }
+    obj.put(DataElement::new(tags::PIXEL_DATA, VR::OB, PrimitiveValue::from(vec![0u8; 4])));
+
+    let uid = obj.element(tags::SOP_INSTANCE_UID).unwrap().to_str().unwrap().to_string();
+    let path = dir.join(format!("{}.dcm", uid));
+    obj.with_meta(FileMetaTableBuilder::new().transfer_syntax(uids::EXPLICIT_VR_LITTLE_ENDIAN))
+        .unwrap()
+        .write_to_file(&path)
+        .unwrap();
+    path
+}
+
+fn attrs<'a>(
+    patient: &'a str,
+    name: &'a str,
+    study: &'a str,
+    date: &'a str,
+    accession: &'a str,
+    series: &'a str,
+    instance: &'a str,
+) -> Vec<(dicom::core::Tag, VR, &'a str)> {
+    vec![
+        (tags::PATIENT_ID, VR::LO, patient),
+        (tags::PATIENT_NAME, VR::PN, name),
+        (tags::STUDY_INSTANCE_UID, VR::UI, study),
+        (tags::STUDY_DATE, VR::DA, date),
+        (tags::ACCESSION_NUMBER, VR::SH, accession),
+        (tags::SERIES_INSTANCE_UID, VR::UI, series),
+        (tags::SOP_INSTANCE_UID, VR::UI, instance),
+    ]
+}
+
+#[test]
+fn test_ingest_upserts_all_levels() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut store = StudyStore::open(&dir.path().join("index.db")).unwrap();
+
+    let mut first = attrs("CC1", "PEREZ^JUAN", "1.2.3.1", "20260110", "ACC1", "1.2.3.1.1", "1.2.3.1.1.1");
+    first.push((tags::PATIENT_SEX, VR::CS, "M"));
+    first.push((tags::REFERRING_PHYSICIAN_NAME, VR::PN, "GOMEZ^ANA"));
+    let first = instance(dir.path(), &first);
+    // Sin sexo ni nombre: no debe pisar lo ya registrado
+    let second = instance(
+        dir.path(),
+        &attrs("CC1", "", "1.2.3.1", "20260110", "ACC1", "1.2.3.1.1", "1.2.3.1.1.2"),
+    );
+
+    store.ingest(&first).unwrap();
+    store.ingest_all([&second, &first]).unwrap();
+
+    let patient = store.patient("CC1").unwrap().unwrap();
+    assert_eq!(patient.patient_name.family(), "PEREZ");
+    assert_eq!(patient.patient_sex.as_deref(), Some("M"));
+
+    let study = store.study("1.2.3.1").unwrap().unwrap();
+    assert_eq!(study.study_date.as_deref(), Some("20260110"));
+    assert_eq!(study.referring_physician.unwrap().given(), "ANA");
+    assert_eq!(study.retention_expires_at - study.created_at, 15 * 86_400);
+
+    let series = store.series_for_study("1.2.3.1").unwrap();
+    assert_eq!(series.len(), 1);
+    assert_eq!(series[0].number_of_instances, 2);
+
+    let instances = store.instances_for_series("1.2.3.1.1").unwrap();
+    assert_eq!(instances.len(), 2);
+    assert_eq!(instances[0].file_path, first.file_path);
+    assert_eq!(instances[0].file_sha256, first.checksums.as_ref().unwrap().file_sha256);
+    assert_eq!(instances[0].file_size_bytes, std::fs::metadata(&first.file_path).unwrap().len());
+    assert_eq!((instances[0].rows, instances[0].bits_stored), (2, 8));
+
+    let journal: String = store
+        .connection()
+        .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(journal, "wal");
+}
+
+#[test]
+fn test_queries_by_patient_date_and_accession() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut store = StudyStore::open_in_memory().unwrap();
+
+    let instances = [
+        attrs("CC1", "PEREZ^JUAN", "1.2.3.1", "20260105", "ACC1", "1.2.3.1.1", "1.2.3.1.1.1"),
+        attrs("CC1", "PEREZ^JUAN", "1.2.3.2", "20260120", "ACC2", "1.2.3.2.1", "1.2.3.2.1.1"),
+        attrs("CC2", "LOPEZ^ANA", "1.2.3.3", "20260115", "ACC2", "1.2.3.3.1", "1.2.3.3.1.1"),
+        attrs("CC2", "LOPEZ^ANA", "1.2.3.4", "", "", "1.2.3.4.1", "1.2.3.4.1.1"),
+    ]
+    .map(|a| instance(dir.path(), &a));
+    assert_eq!(store.ingest_all(&instances).unwrap(), 4);
+
+    let uids = |studies: Vec<storage_engine::StudyRecord>| -> Vec<String> {
+        studies.into_iter().map(|s| s.study_instance_uid).collect()
+    };
+    assert_eq!(uids(store.studies_by_patient("CC1").unwrap()), ["1.2.3.2", "1.2.3.1"]);
+
+    let date = |d| NaiveDate::from_ymd_opt(2026, 1, d).unwrap();
+    assert_eq!(uids(store.studies_by_date_range(date(5), date(15)).unwrap()), ["1.2.3.1", "1.2.3.3"]);
+    assert!(store.studies_by_date_range(date(21), date(31)).unwrap().is_empty());
+
+    let mut by_accession = uids(store.studies_by_accession("ACC2").unwrap());
+    by_accession.sort();
+    assert_eq!(by_accession, ["1.2.3.2", "1.2.3.3"]);
+    assert_eq!(store.study("1.2.3.4").unwrap().unwrap().study_date, None);
+}
+
+#[test]
+fn test_ingest_all_is_atomic() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut store = StudyStore::open_in_memory().unwrap();
+
+    let valid = instance(
+        dir.path(),
+        &attrs("CC1", "PEREZ^JUAN", "1.2.3.1", "20260105", "ACC1", "1.2.3.1.1", "1.2.3.1.1.1"),
+    );
+    let mut missing_pixels = valid.clone();
+    missing_pixels.metadata.sop_instance_uid = "1.2.3.1.1.2".to_string();
+    missing_pixels.pixel_descriptor = None;
+    missing_pixels.pixel_data = None;
+
+    let result = store.ingest_all([&valid, &missing_pixels]);
+    assert!(matches!(result, Err(StorageError::InvalidInstance(_))));
+    assert!(store.patient("CC1").unwrap().is_none());
+}
+
+#[test]
+fn test_ingest_rejects_non_conformant_uids() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut store = StudyStore::open_in_memory().unwrap();
+
+    // En modo recuperación el parser acepta UIDs que terminarían en rutas
+    let path = write_instance(
+        dir.path(),
+        &attrs("CC1", "PEREZ^JUAN", "../../x", "20260105", "ACC1", "1.2.3.1.1", "1.2.3.1.1.1"),
+    );
+    let options = ParseOptions {
+        recovery: true,
+        ..ParseOptions::default()
+    };
+    let mut bad = DicomParser::with_options(options).parse_file(&path).unwrap();
+    assert_eq!(bad.metadata.study_instance_uid, "../../x");
+    let result = store.ingest(&bad);
+    assert!(matches!(result, Err(StorageError::InvalidInstance(ref m)) if m.contains("Study Instance UID")));
+
+    bad.metadata.study_instance_uid = "1.2.3.1".to_string();
+    bad.metadata.series_instance_uid = "1.2.3.1/../1".to_string();
+    let result = store.ingest(&bad);
+    assert!(matches!(result, Err(StorageError::InvalidInstance(ref m)) if m.contains("Series Instance UID")));
+
+    bad.metadata.series_instance_uid = "1.2.3.1.1".to_string();
+    bad.metadata.sop_instance_uid = "1.2.03".to_string();
+    let result = store.ingest(&bad);
+    assert!(matches!(result, Err(StorageError::InvalidInstance(ref m)) if m.contains("SOP Instance UID")));
+    assert!(store.patient("CC1").unwrap().is_none());
+}
+
+#[test]
+fn test_import_into_blob_store_and_fsck() {
+    use storage_engine::{BlobStore, FsckIssue};
+
+    let incoming = tempfile::tempdir().unwrap();
+    let root = tempfile::tempdir().unwrap();
+    let blobs = BlobStore::open(root.path()).unwrap();
+    let mut store = StudyStore::open_in_memory().unwrap();
+
+    let first = instance(
+        incoming.path(),
+        &attrs("CC1", "PEREZ^JUAN", "1.2.3.1", "20260105", "ACC1", "1.2.3.1.1", "1.2.3.1.1.1"),
+    );
+    let second = instance(
+        incoming.path(),
+        &attrs("CC1", "PEREZ^JUAN", "1.2.3.1", "20260105", "ACC1", "1.2.3.1.1", "1.2.3.1.1.2"),
+    );
+    let stored = store.import_all(&blobs, [&first, &second]).unwrap();
+    // Reimportar el mismo archivo no lo duplica
+    assert!(store.import(&blobs, &first).unwrap().deduplicated);
+
+    let rows = store.instances_for_series("1.2.3.1.1").unwrap();
+    assert_eq!(rows[0].file_path, stored[0].path);
+    assert_eq!(rows[0].file_sha256, stored[0].sha256);
+    assert!(rows[0].file_path.starts_with(root.path()));
+
+    let parsed = blobs.parse(&DicomParser::new(), &stored[1].sha256).unwrap();
+    assert_eq!(parsed.instance_uid(), "1.2.3.1.1.2");
+
+    let report = store.fsck(&blobs).unwrap();
+    assert!(report.is_clean(), "{:?}", report.issues);
+    assert_eq!((report.instances_checked, report.blobs_checked), (2, 2));
+
+    // Archivo alterado, archivo borrado y objeto sin fila
+    let mut bytes = std::fs::read(&stored[0].path).unwrap();
+    *bytes.last_mut().unwrap() ^= 0xFF;
+    std::fs::write(&stored[0].path, bytes).unwrap();
+    std::fs::remove_file(&stored[1].path).unwrap();
+    let orphan = blobs.put_bytes(b"sin fila").unwrap();
+
+    assert!(blobs.parse(&DicomParser::new(), &stored[0].sha256).is_err());
+    let report = store.fsck(&blobs).unwrap();
+    assert_eq!(report.issues.len(), 3);
+    assert!(report
+        .issues
+        .iter()
+        .any(|issue| matches!(issue, FsckIssue::ChecksumMismatch { sop_instance_uid, .. } if sop_instance_uid == "1.2.3.1.1.1")));
+    assert!(report
+        .issues
+        .iter()
+        .any(|issue| matches!(issue, FsckIssue::MissingFile { sop_instance_uid, .. } if sop_instance_uid == "1.2.3.1.1.2")));
+    assert!(report.issues.contains(&FsckIssue::Orphan { path: orphan.path }));
+}
+
+#[test]
+fn test_import_all_removes_new_blobs_on_failure() {
+    use storage_engine::BlobStore;
+
+    let incoming = tempfile::tempdir().unwrap();
+    let root = tempfile::tempdir().unwrap();
+    let blobs = BlobStore::open(root.path()).unwrap();
+    let mut store = StudyStore::open_in_memory().unwrap();
+
+    let [existing, new, changed] = ["1.2.3.1.1.1", "1.2.3.1.1.2", "1.2.3.1.1.3"].map(|uid| {
+        instance(
+            incoming.path(),
+            &attrs("CC1", "PEREZ^JUAN", "1.2.3.1", "20260105", "ACC1", "1.2.3.1.1", uid),
+        )
+    });
+    let kept = store.import(&blobs, &existing).unwrap();
+
+    // El archivo cambia después de parsearse
+    let mut bytes = std::fs::read(&changed.file_path).unwrap();
+    *bytes.last_mut().unwrap() ^= 0xFF;
+    std::fs::write(&changed.file_path, bytes).unwrap();
+
+    let result = store.import_all(&blobs, [&new, &existing, &changed]);
+    assert!(matches!(result, Err(StorageError::ChecksumMismatch { ref path, .. }) if *path == changed.file_path));
+
+    // Solo queda el objeto que ya estaba, todavía referenciado
+    let listed: Vec<_> = blobs.list().unwrap().into_iter().map(|blob| blob.path).collect();
+    assert_eq!(listed, [kept.path]);
+    assert_eq!(store.instances_for_series("1.2.3.1.1").unwrap().len(), 1);
+    assert!(store.fsck(&blobs).unwrap().is_clean());
+}
+
+#[test]
+fn test_lifecycle_archives_then_purges_with_holds_and_audit() {
+    use flate2::read::GzDecoder;
+    use storage_engine::{BlobStore, LifecycleAction, LifecycleOptions, LifecycleWorker, RetentionHold};
+
+    let incoming = tempfile::tempdir().unwrap();
+    let root = tempfile::tempdir().unwrap();
+    let blobs = BlobStore::open(root.path().join("blobs")).unwrap();
+    let mut store = StudyStore::open_in_memory().unwrap();
+
+    let studies = [
+        attrs("CC1", "PEREZ^JUAN", "1.2.3.1", "20260105", "ACC1", "1.2.3.1.1", "1.2.3.1.1.1"),
+        attrs("CC1", "PEREZ^JUAN", "1.2.3.2", "20260106", "ACC2", "1.2.3.2.1", "1.2.3.2.1.1"),
+        attrs("CC2", "LOPEZ^ANA", "1.2.3.3", "20260107", "ACC3", "1.2.3.3.1", "1.2.3.3.1.1"),
+    ]
+    .map(|a| instance(incoming.path(), &a));
+    let stored = store.import_all(&blobs, &studies).unwrap();
+
+    store.set_report_completed("1.2.3.1", true).unwrap();
+    store.set_report_completed("1.2.3.2", true).unwrap();
+    assert!(store.set_protected("1.2.3.2", true).unwrap());
+    assert!(!store.set_protected("9.9.9", true).unwrap());
+
+    let expires = store.study("1.2.3.1").unwrap().unwrap().retention_expires_at;
+    let mut options = LifecycleOptions::new(root.path().join("archive"));
+    options.deletion_grace = std::time::Duration::from_secs(3600);
+
+    // Antes de expirar no hay nada que hacer
+    let worker = LifecycleWorker::new(options.clone());
+    assert!(worker.run_at(&mut store, expires - 1).unwrap().actions.is_empty());
+
+    // Dry-run: mismo plan, sin efectos
+    let dry = LifecycleWorker::new(LifecycleOptions {
+        dry_run: true,
+        ..options.clone()
+    });
+    let plan = dry.run_at(&mut store, expires).unwrap();
+    assert_eq!(plan.actions.len(), 3);
+    assert!(!root.path().join("archive").exists());
+    assert!(!store.study("1.2.3.1").unwrap().unwrap().is_archived);
+
+    let report = worker.run_at(&mut store, expires).unwrap();
+    assert_eq!(report.actions, plan.actions);
+    let bundle = root.path().join("archive").join("1.2.3.1.tar.gz");
+    assert_eq!(
+        report.actions,
+        [
+            LifecycleAction::Archived {
+                study_instance_uid: "1.2.3.1".to_string(),
+                bundle: bundle.clone(),
+                instances: 1,
+                deletion_scheduled_at: expires + 3600,
+            },
+            LifecycleAction::Held {
+                study_instance_uid: "1.2.3.2".to_string(),
+                reason: RetentionHold::Protected,
+            },
+            LifecycleAction::Held {
+                study_instance_uid: "1.2.3.3".to_string(),
+                reason: RetentionHold::NoFinalReport,
+            },
+        ]
+    );
+
+    let mut archive = tar::Archive::new(GzDecoder::new(std::fs::File::open(&bundle).unwrap()));
+    let entries: Vec<String> = archive
+        .entries()
+        .unwrap()
+        .map(|e| e.unwrap().path().unwrap().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(entries, ["manifest.json", "1.2.3.1.1/1.2.3.1.1.1.dcm"]);
+
+    // Durante el período de gracia el archivo sigue en disco
+    assert!(worker.run_at(&mut store, expires + 3599).unwrap().actions.iter().all(|a| matches!(a, LifecycleAction::Held { .. })));
+    assert!(stored[0].path.exists());
+
+    // El informe es del estudio, no de las instancias: sobrevive al borrado
+    add_report(&store, "1.2.3.1");
+
+    let report = worker.run_at(&mut store, expires + 3600).unwrap();
+    assert_eq!(
+        report.actions[0],
+        LifecycleAction::Purged {
+            study_instance_uid: "1.2.3.1".to_string(),
+            files_removed: 1,
+            bytes_freed: stored[0].size,
+        }
+    );
+    assert!(!stored[0].path.exists());
+    let study = store.study("1.2.3.1").unwrap().unwrap();
+    assert!(study.is_archived);
+    assert_eq!(study.archive_bundle_path.as_deref(), Some(bundle.as_path()));
+    assert_eq!(study.deletion_scheduled_at, None);
+    assert!(store.series_for_study("1.2.3.1").unwrap().is_empty());
+    let reports: u32 = store
+        .connection()
+        .query_row("SELECT COUNT(*) FROM reports WHERE study_instance_uid = '1.2.3.1'", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(reports, 1);
+    assert!(store.patient("CC1").unwrap().is_some());
+    assert!(store.fsck(&blobs).unwrap().is_clean());
+
+    // Ya borrado: no se vuelve a procesar
+    let report = worker.run_at(&mut store, expires + 7200).unwrap();
+    assert!(report.actions.iter().all(|a| matches!(a, LifecycleAction::Held { .. })));
+
+    let events: Vec<(String, String)> = store
+        .connection()
+        .prepare("SELECT event_type, entity_id FROM audit_log ORDER BY log_id")
+        .unwrap()
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        events,
+        [
+            ("study_archived".to_string(), "1.2.3.1".to_string()),
+            ("study_purged".to_string(), "1.2.3.1".to_string()),
+        ]
+    );
+}
+
+/// Informe firmado de un estudio (con su radiólogo)
+fn add_report(store: &StudyStore, study_uid: &str) {
+    store
+        .connection()
+        .execute_batch(
+            "INSERT OR IGNORE INTO radiologists (radiologist_id, full_name, license_number, private_key_pem, public_key_pem)
+             VALUES ('R1', 'GOMEZ^LUIS', 'RM-1', 'key', 'pub');",
+        )
+        .unwrap();
+    store
+        .connection()
+        .execute(
+            "INSERT INTO reports (report_id, study_instance_uid, radiologist_id, findings, conclusions,
+                 pdf_file_path, pdf_size_bytes, pdf_sha256, signature_sha256, signed_at)
+             VALUES (?1, ?1, 'R1', 'Sin hallazgos', 'Normal', 'informe.pdf', 0, '', '', 0)",
+            [study_uid],
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_lifecycle_purge_keeps_files_registered_with_ingest() {
+    use storage_engine::{BlobStore, LifecycleAction, LifecycleOptions, LifecycleWorker};
+
+    let incoming = tempfile::tempdir().unwrap();
+    let root = tempfile::tempdir().unwrap();
+    let blobs = BlobStore::open(root.path().join("blobs")).unwrap();
+    let mut store = StudyStore::open_in_memory().unwrap();
+
+    let ingested = instance(
+        incoming.path(),
+        &attrs("CC1", "PEREZ^JUAN", "1.2.3.1", "20260105", "ACC1", "1.2.3.1.1", "1.2.3.1.1.1"),
+    );
+    let imported = instance(
+        incoming.path(),
+        &attrs("CC1", "PEREZ^JUAN", "1.2.3.2", "20260105", "ACC2", "1.2.3.2.1", "1.2.3.2.1.1"),
+    );
+    store.ingest(&ingested).unwrap();
+    let stored = store.import(&blobs, &imported).unwrap();
+    assert!(!store.instances_for_series("1.2.3.1.1").unwrap()[0].store_owned);
+    assert!(store.instances_for_series("1.2.3.2.1").unwrap()[0].store_owned);
+
+    store.set_report_completed("1.2.3.1", true).unwrap();
+    store.set_report_completed("1.2.3.2", true).unwrap();
+    let expires = store.study("1.2.3.1").unwrap().unwrap().retention_expires_at;
+    let mut options = LifecycleOptions::new(root.path().join("archive"));
+    options.deletion_grace = std::time::Duration::ZERO;
+    let worker = LifecycleWorker::new(options);
+    worker.run_at(&mut store, expires).unwrap();
+
+    let report = worker.run_at(&mut store, expires).unwrap();
+    assert_eq!(
+        report.actions,
+        [
+            LifecycleAction::Purged {
+                study_instance_uid: "1.2.3.1".to_string(),
+                files_removed: 0,
+                bytes_freed: 0,
+            },
+            LifecycleAction::Purged {
+                study_instance_uid: "1.2.3.2".to_string(),
+                files_removed: 1,
+                bytes_freed: stored.size,
+            },
+        ]
+    );
+    // El original del llamador sigue en disco; la copia del store no
+    assert!(ingested.file_path.exists());
+    assert!(!stored.path.exists());
+    assert!(store.series_for_study("1.2.3.1").unwrap().is_empty());
+}
+
+#[test]
+fn test_lifecycle_deletes_nothing_on_checksum_mismatch() {
+    use flate2::write::GzEncoder;
+    use storage_engine::{BlobStore, LifecycleAction, LifecycleOptions, LifecycleWorker};
+
+    let incoming = tempfile::tempdir().unwrap();
+    let root = tempfile::tempdir().unwrap();
+    let blobs = BlobStore::open(root.path().join("blobs")).unwrap();
+    let mut store = StudyStore::open_in_memory().unwrap();
+
+    let studies = [
+        attrs("CC1", "PEREZ^JUAN", "1.2.3.1", "20260105", "ACC1", "1.2.3.1.1", "1.2.3.1.1.1"),
+        attrs("CC2", "LOPEZ^ANA", "1.2.3.2", "20260106", "ACC2", "1.2.3.2.1", "1.2.3.2.1.1"),
+    ]
+    .map(|a| instance(incoming.path(), &a));
+    let stored = store.import_all(&blobs, &studies).unwrap();
+    for uid in ["1.2.3.1", "1.2.3.2"] {
+        store.set_report_completed(uid, true).unwrap();
+    }
+
+    // El archivo de A cambió después de ingerirlo
+    let mut tampered = std::fs::read(&stored[0].path).unwrap();
+    *tampered.last_mut().unwrap() ^= 0xFF;
+    std::fs::write(&stored[0].path, &tampered).unwrap();
+
+    let mut options = LifecycleOptions::new(root.path().join("archive"));
+    options.deletion_grace = std::time::Duration::from_secs(0);
+    let worker = LifecycleWorker::new(options);
+    let expires = store.study("1.2.3.1").unwrap().unwrap().retention_expires_at;
+
+    let report = worker.run_at(&mut store, expires).unwrap();
+    assert!(matches!(
+        &report.actions[0],
+        LifecycleAction::Failed { study_instance_uid, error }
+            if study_instance_uid == "1.2.3.1" && error.contains(&stored[0].sha256)
+    ));
+    assert!(matches!(&report.actions[1], LifecycleAction::Archived { .. }));
+    assert!(!root.path().join("archive").join("1.2.3.1.tar.gz").exists());
+    assert!(!store.study("1.2.3.1").unwrap().unwrap().is_archived);
+
+    // El bundle de B se corrompió antes del borrado
+    let bundle = root.path().join("archive").join("1.2.3.2.tar.gz");
+    let mut tar = tar::Builder::new(GzEncoder::new(std::fs::File::create(&bundle).unwrap(), Default::default()));
+    let mut header = tar::Header::new_gnu();
+    header.set_size(4);
+    header.set_cksum();
+    tar.append_data(&mut header, "1.2.3.2.1/1.2.3.2.1.1.dcm", &[0u8; 4][..]).unwrap();
+    tar.into_inner().unwrap().finish().unwrap();
+
+    let report = worker.run_at(&mut store, expires).unwrap();
+    assert!(matches!(
+        &report.actions[0],
+        LifecycleAction::Failed { study_instance_uid, error }
+            if study_instance_uid == "1.2.3.2" && error.contains("no coincide")
+    ));
+    assert!(stored[1].path.exists());
+    assert_eq!(store.series_for_study("1.2.3.2").unwrap().len(), 1);
+
+    let failures: Vec<String> = store
+        .connection()
+        .prepare("SELECT event_type FROM audit_log WHERE event_type LIKE '%failed' ORDER BY log_id")
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(failures[..2], ["study_archive_failed", "study_purge_failed"]);
+}
+
+/// Base v1 como la crea el instalador (`sqlite3 eco-col.db < sql/schema.sql`)
+fn v1_fixture(path: &Path, user_version: u32) {
+    let conn = rusqlite::Connection::open(path).unwrap();
+    conn.execute_batch(include_str!("../../../sql/schema.sql")).unwrap();
+    conn.execute_batch(
+        "INSERT INTO patients (patient_id, patient_name) VALUES ('CC1', 'PEREZ^JUAN');
+         INSERT INTO studies (study_instance_uid, patient_id, study_date, accession_number, retention_expires_at)
+         VALUES ('1.2.3.1', 'CC1', '20250301', 'ACC1', 0);
+         UPDATE system_config SET config_value = '30' WHERE config_key = 'retention_days';",
+    )
+    .unwrap();
+    conn.pragma_update(None, "user_version", user_version).unwrap();
+}
+
+fn backup_files(dir: &Path) -> Vec<std::path::PathBuf> {
+    std::fs::read_dir(dir)
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "bak"))
+        .collect()
+}
+
+#[test]
+fn test_upgrade_v1_databases() {
+    use storage_engine::SCHEMA_VERSION;
+
+    // Sin versión (anterior a las migraciones) y con user_version = 1
+    for user_version in [0, 1] {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("eco-col.db");
+        v1_fixture(&path, user_version);
+
+        let store = StudyStore::open(&path).unwrap();
+        assert_eq!(store.schema_version().unwrap(), SCHEMA_VERSION);
+        assert_eq!(store.retention_days().unwrap(), 30);
+        assert_eq!(store.studies_by_accession("ACC1").unwrap().len(), 1);
+        let index: u32 = store
+            .connection()
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_schema WHERE type = 'index' AND name = 'idx_studies_retention'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(index, 1);
+
+        let backups = backup_files(dir.path());
+        assert_eq!(backups.len(), 1, "{:?}", backups);
+        assert!(backups[0]
+            .to_string_lossy()
+            .contains(&format!("eco-col.db.v{}-", user_version)));
+        let backup = rusqlite::Connection::open(&backups[0]).unwrap();
+        let (version, studies): (u32, u32) = (
+            backup.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap(),
+            backup.query_row("SELECT COUNT(*) FROM studies", [], |row| row.get(0)).unwrap(),
+        );
+        assert_eq!((version, studies), (user_version, 1));
+
+        // Ya migrada: no hay otra copia
+        drop(store);
+        StudyStore::open(&path).unwrap();
+        assert_eq!(backup_files(dir.path()).len(), 1);
+    }
+}
+
+#[test]
+fn test_refuse_newer_schema() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("eco-col.db");
+    v1_fixture(&path, 99);
+
+    let result = StudyStore::open(&path);
+    assert!(matches!(result, Err(StorageError::SchemaTooNew { found: 99, .. })));
+
+    let conn = rusqlite::Connection::open(&path).unwrap();
+    let version: u32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+    assert_eq!(version, 99);
+}
+
+#[test]
+fn test_quota_evicts_archived_then_least_recently_accessed() {
+    use storage_engine::{
+        BlobStore, LifecycleAction, LifecycleOptions, LifecycleWorker, QuotaManager, QuotaOptions, StorageUsage,
+    };
+
+    let incoming = tempfile::tempdir().unwrap();
+    let root = tempfile::tempdir().unwrap();
+    let blobs = BlobStore::open(root.path().join("blobs")).unwrap();
+    let mut store = StudyStore::open_in_memory().unwrap();
+
+    // A archivado, B con informe, C protegido, D sin informe
+    let studies = [
+        attrs("CC1", "A", "1.2.3.1", "20260101", "ACC1", "1.2.3.1.1", "1.2.3.1.1.1"),
+        attrs("CC2", "B", "1.2.3.2", "20260102", "ACC2", "1.2.3.2.1", "1.2.3.2.1.1"),
+        attrs("CC3", "C", "1.2.3.3", "20260103", "ACC3", "1.2.3.3.1", "1.2.3.3.1.1"),
+        attrs("CC4", "D", "1.2.3.4", "20260104", "ACC4", "1.2.3.4.1", "1.2.3.4.1.1"),
+    ]
+    .map(|a| instance(incoming.path(), &a));
+    let stored = store.import_all(&blobs, &studies).unwrap();
+
+    // A pasa por el ciclo de vida (el resto no tiene informe todavía)
+    store.set_report_completed("1.2.3.1", true).unwrap();
+    let expires = store.study("1.2.3.1").unwrap().unwrap().retention_expires_at;
+    let archived = LifecycleWorker::new(LifecycleOptions::new(root.path().join("archive")))
+        .run_at(&mut store, expires)
+        .unwrap();
+    assert!(matches!(&archived.actions[0], LifecycleAction::Archived { study_instance_uid, .. } if study_instance_uid == "1.2.3.1"));
+
+    for uid in ["1.2.3.2", "1.2.3.3"] {
+        store.set_report_completed(uid, true).unwrap();
+    }
+    store.set_protected("1.2.3.3", true).unwrap();
+    store
+        .connection()
+        .execute_batch(
+            "UPDATE patients SET last_accessed = CASE patient_id
+                 WHEN 'CC1' THEN 300 WHEN 'CC2' THEN 100 WHEN 'CC3' THEN 50 ELSE 10 END;",
+        )
+        .unwrap();
+    // Acceso reciente a B: igual va después de A, que está archivado
+    assert!(store.mark_accessed("1.2.3.2").unwrap());
+
+    let usage = store.storage_usage().unwrap();
+    assert_eq!((usage.instances, usage.studies), (4, 4));
+    assert_eq!(usage.bytes, stored.iter().map(|b| b.size).sum::<u64>());
+
+    let lifecycle = LifecycleOptions::new(root.path().join("archive"));
+    let options = QuotaOptions {
+        high_watermark: usage.bytes - 1,
+        low_watermark: usage.bytes - stored[0].size - 1,
+        lifecycle: lifecycle.clone(),
+    };
+
+    let dry = QuotaManager::new(QuotaOptions {
+        lifecycle: LifecycleOptions {
+            dry_run: true,
+            ..lifecycle.clone()
+        },
+        ..options.clone()
+    })
+    .unwrap();
+    let plan = dry.enforce(&mut store).unwrap();
+    assert_eq!(store.storage_usage().unwrap(), usage);
+
+    let quota = QuotaManager::new(options.clone()).unwrap();
+    assert!(quota.under_pressure(&store).unwrap());
+    let report = quota.enforce(&mut store).unwrap();
+    assert_eq!(report.actions.len(), plan.actions.len());
+    assert_eq!(report.usage_after, plan.usage_after);
+
+    let kinds: Vec<(&str, &str)> = report
+        .actions
+        .iter()
+        .map(|action| match action {
+            LifecycleAction::Archived { study_instance_uid, .. } => ("archived", study_instance_uid.as_str()),
+            LifecycleAction::Purged { study_instance_uid, .. } => ("purged", study_instance_uid.as_str()),
+            other => panic!("acción inesperada: {:?}", other),
+        })
+        .collect();
+    assert_eq!(kinds, [("purged", "1.2.3.1"), ("archived", "1.2.3.2"), ("purged", "1.2.3.2")]);
+    assert_eq!(report.shortfall, 0);
+    assert_eq!(report.usage_after, usage.bytes - stored[0].size - stored[1].size);
+    assert_eq!(store.storage_usage().unwrap().bytes, report.usage_after);
+    assert!(!quota.under_pressure(&store).unwrap());
+    assert!(root.path().join("archive").join("1.2.3.2.tar.gz").exists());
+    assert!(!stored[1].path.exists());
+    // Desalojado: el estudio queda archivado, con el paciente
+    let evicted = store.study("1.2.3.2").unwrap().unwrap();
+    assert!(evicted.is_archived);
+    assert!(evicted.archive_bundle_path.is_some());
+    assert!(store.patient("CC2").unwrap().is_some());
+    assert!(store.fsck(&blobs).unwrap().is_clean());
+
+    // La cuota cubre solo las instancias vivas: ni los bundles ni los
+    // estudios ya borrados cuentan
+    let bundles: u64 = ["1.2.3.1", "1.2.3.2"]
+        .iter()
+        .map(|uid| std::fs::metadata(root.path().join("archive").join(format!("{}.tar.gz", uid))).unwrap().len())
+        .sum();
+    assert!(bundles > 0);
+    assert_eq!(
+        store.storage_usage().unwrap(),
+        StorageUsage {
+            bytes: stored[2].size + stored[3].size,
+            instances: 2,
+            studies: 2,
+        }
+    );
+
+    // Sin espacio suficiente: lo protegido y lo no informado se conserva
+    let strict = QuotaManager::new(QuotaOptions {
+        high_watermark: 0,
+        low_watermark: 0,
+        ..options
+    })
+    .unwrap();
+    let report = strict.enforce(&mut store).unwrap();
+    assert!(report.actions.is_empty());
+    assert_eq!(report.shortfall, stored[2].size + stored[3].size);
+    assert!(store.study("1.2.3.3").unwrap().is_some());
+    assert!(store.study("1.2.3.4").unwrap().is_some());
+
+    let evicted: u32 = store
+        .connection()
+        .query_row("SELECT COUNT(*) FROM audit_log WHERE event_type = 'study_evicted'", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(evicted, 2);
+}
+
+#[test]
+fn test_quota_rejects_inverted_watermarks() {
+    use storage_engine::{LifecycleOptions, QuotaManager, QuotaOptions};
+
+    let options = QuotaOptions {
+        high_watermark: 10,
+        low_watermark: 20,
+        lifecycle: LifecycleOptions::new("archive"),
+    };
+    assert!(matches!(QuotaManager::new(options), Err(StorageError::InvalidConfig(_))));
+}
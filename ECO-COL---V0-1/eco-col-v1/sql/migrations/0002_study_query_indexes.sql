@@ -0,0 +1,7 @@
+-- ECO-COL V1 - Migración 2
+-- Índices para las consultas del índice de estudios y del ciclo de vida
+
+CREATE INDEX IF NOT EXISTS idx_studies_date ON studies(study_date);
+CREATE INDEX IF NOT EXISTS idx_studies_accession ON studies(accession_number);
+CREATE INDEX IF NOT EXISTS idx_studies_retention ON studies(is_archived, retention_expires_at);
+CREATE INDEX IF NOT EXISTS idx_studies_deletion ON studies(deletion_scheduled_at) WHERE deletion_scheduled_at IS NOT NULL;
@@ -0,0 +1,5 @@
+-- ECO-COL V1 - Migración 3
+-- Ruta del bundle de un estudio archivado: tras el borrado de las
+-- instancias el estudio se conserva (con informes y anotaciones)
+
+ALTER TABLE studies ADD COLUMN archive_bundle_path TEXT;
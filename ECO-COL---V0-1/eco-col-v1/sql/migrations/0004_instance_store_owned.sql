@@ -0,0 +1,6 @@
+-- ECO-COL V1 - Migración 4
+-- Instancias cuyo archivo es del BlobStore (`StudyStore::import`): solo
+-- esos archivos se borran al purgar; los registrados con `ingest` siguen
+-- siendo del llamador
+
+ALTER TABLE instances ADD COLUMN store_owned INTEGER NOT NULL DEFAULT 0;